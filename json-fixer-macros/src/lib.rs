@@ -0,0 +1,104 @@
+//! Compile time fixing of relaxed JSON literals.
+//!
+//! The [`fixjson!`] macro runs [`JsonFixer::fix`](json_fixer::JsonFixer::fix) while the
+//! crate is compiled and expands to the fixed JSON string, so tests and fixtures can be
+//! written in relaxed syntax without paying for the fixing at runtime.
+
+use proc_macro::{Literal, TokenStream, TokenTree};
+
+use json_fixer::JsonFixer;
+
+/// Fixes a JSON string literal at compile time and expands to the fixed `&'static str`.
+///
+/// The literal is fixed with the default configuration. Inputs that cannot be fixed are
+/// reported as compile errors.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer_macros::fixjson;
+///
+/// const USER: &str = fixjson!(r#"{ name: 'John', tags: ['a' 'b'], }"#);
+/// assert_eq!(USER, r#"{"name":"John","tags":["a","b"]}"#);
+/// ```
+///
+/// ```compile_fail
+/// use json_fixer_macros::fixjson;
+///
+/// let user = fixjson!("{ name 'John' }");
+/// ```
+#[proc_macro]
+pub fn fixjson(input: TokenStream) -> TokenStream {
+    let input = match string_literal(input) {
+        Ok(input) => input,
+        Err(message) => return compile_error(&message),
+    };
+
+    match JsonFixer::fix(&input) {
+        Ok(fixed) => TokenTree::Literal(Literal::string(&fixed)).into(),
+        Err(err) => compile_error(&format!("fixjson!: cannot fix JSON: {}", err)),
+    }
+}
+
+/// Returns the value of the single string literal of `input`.
+fn string_literal(input: TokenStream) -> Result<String, String> {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        // Literals passed through `macro_rules!` are wrapped in an invisible group
+        (Some(TokenTree::Group(group)), None) => return string_literal(group.stream()),
+        _ => return Err("fixjson! expects a single string literal".to_string()),
+    };
+
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return Ok(raw[hashes + 1..raw.len() - hashes - 1].to_string());
+    }
+    match literal.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(content) => unescape(content),
+        None => Err("fixjson! expects a string literal, eg. r#\"{a: 1}\"#".to_string()),
+    }
+}
+
+/// Resolves the escapes of the content of a Rust string literal.
+fn unescape(content: &str) -> Result<String, String> {
+    let mut value = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some(ch @ ('\\' | '"' | '\'')) => value.push(ch),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let code = u8::from_str_radix(&hex, 16).map_err(|err| err.to_string())?;
+                value.push(code as char);
+            }
+            Some('u') => {
+                let hex: String = chars.by_ref().skip(1).take_while(|ch| *ch != '}').collect();
+                let code = u32::from_str_radix(&hex, 16).map_err(|err| err.to_string())?;
+                value.push(char::from_u32(code).ok_or("invalid unicode escape")?);
+            }
+            Some('\n') => {
+                // Line continuation, the leading whitespaces of the next line are skipped
+                while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+            }
+            other => return Err(format!("unknown escape {:?}", other)),
+        }
+    }
+    Ok(value)
+}
+
+/// Expands to a `compile_error!` with `message`.
+fn compile_error(message: &str) -> TokenStream {
+    format!("compile_error!({})", Literal::string(message))
+        .parse()
+        .expect("compile_error! is a valid expression")
+}
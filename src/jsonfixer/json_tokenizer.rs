@@ -6,17 +6,114 @@
 use std::iter::Peekable;
 use std::str::Chars;
 
+use super::jsonescape::{hex_unit, push_lone_surrogate, unescaped_char, unicode_escape};
+use super::jsonfixer_config::{JsonFixerConfig, NonFiniteNumbers, Repairs, Whitespaces};
 use super::jsonfixer_error::{SyntaxError, JsonFixerError};
+use super::jsonfixer_report::{Dropped, Repair, RepairKind};
+use super::jsonindex::Span;
+use super::jsonvalidator::lone_surrogate;
+
+/// Prefixes of Python string literals, in lowercase.
+const PYTHON_STRING_PREFIXES: [&str; 8] = ["b", "r", "u", "f", "rb", "br", "fr", "rf"];
+
+/// Escape sequences of quotes and backslashes unescaped by `unescape_quotes`, without their
+/// leading backslash.
+const QUOTE_ESCAPES: [(&str, char); 10] = [
+    ("\"", '"'),
+    ("'", '\''),
+    ("\\", '\\'),
+    ("/", '/'),
+    ("x22", '"'),
+    ("x27", '\''),
+    ("x5c", '\\'),
+    ("u0022", '"'),
+    ("u0027", '\''),
+    ("u005c", '\\'),
+];
+
+/// Named HTML entities decoded by `decode_html_entities`, the other ones are kept as is.
+const HTML_ENTITIES: [(&str, char); 20] = [
+    ("quot", '"'),
+    ("amp", '&'),
+    ("apos", '\''),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("nbsp", '\u{A0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("euro", '€'),
+    ("deg", '°'),
+    ("laquo", '«'),
+    ("raquo", '»'),
+    ("lsquo", '\u{2018}'),
+    ("rsquo", '\u{2019}'),
+    ("ldquo", '\u{201C}'),
+    ("rdquo", '\u{201D}'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("hellip", '…'),
+];
 
 /// Represents a position in the input text.
+///
+/// Lines and columns are both 1-based, the columns counting characters rather than bytes. A
+/// line break is the last character of its line, eg. the `\n` of `"ab\ncd"` is at line 1,
+/// column 3 and the `c` at line 2, column 1.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Position {
     /// Line number (1-based)
     pub line: usize,
-    /// Column number (0-based)
+    /// Column number (1-based)
     pub column: usize,
 }
 
+impl Position {
+    /// Converts the position to the coordinates reported by the previous versions, whose
+    /// columns were one too many on the lines following the first one.
+    ///
+    /// The previous versions reported a line break at column 1 of the next line, so the
+    /// conversion only holds for the other characters.
+    pub fn to_legacy(&self) -> Self {
+        match self.line {
+            1 => self.clone(),
+            line => Self {
+                line,
+                column: self.column + 1,
+            },
+        }
+    }
+
+    /// Converts coordinates reported by the previous versions, see [`Position::to_legacy`].
+    pub fn from_legacy(legacy: &Position) -> Self {
+        match legacy.line {
+            1 => legacy.clone(),
+            line => Self {
+                line,
+                column: legacy.column.saturating_sub(1).max(1),
+            },
+        }
+    }
+}
+
+/// Returns the byte offset of the character at `position`, or the length of the input for
+/// the positions past its end.
+pub(crate) fn byte_offset(input: &str, position: &Position) -> usize {
+    let (mut line, mut column) = (1, 0);
+    for (offset, ch) in input.char_indices() {
+        column += 1;
+        if (line, column) >= (position.line, position.column) {
+            return offset;
+        }
+        // A line break is the last character of its line
+        if ch == '\n' {
+            line += 1;
+            column = 0;
+        }
+    }
+    input.len()
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     LeftBrace(Position),      // '{'
@@ -25,6 +122,8 @@ pub enum Token {
     RightBracket(Position),   // ']'
     Colon(Position),          // ':'
     Comma(Position),          // ','
+    LeftParen(Position),      // '(' of a Python tuple
+    RightParen(Position),     // ')' of a Python tuple
     String(String, Position), // JSON string
     Number(String, Position), // JSON number will kept as string to preserve the numbers like 1e5
     Boolean(bool, Position),  // true or false
@@ -34,6 +133,29 @@ pub enum Token {
 }
 
 impl Token {
+    /// Returns `true` if the token starts a value, eg. a string or a `{`.
+    pub fn starts_value(&self) -> bool {
+        matches!(
+            self,
+            Self::LeftBrace(_)
+                | Self::LeftBracket(_)
+                | Self::LeftParen(_)
+                | Self::String(_, _)
+                | Self::Number(_, _)
+                | Self::Boolean(_, _)
+                | Self::Null(_)
+                | Self::UnquotedString(_, _)
+        )
+    }
+
+    /// Returns `true` if the token is the `closing` bracket, brace or parenthesis.
+    pub fn closes(&self, closing: char) -> bool {
+        matches!(
+            (self, closing),
+            (Self::RightBrace(_), '}') | (Self::RightBracket(_), ']') | (Self::RightParen(_), ')')
+        )
+    }
+
     /// Converts the token to its string representation.
     pub fn get(&self) -> String {
         match self {
@@ -43,12 +165,14 @@ impl Token {
             Self::RightBracket(_) => "']'".to_string(),
             Self::Colon(_) => "':'".to_string(),
             Self::Comma(_) => "','".to_string(),
+            Self::LeftParen(_) => "'('".to_string(),
+            Self::RightParen(_) => "')'".to_string(),
             Self::String(s, _) => format!("String({s})"),
             Self::Number(n, _) => format!("Number({n})"),
             Self::Boolean(b, _) => format!("Boolean({b})"),
             Self::Null(_) => "null".to_string(),
-            Self::Whitespace(s, _) => format!("{}", s),
-            Self::UnquotedString(s, _) => format!("{}", s),
+            Self::Whitespace(s, _) => s.to_string(),
+            Self::UnquotedString(s, _) => s.to_string(),
         }
     }
     pub fn pos(&self) -> &Position {
@@ -59,6 +183,8 @@ impl Token {
             Self::RightBracket(pos) => pos,
             Self::Colon(pos) => pos,
             Self::Comma(pos) => pos,
+            Self::LeftParen(pos) => pos,
+            Self::RightParen(pos) => pos,
             Self::String(_, pos) => pos,
             Self::Number(_, pos) => pos,
             Self::Boolean(_, pos) => pos,
@@ -69,23 +195,174 @@ impl Token {
     }
 }
 
+/// What the parser does with a token read by the tokenizer, see [`TokenHook`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenAction {
+    /// Parse the token as is
+    Keep,
+    /// Parse the given token instead
+    Replace(Token),
+    /// Skip the token, the repairs found while reading it are dropped as well
+    Drop,
+}
+
+/// A hook called by the parser for every token read by the tokenizer, before it is parsed.
+///
+/// Hooks let bespoke repairs be implemented by rewriting or dropping tokens, eg. to strip
+/// vendor specific directives. Closures taking a `&Token` and returning a `TokenAction`
+/// are hooks.
+pub trait TokenHook {
+    fn on_token(&mut self, token: &Token) -> TokenAction;
+}
+
+impl<F: FnMut(&Token) -> TokenAction> TokenHook for F {
+    fn on_token(&mut self, token: &Token) -> TokenAction {
+        self(token)
+    }
+}
+
+/// Characters of the input, with one level of quote escapes removed when `unescape` is set,
+/// eg. `{\"a\": 1}` is read as `{"a": 1}`.
+#[derive(Clone)]
+struct InputChars<'a> {
+    chars: Chars<'a>,
+    unescape: bool,
+}
+
+impl Iterator for InputChars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch != '\\' || !self.unescape {
+            return Some(ch);
+        }
+
+        let rest = self.chars.as_str();
+        for (escape, unescaped) in QUOTE_ESCAPES {
+            let matches = rest
+                .get(..escape.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(escape));
+            if matches {
+                self.chars = rest[escape.len()..].chars();
+                return Some(unescaped);
+            }
+        }
+        Some(ch)
+    }
+}
+
+/// State of the input read by a tokenizer, see `JsonTokenizer::skip_corrupted`.
+struct Cursor<'a> {
+    input: Peekable<InputChars<'a>>,
+    line: usize,
+    column: usize,
+    line_break: bool,
+    offset: usize,
+}
+
 /// Tokenizer that converts JSON input text into a stream of tokens.
 pub struct JsonTokenizer<'a> {
-    input: Peekable<Chars<'a>>,
+    input: Peekable<InputChars<'a>>,
     line: usize,
     column: usize,
+    /// The last character read ends its line, the next one starts the following line
+    line_break: bool,
+    offset: usize,
+    /// One level of quote escapes is removed from the input, see `with_config`
+    unescape: bool,
+    repairs: Vec<Repair>,
+    identifier_chars: String,
+    whitespaces: Whitespaces,
+    python_compat: bool,
+    /// Comments are accepted between tokens, see `JsonFixerConfig::strip_comments`
+    comments: bool,
+    /// The comments are kept in the whitespaces, see `JsonFixerConfig::keep_comments`
+    keep_comments: bool,
+    hex_numbers: bool,
+    strip_leading_zeros: bool,
+    /// `NaN` and `Infinity` are numbers rather than bare words, see
+    /// `JsonFixerConfig::non_finite_numbers`
+    non_finite: bool,
+    allowed_repairs: Repairs,
+    leading_plus: bool,
+    /// The lone surrogate escapes are kept, see `Compliance::lone_surrogates`
+    lone_surrogates: bool,
+    decode_html_entities: bool,
 }
 
 impl<'a> JsonTokenizer<'a> {
-    /// Creates a new tokenizer instance.
+    /// Creates a new tokenizer instance using the default configuration.
     pub fn new(input: &'a str) -> Self {
+        Self::with_config(input, &JsonFixerConfig::default())
+    }
+
+    /// Creates a new tokenizer instance using the lexing options of `config`.
+    ///
+    /// With `config.unescape_quotes`, the positions and offsets of the tokens are the ones
+    /// of the input once unescaped.
+    pub fn with_config(input: &'a str, config: &JsonFixerConfig) -> Self {
+        let unescape = config.unescape_quotes && has_escaped_quotes(input);
+        let mut repairs = Vec::new();
+        if unescape {
+            repairs.push(Repair::at_path(
+                RepairKind::UnescapedQuotes,
+                "",
+                "Unescaped the quotes of the input",
+            ));
+        }
+
         Self {
-            input: input.chars().peekable(),
+            input: InputChars {
+                chars: input.chars(),
+                unescape,
+            }
+            .peekable(),
             line: 1,
             column: 0,
+            line_break: false,
+            offset: 0,
+            unescape,
+            repairs,
+            identifier_chars: config.identifier_chars.clone(),
+            whitespaces: config.whitespaces.clone(),
+            python_compat: config.python_compat,
+            comments: config.strip_comments || config.keep_comments,
+            keep_comments: config.keeps_comments(),
+            hex_numbers: config.hex_numbers,
+            strip_leading_zeros: config.strip_leading_zeros,
+            non_finite: config.non_finite_numbers != NonFiniteNumbers::BareWord,
+            allowed_repairs: config.repairs.clone(),
+            leading_plus: config.compliance.leading_plus,
+            lone_surrogates: config.compliance.lone_surrogates,
+            decode_html_entities: config.decode_html_entities,
+        }
+    }
+
+    /// Returns `true` if `ch` can appear in an unquoted identifier.
+    fn is_identifier_char(&self, ch: char) -> bool {
+        ch.is_alphanumeric() || ch == '_' || self.identifier_chars.contains(ch)
+    }
+
+    /// Returns `true` if `ch` is a configured identifier character that can start an identifier.
+    fn is_identifier_start(&self, ch: char) -> bool {
+        ch.is_alphabetic()
+            || !matches!(ch, '-' | '.' | '+' | '0'..='9') && self.identifier_chars.contains(ch)
+    }
+
+    /// Returns `true` if `ch` separates tokens, see `JsonFixerConfig::whitespaces`.
+    fn is_whitespace(&self, ch: char) -> bool {
+        match self.whitespaces {
+            Whitespaces::Unicode => ch.is_whitespace(),
+            Whitespaces::Json => matches!(ch, ' ' | '\t' | '\n' | '\r'),
         }
     }
 
+    /// Returns the repairs applied to the tokens produced so far.
+    pub fn take_repairs(&mut self) -> Vec<Repair> {
+        std::mem::take(&mut self.repairs)
+    }
+
     /// Returns the next token from the input stream.
     ///
     /// # Errors
@@ -94,16 +371,33 @@ impl<'a> JsonTokenizer<'a> {
     pub fn next_token(&mut self) -> Result<Option<Token>, JsonFixerError> {
         if let Some(ch) = self.advance() {
             match ch {
-                ch if ch.is_whitespace() => self.tokenize_whitespaces(ch).map(Some),
+                ch if self.is_whitespace(ch) => self.tokenize_whitespaces(ch).map(Some),
                 '{' => Ok(Some(Token::LeftBrace(self.current_position()))),
                 '}' => Ok(Some(Token::RightBrace(self.current_position()))),
                 '[' => Ok(Some(Token::LeftBracket(self.current_position()))),
                 ']' => Ok(Some(Token::RightBracket(self.current_position()))),
                 ':' => Ok(Some(Token::Colon(self.current_position()))),
                 ',' => Ok(Some(Token::Comma(self.current_position()))),
-                '\'' | '"' => self.tokenize_string(ch).map(Some),
+                '(' if self.python_compat => Ok(Some(Token::LeftParen(self.current_position()))),
+                ')' if self.python_compat => Ok(Some(Token::RightParen(self.current_position()))),
+                '/' if self.comments && matches!(self.peek(), Some('/' | '*')) => {
+                    let start_pos = self.current_position();
+                    let mut whitespaces = String::new();
+                    self.read_comment(&mut whitespaces);
+                    self.read_whitespaces(&mut whitespaces);
+                    if whitespaces.is_empty() {
+                        self.next_token()
+                    } else {
+                        Ok(Some(Token::Whitespace(whitespaces, start_pos)))
+                    }
+                }
+                '\'' | '"' => {
+                    let start_pos = self.current_position();
+                    self.tokenize_string(ch, start_pos, false).map(Some)
+                }
                 '.' | '+' | '-' | '0'..='9' => self.tokenize_number(ch).map(Some),
                 'a'..='z' | 'A'..='Z' | '_' => self.tokenize_identifier(ch).map(Some),
+                ch if self.is_identifier_start(ch) => self.tokenize_identifier(ch).map(Some),
                 ch => Err(JsonFixerError::Syntax(SyntaxError::UnexpectedCharacter(
                     ch,
                     Position {
@@ -121,72 +415,325 @@ impl<'a> JsonTokenizer<'a> {
         let start_pos = self.current_position();
         let mut whitespaces = String::new();
         whitespaces.push(first_space);
+        self.read_whitespaces(&mut whitespaces);
+
+        Ok(Token::Whitespace(whitespaces, start_pos))
+    }
 
-        while let Some(next_ch) = self.input.peek() {
-            if !next_ch.is_whitespace() {
+    /// Reads the whitespaces that follow into `whitespaces`, along with the comments found
+    /// among them when comments are accepted.
+    fn read_whitespaces(&mut self, whitespaces: &mut String) {
+        while let Some(&next_ch) = self.input.peek() {
+            if self.is_whitespace(next_ch) {
+                whitespaces.push(self.advance().unwrap());
+            } else if next_ch == '/' && self.comment_follows() {
+                self.advance();
+                self.read_comment(whitespaces);
+            } else {
                 break;
             }
+        }
+    }
+
+    /// Returns `true` if a comment starts at the next character, see
+    /// `JsonFixerConfig::strip_comments`.
+    fn comment_follows(&self) -> bool {
+        let mut next = self.input.clone();
+        self.comments && next.next() == Some('/') && matches!(next.next(), Some('/' | '*'))
+    }
+
+    /// Reads a comment whose leading `/` was read, a line comment up to its line break and a
+    /// block comment up to its `*/` or the end of the input. The comment is appended to
+    /// `whitespaces` when comments are kept, otherwise it is stripped and reported.
+    fn read_comment(&mut self, whitespaces: &mut String) {
+        let position = self.current_position();
+        let start = self.offset - 1;
+        let mut comment = String::from('/');
+        comment.extend(self.advance());
+        let block = comment == "/*";
 
-            whitespaces.push(self.advance().unwrap());
+        while let Some(&ch) = self.peek() {
+            if !block && matches!(ch, '\n' | '\r') {
+                break;
+            }
+            comment.push(self.advance().unwrap());
+            if block && comment.len() > 3 && comment.ends_with("*/") {
+                break;
+            }
         }
 
-        Ok(Token::Whitespace(whitespaces, start_pos))
+        if self.keep_comments {
+            whitespaces.push_str(&comment);
+            // A line comment kept at the end of the input still ends its line
+            if !block && self.peek().is_none() {
+                whitespaces.push('\n');
+            }
+            return;
+        }
+        if !block {
+            // No trailing spaces left where a line comment was
+            let len = whitespaces.trim_end_matches([' ', '\t']).len();
+            whitespaces.truncate(len);
+        }
+
+        // The offsets of an unescaped input are not the ones of the input
+        let span = (!self.unescape).then(|| Span {
+            start,
+            end: self.offset,
+            position: position.clone(),
+        });
+        let message = if block {
+            "Stripped block comment"
+        } else {
+            "Stripped line comment"
+        };
+        self.repairs.push(
+            Repair::at(RepairKind::StrippedComment, position, message).with_dropped(Dropped {
+                text: comment,
+                span,
+            }),
+        );
     }
 
     fn peek(&mut self) -> Option<&char> {
         self.input.peek()
     }
     fn advance(&mut self) -> Option<char> {
-        if let Some(ch) = self.input.next() {
-            self.column += 1;
+        let ch = self.input.next()?;
+        self.offset += ch.len_utf8();
 
-            if ch == '\n' {
-                self.line += 1;
-                self.column = 1;
+        // A line break is the last character of its line
+        if self.line_break {
+            self.line += 1;
+            self.column = 0;
+        }
+        self.line_break = ch == '\n';
+        self.column += 1;
+        Some(ch)
+    }
+    /// Skips a corrupted section of the input up to the end of the next line ending with a
+    /// comma, or up to the closing bracket of the enclosing container which is left to be
+    /// read. When no line ends with a comma before that bracket, the section ends at the
+    /// first comma separating the entries of the container instead, eg. after the `@@@,` of
+    /// `{"a": @@@, "b": 1}`. `open` is the number of brackets opened by the section so far,
+    /// the brackets of the section having to be closed before a boundary is found.
+    ///
+    /// Returns the position of the comma ending the section, if any.
+    pub fn skip_corrupted(&mut self, mut open: usize) -> Option<Position> {
+        // Comma ending the line so far
+        let mut comma = None;
+        // First comma separating the entries, and the state of the input right after it
+        let mut first_comma = None;
+        // Quote of the string being skipped, a string never spans lines
+        let mut quote = None;
+
+        while let Some(&ch) = self.peek() {
+            match (quote, ch) {
+                (_, '\n') => {
+                    quote = None;
+                    if comma.is_some() {
+                        self.advance();
+                        return comma;
+                    }
+                }
+                (Some(q), ch) => {
+                    if ch == q {
+                        quote = None;
+                    } else if ch == '\\' {
+                        self.advance();
+                    }
+                }
+                (None, '}' | ']') if open == 0 => return self.resume_after(first_comma),
+                (None, '}' | ']') => open -= 1,
+                (None, '{' | '[') => open += 1,
+                (None, '"' | '\'') => quote = Some(ch),
+                (None, ',') if open == 0 => {
+                    self.advance();
+                    comma = Some(self.current_position());
+                    if first_comma.is_none() {
+                        first_comma = Some((self.current_position(), self.cursor()));
+                    }
+                    continue;
+                }
+                (None, ch) if ch.is_whitespace() => {
+                    self.advance();
+                    continue;
+                }
+                _ => (),
             }
-            Some(ch)
-        } else {
-            None
+            comma = None;
+            self.advance();
         }
+        comma.or_else(|| self.resume_after(first_comma))
+    }
+
+    /// Moves back to the `cursor` following the comma at `position`, if any, and returns
+    /// its position.
+    fn resume_after(&mut self, comma: Option<(Position, Cursor<'a>)>) -> Option<Position> {
+        let (position, cursor) = comma?;
+        self.input = cursor.input;
+        self.line = cursor.line;
+        self.column = cursor.column;
+        self.line_break = cursor.line_break;
+        self.offset = cursor.offset;
+        Some(position)
+    }
+
+    /// Returns the state of the input, to move back to it.
+    fn cursor(&self) -> Cursor<'a> {
+        Cursor {
+            input: self.input.clone(),
+            line: self.line,
+            column: self.column,
+            line_break: self.line_break,
+            offset: self.offset,
+        }
+    }
+
+    /// Returns the byte offset of the next character to be read.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns `true` if the next character, whitespaces aside, starts a value, without
+    /// reading it.
+    pub fn value_follows(&self) -> bool {
+        let next = self.input.clone().find(|&ch| !self.is_whitespace(ch));
+        next.is_some_and(|ch| match ch {
+            '"' | '\'' | '{' | '[' | '-' | '+' | '.' => true,
+            '(' => self.python_compat,
+            ch => self.is_identifier_char(ch),
+        })
     }
+
+    /// Returns `true` if the quotes of the input are unescaped, the offsets then being the
+    /// ones of the input once unescaped.
+    pub fn unescapes(&self) -> bool {
+        self.unescape
+    }
+
+    /// Returns the position of the last character read, the first column of the input before
+    /// any is read.
     pub fn current_position(&self) -> Position {
         Position {
             line: self.line,
-            column: self.column,
+            column: self.column.max(1),
         }
     }
-    fn tokenize_string(&mut self, quote_char: char) -> Result<Token, JsonFixerError> {
-        let start_pos = self.current_position();
+    /// Tokenizes a string whose opening quote was consumed, the backslashes of `raw` strings
+    /// are kept as is.
+    fn tokenize_string(
+        &mut self,
+        quote_char: char,
+        start_pos: Position,
+        raw: bool,
+    ) -> Result<Token, JsonFixerError> {
+        if quote_char == '\'' && !self.allowed_repairs.normalize_quotes {
+            return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedCharacter(
+                quote_char, start_pos,
+            )));
+        }
         let mut result = String::new();
 
         while let Some(ch) = self.advance() {
             match ch {
-                ch if ch == quote_char => return Ok(Token::String(result, start_pos)),
+                ch if ch == quote_char => {
+                    if quote_char == '\'' {
+                        self.repairs.push(Repair::at(
+                            RepairKind::NormalizedQuotes,
+                            start_pos.clone(),
+                            "Converted single quotes to double quotes",
+                        ));
+                    }
+                    if self.decode_html_entities {
+                        if let Some(decoded) = decode_html_entities(&result) {
+                            self.repairs.push(Repair::at(
+                                RepairKind::DecodedEntities,
+                                start_pos.clone(),
+                                "Decoded HTML entities",
+                            ));
+                            result = decoded;
+                        }
+                    }
+                    return Ok(Token::String(result, start_pos));
+                }
+                '\\' if raw => {
+                    // Only the quote can be escaped, the backslash is kept
+                    result.push(ch);
+                    if let Some(next_ch) = self.advance() {
+                        result.push(next_ch);
+                    }
+                }
                 '\\' => {
+                    let escape_pos = self.current_position();
                     if let Some(next_ch) = self.advance() {
                         match next_ch {
-                            '"' | '\\' | '/' => result.push(next_ch),
-                            // handle controle characters
-                            'b' => result.push('\x08'), // \b = backspace
-                            'f' => result.push('\x0C'),
-                            'n' => result.push('\n'),
-                            'r' => result.push('\r'),
-                            't' => result.push('\t'),
                             'u' => {
-                                // Handle unicode escape sequences
-                                let mut hex = String::with_capacity(4);
-                                for _ in 0..4 {
-                                    if let Some(h) = self.advance() {
-                                        hex.push(h);
+                                // The escapes of a surrogate pair are decoded together, and a
+                                // lone surrogate is kept as written unless the compliance
+                                // rejects it
+                                let rest: String = std::iter::once('u')
+                                    .chain(self.input.clone().take(10))
+                                    .collect();
+                                if let Some((chr, length)) = unicode_escape(&rest) {
+                                    for _ in 1..length {
+                                        self.advance();
+                                    }
+                                    result.push(chr);
+                                } else if let Some(unit) = hex_unit(&rest) {
+                                    for _ in 0..4 {
+                                        self.advance();
                                     }
+                                    if self.lone_surrogates {
+                                        push_lone_surrogate(&mut result, unit);
+                                    } else {
+                                        self.replace_surrogate(unit, escape_pos)?;
+                                        result.push(char::REPLACEMENT_CHARACTER);
+                                    }
+                                } else {
+                                    self.fix_escape(next_ch, escape_pos)?;
+                                    result.push(next_ch);
                                 }
-                                if let Ok(code) = u32::from_str_radix(&hex, 16) {
-                                    if let Some(chr) = std::char::from_u32(code) {
-                                        result.push(chr);
+                            }
+                            'x' => {
+                                // JavaScript escape of a Latin-1 character eg. \x41
+                                self.fix_escape(next_ch, escape_pos)?;
+                                let mut next = self.input.clone();
+                                let hex: String = next.by_ref().take(2).collect();
+                                match u8::from_str_radix(&hex, 16) {
+                                    Ok(code) if hex.len() == 2 => {
+                                        self.advance();
+                                        self.advance();
+                                        result.push(char::from(code));
                                     }
+                                    _ => result.push(next_ch),
                                 }
                             }
-                            _ => result.push(next_ch),
+                            'v' => {
+                                self.fix_escape(next_ch, escape_pos)?;
+                                result.push('\x0B');
+                            }
+                            '0' if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) => {
+                                self.fix_escape(next_ch, escape_pos)?;
+                                result.push('\0');
+                            }
+                            // JavaScript line continuations
+                            '\n' | '\u{2028}' | '\u{2029}' => {
+                                self.fix_escape(next_ch, escape_pos)?;
+                            }
+                            '\r' => {
+                                self.fix_escape(next_ch, escape_pos)?;
+                                if self.peek() == Some(&'\n') {
+                                    self.advance();
+                                }
+                            }
+                            _ => match unescaped_char(next_ch) {
+                                Some(unescaped) => result.push(unescaped),
+                                None => {
+                                    self.fix_escape(next_ch, escape_pos)?;
+                                    result.push(next_ch);
+                                }
+                            },
                         }
                     }
                 }
@@ -198,20 +745,65 @@ impl<'a> JsonTokenizer<'a> {
         ))) // placeholder
     }
 
+    /// Records the repair of the invalid escape `\escape` found at `position`, or returns its
+    /// error when `Repairs::fix_escapes` is disabled.
+    fn fix_escape(&mut self, escape: char, position: Position) -> Result<(), JsonFixerError> {
+        if !self.allowed_repairs.fix_escapes {
+            return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedCharacter(
+                escape, position,
+            )));
+        }
+        let message = format!("Replaced the invalid escape '\\{}'", escape.escape_debug());
+        self.repairs
+            .push(Repair::at(RepairKind::FixedEscape, position, message));
+        Ok(())
+    }
+
+    /// Records the replacement of the lone surrogate escape of `unit` found at `position`, or
+    /// returns its error when `Repairs::fix_escapes` is disabled.
+    fn replace_surrogate(&mut self, unit: u32, position: Position) -> Result<(), JsonFixerError> {
+        if !self.allowed_repairs.fix_escapes {
+            return Err(JsonFixerError::Syntax(lone_surrogate(unit, position)));
+        }
+        let message = format!("Replaced the lone surrogate '\\u{:04x}' by U+FFFD", unit);
+        self.repairs
+            .push(Repair::at(RepairKind::ReplacedSurrogate, position, message));
+        Ok(())
+    }
+
     fn tokenize_number(&mut self, first_char: char) -> Result<Token, JsonFixerError> {
         let start_pos = self.current_position();
         let mut number = String::from(first_char);
+        // The number as found in the input
+        let mut literal = String::from(first_char);
+
+        // Signed non-finite numbers eg. -Infinity, left to the parser
+        if self.non_finite && matches!(first_char, '+' | '-') {
+            let word: String = self
+                .input
+                .clone()
+                .take_while(char::is_ascii_alphabetic)
+                .collect();
+            if word == "Infinity" || word == "NaN" {
+                for _ in 0..word.len() {
+                    self.advance();
+                }
+                return Ok(Token::UnquotedString(literal + &word, start_pos));
+            }
+        }
+        if self.hex_numbers && self.hex_follows(first_char) {
+            return self.tokenize_hex(first_char, start_pos);
+        }
 
         // Handle numbers that start with plus
         if first_char == '+' || first_char == '.' {
-            // If there is no digit after +, it's invalid
-            if let Some(next_char) = self.peek() {
-                if !next_char.is_digit(10) {
-                    return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
-                        number, start_pos,
-                    )));
-                }
-            } else {
+            // If there is no digit after + or +., it's invalid
+            let mut next = self.input.clone();
+            let mut next_char = next.next();
+            if first_char == '+' && next_char == Some('.') {
+                next_char = next.next();
+            }
+            if !next_char.is_some_and(|ch| ch.is_ascii_digit()) {
                 return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
                     number, start_pos,
                 )));
@@ -220,60 +812,314 @@ impl<'a> JsonTokenizer<'a> {
             if first_char == '+' {
                 // Remove the +
                 number.clear();
-            }
-
-            if first_char == '.' {
-                // Add 0 before the . eg. .123 -> 0.123
-                number.clear();
-                number.push('0');
-                number.push('.');
+                self.repairs.push(Repair::at(
+                    RepairKind::FixedNumber,
+                    start_pos.clone(),
+                    "Removed leading '+'",
+                ));
             }
         }
 
-        let mut multi_dots = false;
         while let Some(&ch) = self.peek() {
-            if !ch.is_digit(10) && ch != '.' && ch != 'e' && ch != 'E' && ch != '+' && ch != '-' {
+            if !ch.is_ascii_digit() && ch != '.' && ch != 'e' && ch != 'E' && ch != '+' && ch != '-' {
                 break;
             }
-            if first_char == '.' && ch == '.' {
-                // Cannot accept two dots, a first dot already accepted
-                multi_dots = true;
-            }
 
-            number.push(self.advance().unwrap());
+            let ch = self.advance().unwrap();
+            number.push(ch);
+            literal.push(ch);
         }
 
-        // it's a number that includes many dots
-        if multi_dots {
+        let sign = usize::from(number.starts_with('-'));
+        let fraction = number[sign..].strip_prefix('.');
+        if fraction.is_some_and(|digits| digits.starts_with(|ch: char| ch.is_ascii_digit())) {
+            // Add 0 before the . eg. .123 -> 0.123 or -.5 -> -0.5
+            number.insert(sign, '0');
+            self.repairs.push(Repair::at(
+                RepairKind::FixedNumber,
+                start_pos.clone(),
+                "Added '0' before leading '.'",
+            ));
+        }
+
+        // Leading zeros of the integer part, removed with `strip_leading_zeros` eg. 007 -> 7
+        // or -00.5 -> -0.5, and rejected when the numbers cannot be fixed
+        let integer = &number[sign..];
+        let digits = integer.bytes().take_while(u8::is_ascii_digit).count();
+        let zeros = integer
+            .bytes()
+            .take_while(|&b| b == b'0')
+            .count()
+            .min(digits.saturating_sub(1));
+        if zeros > 0 && !self.allowed_repairs.fix_numbers {
             return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
-                number, start_pos,
+                literal, start_pos,
             )));
         }
+        if zeros > 0 && self.strip_leading_zeros {
+            number.replace_range(sign..sign + zeros, "");
+            self.repairs.push(Repair::at(
+                RepairKind::FixedNumber,
+                start_pos.clone(),
+                "Removed leading zeros",
+            ));
+        }
 
-        if number.chars().last().unwrap() == '.' {
+        if number.ends_with('.') {
             // remove the .
             number.pop();
+            self.repairs.push(Repair::at(
+                RepairKind::FixedNumber,
+                start_pos.clone(),
+                "Removed trailing '.'",
+            ));
         }
 
-        Ok(Token::Number(number, self.current_position()))
+        // A leading '+' accepted by the configured compliance is not a defect
+        let accepted = self.leading_plus && literal.strip_prefix('+') == Some(number.as_str());
+        if number != literal && !accepted && !self.allowed_repairs.fix_numbers {
+            return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
+                literal, start_pos,
+            )));
+        }
+
+        // The repaired number must follow the JSON grammar, eg. not `1.2.3`, `--1` or `1e`
+        if !is_number(&number, true) {
+            return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
+                literal, start_pos,
+            )));
+        }
+
+        Ok(Token::Number(number, start_pos))
+    }
+
+    /// Returns `true` if the number starting with `first_char` is a hexadecimal number, eg.
+    /// `0x1F` or `-0x1F`.
+    fn hex_follows(&self, first_char: char) -> bool {
+        let mut next = self.input.clone();
+        let zero = match first_char {
+            '0' => true,
+            '+' | '-' => next.next() == Some('0'),
+            _ => false,
+        };
+        zero && matches!(next.next(), Some('x' | 'X'))
+            && next.next().is_some_and(|ch| ch.is_ascii_hexdigit())
+    }
+
+    /// Tokenizes a hexadecimal number into its decimal value, see
+    /// `JsonFixerConfig::hex_numbers`.
+    fn tokenize_hex(
+        &mut self,
+        first_char: char,
+        start_pos: Position,
+    ) -> Result<Token, JsonFixerError> {
+        let mut literal = String::from(first_char);
+        while let Some(&ch) = self.peek() {
+            let prefix = literal.trim_start_matches(['+', '-']).len() < 2;
+            if !(ch.is_ascii_hexdigit() || prefix && matches!(ch, '0' | 'x' | 'X')) {
+                break;
+            }
+            literal.push(self.advance().unwrap());
+        }
+
+        if !self.allowed_repairs.fix_numbers {
+            return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
+                literal, start_pos,
+            )));
+        }
+        let digits = &literal[literal.find(['x', 'X']).unwrap() + 1..];
+        let mut number = hex_to_decimal(digits);
+        if first_char == '-' && number != "0" {
+            number.insert(0, '-');
+        }
+        self.repairs.push(Repair::at(
+            RepairKind::FixedNumber,
+            start_pos.clone(),
+            format!("Converted hexadecimal '{}' to {}", literal, number),
+        ));
+        Ok(Token::Number(number, start_pos))
     }
 
     fn tokenize_identifier(&mut self, first_char: char) -> Result<Token, JsonFixerError> {
         let start_pos = self.current_position();
         let mut ident = String::from(first_char);
         while let Some(&ch) = self.input.peek() {
-            if !ch.is_alphanumeric() && ch != '_' {
+            if !self.is_identifier_char(ch) {
                 break;
             }
 
             ident.push(self.advance().unwrap());
         }
 
+        // Python string prefixes eg. b'bytes', r"raw" or f'format'
+        if self.python_compat && PYTHON_STRING_PREFIXES.contains(&ident.to_lowercase().as_str()) {
+            if let Some(&quote_char) = self.peek() {
+                if quote_char == '\'' || quote_char == '"' {
+                    self.advance();
+                    self.repairs.push(Repair::at(
+                        RepairKind::StrippedStringPrefix,
+                        start_pos.clone(),
+                        format!("Stripped string prefix '{}'", ident),
+                    ));
+                    let raw = ident.to_lowercase().contains('r');
+                    return self.tokenize_string(quote_char, start_pos, raw);
+                }
+            }
+        }
+
         match ident.as_str() {
             "true" => Ok(Token::Boolean(true, start_pos)),
             "false" => Ok(Token::Boolean(false, start_pos)),
             "null" => Ok(Token::Null(start_pos)),
+            "True" | "False" | "None" if self.python_compat => {
+                Ok(self.python_constant(&ident, start_pos))
+            }
             _ => Ok(Token::UnquotedString(ident, start_pos)),
         }
     }
+
+    /// Converts a Python constant, `True`, `False` or `None`, to the JSON literal.
+    fn python_constant(&mut self, ident: &str, pos: Position) -> Token {
+        let (kind, token, literal) = match ident {
+            "None" => (RepairKind::CoercedNull, Token::Null(pos.clone()), "null"),
+            "True" => (
+                RepairKind::CoercedBoolean,
+                Token::Boolean(true, pos.clone()),
+                "true",
+            ),
+            _ => (
+                RepairKind::CoercedBoolean,
+                Token::Boolean(false, pos.clone()),
+                "false",
+            ),
+        };
+        self.repairs.push(Repair::at(
+            kind,
+            pos,
+            format!("Converted Python '{}' to {}", ident, literal),
+        ));
+        token
+    }
+}
+
+/// Returns `true` if `s` is a number of the JSON grammar, eg. `-1.5e3`, or `007` when
+/// `leading_zeros` are accepted. Numbers are checked against the grammar rather than parsed,
+/// so that the numbers accepted do not depend on the float parsing of Rust, eg. `inf` or
+/// `1e99999`.
+pub(crate) fn is_number(s: &str, leading_zeros: bool) -> bool {
+    fn digits(s: &[u8]) -> usize {
+        s.iter().take_while(|b| b.is_ascii_digit()).count()
+    }
+
+    let s = s.strip_prefix('-').unwrap_or(s).as_bytes();
+    let mut i = digits(s);
+    if i == 0 || i > 1 && s[0] == b'0' && !leading_zeros {
+        return false;
+    }
+    if s.get(i) == Some(&b'.') {
+        let fraction = digits(&s[i + 1..]);
+        if fraction == 0 {
+            return false;
+        }
+        i += 1 + fraction;
+    }
+    if let Some(b'e' | b'E') = s.get(i) {
+        i += 1;
+        if let Some(b'+' | b'-') = s.get(i) {
+            i += 1;
+        }
+        let exponent = digits(&s[i..]);
+        if exponent == 0 {
+            return false;
+        }
+        i += exponent;
+    }
+    i == s.len()
+}
+
+/// Converts hexadecimal digits to decimal ones, eg. `1F` to `31`, whatever their number.
+fn hex_to_decimal(hex: &str) -> String {
+    // Decimal digits, least significant first
+    let mut digits: Vec<u32> = vec![0];
+    for h in hex.chars().filter_map(|ch| ch.to_digit(16)) {
+        let mut carry = h;
+        for digit in digits.iter_mut() {
+            let value = *digit * 16 + carry;
+            *digit = value % 10;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            digits.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|d| char::from_digit(*d, 10).unwrap())
+        .collect()
+}
+
+/// Decodes the HTML entities of `s`, eg. `&quot;`, `&#39;` or `&#x2F;`, returns `None` if it
+/// has none. Entities must end with a `;`, unknown ones are kept as is.
+fn decode_html_entities(s: &str) -> Option<String> {
+    if !s.contains('&') {
+        return None;
+    }
+
+    let mut decoded = String::with_capacity(s.len());
+    let mut found = false;
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..].find(';').map(|end| &rest[1..=end]);
+        let ch = entity.and_then(|entity| match entity.strip_prefix('#') {
+            Some(code) => match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => code.parse().ok(),
+            }
+            .and_then(char::from_u32),
+            None => HTML_ENTITIES
+                .iter()
+                .find(|(name, _)| *name == entity)
+                .map(|(_, ch)| *ch),
+        });
+        match (entity, ch) {
+            (Some(entity), Some(ch)) => {
+                decoded.push(ch);
+                rest = &rest[entity.len() + 2..];
+                found = true;
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    found.then_some(decoded)
+}
+
+/// Returns `true` if the first quote of `input` is escaped, eg. `{\"a\": 1}` or
+/// `{\x22a\x22: 1}`, as found in JSON extracted from HTML attributes or JavaScript strings.
+fn has_escaped_quotes(input: &str) -> bool {
+    let Some(start) = input.find(['"', '\'', '\\']) else {
+        return false;
+    };
+    let Some(escape) = input[start..].strip_prefix('\\') else {
+        return false;
+    };
+
+    QUOTE_ESCAPES.iter().any(|(prefix, unescaped)| {
+        matches!(unescaped, '"' | '\'')
+            && escape
+                .get(..prefix.len())
+                .is_some_and(|escape| escape.eq_ignore_ascii_case(prefix))
+    })
 }
@@ -0,0 +1,73 @@
+//! Annotation of the original input with the repairs the fixer applies to it.
+
+use super::{
+    json_tokenizer::{byte_offset, JsonTokenizer, Token},
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonfixer_report::{Repair, RepairKind},
+    jsonparser::JsonParser,
+};
+
+/// Fixes `input` with the default configuration and returns it as written, with a comment
+/// marker after each repaired token, eg. `[1/*<-- inserted missing ','*/ 2]`.
+///
+/// The markers of the commas and brackets inserted before a token, or replacing it, are
+/// written where the insertion is made, after the token preceding it, eg.
+/// `[1/*<-- inserted missing ']' before '}'*/}`. The repairs applied on the parsed document,
+/// which have no position in the input, are not marked.
+pub fn annotate(input: &str) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let report = JsonParser::with_borrowed_config(input, &config).parse_with_report()?;
+
+    // The start and end offsets of each token, whitespaces aside. The offsets are used
+    // rather than the token positions, a number being positioned at its last character.
+    let mut tokens = Vec::new();
+    let mut tokenizer = JsonTokenizer::with_config(input, &config);
+    let mut start = 0;
+    while let Some(token) = tokenizer.next_token()? {
+        if !matches!(token, Token::Whitespace(_, _)) {
+            tokens.push((start, tokenizer.offset()));
+        }
+        start = tokenizer.offset();
+    }
+
+    let mut markers: Vec<(usize, String)> = report
+        .repairs
+        .iter()
+        .filter_map(|repair| {
+            let position = byte_offset(input, repair.position.as_ref()?);
+            let offset = tokens
+                .iter()
+                .rev()
+                .find(|(start, _)| match repair.kind {
+                    RepairKind::InsertedComma | RepairKind::ClosedBracket => *start < position,
+                    _ => *start <= position,
+                })
+                .map_or(0, |&(_, end)| end);
+            Some((offset, marker(repair)))
+        })
+        .collect();
+    // Stable, so the markers of a token keep the order of the repairs
+    markers.sort_by_key(|&(offset, _)| offset);
+
+    let mut annotated = String::with_capacity(input.len());
+    let mut written = 0;
+    for (offset, marker) in markers {
+        annotated.push_str(&input[written..offset]);
+        annotated.push_str(&marker);
+        written = offset;
+    }
+    annotated.push_str(&input[written..]);
+    Ok(annotated)
+}
+
+/// Writes the message of a repair as a comment, eg. `/*<-- removed trailing ','*/`.
+fn marker(repair: &Repair) -> String {
+    let mut chars = repair.message.chars();
+    let message = match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => repair.kind.to_string(),
+    };
+    // A key or value quoted in the message must not end the comment
+    format!("/*<-- {}*/", message.replace("*/", "* /"))
+}
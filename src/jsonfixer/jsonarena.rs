@@ -0,0 +1,195 @@
+//! Value tree allocated in an arena.
+//!
+//! Building a [`JsonValue`](super::jsonparser::JsonValue) allocates every string and every
+//! array or object separately. The values built here are allocated in a bump arena instead,
+//! so a whole document is freed at once when the arena is dropped or reset.
+
+use bumpalo::{collections::Vec as ArenaVec, Bump};
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonparser::JsonParser,
+    jsonvalidator::{self, JsonValidator},
+};
+
+/// The arena the values are allocated in.
+pub type Arena = Bump;
+
+/// A JSON value allocated in an [`Arena`].
+///
+/// Strings are unescaped, numbers are kept as written in the fixed JSON.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+    Null,
+    Boolean(bool),
+    Number(&'a str),
+    String(&'a str),
+    Array(&'a [ArenaValue<'a>]),
+    Object(&'a [(&'a str, ArenaValue<'a>)]),
+}
+
+impl<'a> ArenaValue<'a> {
+    /// Returns the value of the last member named `key` when this value is an object.
+    pub fn get(&self, key: &str) -> Option<&ArenaValue<'a>> {
+        match self {
+            ArenaValue::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// Fixes `input` and builds its value in `arena`.
+///
+/// Valid inputs are built directly from the input, the others are fixed with the default
+/// configuration first.
+pub fn fix_to_value_in<'a>(
+    arena: &'a Arena,
+    input: &str,
+) -> Result<ArenaValue<'a>, JsonFixerError> {
+    if JsonValidator::validate(input).is_ok() {
+        return Ok(ArenaBuilder::new(arena, input).build());
+    }
+
+    let output = JsonParser::new(input, JsonFixerConfig::default()).parse()?;
+    if let Err(diagnostic) = JsonValidator::validate(&output) {
+        return Err(JsonFixerError::Internal { output, diagnostic });
+    }
+    Ok(ArenaBuilder::new(arena, &output).build())
+}
+
+/// Builds the value of an input that was checked by the validator.
+struct ArenaBuilder<'a, 'i> {
+    arena: &'a Arena,
+    input: &'i str,
+    bytes: &'i [u8],
+    pos: usize,
+}
+
+impl<'a, 'i> ArenaBuilder<'a, 'i> {
+    fn new(arena: &'a Arena, input: &'i str) -> Self {
+        Self {
+            arena,
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn build(mut self) -> ArenaValue<'a> {
+        self.skip_whitespaces();
+        self.build_value()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while matches!(self.bytes.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn build_value(&mut self) -> ArenaValue<'a> {
+        match self.bytes[self.pos] {
+            b'{' => self.build_object(),
+            b'[' => self.build_array(),
+            b'"' => ArenaValue::String(self.build_string()),
+            b't' => {
+                self.pos += "true".len();
+                ArenaValue::Boolean(true)
+            }
+            b'f' => {
+                self.pos += "false".len();
+                ArenaValue::Boolean(false)
+            }
+            b'n' => {
+                self.pos += "null".len();
+                ArenaValue::Null
+            }
+            _ => self.build_number(),
+        }
+    }
+
+    fn build_object(&mut self) -> ArenaValue<'a> {
+        let mut members = ArenaVec::new_in(self.arena);
+        self.pos += 1; // Consume '{'
+        self.skip_whitespaces();
+        if self.bytes[self.pos] == b'}' {
+            self.pos += 1;
+            return ArenaValue::Object(members.into_bump_slice());
+        }
+
+        loop {
+            self.skip_whitespaces();
+            let key = self.build_string();
+            self.skip_whitespaces();
+            self.pos += 1; // Consume ':'
+            self.skip_whitespaces();
+            members.push((key, self.build_value()));
+            self.skip_whitespaces();
+
+            self.pos += 1; // Consume ',' or '}'
+            if self.bytes[self.pos - 1] == b'}' {
+                return ArenaValue::Object(members.into_bump_slice());
+            }
+        }
+    }
+
+    fn build_array(&mut self) -> ArenaValue<'a> {
+        let mut items = ArenaVec::new_in(self.arena);
+        self.pos += 1; // Consume '['
+        self.skip_whitespaces();
+        if self.bytes[self.pos] == b']' {
+            self.pos += 1;
+            return ArenaValue::Array(items.into_bump_slice());
+        }
+
+        loop {
+            self.skip_whitespaces();
+            items.push(self.build_value());
+            self.skip_whitespaces();
+
+            self.pos += 1; // Consume ',' or ']'
+            if self.bytes[self.pos - 1] == b']' {
+                return ArenaValue::Array(items.into_bump_slice());
+            }
+        }
+    }
+
+    fn build_string(&mut self) -> &'a str {
+        self.pos += 1; // Consume the opening quote
+        let start = self.pos;
+        let mut escaped = false;
+        loop {
+            match self.bytes[self.pos] {
+                b'\\' => {
+                    escaped = true;
+                    self.pos += 2;
+                }
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        let content = &self.input[start..self.pos];
+        self.pos += 1; // Consume the closing quote
+
+        if escaped {
+            self.arena.alloc_str(&jsonvalidator::decode_string(content))
+        } else {
+            self.arena.alloc_str(content)
+        }
+    }
+
+    fn build_number(&mut self) -> ArenaValue<'a> {
+        let start = self.pos;
+        while matches!(
+            self.bytes.get(self.pos),
+            Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E')
+        ) {
+            self.pos += 1;
+        }
+        ArenaValue::Number(self.arena.alloc_str(&self.input[start..self.pos]))
+    }
+}
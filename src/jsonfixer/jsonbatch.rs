@@ -0,0 +1,246 @@
+//! Statistics of the repairs and failures across a batch of documents.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{
+    json_tokenizer::Position,
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonfixer_report::{FixReport, RepairKind},
+    jsonparser::JsonParser,
+};
+
+/// Maximum number of failures kept as samples by a [`BatchReport`].
+pub const MAX_FAILURE_SAMPLES: usize = 10;
+
+/// Characters separating the documents of a stream: byte order marks (U+FEFF) and form feeds.
+pub const DOCUMENT_SEPARATORS: [char; 2] = ['\u{FEFF}', '\u{000C}'];
+
+/// A document of a batch that could not be fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureSample {
+    /// Index of the document in the batch.
+    pub index: usize,
+    /// The error the document failed with.
+    pub message: String,
+}
+
+/// Aggregated results of fixing a batch of documents, to monitor the data quality of the
+/// producers of the documents.
+///
+/// Reports are fed by [`JsonFixer::fix_batch`](super::JsonFixer::fix_batch), or document by
+/// document with [`BatchReport::record`], or by
+/// [`JsonFixer::fix_documents`](super::JsonFixer::fix_documents) for a stream of documents,
+/// and the reports of several batches or threads are
+/// combined with [`BatchReport::merge`].
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    /// Number of documents recorded.
+    pub documents: usize,
+    /// Number of documents that could not be fixed.
+    pub failures: usize,
+    /// Number of documents that were already valid.
+    pub clean: usize,
+    /// Number of repairs of each kind, across all the documents.
+    pub repairs: HashMap<RepairKind, usize>,
+    /// The first failures, at most `MAX_FAILURE_SAMPLES` of them.
+    pub failure_samples: Vec<FailureSample>,
+    /// Positions of the separators found between the documents of a stream, see
+    /// [`JsonFixer::fix_documents`](super::JsonFixer::fix_documents).
+    pub separators: Vec<Position>,
+    /// Size of every document in bytes, sorted once queried.
+    sizes: Vec<usize>,
+    sorted: bool,
+}
+
+impl BatchReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the result of fixing `input`, the next document of the batch.
+    pub fn record(&mut self, input: &str, result: &Result<FixReport, JsonFixerError>) {
+        let index = self.documents;
+        self.documents += 1;
+        self.sizes.push(input.len());
+        self.sorted = false;
+
+        match result {
+            Ok(report) => {
+                if report.is_clean() {
+                    self.clean += 1;
+                }
+                for repair in &report.repairs {
+                    *self.repairs.entry(repair.kind).or_default() += 1;
+                }
+            }
+            Err(err) => {
+                self.failures += 1;
+                if self.failure_samples.len() < MAX_FAILURE_SAMPLES {
+                    self.failure_samples.push(FailureSample {
+                        index,
+                        message: err.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Adds the documents of `other`, recorded after the ones of this report.
+    pub fn merge(&mut self, other: BatchReport) {
+        let offset = self.documents;
+        self.documents += other.documents;
+        self.failures += other.failures;
+        self.clean += other.clean;
+        for (kind, count) in other.repairs {
+            *self.repairs.entry(kind).or_default() += count;
+        }
+        let room = MAX_FAILURE_SAMPLES.saturating_sub(self.failure_samples.len());
+        self.failure_samples
+            .extend(
+                other
+                    .failure_samples
+                    .into_iter()
+                    .take(room)
+                    .map(|sample| FailureSample {
+                        index: sample.index + offset,
+                        ..sample
+                    }),
+            );
+        self.separators.extend(other.separators);
+        self.sizes.extend(other.sizes);
+        self.sorted = false;
+    }
+
+    /// Counts the repairs of the given kind.
+    pub fn count(&self, kind: RepairKind) -> usize {
+        self.repairs.get(&kind).copied().unwrap_or(0)
+    }
+
+    /// Ratio of the documents that could not be fixed, `0.0` for an empty batch.
+    pub fn failure_rate(&self) -> f64 {
+        if self.documents == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.documents as f64
+        }
+    }
+
+    /// Returns the size in bytes below or at which `percentile` percent of the documents are,
+    /// eg. `size_percentile(50.0)` for the median, `None` for an empty batch.
+    pub fn size_percentile(&mut self, percentile: f64) -> Option<usize> {
+        if self.sizes.is_empty() {
+            return None;
+        }
+        if !self.sorted {
+            self.sizes.sort_unstable();
+            self.sorted = true;
+        }
+
+        // Nearest rank
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.sizes.len() as f64).ceil();
+        Some(self.sizes[(rank as usize).max(1) - 1])
+    }
+}
+
+impl fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} documents, {} clean, {} failed",
+            self.documents, self.clean, self.failures
+        )?;
+
+        let mut repairs: Vec<_> = self.repairs.iter().collect();
+        repairs.sort_by(|a, b| {
+            b.1.cmp(a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        for (kind, count) in repairs {
+            write!(f, "\n  {}: {}", kind, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fixes every input with `config` and records the results in a report.
+pub fn fix_batch<'a>(
+    inputs: impl IntoIterator<Item = &'a str>,
+    config: &JsonFixerConfig,
+) -> (Vec<Result<FixReport, JsonFixerError>>, BatchReport) {
+    let mut report = BatchReport::new();
+    let results = inputs
+        .into_iter()
+        .map(|input| {
+            let result = JsonParser::with_borrowed_config(input, config).parse_with_report();
+            report.record(input, &result);
+            result
+        })
+        .collect();
+    (results, report)
+}
+
+/// Splits a stream into its documents and fixes them, see [`fix_batch`].
+pub fn fix_documents(
+    input: &str,
+    config: &JsonFixerConfig,
+) -> (Vec<Result<FixReport, JsonFixerError>>, BatchReport) {
+    let (documents, separators) = split_documents(input);
+    let (results, mut report) = fix_batch(documents, config);
+    report.separators = separators;
+    (results, report)
+}
+
+/// Splits `input` at the [`DOCUMENT_SEPARATORS`] found outside strings, leaving out the blank
+/// documents, and returns the documents along with the positions of the separators.
+///
+/// A byte order mark starting the input is not a separator. Strings do not span lines, so
+/// that an unclosed quote does not hide the separators of the next lines.
+fn split_documents(input: &str) -> (Vec<&str>, Vec<Position>) {
+    let mut documents = Vec::new();
+    let mut separators = Vec::new();
+    let mut start = 0;
+    let mut line = 1;
+    let mut column = 0;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (offset, ch) in input.char_indices() {
+        column += 1;
+        if ch == '\n' {
+            line += 1;
+            column = 0;
+            quote = None;
+            escaped = false;
+            continue;
+        }
+
+        if let Some(quote_char) = quote {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == quote_char {
+                quote = None;
+            }
+            continue;
+        }
+
+        match ch {
+            '"' | '\'' => quote = Some(ch),
+            '\u{FEFF}' if offset == 0 => start = ch.len_utf8(),
+            ch if DOCUMENT_SEPARATORS.contains(&ch) => {
+                documents.push(&input[start..offset]);
+                separators.push(Position { line, column });
+                start = offset + ch.len_utf8();
+            }
+            _ => (),
+        }
+    }
+    documents.push(&input[start..]);
+
+    documents.retain(|document| !document.trim().is_empty());
+    (documents, separators)
+}
@@ -0,0 +1,65 @@
+//! What the linked version of the crate supports, queried at runtime.
+
+use super::{jsonfixer_config::OutputStyle, jsonfixer_report::RepairKind};
+
+/// Cargo features of the crate, in the order of `Cargo.toml`, and whether they are enabled.
+const FEATURES: [(&str, bool); 9] = [
+    ("serde", cfg!(feature = "serde")),
+    ("terminal", cfg!(feature = "terminal")),
+    ("memmap", cfg!(feature = "memmap")),
+    ("arena", cfg!(feature = "arena")),
+    ("bignum", cfg!(feature = "bignum")),
+    ("schema", cfg!(feature = "schema")),
+    ("testing", cfg!(feature = "testing")),
+    ("encoding", cfg!(feature = "encoding")),
+    ("hash", cfg!(feature = "hash")),
+];
+
+/// Version, enabled features, repairs and output styles of the linked crate.
+///
+/// Host applications, eg. GUIs or plugin systems, can build their settings from it instead
+/// of hard coding what a given version of the crate supports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capabilities {
+    /// Version of the crate, eg. `0.1.0`.
+    pub version: &'static str,
+    /// The enabled Cargo features, eg. `serde`.
+    pub features: Vec<&'static str>,
+    /// Every kind of repair the fixer can apply.
+    pub repairs: Vec<RepairKind>,
+    /// Every syntax the output can be written in.
+    pub output_styles: Vec<OutputStyle>,
+}
+
+impl Capabilities {
+    /// Returns `true` if the Cargo feature `name` is enabled.
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.contains(&name)
+    }
+}
+
+/// Returns the capabilities of the linked crate.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::{OutputStyle, RepairKind};
+///
+/// let capabilities = json_fixer::capabilities();
+///
+/// assert!(capabilities.repairs.contains(&RepairKind::QuotedKey));
+/// assert!(capabilities.output_styles.contains(&OutputStyle::Json5));
+/// assert_eq!(capabilities.has_feature("serde"), cfg!(feature = "serde"));
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        repairs: RepairKind::ALL.to_vec(),
+        output_styles: OutputStyle::ALL.to_vec(),
+    }
+}
@@ -0,0 +1,207 @@
+//! Stable codes of the errors and repairs, for dashboards and suppression lists.
+
+use std::fmt;
+
+use super::{
+    jsonfixer_error::{JsonFixerError, JsonFormatError, LimitError, SyntaxError},
+    jsonfixer_report::{Repair, RepairKind},
+};
+
+/// Stable identifier of an error or repair, eg. `JF1001` for an unmatched quote.
+///
+/// Codes are never reused nor renumbered across versions: `JF1xxx` are syntax errors,
+/// `JF2xxx` repairs, `JF3xxx` limits, `JF4xxx` formatting errors, `JF5xxx` the other
+/// errors and `JF6xxx` the violations of expectations on the fixed value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Code(u16);
+
+/// Every code along with its description, in numeric order.
+pub const CATALOGUE: [(Code, &str); 52] = [
+    (Code(1001), "unmatched quote"),
+    (Code(1002), "unexpected character"),
+    (Code(1003), "unexpected end of input"),
+    (Code(1004), "missing comma"),
+    (Code(1005), "invalid number"),
+    (Code(1006), "unexpected token"),
+    (Code(1007), "duplicate key"),
+    (Code(2001), "quoted key"),
+    (Code(2002), "normalized quotes"),
+    (Code(2003), "quoted value"),
+    (Code(2004), "inserted comma"),
+    (Code(2005), "removed comma"),
+    (Code(2006), "closed bracket"),
+    (Code(2007), "fixed number"),
+    (Code(2008), "homogenized array"),
+    (Code(2009), "converted to array"),
+    (Code(2010), "stripped string prefix"),
+    (Code(2011), "unwrapped JSON string"),
+    (Code(2012), "truncated string"),
+    (Code(2013), "truncated container"),
+    (Code(2014), "repeated colon"),
+    (Code(2015), "decoded entities"),
+    (Code(2016), "unescaped quotes"),
+    (Code(2017), "salvaged section"),
+    (Code(2018), "compacted whitespace"),
+    (Code(2019), "coerced boolean"),
+    (Code(2020), "coerced null"),
+    (Code(2021), "cleaned key"),
+    (Code(2022), "pruned key"),
+    (Code(2023), "inserted default"),
+    (Code(2024), "removed duplicate"),
+    (Code(2025), "stray word"),
+    (Code(2026), "stripped comment"),
+    (Code(2027), "fixed escape"),
+    (Code(2028), "replaced surrogate"),
+    (Code(3001), "string too long"),
+    (Code(3002), "key too long"),
+    (Code(3003), "too many elements"),
+    (Code(3004), "too many members"),
+    (Code(3005), "too many repairs"),
+    (Code(3006), "too deep"),
+    (Code(4001), "line too long"),
+    (Code(4002), "invalid indentation"),
+    (Code(5001), "formatting failed"),
+    (Code(5002), "input or output error"),
+    (Code(5003), "invalid output"),
+    (Code(5004), "serde error"),
+    (Code(5005), "unstreamable configuration"),
+    (Code(5006), "unreplayable configuration"),
+    (Code(5007), "uninsertable value"),
+    (Code(5008), "incompatible configuration"),
+    (Code(6001), "unexpected shape"),
+];
+
+impl Code {
+    /// Returns the code written `s`, eg. `JF1001`, or `None` if there is no such code.
+    pub fn parse(s: &str) -> Option<Self> {
+        let number = s.strip_prefix("JF")?;
+        if number.len() != 4 {
+            return None;
+        }
+        let code = Self(number.parse().ok()?);
+        CATALOGUE.iter().any(|(c, _)| *c == code).then_some(code)
+    }
+
+    /// Returns the number of the code, eg. `1001`.
+    pub fn number(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the short description of the code, eg. `unmatched quote`.
+    pub fn description(self) -> &'static str {
+        CATALOGUE
+            .iter()
+            .find(|(code, _)| *code == self)
+            .map_or("", |(_, description)| description)
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JF{:04}", self.0)
+    }
+}
+
+impl SyntaxError {
+    /// Returns the stable code of the error.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::UnmatchedQuotes(_) => Code(1001),
+            Self::UnexpectedCharacter(_, _) => Code(1002),
+            Self::UnexpectedEndOfInput(_) => Code(1003),
+            Self::MissingComma(_) => Code(1004),
+            Self::InvalidNumber(_, _) => Code(1005),
+            Self::UnexpectedToken(_, _) => Code(1006),
+            Self::DuplicateKey(_, _) => Code(1007),
+        }
+    }
+}
+
+impl LimitError {
+    /// Returns the stable code of the error.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::StringTooLong { .. } => Code(3001),
+            Self::KeyTooLong { .. } => Code(3002),
+            Self::TooManyElements { .. } => Code(3003),
+            Self::TooManyMembers { .. } => Code(3004),
+            Self::TooManyRepairs { .. } => Code(3005),
+            Self::TooDeep { .. } => Code(3006),
+        }
+    }
+}
+
+impl JsonFormatError {
+    /// Returns the stable code of the error.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::LineTooLong { .. } => Code(4001),
+            Self::InvalidIndentation { .. } => Code(4002),
+        }
+    }
+}
+
+impl JsonFixerError {
+    /// Returns the stable code of the error, the one of the syntax, limit or formatting
+    /// error it wraps if any.
+    pub fn code(&self) -> Code {
+        match self {
+            Self::Syntax(error) => error.code(),
+            Self::Limit(error) => error.code(),
+            Self::Format(error) => error.code(),
+            Self::IO(_) => Code(5001),
+            Self::IoError(_) => Code(5002),
+            Self::Internal { .. } => Code(5003),
+            Self::Unstreamable(_) => Code(5005),
+            Self::Unreplayable(_) => Code(5006),
+            Self::Uninsertable { .. } => Code(5007),
+            Self::Incompatible { .. } => Code(5008),
+            Self::Shape(_) => Code(6001),
+            #[cfg(feature = "serde")]
+            Self::SerdeError(_) => Code(5004),
+        }
+    }
+}
+
+impl RepairKind {
+    /// Returns the stable code of the repairs of this kind.
+    pub fn code(self) -> Code {
+        match self {
+            Self::QuotedKey => Code(2001),
+            Self::NormalizedQuotes => Code(2002),
+            Self::QuotedValue => Code(2003),
+            Self::InsertedComma => Code(2004),
+            Self::RemovedComma => Code(2005),
+            Self::ClosedBracket => Code(2006),
+            Self::FixedNumber => Code(2007),
+            Self::HomogenizedArray => Code(2008),
+            Self::ConvertedToArray => Code(2009),
+            Self::StrippedStringPrefix => Code(2010),
+            Self::UnwrappedJsonString => Code(2011),
+            Self::TruncatedString => Code(2012),
+            Self::TruncatedContainer => Code(2013),
+            Self::RepeatedColon => Code(2014),
+            Self::DecodedEntities => Code(2015),
+            Self::UnescapedQuotes => Code(2016),
+            Self::Salvaged => Code(2017),
+            Self::CompactedWhitespace => Code(2018),
+            Self::CoercedBoolean => Code(2019),
+            Self::CoercedNull => Code(2020),
+            Self::CleanedKey => Code(2021),
+            Self::PrunedKey => Code(2022),
+            Self::InsertedDefault => Code(2023),
+            Self::RemovedDuplicate => Code(2024),
+            Self::StrayWord => Code(2025),
+            Self::StrippedComment => Code(2026),
+            Self::FixedEscape => Code(2027),
+            Self::ReplacedSurrogate => Code(2028),
+        }
+    }
+}
+
+impl Repair {
+    /// Returns the stable code of the repair, the one of its kind.
+    pub fn code(&self) -> Code {
+        self.kind.code()
+    }
+}
@@ -0,0 +1,88 @@
+//! Structural comparison of JSON documents.
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonnumber::JsonNumber,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+};
+
+/// Options of [`JsonFixer::semantically_equal`](super::JsonFixer::semantically_equal).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompareOptions {
+    /// Compares numbers by value rather than as written, so that `1e2`, `100` and `100.0`
+    /// are equal. Numbers with a fraction or an exponent are compared as `f64`, integers
+    /// exactly, or every number exactly with the `bignum` feature.
+    pub ignore_number_format: bool,
+}
+
+/// Fixes `a` and `b` with the default configuration and compares their values.
+pub fn semantically_equal(
+    a: &str,
+    b: &str,
+    options: &CompareOptions,
+) -> Result<bool, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let (a, _) = JsonParser::with_borrowed_config(a, &config).parse_document()?;
+    let (b, _) = JsonParser::with_borrowed_config(b, &config).parse_document()?;
+    Ok(values_equal(&a, &b, options))
+}
+
+/// Compares two values, ignoring the order of object members and the whitespaces kept in
+/// containers.
+pub(crate) fn values_equal(a: &JsonValue, b: &JsonValue, options: &CompareOptions) -> bool {
+    match (a, b) {
+        (JsonValue::Number(a), JsonValue::Number(b)) if options.ignore_number_format => {
+            numbers_equal(a, b)
+        }
+        (JsonValue::Number(a), JsonValue::Number(b)) => a.to_string() == b.to_string(),
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            let (a, b) = (entries(a), entries(b));
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| entry_values_equal(a, b, options))
+        }
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            let (mut a, mut b) = (entries(a), entries(b));
+            if a.len() != b.len() {
+                return false;
+            }
+            // Stable sorts, members sharing a key keep their order
+            a.sort_by(|x, y| x.key.cmp(&y.key));
+            b.sort_by(|x, y| x.key.cmp(&y.key));
+            a.iter()
+                .zip(b)
+                .all(|(a, b)| a.key == b.key && entry_values_equal(a, b, options))
+        }
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(feature = "bignum")]
+fn numbers_equal(a: &JsonNumber, b: &JsonNumber) -> bool {
+    a.exact_eq(b)
+}
+
+#[cfg(not(feature = "bignum"))]
+fn numbers_equal(a: &JsonNumber, b: &JsonNumber) -> bool {
+    a.normalized() == b.normalized()
+}
+
+/// The entries of a container holding a value, its whitespaces left out.
+fn entries(entries: &[JsonEntryValue]) -> Vec<&JsonEntryValue> {
+    entries
+        .iter()
+        .filter(|entry| {
+            let value = entry.value.as_ref();
+            value.is_some_and(|value| !matches!(value, JsonValue::Space(_)))
+        })
+        .collect()
+}
+
+fn entry_values_equal(a: &JsonEntryValue, b: &JsonEntryValue, options: &CompareOptions) -> bool {
+    match (&a.value, &b.value) {
+        (Some(a), Some(b)) => values_equal(a, b, options),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
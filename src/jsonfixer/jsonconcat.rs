@@ -0,0 +1,107 @@
+//! Concatenation of many documents into one array or object.
+
+use std::collections::HashSet;
+use std::io::{Read, Write};
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::{JsonFixerError, SyntaxError},
+    jsonformatter::{Formatter, JsonFormatter},
+    jsonmembers::expect_container,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+};
+
+/// Options of [`JsonFixer::concat`](super::JsonFixer::concat).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConcatOptions {
+    /// Merges the members of the documents, which must be objects, into one object instead
+    /// of writing the documents as the elements of an array
+    pub merge_objects: bool,
+    /// Leaves out the documents written before, or the members whose key was written before
+    /// when merging objects, the first one being kept
+    pub deduplicate: bool,
+}
+
+/// Fixes the document read from each reader with `config` and writes them to `writer` as the
+/// elements of one array, or as the members of one object, returning the number of elements
+/// or members written.
+///
+/// Each document is written as soon as it is fixed, so only one document is held in memory
+/// at a time, besides the documents or keys kept to deduplicate. The array or object is
+/// formatted with `config` as if it were formatted whole, the documents being indented as
+/// its elements or members when beautified.
+pub fn concat<R, W>(
+    readers: impl IntoIterator<Item = R>,
+    mut writer: W,
+    config: &JsonFixerConfig,
+    options: &ConcatOptions,
+) -> Result<usize, JsonFixerError>
+where
+    R: Read,
+    W: Write,
+{
+    let mut seen = HashSet::new();
+    let mut written = 0;
+    let mut output = String::new();
+
+    for mut reader in readers {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .map_err(JsonFixerError::IoError)?;
+        let opening = match options.merge_objects {
+            true => Some(expect_container(&input, '{')?),
+            false => None,
+        };
+        let (value, _) = JsonParser::with_borrowed_config(&input, config).parse_document()?;
+
+        let entries = match (value, opening) {
+            (JsonValue::Object(entries), Some(_)) => entries,
+            // A Python set, eg. {1, 2}
+            (_, Some(pos)) => {
+                return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
+                    "\nExpected an object but found a set".to_string(),
+                    pos,
+                )))
+            }
+            (value, None) => {
+                if options.deduplicate && !seen.insert(JsonFormatter.format(&value, config)?) {
+                    continue;
+                }
+                let mut element = JsonEntryValue::new();
+                element.value = Some(value);
+                vec![element]
+            }
+        };
+        for entry in entries {
+            if entry.value.is_none() {
+                continue;
+            }
+            if let (Some(key), true) = (&entry.key, options.deduplicate) {
+                if !seen.insert(key.clone()) {
+                    continue;
+                }
+            }
+            output.clear();
+            JsonFormatter.format_root_member(&entry, written, config, &mut output)?;
+            write(&mut writer, &output)?;
+            written += 1;
+        }
+    }
+
+    output.clear();
+    match (written, options.merge_objects) {
+        (0, true) => output = JsonFormatter.format(&JsonValue::Object(Vec::new()), config)?,
+        (0, false) => output = JsonFormatter.format(&JsonValue::Array(Vec::new()), config)?,
+        (_, true) => JsonFormatter.close_root('}', config, &mut output)?,
+        (_, false) => JsonFormatter.close_root(']', config, &mut output)?,
+    }
+    write(&mut writer, &output)?;
+    Ok(written)
+}
+
+fn write<W: Write>(writer: &mut W, s: &str) -> Result<(), JsonFixerError> {
+    writer
+        .write_all(s.as_bytes())
+        .map_err(JsonFixerError::IoError)
+}
@@ -0,0 +1,124 @@
+//! Detection and transcoding of the encoding of byte inputs.
+
+use std::fmt;
+
+use super::{
+    jsonfixer_config::JsonFixerConfig, jsonfixer_error::JsonFixerError, jsonparser::JsonParser,
+};
+
+/// Characters of the bytes `0x80` to `0x9F` in Windows-1252, `None` for the five unassigned
+/// bytes. Every other byte is the character of the same code point, like in ISO-8859-1.
+const WINDOWS_1252: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('\u{2018}'),
+    Some('\u{2019}'),
+    Some('\u{201C}'),
+    Some('\u{201D}'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Encoding of a byte input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Valid UTF-8, with or without a byte order mark
+    Utf8,
+    /// Windows-1252, the superset of ISO-8859-1 exported by most Windows tools
+    Windows1252,
+    /// ISO-8859-1, also known as Latin-1
+    Latin1,
+}
+
+impl Encoding {
+    /// Detects the encoding of `bytes`.
+    ///
+    /// Inputs that are valid UTF-8 are UTF-8, as single-byte encodings seldom form valid
+    /// UTF-8 sequences. The other inputs are Windows-1252 if they only use its assigned bytes
+    /// in the `0x80` to `0x9F` range, eg. `0x93` for `“`, and ISO-8859-1 otherwise, where
+    /// these bytes are control characters.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if std::str::from_utf8(bytes).is_ok() {
+            return Self::Utf8;
+        }
+        let windows_1252 = bytes
+            .iter()
+            .filter(|byte| (0x80..=0x9F).contains(*byte))
+            .all(|byte| WINDOWS_1252[(byte - 0x80) as usize].is_some());
+        if windows_1252 {
+            Self::Windows1252
+        } else {
+            Self::Latin1
+        }
+    }
+
+    /// Decodes `bytes`, dropping the UTF-8 byte order mark. Invalid UTF-8 sequences are
+    /// replaced by `U+FFFD`.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => {
+                let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+            Self::Windows1252 => bytes
+                .iter()
+                .map(|&byte| match byte {
+                    0x80..=0x9F => WINDOWS_1252[(byte - 0x80) as usize].unwrap_or(byte as char),
+                    byte => byte as char,
+                })
+                .collect(),
+            Self::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "UTF-8"),
+            Self::Windows1252 => write!(f, "Windows-1252"),
+            Self::Latin1 => write!(f, "ISO-8859-1"),
+        }
+    }
+}
+
+/// Result of [`JsonFixer::fix_bytes`](super::JsonFixer::fix_bytes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncodingReport {
+    /// The fixed JSON string.
+    pub output: String,
+    /// The detected encoding of the input.
+    pub encoding: Encoding,
+}
+
+/// Detects the encoding of `input` and fixes it once transcoded to UTF-8.
+pub fn fix_bytes(input: &[u8], config: &JsonFixerConfig) -> Result<EncodingReport, JsonFixerError> {
+    let encoding = Encoding::detect(input);
+    let input = encoding.decode(input);
+    let output = JsonParser::with_borrowed_config(&input, config).parse()?;
+    Ok(EncodingReport { output, encoding })
+}
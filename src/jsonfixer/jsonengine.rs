@@ -0,0 +1,98 @@
+//! Reusable fixer keeping its buffers between calls.
+
+use std::sync::Arc;
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+};
+
+/// Maximum number of emptied entry vectors kept between two calls.
+const MAX_POOLED: usize = 1024;
+
+/// A JSON fixer owning scratch buffers that are reused from one call to the next.
+///
+/// Fixing many small documents with [`JsonFixer`](super::JsonFixer) allocates a new output
+/// string and new entry vectors for every document. The engine keeps them around instead, so
+/// once warmed up most calls don't allocate at all.
+///
+/// The configuration is kept behind an `Arc`, so a single configuration can be shared by
+/// the engines of several threads. The engine itself is `Send` and can be moved to the thread
+/// using it.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::{JsonFixerConfig, JsonFixerEngine};
+///
+/// let mut engine = JsonFixerEngine::new(JsonFixerConfig::default());
+/// assert_eq!(engine.fix("{a: 1}").unwrap(), r#"{"a":1}"#);
+/// assert_eq!(engine.fix("[1 2]").unwrap(), "[1,2]");
+/// ```
+pub struct JsonFixerEngine {
+    config: Arc<JsonFixerConfig>,
+    output: String,
+    pool: Vec<Vec<JsonEntryValue>>,
+}
+
+impl JsonFixerEngine {
+    /// Creates a new engine fixing its inputs with the given configuration, either owned or
+    /// shared with an `Arc<JsonFixerConfig>`.
+    pub fn new(config: impl Into<Arc<JsonFixerConfig>>) -> Self {
+        Self {
+            config: config.into(),
+            output: String::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    /// Returns the configuration of the engine.
+    pub fn config(&self) -> &JsonFixerConfig {
+        &self.config
+    }
+
+    /// Fixes `input` and returns the fixed JSON, borrowed from the engine output buffer until
+    /// the next call.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&str)` - The fixed JSON string
+    /// * `Err(JsonFixerError)` - If the input cannot be fixed
+    pub fn fix(&mut self, input: &str) -> Result<&str, JsonFixerError> {
+        self.output.clear();
+
+        let pool = std::mem::take(&mut self.pool);
+        let mut parser = JsonParser::with_borrowed_config(input, &self.config).with_pool(pool);
+        let result = parser.parse_document();
+        self.pool = parser.take_pool();
+
+        let (value, _) = result?;
+        let result = parser.format_document_into(&value, &mut self.output);
+        recycle(value, &mut self.pool);
+        result?;
+
+        Ok(&self.output)
+    }
+}
+
+/// Empties the arrays and objects of `value` into `pool`.
+fn recycle(value: JsonValue, pool: &mut Vec<Vec<JsonEntryValue>>) {
+    let mut entries = match value {
+        JsonValue::Object(entries) | JsonValue::Array(entries) => entries,
+        _ => return,
+    };
+
+    for entry in entries.drain(..) {
+        if let Some(value) = entry.value {
+            recycle(value, pool);
+        }
+    }
+    if pool.len() < MAX_POOLED {
+        pool.push(entries);
+    }
+}
@@ -0,0 +1,210 @@
+//! Escaping and unescaping of the content of JSON strings.
+
+use std::fmt::Write;
+
+use super::{
+    json_tokenizer::{JsonTokenizer, Token},
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::{JsonFixerError, SyntaxError},
+};
+
+/// The characters [`escape_str`] escapes besides the ones JSON requires.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EscapePolicy {
+    /// Only the quotes, backslashes and control characters, like the fixed output
+    Json,
+    /// Also every non-ASCII character, eg. `é` to `\u00e9` and `😀` to a surrogate pair, for
+    /// consumers that are not UTF-8 safe
+    Ascii,
+    /// Also `<`, `>`, `&`, `'` and the U+2028 and U+2029 separators, eg. `</script>` to
+    /// `\u003c/script\u003e`, for strings embedded in HTML or JavaScript sources
+    Html,
+}
+
+/// Escapes `s` to be written between double quotes in a JSON document, eg. `say "hi"` and a
+/// newline to `say \"hi\"\n`. The surrounding quotes are not added.
+pub fn escape_str(s: &str, policy: EscapePolicy) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    escape_into(&mut escaped, s, policy);
+    escaped
+}
+
+/// Marks a lone surrogate kept in a string, which Rust strings cannot hold, see
+/// `push_lone_surrogate`.
+const SURROGATE_MARKER: char = '\u{FDD0}';
+
+/// Private use character standing for the first surrogate after `SURROGATE_MARKER`.
+const SURROGATE_BASE: u32 = 0x10F800;
+
+/// Appends the lone surrogate `unit`, eg. the `0xD800` of `\ud800`, to a string so that it
+/// is escaped as written by [`escape_str`]. It is held as the noncharacter U+FDD0 followed
+/// by a private use character, replaced by U+FFFD by `replace_lone_surrogates`.
+pub(crate) fn push_lone_surrogate(output: &mut String, unit: u32) {
+    output.push(SURROGATE_MARKER);
+    output.extend(char::from_u32(SURROGATE_BASE + (unit - 0xD800)));
+}
+
+/// Returns the lone surrogate held by the character following `SURROGATE_MARKER`.
+fn lone_surrogate(marked: &char) -> Option<u32> {
+    let unit = (*marked as u32).checked_sub(SURROGATE_BASE)? + 0xD800;
+    (unit <= 0xDFFF).then_some(unit)
+}
+
+/// Replaces the lone surrogates kept in `s` by U+FFFD, for consumers that cannot hold them.
+#[cfg(feature = "serde")]
+pub(crate) fn replace_lone_surrogates(s: String) -> String {
+    if !s.contains(SURROGATE_MARKER) {
+        return s;
+    }
+    let mut replaced = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == SURROGATE_MARKER && chars.next_if(|c| lone_surrogate(c).is_some()).is_some() {
+            replaced.push(char::REPLACEMENT_CHARACTER);
+        } else {
+            replaced.push(c);
+        }
+    }
+    replaced
+}
+
+/// Appends `s` escaped to `output`, see [`escape_str`].
+pub(crate) fn escape_into(output: &mut String, s: &str, policy: EscapePolicy) {
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            SURROGATE_MARKER if chars.peek().and_then(lone_surrogate).is_some() => {
+                let unit = chars.next().as_ref().and_then(lone_surrogate);
+                push_unit_escape(output, unit.unwrap_or_default());
+            }
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{0008}' => output.push_str("\\b"),
+            '\u{000C}' => output.push_str("\\f"),
+            c if c.is_control() => push_unicode_escape(output, c),
+            c if policy == EscapePolicy::Ascii && !c.is_ascii() => push_unicode_escape(output, c),
+            '<' | '>' | '&' | '\'' | '\u{2028}' | '\u{2029}' if policy == EscapePolicy::Html => {
+                push_unicode_escape(output, c)
+            }
+            c => output.push(c),
+        }
+    }
+}
+
+/// Unescapes the content of a JSON string, eg. `say \"hi\"\n` to `say "hi"` and a newline.
+///
+/// Unescaping is lenient like the tokenizer: an unknown escape is replaced by the escaped
+/// character, eg. `\q` to `q`. The invalid `\u` escapes, including lone surrogates, and a
+/// trailing backslash are kept as written.
+pub fn unescape_str(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('\\') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let Some(escape) = rest.chars().next() else {
+            unescaped.push('\\');
+            break;
+        };
+        if escape == 'u' {
+            if let Some((ch, length)) = unicode_escape(rest) {
+                unescaped.push(ch);
+                rest = &rest[length..];
+                continue;
+            }
+            unescaped.push('\\');
+        }
+        unescaped.push(unescaped_char(escape).unwrap_or(escape));
+        rest = &rest[escape.len_utf8()..];
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Fixes a single string literal, eg. `'it\'s'` or a double quoted string holding raw tabs,
+/// into a double quoted JSON string. The string is read like the strings of a document with
+/// the default configuration, then escaped like the fixed output.
+///
+/// Only whitespaces can surround the string. An unclosed string is not closed, the input
+/// being more likely to be something else than a truncated string.
+pub fn fix_string_literal(input: &str) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let mut tokenizer = JsonTokenizer::with_config(input, &config);
+    let mut literal = None;
+
+    while let Some(token) = tokenizer.next_token()? {
+        match token {
+            Token::Whitespace(_, _) => (),
+            Token::String(s, _) if literal.is_none() => literal = Some(s),
+            token => {
+                let error = SyntaxError::UnexpectedToken(token.get(), token.pos().clone());
+                return Err(JsonFixerError::Syntax(error));
+            }
+        }
+    }
+
+    match literal {
+        Some(s) => Ok(format!("\"{}\"", escape_str(&s, EscapePolicy::Json))),
+        None => Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(
+            tokenizer.current_position(),
+        ))),
+    }
+}
+
+/// Returns the character of a single character escape, eg. a newline for the `n` of `\n`.
+pub(crate) fn unescaped_char(escape: char) -> Option<char> {
+    match escape {
+        '"' | '\\' | '/' => Some(escape),
+        'b' => Some('\x08'),
+        'f' => Some('\x0C'),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        _ => None,
+    }
+}
+
+/// Appends `c` as a `\u` escape, or as a surrogate pair of escapes outside the basic
+/// multilingual plane.
+fn push_unicode_escape(output: &mut String, c: char) {
+    let mut units = [0; 2];
+    for unit in c.encode_utf16(&mut units) {
+        push_unit_escape(output, u32::from(*unit));
+    }
+}
+
+/// Appends the UTF-16 code unit `unit` as a `\u` escape.
+fn push_unit_escape(output: &mut String, unit: u32) {
+    // Writing to a String cannot fail
+    let _ = write!(output, "\\u{:04x}", unit);
+}
+
+/// Decodes the `\u` escape `rest` starts with, its backslash left out, along with the escape
+/// of the low surrogate following a high surrogate. Returns the character and the length of
+/// the escapes.
+pub(crate) fn unicode_escape(rest: &str) -> Option<(char, usize)> {
+    let unit = hex_unit(rest)?;
+    if let Some(ch) = char::from_u32(unit) {
+        return Some((ch, 5));
+    }
+
+    let low = rest[5..].strip_prefix('\\').and_then(hex_unit)?;
+    if !(0xD800..0xDC00).contains(&unit) || !(0xDC00..0xE000).contains(&low) {
+        return None;
+    }
+    let code = 0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00);
+    Some((char::from_u32(code)?, 11))
+}
+
+/// Parses the 4 hexadecimal digits following the `u` that `s` starts with.
+pub(crate) fn hex_unit(s: &str) -> Option<u32> {
+    let hex = s.strip_prefix('u')?.get(..4)?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
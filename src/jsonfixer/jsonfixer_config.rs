@@ -1,8 +1,324 @@
+use std::cmp::Ordering;
+
+use crate::jsonfixer::jsoncode::Code;
+use crate::jsonfixer::jsonfixer_error::JsonFixerError;
+use crate::jsonfixer::jsonfixer_report::{Confidence, RepairKind};
 use crate::jsonfixer::jsonformatter::IndentStyle;
+use crate::jsonfixer::jsonparser::JsonValue;
+use crate::jsonfixer::jsonpath;
+
+/// How keys appearing more than once in the same object are handled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DuplicateKeys {
+    /// Keep every occurrence, the output may contain duplicated keys
+    KeepAll,
+    /// Keep the first occurrence only
+    KeepFirst,
+    /// Keep the last occurrence only
+    KeepLast,
+    /// Deep merge duplicated objects, concatenate duplicated arrays, otherwise keep the last value
+    MergeObjects,
+}
+
+/// What to do with lines longer than `max_line_length`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineLengthPolicy {
+    /// Return a `JsonFormatError::LineTooLong` error
+    Error,
+    /// Break arrays and objects over multiple lines, strings are kept intact
+    Wrap,
+}
+
+/// How arrays mixing scalars and single key objects, eg. `[1, {"value": 2}, 3]`, are normalized.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HomogenizeArrays {
+    /// Keep the array as is
+    Leave,
+    /// Replace the single key objects by their value, eg. `[1, 2, 3]`, when they all have
+    /// the same key
+    Unwrap,
+    /// Wrap the scalars in objects using the common key, eg. `[{"value": 1}, {"value": 2}, {"value": 3}]`
+    Wrap,
+}
+
+/// What to do with strings and keys longer than `max_string_length` and `max_key_length`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LengthPolicy {
+    /// Return a `LimitError` error
+    Error,
+    /// Truncate them and append the marker, the marker counts in the maximum length and is
+    /// itself truncated when longer. The keys made equal are then handled by `duplicate_keys`
+    Truncate(String),
+}
+
+/// What to do with arrays and objects larger than `max_array_elements` and
+/// `max_object_members`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizePolicy {
+    /// Return a `LimitError` error
+    Error,
+    /// Keep the first elements or members and drop the others
+    Truncate,
+}
+
+/// What to do with a colon repeated after the value of a member, eg. `{"a": "b": 1}`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepeatedColons {
+    /// Return a `SyntaxError` error
+    Error,
+    /// Nest the value in an object under the key before the colon, eg. `{"a": {"b": 1}}`
+    Nest,
+    /// Drop the stray key before the colon, eg. `{"a": 1}`
+    Drop,
+}
+
+/// What to do with a bare word in value position, eg. `{"a": oops}`.
+///
+/// A word followed by a value in an object member or at the root is a stray, the value
+/// being the actual one, eg. `{"a": oops 1}`, while the words of an array are elements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BareWords {
+    /// Return a `SyntaxError` error
+    Error,
+    /// Quote the word when the `quote_values` repair is enabled, eg. `{"a": "oops"}`,
+    /// otherwise return a `SyntaxError` error, or drop it when it is a stray
+    Quote,
+    /// Drop the word along with its member or element, eg. `{}`, or only the word when it is
+    /// a stray, eg. `{"a": 1}`
+    Drop,
+    /// Replace the word by `null`, eg. `{"a": null}`, or drop it when it is a stray
+    Null,
+}
+
+/// What to do with the non-finite numbers of JavaScript and JSON5, `NaN`, `Infinity` and
+/// `-Infinity`, which JSON cannot represent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NonFiniteNumbers {
+    /// Handle them like the other bare words, see `JsonFixerConfig::bare_words`
+    BareWord,
+    /// Return a `SyntaxError::InvalidNumber` error, whatever `bare_words` allows
+    Error,
+    /// Replace them by `null`, eg. `[null]` for `[NaN]`
+    Null,
+    /// Replace them by a string without leading `+`, eg. `["-Infinity"]` for `[-Infinity]`
+    /// and `["Infinity"]` for `[+Infinity]`
+    String,
+    /// Write them as found in JSON5 output, which accepts them, the other output styles
+    /// failing with `JsonFixerError::Incompatible`
+    Keep,
+}
+
+/// What to do with the `undefined` of JavaScript object dumps, which JSON cannot represent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Undefined {
+    /// Handle it like the other bare words, see `JsonFixerConfig::bare_words`
+    BareWord,
+    /// Replace it by `null`, eg. `{"a": null}` for `{"a": undefined}`
+    Null,
+    /// Drop its member or element like `JSON.stringify` does, eg. `{}` for `{"a": undefined}`
+    Drop,
+}
+
+/// Which characters separate the tokens of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Whitespaces {
+    /// Every Unicode whitespace, eg. non-breaking spaces, vertical tabs or form feeds, which
+    /// are kept with the original formatting
+    Unicode,
+    /// Only the whitespaces of the JSON grammar, spaces, tabs, line feeds and carriage
+    /// returns, the other ones being reported as unexpected characters
+    Json,
+}
+
+/// The syntax the output is written in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputStyle {
+    /// Strict JSON
+    Json,
+    /// JSON5, which allows the options producing JSON5 only syntax, eg. `emit_trailing_commas`
+    Json5,
+    /// JSON with comments, eg. VS Code settings files, which is strict JSON besides comments
+    Jsonc,
+}
+
+impl OutputStyle {
+    /// Every output style, in declaration order.
+    pub const ALL: [OutputStyle; 3] = [Self::Json, Self::Json5, Self::Jsonc];
+}
+
+/// How the keys are quoted in JSON5 output, the other output styles always quote them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteKeys {
+    /// Quote every key, eg. `{"name": 1}`
+    Always,
+    /// Keep the keys that were unquoted in the input unquoted, eg. `{name: 1, "id": 2}`
+    AsInput,
+    /// Only quote the keys that are not identifiers, eg. `{name: 1, "first-name": 2}`
+    OnlyWhenNeeded,
+}
+
+/// Which strings standing for a missing value, eg. `"NULL"` or `"None"`, are converted to
+/// `null` by `normalize_nulls`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NormalizeNulls {
+    /// Keep every string
+    Never,
+    /// Only convert the strings of the fields at the paths of `null_paths`
+    Hinted,
+    /// Convert the strings of every field and element
+    Always,
+}
+
+/// The repairs the fixer is allowed to apply, a disabled repair makes the fixing fail with
+/// the syntax error of the defect instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repairs {
+    /// Quote unquoted keys, eg. `{a: 1}`
+    pub quote_keys: bool,
+    /// Convert single quoted strings to double quoted ones, eg. `'a'`
+    pub normalize_quotes: bool,
+    /// Insert missing commas, eg. `[1 2]`
+    pub insert_commas: bool,
+    /// Drop trailing, leading and repeated commas, eg. `[1,,2,]`
+    pub remove_commas: bool,
+    /// Quote unquoted single word values, eg. `{"a": value}`
+    pub quote_values: bool,
+    /// Close the arrays and objects left open at the end of the input, eg. `[1, 2`
+    pub close_brackets: bool,
+    /// Rewrite numbers into valid JSON numbers, eg. `+1`, `.5` or `1.`
+    pub fix_numbers: bool,
+    /// Replace the invalid escapes of strings, eg. `\q` to `q`, and the lone surrogates
+    /// rejected by `Compliance::lone_surrogates` by U+FFFD
+    pub fix_escapes: bool,
+}
+
+impl Default for Repairs {
+    /// Every repair enabled except `quote_values`.
+    fn default() -> Self {
+        Self {
+            quote_values: false,
+            ..Self::all()
+        }
+    }
+}
+
+impl Repairs {
+    /// Enables every repair.
+    pub fn all() -> Self {
+        Self {
+            quote_keys: true,
+            normalize_quotes: true,
+            insert_commas: true,
+            remove_commas: true,
+            quote_values: true,
+            close_brackets: true,
+            fix_numbers: true,
+            fix_escapes: true,
+        }
+    }
+
+    /// Disables every repair.
+    pub fn none() -> Self {
+        Self {
+            quote_keys: false,
+            normalize_quotes: false,
+            insert_commas: false,
+            remove_commas: false,
+            quote_values: false,
+            close_brackets: false,
+            fix_numbers: false,
+            fix_escapes: false,
+        }
+    }
+
+    /// Returns `true` if repairs of the given kind can be applied. The repairs enabled by
+    /// other options of the configuration are always allowed.
+    pub fn allows(&self, kind: RepairKind) -> bool {
+        match kind {
+            RepairKind::QuotedKey => self.quote_keys,
+            RepairKind::NormalizedQuotes => self.normalize_quotes,
+            RepairKind::InsertedComma => self.insert_commas,
+            RepairKind::RemovedComma => self.remove_commas,
+            RepairKind::QuotedValue => self.quote_values,
+            RepairKind::ClosedBracket => self.close_brackets,
+            RepairKind::FixedNumber => self.fix_numbers,
+            RepairKind::FixedEscape | RepairKind::ReplacedSurrogate => self.fix_escapes,
+            RepairKind::HomogenizedArray
+            | RepairKind::ConvertedToArray
+            | RepairKind::StrippedStringPrefix
+            | RepairKind::UnwrappedJsonString
+            | RepairKind::TruncatedString
+            | RepairKind::TruncatedContainer
+            | RepairKind::RepeatedColon
+            | RepairKind::DecodedEntities
+            | RepairKind::UnescapedQuotes
+            | RepairKind::Salvaged
+            | RepairKind::CompactedWhitespace
+            | RepairKind::CoercedBoolean
+            | RepairKind::CoercedNull
+            | RepairKind::CleanedKey
+            | RepairKind::PrunedKey
+            | RepairKind::InsertedDefault
+            | RepairKind::RemovedDuplicate
+            | RepairKind::StrayWord
+            | RepairKind::StrippedComment => true,
+        }
+    }
+}
+
+/// Gray areas of the JSON specifications that downstream parsers handle differently, checked
+/// by the strict validator used by the `strict` mode and `verify_output`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compliance {
+    /// Accept numbers with a leading `+`, eg. `+1`, which neither RFC 8259 nor ECMA-404 allow
+    pub leading_plus: bool,
+    /// Accept objects having the same key more than once
+    pub duplicate_keys: bool,
+    /// Accept escaped UTF-16 surrogates that are not part of a pair, eg. `"\ud800"`, which
+    /// are then kept as written. Rejected ones are replaced by U+FFFD, see
+    /// `Repairs::fix_escapes`
+    pub lone_surrogates: bool,
+    /// Accept documents whose root is not an object or an array, eg. `42`
+    pub scalar_root: bool,
+}
+
+impl Default for Compliance {
+    /// The grammar shared by RFC 8259 and ECMA-404.
+    fn default() -> Self {
+        Self {
+            leading_plus: false,
+            duplicate_keys: true,
+            lone_surrogates: true,
+            scalar_root: true,
+        }
+    }
+}
+
+impl Compliance {
+    /// The I-JSON profile of RFC 7493: unique keys, no lone surrogates and an object or an
+    /// array at the root.
+    pub fn i_json() -> Self {
+        Self {
+            leading_plus: false,
+            duplicate_keys: false,
+            lone_surrogates: false,
+            scalar_root: false,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct JsonFixerConfig {
     pub preserve: bool,      // Keep whitesapces, keeps original format
+    /*
+    Preserves the line structure of the input but normalizes its whitespaces, for review
+    friendly diffs: lines are indented with `indent_style` and `indent_size` once per enclosing
+    array or object, other runs of spaces and tabs become a single space and trailing ones are
+    removed. Implies `preserve`
+    eg. with an indent size of 2,
+    {\n      "a":    1,\n\t"b": [1,   2]\n} to {\n  "a": 1,\n  "b": [1, 2]\n}
+     */
+    pub preserve_normalize: bool,
     pub space_between: bool, // Adds one space after between key and value eg. {"key":"value"} to { "key" : "value" }
     /*
     Make it humain readable
@@ -15,32 +331,532 @@ pub struct JsonFixerConfig {
     pub beautify: bool,
     pub indent_style: IndentStyle,
     pub indent_size: usize,
-    pub sort_keys: bool,
+    pub sort_keys: bool, // Sorts object keys, stable so that duplicate keys keep their order
+    /*
+    Keys written first by `sort_keys`, in this order, the other keys are sorted after them
+    eg. with ["$schema", "id", "name"], {"b": 1, "name": 2, "$schema": 3}
+    to {"$schema": 3, "name": 2, "b": 1}
+     */
+    pub key_priority: Vec<String>,
+    /*
+    Canonical order of the keys of the objects matching a path pattern, first match wins
+    The listed keys are written first in this order, the other keys after them in input
+    order, or sorted with `sort_keys`
+    eg. ("", ["name", "version", "description"]) for package manifests
+     */
+    pub key_order: Vec<(String, Vec<String>)>,
+    pub verify_output: bool, // Re-validates the produced output with the strict validator before returning it
+    pub duplicate_keys: DuplicateKeys,
+    /*
+    Overrides `duplicate_keys` for the objects matching a path pattern, first match wins
+    eg. ("settings", DuplicateKeys::MergeObjects) or ("users[*]", DuplicateKeys::KeepLast)
+     */
+    pub duplicate_keys_by_path: Vec<(String, DuplicateKeys)>,
+    pub homogenize_arrays: HomogenizeArrays,
+    pub max_line_length: Option<usize>, // Maximum length of an output line, in characters
+    pub line_length_policy: LineLengthPolicy,
+    pub max_string_display: Option<usize>, // Truncates long strings, only used by the preview rendering
+    /*
+    Characters shown on each side of the column of an error when its line is rendered, the
+    rest of longer lines being elided with '…'. None renders the whole line
+    eg. Some(40) renders at most 81 characters of a minified megabyte-long line
+     */
+    pub error_window: Option<usize>,
+    /*
+    Characters allowed in unquoted keys besides letters, digits and '_', none by default
+    eg. with "-.@", {content-type: 1, user.name: 2, @id: 3} is fixed
+    Characters starting a number ('-', '.', '+', digits) are only allowed after the first character
+     */
+    pub identifier_chars: String,
+    /*
+    Characters read as whitespaces between tokens, the Unicode ones copied from word
+    processors or web pages being accepted by default
+    eg. with Whitespaces::Json, "[1,\u00a0 2]" fails at the non-breaking space
+     */
+    pub whitespaces: Whitespaces,
+    pub quote_numeric_keys: bool, // Quotes numbers found in key position eg. {1: "a"} to {"1": "a"}
+    /*
+    Quotes the whole input as a string when it is bare text starting with a word that cannot
+    be fixed otherwise eg. hello world to "hello world", for the plain text answers where a
+    JSON string is expected
+    The other scalar roots eg. 'a', 1.5 or true are fixed like nested scalars in every mode
+     */
+    pub quote_bare_root: bool,
+    pub repeated_colons: RepeatedColons, // What to do with a colon repeated after a value eg. {"a": "b": 1}
+    pub bare_words: BareWords, // What to do with a bare word in value position eg. {"a": oops 1}
+    pub non_finite_numbers: NonFiniteNumbers, // What to do with NaN, Infinity and -Infinity
+    pub undefined: Undefined,  // What to do with the undefined of JavaScript eg. {"a": undefined}
+    /*
+    Accepts the hexadecimal numbers of JSON5 and JavaScript, converted to decimal
+    eg. {"color": 0xFF} to {"color": 255}
+     */
+    pub hex_numbers: bool,
+    /*
+    Removes the leading zeros of the numbers, kept as written otherwise
+    eg. {"id": 007} to {"id": 7}
+     */
+    pub strip_leading_zeros: bool,
+    /*
+    Accepts Python literals found in pastes of Python values
+    eg. sets {'a', 'b'} and tuples ('a', 'b') are converted to arrays ["a", "b"]
+    and the prefixes of strings b'bytes', r"raw\d" or f'{x}' are stripped,
+    the constants True, False and None are converted to true, false and null
+     */
+    pub python_compat: bool,
+    /*
+    Strips the JavaScript comments found between tokens, often left in config files and LLM output
+    eg. {"a": 1, // count\n"b": /* unused */ 2} to {"a": 1, "b": 2}
+     */
+    pub strip_comments: bool,
+    /*
+    Keeps the comments in the JSON5 and JSONC output instead of stripping them, a comment on the
+    line where a value ends and the comments before an entry following it through sorting and
+    reformatting, the comments within a member are only kept by the preserved layout and none
+    is written in the compact layout
+    eg. {"b": 2, // count\n"a": 1} sorted to {"a": 1, "b": 2 // count}
+     */
+    pub keep_comments: bool,
+    pub repairs: Repairs, // The repairs allowed while fixing
+    pub strict: bool, // Rejects any input that is not valid RFC 8259 JSON, see `JsonFixerConfig::strict`
+    pub compliance: Compliance, // What the strict validator accepts
+    /*
+    Syntax of the output, the output is only re-validated by `verify_output` when it is
+    written as strict JSON
+     */
+    pub output_style: OutputStyle,
+    /*
+    Ends each element of multi-line arrays and objects with a comma, only with OutputStyle::Json5
+    eg. [
+        1,
+        2,
+    ]
+     */
+    pub emit_trailing_commas: bool,
+    /*
+    How the keys are quoted with OutputStyle::Json5, the keys that are not identifiers are
+    always quoted
+    eg. with QuoteKeys::AsInput, {name: 'a', "id": 1, first-name: 'b'} to
+    {name: "a", "id": 1, "first-name": "b"}
+     */
+    pub quote_keys: QuoteKeys,
+    /*
+    Strings are never re-parsed: a string holding JSON is kept as a string and escaped once
+    When set, the strings holding a valid JSON array or object are replaced by their value
+    eg. {"payload": "{\"a\":1}"} to {"payload": {"a":1}}
+     */
+    pub unwrap_stringified_json: bool,
+    pub max_string_length: Option<usize>, // Maximum length of a string value, in characters
+    pub max_key_length: Option<usize>,    // Maximum length of a key, in characters
+    pub length_policy: LengthPolicy,      // What to do with longer strings and keys
+    pub max_array_elements: Option<usize>, // Maximum number of elements of an array
+    pub max_object_members: Option<usize>, // Maximum number of members of an object, once duplicates are handled
+    pub size_policy: SizePolicy,           // What to do with larger arrays and objects
+    /*
+    Rejects the documents needing more repairs than this with `LimitError::TooManyRepairs`,
+    as a quality gate against garbage that merely happens to be fixable. Parsing is aborted
+    as soon as the limit is exceeded
+    eg. Some(0) only accepts valid documents, yet formats them
+     */
+    pub max_repairs: Option<usize>,
+    /*
+    Least confident repairs applied, the input failing where a less confident one is needed
+    eg. Confidence::Likely to fail rather than guess where mismatched brackets are closed, or
+    Confidence::Certain to also fail on missing commas and unquoted values
+     */
+    pub min_confidence: Confidence,
+    /*
+    Codes of the repairs applied silently: they are neither reported nor counted against
+    `max_repairs`, and are applied whatever `min_confidence`. Disabled repairs stay disabled
+    eg. vec![RepairKind::RemovedComma.code()] for a project where trailing commas are fine
+     */
+    pub suppress: Vec<Code>,
+    /*
+    Writes the numbers with a fraction or an exponent in their shortest form parsing back to
+    the same value, integers are kept as is
+    eg. 0.10000000000000001 to 0.1, 1.50 to 1.5 and 1e2 to 100
+     */
+    pub normalize_numbers: bool,
+    /*
+    Rounds the numbers normalized by `normalize_numbers` to this number of decimals before
+    writing them in their shortest form, integers are kept as is
+    Only applied with `normalize_numbers`, and never when the original formatting is preserved
+    eg. Some(2) for 21.456789012345678 to 21.46 and 1.5e-7 to 0
+     */
+    pub float_precision: Option<u8>,
+    /*
+    Writes the numbers normalized by `normalize_numbers` without exponent
+    when their exponent is at most 21, for the parsers that do not read exponents
+    eg. 1e-7 to 0.0000001 and 1.5e21 to 1500000000000000000000
+     */
+    pub expand_exponents: bool,
+    /*
+    Decodes the HTML entities found in strings and keys, often left by scrapers
+    eg. "Tom &amp; Jerry" to "Tom & Jerry" and "it&#39;s" to "it's"
+     */
+    pub decode_html_entities: bool,
+    /*
+    Collapses the runs of whitespaces between the words of string values into single spaces,
+    the formatting whitespaces of scraped text being noise, keys and the leading and trailing
+    whitespaces are kept
+    eg. {"text": "Hello,\n\t  world"} to {"text": "Hello, world"}
+     */
+    pub compact_strings_whitespace: bool,
+    /*
+    Converts the truthy and falsey strings of the fields hinted as booleans to booleans, the
+    strings being compared ignoring case and surrounding whitespaces
+    "yes", "y", "true", "on" and "1" to true, "no", "n", "false", "off" and "0" to false
+    Fields are hinted by a key starting with is, has, can or should, eg. is_active, has-items
+    or canEdit, or by a path of `boolean_paths`
+    eg. {"is_admin": "Y", "answer": "no"} to {"is_admin": true, "answer": "no"}
+     */
+    pub normalize_booleans: bool,
+    /*
+    Path patterns of the fields hinted as booleans by `normalize_booleans` besides their key,
+    eg. "users[*].admin", or the paths of a JSON Schema from `JsonFixer::paths_of_type`
+     */
+    pub boolean_paths: Vec<String>,
+    /*
+    Converts the strings standing for a missing value to null, the strings being compared
+    ignoring case and surrounding whitespaces: "null", "nil", "none" and blank strings
+    Either in every field, or only in the fields hinted by `null_paths`
+    eg. {"name": "None", "city": ""} to {"name": null, "city": null}
+     */
+    pub normalize_nulls: NormalizeNulls,
+    /*
+    Path patterns of the fields hinted as nullable by `NormalizeNulls::Hinted`, eg.
+    "users[*].email", or the paths of a JSON Schema from `JsonFixer::paths_of_type`
+     */
+    pub null_paths: Vec<String>,
+    /*
+    Removes the leading and trailing whitespaces of keys, non-breaking spaces included
+    eg. {" name\u00a0": 1} to {"name": 1}
+     */
+    pub trim_keys: bool,
+    /*
+    Path patterns of the members kept, the other members being dropped, see `jsonpath` for
+    the syntax. The members leading to an allowed path are kept, and so is everything within
+    an allowed member. Empty keeps every member
+    eg. ["id", "user.name"] to keep {"id": 1, "user": {"name": "a"}} out of
+    {"id": 1, "user": {"name": "a", "token": "x"}, "debug": true}
+     */
+    pub allowed_keys: Vec<String>,
+    /*
+    Path patterns of the members dropped along with their content, even when allowed
+    eg. ["*.password", "users[*].password"]
+     */
+    pub denied_keys: Vec<String>,
+    /*
+    Default values of members, by path pattern, inserted at the end of the objects they are
+    missing from, see `jsonpath` for the syntax. The pattern must end with a key, and only
+    the objects found in the input, or inserted by another default, are completed
+    eg. ("server.port", JsonValue::Number(JsonNumber::parse("8080"))) to fill in
+    {"server": {"host": "a"}} as {"server": {"host": "a", "port": 8080}}
+     */
+    pub defaults: Vec<(String, JsonValue)>,
+    /*
+    Removes the invisible characters of keys, eg. zero width spaces or byte order marks, and
+    collapses their runs of whitespaces into single spaces
+    eg. {"first\u200b\u00a0 name": 1} to {"first name": 1}
+    The keys made equal are then handled by `duplicate_keys`
+     */
+    pub normalize_key_whitespace: bool,
+    /*
+    Removes one level of escapes from inputs whose first quote is escaped, as found in JSON
+    extracted from HTML attributes or JavaScript strings, before tokenizing them
+    eg. {\"a\": \x22b\x22} to {"a": "b"}
+    Only quotes, backslashes and slashes are unescaped, eg. \" \x22 \u0022 \' \\ or \/
+     */
+    pub unescape_quotes: bool,
+    /*
+    Labels the output, eg. with the tool that generated it
+    Written as a `//` comment banner before the value in JSON5 and JSONC output, and as a
+    "_generated_by" key first in the root object in JSON output
+    Fixing JSON output fails when the root is not an object or already has the key, rather
+    than drop the header or the user's member
+     */
+    pub header: Option<String>,
+    /*
+    Comments written as `//` lines before the members and elements matching a path pattern,
+    first match wins, in pretty printed JSON5 and JSONC output only
+    eg. ("server.port", "Port the server listens on")
+     */
+    pub comments: Vec<(String, String)>,
+    /*
+    Path of an object inserted in the output to track the documents that were altered, with
+    the number of repairs applied and the version of the fixer, see `jsonpath` for the syntax
+    Fixing fails rather than replace a member of the document having the same path
+    eg. with Some("__jsonfixer"), {"a": 1} to
+    {"a": 1, "__jsonfixer": {"repairs": 0, "version": "0.1.0"}}
+     */
+    pub provenance_path: Option<String>,
+    /*
+    Skips the sections that cannot be repaired instead of failing, up to the end of the next
+    line ending with a ',' or to the closing bracket of the enclosing array or object, so that
+    one corrupted record does not lose a whole file
+    The section is replaced by null, or dropped from its object when its key is unknown, and
+    reported as a salvaged section
+    eg. [{"id": 1},\n{"id": 2, "name": ~~},\n{"id": 3}] to [{"id":1},{"id":2,"name":null},{"id":3}]
+     */
+    pub salvage: bool,
 }
 
 impl Default for JsonFixerConfig {
     fn default() -> Self {
         Self {
             preserve: false,
+            preserve_normalize: false,
             space_between: false,
             beautify: false,
             indent_style: IndentStyle::Spaces,
             indent_size: 0,
             sort_keys: false,
+            key_priority: Vec::new(),
+            key_order: Vec::new(),
+            verify_output: false,
+            duplicate_keys: DuplicateKeys::KeepAll,
+            duplicate_keys_by_path: Vec::new(),
+            homogenize_arrays: HomogenizeArrays::Leave,
+            max_line_length: None,
+            line_length_policy: LineLengthPolicy::Error,
+            max_string_display: None,
+            error_window: Some(40),
+            identifier_chars: String::new(),
+            whitespaces: Whitespaces::Unicode,
+            quote_numeric_keys: false,
+            quote_bare_root: false,
+            repeated_colons: RepeatedColons::Error,
+            bare_words: BareWords::Quote,
+            non_finite_numbers: NonFiniteNumbers::BareWord,
+            undefined: Undefined::BareWord,
+            hex_numbers: false,
+            strip_leading_zeros: false,
+            python_compat: false,
+            strip_comments: false,
+            keep_comments: false,
+            repairs: Repairs::default(),
+            strict: false,
+            compliance: Compliance::default(),
+            output_style: OutputStyle::Json,
+            emit_trailing_commas: false,
+            quote_keys: QuoteKeys::Always,
+            unwrap_stringified_json: false,
+            max_string_length: None,
+            max_key_length: None,
+            length_policy: LengthPolicy::Error,
+            max_array_elements: None,
+            max_object_members: None,
+            size_policy: SizePolicy::Error,
+            max_repairs: None,
+            min_confidence: Confidence::Speculative,
+            suppress: Vec::new(),
+            normalize_numbers: false,
+            float_precision: None,
+            expand_exponents: false,
+            decode_html_entities: false,
+            compact_strings_whitespace: false,
+            normalize_booleans: false,
+            boolean_paths: Vec::new(),
+            normalize_nulls: NormalizeNulls::Never,
+            null_paths: Vec::new(),
+            trim_keys: false,
+            allowed_keys: Vec::new(),
+            denied_keys: Vec::new(),
+            defaults: Vec::new(),
+            normalize_key_whitespace: false,
+            unescape_quotes: false,
+            header: None,
+            comments: Vec::new(),
+            provenance_path: None,
+            salvage: false,
         }
     }
 }
 
+/// Lets the fixing methods take the configuration owned, borrowed or shared behind an `Arc`.
+impl AsRef<JsonFixerConfig> for JsonFixerConfig {
+    fn as_ref(&self) -> &JsonFixerConfig {
+        self
+    }
+}
+
 impl JsonFixerConfig {
+    /// Configuration of a strict RFC 8259 validator and formatter.
+    ///
+    /// Every repair is disabled and the input is checked by the strict validator before it is
+    /// parsed, so anything that is not valid JSON is reported as a syntax error. Valid inputs
+    /// are formatted like with the default configuration.
+    pub fn strict() -> Self {
+        Self {
+            strict: true,
+            repairs: Repairs::none(),
+            identifier_chars: String::new(),
+            whitespaces: Whitespaces::Json,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the configuration accepting the whole JSON5 grammar, normalized to strict JSON:
+    /// unquoted keys, single quoted strings, trailing commas, hexadecimal numbers, leading and
+    /// trailing decimal points, leading `+` signs, comments and the escapes and line
+    /// continuations of JavaScript strings. `NaN` and `Infinity` are written `null`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = "{hex: 0x1F, half: .5, ratio: Infinity, // note\n name: 'json5',}";
+    /// let output = JsonFixer::fix_with_config(input, JsonFixerConfig::json5()).unwrap();
+    /// assert_eq!(output, r#"{"hex":31,"half":0.5,"ratio":null,"name":"json5"}"#);
+    /// ```
+    pub fn json5() -> Self {
+        Self {
+            strip_comments: true,
+            hex_numbers: true,
+            non_finite_numbers: NonFiniteNumbers::Null,
+            identifier_chars: "-.@$".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Returns `true` if the repairs of the given confidence are applied, see
+    /// `min_confidence`.
+    pub fn accepts(&self, confidence: Confidence) -> bool {
+        confidence >= self.min_confidence
+    }
+
+    /// Returns `true` if the repairs of the given kind are suppressed, see `suppress`.
+    pub fn suppresses(&self, kind: RepairKind) -> bool {
+        self.suppress.contains(&kind.code())
+    }
+
+    /// Returns `true` if a repair of the given kind and confidence is applied, which it is
+    /// whatever its confidence when its kind is suppressed.
+    pub fn accepts_repair(&self, kind: RepairKind, confidence: Confidence) -> bool {
+        self.suppresses(kind) || self.accepts(confidence)
+    }
+
     pub fn preserve(&self) -> bool {
-        self.preserve
+        self.preserve || self.preserve_normalize
+    }
+
+    /// Decimals the numbers are rounded to, only set when they are normalized and the
+    /// formatting is not preserved.
+    pub fn float_precision(&self) -> Option<u8> {
+        self.float_precision
+            .filter(|_| self.normalize_numbers && !self.preserve())
     }
 
     pub fn space_between(&self) -> bool {
-        self.space_between && self.preserve == false
+        self.space_between && !self.preserve()
     }
 
     pub fn beautify(&self) -> bool {
-        self.beautify && self.preserve == false
+        self.beautify && !self.preserve()
+    }
+
+    /// Fails with `JsonFixerError::Incompatible` if an option cannot apply along with another
+    /// one, as the non-finite numbers kept in output other than JSON5, which would be
+    /// invalid.
+    pub fn check(&self) -> Result<(), JsonFixerError> {
+        if self.non_finite_numbers == NonFiniteNumbers::Keep
+            && self.output_style != OutputStyle::Json5
+        {
+            return Err(JsonFixerError::Incompatible {
+                option: "non_finite_numbers",
+                other: "output_style",
+            });
+        }
+        Ok(())
+    }
+
+    /// Trailing commas are only written in JSON5 output.
+    pub fn trailing_commas(&self) -> bool {
+        self.emit_trailing_commas && self.output_style == OutputStyle::Json5
+    }
+
+    /// Returns `true` if `key` is written without quotes, `unquoted` telling whether it was
+    /// unquoted in the input.
+    pub fn writes_unquoted_key(&self, key: &str, unquoted: bool) -> bool {
+        if self.output_style != OutputStyle::Json5 || !is_identifier(key) {
+            return false;
+        }
+        match self.quote_keys {
+            QuoteKeys::Always => false,
+            QuoteKeys::AsInput => unquoted,
+            QuoteKeys::OnlyWhenNeeded => true,
+        }
+    }
+
+    /// Returns `true` if the comments of the input are kept in the output, see
+    /// `keep_comments`.
+    pub fn keeps_comments(&self) -> bool {
+        self.keep_comments && self.output_style != OutputStyle::Json
+    }
+
+    /// The header is written as a comment banner in the output styles allowing comments.
+    pub fn header_comment(&self) -> Option<&str> {
+        match self.output_style {
+            OutputStyle::Json => None,
+            OutputStyle::Json5 | OutputStyle::Jsonc => self.header.as_deref(),
+        }
+    }
+
+    /// Returns the comment written before the member or element at `path`, see `comments`.
+    pub fn comment_at(&self, path: &str) -> Option<&str> {
+        if self.output_style == OutputStyle::Json {
+            return None;
+        }
+        self.comments
+            .iter()
+            .find(|(pattern, _)| jsonpath::matches(pattern, path))
+            .map(|(_, comment)| comment.as_str())
+    }
+
+    /// The header is written as the `_generated_by` key of the root object in JSON output.
+    pub fn header_key(&self) -> Option<&str> {
+        match self.output_style {
+            OutputStyle::Json => self.header.as_deref(),
+            OutputStyle::Json5 | OutputStyle::Jsonc => None,
+        }
+    }
+
+    /// Order of two keys when `sort_keys` is set: the keys of `key_priority` first, in the
+    /// order of the list, then the other keys in lexicographic order.
+    pub fn compare_keys(&self, a: &str, b: &str) -> Ordering {
+        let rank = |key: &str| {
+            self.key_priority
+                .iter()
+                .position(|priority| priority == key)
+                .unwrap_or(usize::MAX)
+        };
+        rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+    }
+
+    /// Keys of the first `key_order` pattern matching the path of an object.
+    pub fn key_order_at(&self, path: &str) -> Option<&[String]> {
+        self.key_order
+            .iter()
+            .find(|(pattern, _)| jsonpath::matches(pattern, path))
+            .map(|(_, keys)| keys.as_slice())
+    }
+
+    /// Long lines are wrapped only when the output is not already pretty printed or preserved.
+    pub fn wraps_lines(&self) -> bool {
+        self.max_line_length.is_some()
+            && self.line_length_policy == LineLengthPolicy::Wrap
+            && !self.beautify
+            && !self.preserve()
+    }
+}
+
+/// Returns `true` if `key` is an ECMAScript identifier name, which JSON5 allows unquoted.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(first) if first.is_alphabetic() || first == '$' || first == '_' => {
+            chars.all(|ch| ch.is_alphanumeric() || ch == '$' || ch == '_')
+        }
+        _ => false,
     }
 }
@@ -7,12 +7,42 @@ pub enum JsonFixerError {
     Syntax(SyntaxError),
     Format(JsonFormatError),
     IO(std::fmt::Error),
+    /// Error reading the input or writing the output.
+    IoError(std::io::Error),
+    /// The fixer produced an output that is not valid JSON (see `JsonFixerConfig::verify_output`).
+    Internal {
+        output: String,
+        diagnostic: SyntaxError,
+    },
+    /// A limit of the configuration was exceeded and its policy is to error.
+    Limit(LimitError),
+    /// The fixed value does not have the expected shape, see `JsonFixer::fix_expecting`.
+    Shape(ShapeError),
+    /// The option of the configuration needs the whole root container, which is not held when
+    /// it is written as it is parsed, see `JsonFixer::fix_to_writer`.
+    Unstreamable(&'static str),
+    /// The option of the configuration needs the input text, or differs from the lexing
+    /// option the tokens were captured with, see `TokenBuffer::fix_with_config`.
+    Unreplayable(&'static str),
+    /// The value inserted by the option of the configuration cannot be written at `path`
+    /// without replacing a value of the document, eg. an existing member of the same key or
+    /// an array root, see `JsonFixerConfig::provenance_path` and `JsonFixerConfig::header`.
+    Uninsertable {
+        option: &'static str,
+        path: String,
+    },
+    /// The option of the configuration cannot apply along with the other one, eg.
+    /// `non_finite_numbers` kept in JSON output, see `JsonFixerConfig::check`.
+    Incompatible {
+        option: &'static str,
+        other: &'static str,
+    },
     /// Serde error
     #[cfg( feature = "serde")]
     SerdeError(String),
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum JsonFormatError {
     LineTooLong {
         line: usize,
@@ -36,6 +66,99 @@ impl fmt::Display for JsonFormatError {
     }
 }
 
+/// Limits set by the configuration, the paths locate the value or the object of the key.
+#[derive(Debug, PartialEq)]
+pub enum LimitError {
+    StringTooLong {
+        path: String,
+        length: usize,
+        max: usize,
+    },
+    KeyTooLong {
+        path: String,
+        length: usize,
+        max: usize,
+    },
+    TooManyElements {
+        path: String,
+        count: usize,
+        max: usize,
+    },
+    TooManyMembers {
+        path: String,
+        count: usize,
+        max: usize,
+    },
+    /// The document needs more repairs than `max_repairs`, `count` being the repairs found
+    /// when fixing was aborted.
+    TooManyRepairs { count: usize, max: usize },
+    /// The arrays and objects are nested deeper than `max`, see `jsonparser::MAX_DEPTH`.
+    TooDeep { path: String, max: usize },
+}
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StringTooLong { path, length, max } => write!(
+                f,
+                "String at path '{}' is too long of length: {}, expected max length: {}",
+                path, length, max
+            ),
+            Self::KeyTooLong { path, length, max } => write!(
+                f,
+                "Key of the object at path '{}' is too long of length: {}, expected max length: {}",
+                path, length, max
+            ),
+            Self::TooManyElements { path, count, max } => write!(
+                f,
+                "Array at path '{}' has too many elements: {}, expected max elements: {}",
+                path, count, max
+            ),
+            Self::TooManyMembers { path, count, max } => write!(
+                f,
+                "Object at path '{}' has too many members: {}, expected max members: {}",
+                path, count, max
+            ),
+            Self::TooManyRepairs { count, max } => write!(
+                f,
+                "Document needs too many repairs: {}, expected max repairs: {}",
+                count, max
+            ),
+            Self::TooDeep { path, max } => write!(
+                f,
+                "Value at path '{}' is nested too deep, expected max depth: {}",
+                path, max
+            ),
+        }
+    }
+}
+
+/// A mismatch between a fixed value and its expected shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeViolation {
+    /// Path of the value, see `jsonpath`, empty for the root value
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ShapeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at path '{}'", self.message, self.path)
+    }
+}
+
+/// The violations of the expected shape of a fixed value, in document order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeError {
+    pub violations: Vec<ShapeViolation>,
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "{}", violations.join(", "))
+    }
+}
+
 impl std::error::Error for JsonFixerError {}
 
 impl fmt::Display for JsonFixerError {
@@ -44,6 +167,40 @@ impl fmt::Display for JsonFixerError {
             Self::Syntax(err) => write!(f, "Syntax error: {}", err),
             Self::Format(err) => write!(f, "Format error: {}", err),
             Self::IO(err) => write!(f, "IO error: {}", err),
+            Self::IoError(err) => write!(f, "IO error: {}", err),
+            Self::Limit(err) => write!(f, "Limit error: {}", err),
+            Self::Shape(err) => write!(f, "Shape error: {}", err),
+            Self::Unstreamable(option) => {
+                write!(
+                    f,
+                    "Unstreamable configuration: '{}' needs the whole document",
+                    option
+                )
+            }
+            Self::Unreplayable(option) => {
+                write!(
+                    f,
+                    "Unreplayable configuration: '{}' cannot apply to the captured tokens",
+                    option
+                )
+            }
+            Self::Uninsertable { option, path } => {
+                write!(
+                    f,
+                    "Uninsertable value: '{}' cannot be written at path '{}'",
+                    option, path
+                )
+            }
+            Self::Incompatible { option, other } => {
+                write!(
+                    f,
+                    "Incompatible configuration: '{}' cannot apply with '{}'",
+                    option, other
+                )
+            }
+            Self::Internal { diagnostic, .. } => {
+                write!(f, "Internal error: produced invalid JSON: {}", diagnostic)
+            }
             #[cfg(feature = "serde")]
             Self::SerdeError(err) => write!(f, "Serde error: {}", err),
         }
@@ -64,6 +221,36 @@ pub enum SyntaxError {
     InvalidNumber(String, Position),
     /// Unexpected token in the input.
     UnexpectedToken(String, Position),
+    /// Key found more than once in the same object.
+    DuplicateKey(String, Position),
+}
+
+impl SyntaxError {
+    /// Returns the position in the input where the error was found.
+    pub fn position(&self) -> &Position {
+        match self {
+            Self::UnexpectedCharacter(_, pos)
+            | Self::UnmatchedQuotes(pos)
+            | Self::UnexpectedEndOfInput(pos)
+            | Self::MissingComma(pos)
+            | Self::InvalidNumber(_, pos)
+            | Self::UnexpectedToken(_, pos)
+            | Self::DuplicateKey(_, pos) => pos,
+        }
+    }
+
+    /// Returns the position where the error was found, to move it to another input.
+    pub(crate) fn position_mut(&mut self) -> &mut Position {
+        match self {
+            Self::UnexpectedCharacter(_, pos)
+            | Self::UnmatchedQuotes(pos)
+            | Self::UnexpectedEndOfInput(pos)
+            | Self::MissingComma(pos)
+            | Self::InvalidNumber(_, pos)
+            | Self::UnexpectedToken(_, pos)
+            | Self::DuplicateKey(_, pos) => pos,
+        }
+    }
 }
 
 impl fmt::Display for SyntaxError {
@@ -99,6 +286,11 @@ impl fmt::Display for SyntaxError {
                 "Invalid number '{}' at line {}, column {}",
                 ch, pos.line, pos.column
             ),
+            Self::DuplicateKey(key, pos) => write!(
+                f,
+                "Duplicate key '{}' at line {}, column {}",
+                key, pos.line, pos.column
+            ),
         }
     }
 }
@@ -0,0 +1,300 @@
+//! Report of the repairs applied while fixing an input.
+
+use std::fmt;
+
+use super::{json_tokenizer::Position, jsonindex::Span};
+
+/// The different kinds of repairs the fixer can apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepairKind {
+    /// An unquoted key was quoted.
+    QuotedKey,
+    /// A single quoted string was converted to a double quoted one.
+    NormalizedQuotes,
+    /// An unquoted single word value was quoted.
+    QuotedValue,
+    /// A missing comma was inserted between two elements.
+    InsertedComma,
+    /// A trailing, leading or repeated comma was removed.
+    RemovedComma,
+    /// An unclosed object or array was closed at the end of the input.
+    ClosedBracket,
+    /// A number was rewritten into a valid JSON number, eg. `.5` to `0.5` or `007` to `7`.
+    FixedNumber,
+    /// An array mixing scalars and single key objects was normalized.
+    HomogenizedArray,
+    /// A Python set or tuple was converted to an array.
+    ConvertedToArray,
+    /// The prefix of a Python string literal was removed, eg. `b'data'` to `"data"`.
+    StrippedStringPrefix,
+    /// A string holding JSON was replaced by its value, eg. `"{\"a\":1}"` to `{"a":1}`.
+    UnwrappedJsonString,
+    /// A string or key longer than the configured maximum length was truncated.
+    TruncatedString,
+    /// The elements or members of an array or object over the configured maximum were dropped.
+    TruncatedContainer,
+    /// A colon repeated after a value was repaired, eg. `{"a": "b": 1}` to `{"a": {"b": 1}}`.
+    RepeatedColon,
+    /// The HTML entities of a string were decoded, eg. `&quot;` to `"`.
+    DecodedEntities,
+    /// The escaped quotes of the input were unescaped, eg. `{\"a\": 1}` to `{"a": 1}`.
+    UnescapedQuotes,
+    /// A section that could not be repaired was skipped, see `JsonFixerConfig::salvage`.
+    Salvaged,
+    /// The runs of whitespaces of a string were collapsed, see
+    /// `JsonFixerConfig::compact_strings_whitespace`.
+    CompactedWhitespace,
+    /// A string of a field hinted as boolean was converted to a boolean, eg. `"yes"` to
+    /// `true`, see `JsonFixerConfig::normalize_booleans`.
+    CoercedBoolean,
+    /// A string standing for a missing value was converted to `null`, eg. `"NULL"`, see
+    /// `JsonFixerConfig::normalize_nulls`, or the `undefined` of JavaScript, see
+    /// `JsonFixerConfig::undefined`.
+    CoercedNull,
+    /// The whitespaces or invisible characters of a key were cleaned up, see
+    /// `JsonFixerConfig::trim_keys` and `JsonFixerConfig::normalize_key_whitespace`.
+    CleanedKey,
+    /// A member whose key is not allowed was dropped, see `JsonFixerConfig::allowed_keys`
+    /// and `JsonFixerConfig::denied_keys`.
+    PrunedKey,
+    /// A missing member was inserted with its default value, see
+    /// `JsonFixerConfig::defaults`.
+    InsertedDefault,
+    /// A member whose key was already found was dropped, see
+    /// `JsonFixerConfig::duplicate_keys`.
+    RemovedDuplicate,
+    /// A bare word in value position was dropped or replaced by `null`, see
+    /// `JsonFixerConfig::bare_words`.
+    StrayWord,
+    /// A `//` line comment or `/* */` block comment was removed, see
+    /// `JsonFixerConfig::strip_comments`.
+    StrippedComment,
+    /// An invalid escape of a string was replaced by the character it escapes, eg. `\q` to
+    /// `q`, or a JavaScript escape by the character it stands for, eg. `\x41` to `A`.
+    FixedEscape,
+    /// A lone surrogate escape, eg. `\ud800`, rejected by `Compliance::lone_surrogates` was
+    /// replaced by U+FFFD.
+    ReplacedSurrogate,
+}
+
+impl RepairKind {
+    /// Every kind of repair, in declaration order.
+    pub const ALL: [RepairKind; 28] = [
+        Self::QuotedKey,
+        Self::NormalizedQuotes,
+        Self::QuotedValue,
+        Self::InsertedComma,
+        Self::RemovedComma,
+        Self::ClosedBracket,
+        Self::FixedNumber,
+        Self::HomogenizedArray,
+        Self::ConvertedToArray,
+        Self::StrippedStringPrefix,
+        Self::UnwrappedJsonString,
+        Self::TruncatedString,
+        Self::TruncatedContainer,
+        Self::RepeatedColon,
+        Self::DecodedEntities,
+        Self::UnescapedQuotes,
+        Self::Salvaged,
+        Self::CompactedWhitespace,
+        Self::CoercedBoolean,
+        Self::CoercedNull,
+        Self::CleanedKey,
+        Self::PrunedKey,
+        Self::InsertedDefault,
+        Self::RemovedDuplicate,
+        Self::StrayWord,
+        Self::StrippedComment,
+        Self::FixedEscape,
+        Self::ReplacedSurrogate,
+    ];
+}
+
+impl RepairKind {
+    /// Returns the confidence of the repairs of this kind, some repairs being less certain
+    /// than their kind, eg. the brackets closed before a mismatched bracket.
+    pub fn confidence(self) -> Confidence {
+        match self {
+            Self::QuotedValue | Self::InsertedComma | Self::StrayWord => Confidence::Likely,
+            Self::RepeatedColon | Self::Salvaged => Confidence::Speculative,
+            _ => Confidence::Certain,
+        }
+    }
+}
+
+impl fmt::Display for RepairKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::QuotedKey => "quoted key",
+            Self::NormalizedQuotes => "normalized quotes",
+            Self::QuotedValue => "quoted value",
+            Self::InsertedComma => "inserted comma",
+            Self::RemovedComma => "removed comma",
+            Self::ClosedBracket => "closed bracket",
+            Self::FixedNumber => "fixed number",
+            Self::HomogenizedArray => "homogenized array",
+            Self::ConvertedToArray => "converted to array",
+            Self::StrippedStringPrefix => "stripped string prefix",
+            Self::UnwrappedJsonString => "unwrapped JSON string",
+            Self::TruncatedString => "truncated string",
+            Self::TruncatedContainer => "truncated container",
+            Self::RepeatedColon => "repeated colon",
+            Self::DecodedEntities => "decoded entities",
+            Self::UnescapedQuotes => "unescaped quotes",
+            Self::Salvaged => "salvaged section",
+            Self::CompactedWhitespace => "compacted whitespace",
+            Self::CoercedBoolean => "coerced boolean",
+            Self::CoercedNull => "coerced null",
+            Self::CleanedKey => "cleaned key",
+            Self::PrunedKey => "pruned key",
+            Self::InsertedDefault => "inserted default",
+            Self::RemovedDuplicate => "removed duplicate",
+            Self::StrayWord => "stray word",
+            Self::StrippedComment => "stripped comment",
+            Self::FixedEscape => "fixed escape",
+            Self::ReplacedSurrogate => "replaced surrogate",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How sure the fixer is that a repair restores what the input meant, from the least to the
+/// most confident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    /// One guess among other plausible ones, eg. where a mismatched bracket is closed
+    Speculative,
+    /// The usual meaning of the input, eg. a comma missing between two values
+    Likely,
+    /// The only sensible meaning of the input, eg. a trailing comma
+    Certain,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Speculative => "speculative",
+            Self::Likely => "likely",
+            Self::Certain => "certain",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single repair applied to the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub kind: RepairKind,
+    /// Confidence of the repair, the one of its kind unless the repair was a guess.
+    pub confidence: Confidence,
+    /// Position in the input where the repair was applied, if it relates to a token.
+    pub position: Option<Position>,
+    /// Path of the repaired value, for repairs applied on the parsed document.
+    pub path: Option<String>,
+    pub message: String,
+    /// Content of the input discarded by the repair, eg. the extra comma of `[1,,2]`. Set
+    /// for the removed commas, the stripped comments, the salvaged sections and the members,
+    /// elements or characters dropped from the parsed document.
+    pub dropped: Option<Dropped>,
+}
+
+/// Content of the input discarded by a repair, so that it can be checked that nothing
+/// meaningful was deleted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dropped {
+    /// The discarded text, as written in the input when the span is known. Otherwise the
+    /// fixed JSON of the discarded members or elements, eg. `"id":2`, or the characters cut
+    /// from a truncated string.
+    pub text: String,
+    /// Location of the text in the input, `None` for the content discarded once parsed.
+    pub span: Option<Span>,
+}
+
+impl Repair {
+    /// Creates a repair located at a position of the input.
+    pub fn at(kind: RepairKind, position: Position, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            confidence: kind.confidence(),
+            position: Some(position),
+            path: None,
+            message: message.into(),
+            dropped: None,
+        }
+    }
+
+    /// Creates a repair located at a path of the parsed document.
+    pub fn at_path(kind: RepairKind, path: &str, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            confidence: kind.confidence(),
+            position: None,
+            path: Some(path.to_string()),
+            message: message.into(),
+            dropped: None,
+        }
+    }
+
+    /// Sets the confidence of the repair.
+    pub fn with_confidence(mut self, confidence: Confidence) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Sets the path of the value the repair relates to.
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    /// Sets the content discarded by the repair.
+    pub fn with_dropped(mut self, dropped: Dropped) -> Self {
+        self.dropped = Some(dropped);
+        self
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)?;
+        if let Some(pos) = &self.position {
+            write!(f, " at line {}, column {}", pos.line, pos.column)?;
+        }
+        match (&self.position, &self.path) {
+            (None, Some(path)) => write!(f, " at path '{}'", path)?,
+            (Some(_), Some(path)) if !path.is_empty() => write!(f, " (path '{}')", path)?,
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// The fixed output along with the repairs that were needed to produce it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixReport {
+    pub output: String,
+    pub repairs: Vec<Repair>,
+}
+
+impl FixReport {
+    /// Returns `true` if the input was already valid and nothing had to be repaired.
+    pub fn is_clean(&self) -> bool {
+        self.repairs.is_empty()
+    }
+
+    /// Counts the repairs of the given kind.
+    pub fn count(&self, kind: RepairKind) -> usize {
+        self.repairs.iter().filter(|r| r.kind == kind).count()
+    }
+
+    /// Returns the confidence of the least confident repair, `Certain` when nothing was
+    /// repaired.
+    pub fn confidence(&self) -> Confidence {
+        self.repairs
+            .iter()
+            .map(|r| r.confidence)
+            .min()
+            .unwrap_or(Confidence::Certain)
+    }
+}
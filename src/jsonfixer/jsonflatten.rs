@@ -0,0 +1,262 @@
+//! Flattening of nested documents into single level objects, and back.
+//!
+//! The keys of a flattened object are the paths of the values, the members of objects being
+//! joined with a separator and the elements of arrays using brackets, eg. `users[0].name`
+//! with the `.` separator. The keys holding the separator or ending with brackets are
+//! ambiguous and do not unflatten to the original document.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+    jsontransformer::member,
+};
+
+/// Fixes `input` and writes its values as the members of a single level object, using
+/// `config` for both.
+pub fn flatten(
+    input: &str,
+    separator: &str,
+    config: &JsonFixerConfig,
+) -> Result<String, JsonFixerError> {
+    let mut parser = JsonParser::with_borrowed_config(input, config);
+    let (value, _) = parser.parse_document()?;
+
+    let mut output = String::new();
+    parser.format_document_into(&flatten_value(value, separator), &mut output)?;
+    Ok(output)
+}
+
+/// Fixes `input` and nests the members of its root object following their keys, using
+/// `config` for both.
+pub fn unflatten(
+    input: &str,
+    separator: &str,
+    config: &JsonFixerConfig,
+) -> Result<String, JsonFixerError> {
+    let mut parser = JsonParser::with_borrowed_config(input, config);
+    let (value, _) = parser.parse_document()?;
+
+    let mut output = String::new();
+    parser.format_document_into(&unflatten_value(value, separator), &mut output)?;
+    Ok(output)
+}
+
+/// Flattens `value` into an object whose members are its scalars, empty objects and empty
+/// arrays, keyed by their paths. Scalars, empty objects and empty arrays are returned as is.
+pub fn flatten_value(value: JsonValue, separator: &str) -> JsonValue {
+    if !has_values(&value) {
+        return value;
+    }
+
+    let mut members = Vec::new();
+    flatten_into(&mut members, String::new(), value, separator);
+    JsonValue::Object(members)
+}
+
+fn flatten_into(members: &mut Vec<JsonEntryValue>, path: String, value: JsonValue, sep: &str) {
+    if !has_values(&value) {
+        members.push(member(&path, value));
+        return;
+    }
+
+    match value {
+        JsonValue::Object(entries) => {
+            for entry in entries {
+                let (Some(key), Some(value)) = (entry.key, entry.value) else {
+                    continue;
+                };
+                let child = match path.is_empty() {
+                    true => key,
+                    false => format!("{}{}{}", path, sep, key),
+                };
+                flatten_into(members, child, value, sep);
+            }
+        }
+        JsonValue::Array(entries) => {
+            let elements = entries.into_iter().filter_map(|entry| entry.value);
+            for (index, element) in elements.enumerate() {
+                flatten_into(members, format!("{}[{}]", path, index), element, sep);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Returns `true` for the objects and arrays that hold at least one value.
+fn has_values(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Object(entries) | JsonValue::Array(entries) => {
+            entries.iter().any(|entry| entry.value.is_some())
+        }
+        _ => false,
+    }
+}
+
+/// Nests the members of the object `value` following the paths of their keys, the inverse of
+/// [`flatten_value`]. Other values are returned as is.
+///
+/// The elements of an array are written in the order of their indices, the missing indices
+/// being skipped rather than filled with nulls. When a key goes through a scalar set by an
+/// earlier key, eg. `a` then `a.b`, the scalar is replaced. Objects and arrays are merged
+/// with the members nested in them.
+pub fn unflatten_value(value: JsonValue, separator: &str) -> JsonValue {
+    let JsonValue::Object(entries) = value else {
+        return value;
+    };
+
+    let mut root = Node::Leaf(JsonValue::Object(Vec::new()));
+    for entry in entries {
+        let (Some(key), Some(value)) = (entry.key, entry.value) else {
+            continue;
+        };
+        root.insert(&segments(&key, separator), value);
+    }
+    root.into_value()
+}
+
+/// A segment of a flattened key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment<'k> {
+    Key(&'k str),
+    Index(usize),
+}
+
+/// Splits a flattened key into its segments, eg. `a.b[0][1]` into `a`, `b`, `0` and `1`.
+fn segments<'k>(key: &'k str, separator: &str) -> Vec<Segment<'k>> {
+    let parts: Vec<&str> = match separator.is_empty() {
+        true => vec![key],
+        false => key.split(separator).collect(),
+    };
+
+    let mut segments = Vec::new();
+    for part in parts {
+        let mut name = part;
+        let mut indices = Vec::new();
+        while let Some((rest, index)) = trailing_index(name) {
+            indices.push(index);
+            name = rest;
+        }
+        if !name.is_empty() || indices.is_empty() {
+            segments.push(Segment::Key(name));
+        }
+        segments.extend(indices.into_iter().rev().map(Segment::Index));
+    }
+    segments
+}
+
+/// Splits the `[index]` that `part` ends with, if any.
+fn trailing_index(part: &str) -> Option<(&str, usize)> {
+    let inner = part.strip_suffix(']')?;
+    let open = inner.rfind('[')?;
+    let digits = &inner[open + 1..];
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((&inner[..open], digits.parse().ok()?))
+}
+
+/// A value being unflattened.
+enum Node {
+    Leaf(JsonValue),
+    /// Members in the order of their first key, and their positions by key
+    Object(Vec<(String, Node)>, HashMap<String, usize>),
+    Array(BTreeMap<usize, Node>),
+}
+
+impl Node {
+    fn insert(&mut self, segments: &[Segment], value: JsonValue) {
+        let Some((segment, rest)) = segments.split_first() else {
+            *self = Node::Leaf(value);
+            return;
+        };
+
+        let child = match *segment {
+            Segment::Key(key) => {
+                let (members, positions) = self.as_object();
+                let position = *positions.entry(key.to_string()).or_insert_with(|| {
+                    members.push((key.to_string(), Node::Leaf(JsonValue::Null)));
+                    members.len() - 1
+                });
+                &mut members[position].1
+            }
+            Segment::Index(index) => self
+                .as_array()
+                .entry(index)
+                .or_insert(Node::Leaf(JsonValue::Null)),
+        };
+        child.insert(rest, value);
+    }
+
+    /// Turns the node into an object node, keeping the members of an object leaf.
+    fn as_object(&mut self) -> (&mut Vec<(String, Node)>, &mut HashMap<String, usize>) {
+        if !matches!(self, Node::Object(..)) {
+            let mut members = Vec::new();
+            let mut positions = HashMap::new();
+            if let Node::Leaf(JsonValue::Object(entries)) = self.take() {
+                for entry in entries {
+                    let (Some(key), Some(value)) = (entry.key, entry.value) else {
+                        continue;
+                    };
+                    match positions.get(&key) {
+                        Some(&position) => members[position] = (key, Node::Leaf(value)),
+                        None => {
+                            positions.insert(key.clone(), members.len());
+                            members.push((key, Node::Leaf(value)));
+                        }
+                    }
+                }
+            }
+            *self = Node::Object(members, positions);
+        }
+        match self {
+            Node::Object(members, positions) => (members, positions),
+            _ => unreachable!("converted to an object"),
+        }
+    }
+
+    /// Turns the node into an array node, keeping the elements of an array leaf.
+    fn as_array(&mut self) -> &mut BTreeMap<usize, Node> {
+        if !matches!(self, Node::Array(_)) {
+            let mut elements = BTreeMap::new();
+            if let Node::Leaf(JsonValue::Array(entries)) = self.take() {
+                let values = entries.into_iter().filter_map(|entry| entry.value);
+                elements.extend(values.map(Node::Leaf).enumerate());
+            }
+            *self = Node::Array(elements);
+        }
+        match self {
+            Node::Array(elements) => elements,
+            _ => unreachable!("converted to an array"),
+        }
+    }
+
+    /// Takes the node, leaving a null leaf in its place.
+    fn take(&mut self) -> Self {
+        std::mem::replace(self, Node::Leaf(JsonValue::Null))
+    }
+
+    fn into_value(self) -> JsonValue {
+        match self {
+            Node::Leaf(value) => value,
+            Node::Object(members, _) => JsonValue::Object(
+                members
+                    .into_iter()
+                    .map(|(key, node)| member(&key, node.into_value()))
+                    .collect(),
+            ),
+            Node::Array(elements) => JsonValue::Array(
+                elements
+                    .into_values()
+                    .map(|node| {
+                        let mut element = JsonEntryValue::new();
+                        element.value = Some(node.into_value());
+                        element
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
@@ -1,6 +1,13 @@
+use std::cmp::Ordering;
 use std::fmt::Write;
 
 use super::{jsonparser::JsonEntryValue, JsonFixerConfig, JsonFixerError, jsonparser::JsonValue};
+use super::json_tokenizer::{JsonTokenizer, Token};
+use super::jsonescape::{self, EscapePolicy};
+use super::jsonfixer_config::LineLengthPolicy;
+use super::jsonfixer_error::JsonFormatError;
+use super::jsonnumber::{self, JsonNumber};
+use super::jsonpath;
 
 #[derive(Debug, Clone)]
 pub enum IndentStyle {
@@ -17,11 +24,59 @@ impl IndentStyle {
     }
 }
 
+/// Writes a parsed and fixed value as text.
+///
+/// Implement this trait to produce custom output styles and pass the implementation to
+/// [`JsonFixer::fix_with_formatter`](crate::JsonFixer::fix_with_formatter). The value is the
+/// fixed document with the configured transformations applied, whitespaces only entries of
+/// arrays and objects (`entry.value` is `None`) are only kept to preserve the input format
+/// and can be skipped. A formatter may delegate to [`JsonFormatter`] for the parts written as
+/// regular JSON.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::{Formatter, JsonFixerConfig, JsonFixerError, JsonFormatter, JsonValue};
+///
+/// /// Writes the elements of a root array as newline delimited JSON.
+/// struct NdJsonFormatter;
+///
+/// impl Formatter for NdJsonFormatter {
+///     fn format(
+///         &self,
+///         value: &JsonValue,
+///         config: &JsonFixerConfig,
+///     ) -> Result<String, JsonFixerError> {
+///         let elements: Vec<&JsonValue> = match value {
+///             JsonValue::Array(entries) => {
+///                 entries.iter().filter_map(|e| e.value.as_ref()).collect()
+///             }
+///             value => vec![value],
+///         };
+///         let mut output = String::new();
+///         for element in elements {
+///             output.push_str(&JsonFormatter.format(element, config)?);
+///             output.push('\n');
+///         }
+///         Ok(output)
+///     }
+/// }
+/// ```
+/// Rewrites a number with a fraction or an exponent in its shortest form that parses back to
+/// the same `f64`, eg. `0.10000000000000001` to `0.1` or `1.50e2` to `150`. Numbers are written
+/// with an exponent below 1e-6 and from 1e21, like JavaScript does. Integers are kept as is so
+/// that they never lose precision, and so are the numbers out of the range of `f64`.
+pub fn normalize_number(n: &str) -> String {
+    JsonNumber::parse(n).normalized()
+}
+
 pub trait Formatter {
+    /// Formats `value` according to `config`.
     fn format(&self, value: &JsonValue, config: &JsonFixerConfig)
     -> Result<String, JsonFixerError>;
 }
 
+/// The default formatter, writing the value as JSON (or JSON5, see `OutputStyle`).
 pub struct JsonFormatter;
 
 impl Formatter for JsonFormatter {
@@ -31,66 +86,443 @@ impl Formatter for JsonFormatter {
         config: &JsonFixerConfig,
     ) -> Result<String, JsonFixerError> {
         let mut output = String::new();
-        self.format_value(value, &mut output, 0, config)?;
+        self.format_into(value, config, &mut output)?;
         Ok(output)
     }
 }
 
 impl JsonFormatter {
+    /// Formats `value` at the end of `output`, which is expected to be empty.
+    ///
+    /// The root value is written without the whitespaces around it in the input, in the
+    /// compact, pretty and preserved layouts alike, and scalar roots are written like the
+    /// scalars nested in arrays and objects.
+    pub fn format_into(
+        &self,
+        value: &JsonValue,
+        config: &JsonFixerConfig,
+        output: &mut String,
+    ) -> Result<(), JsonFixerError> {
+        self.write_header_comment(output, config);
+        let expand = config.wraps_lines() && self.exceeds_line(value, output, "", 0, config, 0)?;
+        let start = output.len();
+        self.format_value_layout(value, output, "", 0, config, expand)?;
+        if config.preserve_normalize {
+            let normalized = normalize_whitespaces(&output[start..], config);
+            output.truncate(start);
+            output.push_str(&normalized);
+        }
+        self.check_line_length(output, config)
+    }
+
+    /// Formats a member of a root object, or an element of a root array when its key is
+    /// `None`, at the end of `output`, the root being written one member at a time as in
+    /// `format_into`, see `jsonstream`. `index` counts the members written before it, the
+    /// first one being preceded by the opening bracket.
+    pub(crate) fn format_root_member(
+        &self,
+        entry: &JsonEntryValue,
+        index: usize,
+        config: &JsonFixerConfig,
+        output: &mut String,
+    ) -> Result<(), JsonFixerError> {
+        let multiline = config.beautify();
+        if index == 0 {
+            self.write_header_comment(output, config);
+            output.push(if entry.key.is_some() { '{' } else { '[' });
+        } else {
+            output.push(',');
+        }
+        if multiline {
+            self.write_newline(output, 1, config)?;
+        }
+        if config.space_between() {
+            output.push(' ');
+        }
+        if multiline {
+            self.write_indent(output, 1, config)?;
+        }
+
+        let child_path = child_path(config, || match &entry.key {
+            Some(key) => jsonpath::key("", key),
+            None => jsonpath::index("", index),
+        });
+        if multiline {
+            self.write_comment(output, &child_path, 1, config)?;
+        }
+        if entry.key.is_some() {
+            self.write_key(output, entry, config)?;
+            output.push(':');
+            if config.space_between() || config.beautify() {
+                output.push(' ');
+            }
+        }
+        match &entry.value {
+            Some(value) => self.format_value(value, output, &child_path, 1, config),
+            None => Ok(()),
+        }
+    }
+
+    /// Closes a root container written by `format_root_member`, with the `closing` bracket.
+    pub(crate) fn close_root(
+        &self,
+        closing: char,
+        config: &JsonFixerConfig,
+        output: &mut String,
+    ) -> Result<(), JsonFixerError> {
+        if config.beautify() {
+            if config.trailing_commas() {
+                output.push(',');
+            }
+            self.write_newline(output, 0, config)?;
+        }
+        if config.space_between() {
+            output.push(' ');
+        }
+        output.push(closing);
+        Ok(())
+    }
+
+    /// Writes the header as `//` lines in the output styles allowing comments.
+    fn write_header_comment(&self, output: &mut String, config: &JsonFixerConfig) {
+        if let Some(header) = config.header_comment() {
+            for line in header.lines() {
+                output.push_str("//");
+                if !line.is_empty() {
+                    output.push(' ');
+                    output.push_str(line);
+                }
+                output.push('\n');
+            }
+        }
+    }
+
+    /// Reports the lines of `input` whose leading whitespace does not match the indentation
+    /// configured by `indent_style` and `indent_size`.
+    ///
+    /// The input is only tokenized, nothing is fixed. Each line is expected to be indented once
+    /// per enclosing array or object, lines starting with a closing bracket are indented like
+    /// the line that opened it.
+    pub fn check_indentation(
+        input: &str,
+        config: &JsonFixerConfig,
+    ) -> Result<Vec<JsonFormatError>, JsonFixerError> {
+        let indent = config.indent_style.with_size(Some(config.indent_size));
+        let mut tokenizer = JsonTokenizer::with_config(input, config);
+        let mut errors = Vec::new();
+
+        let mut depth: usize = 0;
+        let mut line = 1;
+        // Leading whitespaces of the current line, None once its first token was checked
+        let mut leading = Some(String::new());
+
+        while let Some(token) = tokenizer.next_token()? {
+            if let Token::Whitespace(sp, _) = &token {
+                match sp.rfind('\n') {
+                    Some(i) => {
+                        line += sp.matches('\n').count();
+                        leading = Some(sp[i + 1..].to_string());
+                    }
+                    None => {
+                        if let Some(leading) = leading.as_mut() {
+                            leading.push_str(sp);
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let closing = matches!(
+                token,
+                Token::RightBrace(_) | Token::RightBracket(_) | Token::RightParen(_)
+            );
+            if closing {
+                depth = depth.saturating_sub(1);
+            }
+            if let Some(leading) = leading.take() {
+                if leading != indent.repeat(depth) {
+                    errors.push(JsonFormatError::InvalidIndentation { line });
+                }
+            }
+            if matches!(
+                token,
+                Token::LeftBrace(_) | Token::LeftBracket(_) | Token::LeftParen(_)
+            ) {
+                depth += 1;
+            }
+            if let Token::String(s, _) = &token {
+                // Multi-line strings
+                line += s.matches('\n').count();
+            }
+        }
+
+        Ok(errors)
+    }
+
     fn format_value(
         &self,
         value: &JsonValue,
         output: &mut String,
+        path: &str,
         depth: usize,
         config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        self.format_value_layout(value, output, path, depth, config, false)
+    }
+
+    /// Formats a value, `expand` breaks the array or object over multiple lines to respect
+    /// the maximum line length.
+    fn format_value_layout(
+        &self,
+        value: &JsonValue,
+        output: &mut String,
+        path: &str,
+        depth: usize,
+        config: &JsonFixerConfig,
+        expand: bool,
     ) -> Result<(), JsonFixerError> {
         match value {
             JsonValue::Null => output.push_str("null"),
             JsonValue::Boolean(b) => output.push_str(if *b { "true" } else { "false" }),
-            JsonValue::Number(n) => write!(output, "{}", n).map_err(|e| JsonFixerError::IO(e))?,
-            JsonValue::String(s) => {
-                output.push('"');
-                //self.escaped_string(output, &s.replace('"', "\\\""))?;
-                output.push_str(s);
-                output.push('"');
-            }
+            JsonValue::Number(n) => self.write_number(output, n, config)?,
+            JsonValue::String(s) => self.escaped_string(output, s)?,
             JsonValue::Array(arr) => {
                 if config.preserve() {
-                    self.format_array_preserved(arr, output, depth, config)?;
+                    self.format_array_preserved(arr, output, path, depth, config)?;
                 } else {
-                    self.format_array(arr, output, depth, config)?;
+                    self.format_array(arr, output, path, depth, config, expand)?;
                 }
             }
             JsonValue::Object(obj) => {
                 if config.preserve() {
-                    self.format_object_preserved(obj, output, depth, config)?;
+                    self.format_object_preserved(obj, output, path, depth, config)?;
                 } else {
-                    self.format_object(obj, output, depth, config)?;
+                    self.format_object(obj, output, path, depth, config, expand)?;
                 }
             }
-            JsonValue::Space(sp) => write!(output, "{}", sp).map_err(|e| JsonFixerError::IO(e))?,
+            JsonValue::Space(sp) => write!(output, "{}", sp).map_err(JsonFixerError::IO)?,
         }
         Ok(())
     }
-    fn _escaped_string(&self, output: &mut String, s: &str) -> Result<(), JsonFixerError> {
-        for c in s.chars() {
-            match c {
-                '"' => output.push_str("\\\""),
-                '\\' => output.push_str("\\\\"),
-                '\n' => output.push_str("\\n"),
-                '\r' => output.push_str("\\r"),
-                '\t' => output.push_str("\\t"),
-                '\u{0008}' => output.push_str("\\b"),
-                '\u{000C}' => output.push_str("\\f"),
-                c if c.is_control() => {
-                    write!(output, "\\u{:04x}", c as u32).map_err(|e| JsonFixerError::IO(e))?
-                }
-                c => output.push(c),
+    /// Formats an element of an array or object. When the parent is expanded, the element
+    /// is expanded as well if it does not fit on the current line.
+    #[allow(clippy::too_many_arguments)]
+    fn format_child(
+        &self,
+        value: &JsonValue,
+        output: &mut String,
+        path: &str,
+        depth: usize,
+        config: &JsonFixerConfig,
+        parent_expanded: bool,
+        trailing: usize,
+    ) -> Result<(), JsonFixerError> {
+        let expand =
+            parent_expanded && self.exceeds_line(value, output, path, depth, config, trailing)?;
+        self.format_value_layout(value, output, path, depth, config, expand)
+    }
+
+    /// Checks if an array or object written on a single line would exceed the maximum line
+    /// length, `trailing` counts the characters that will follow it on the same line.
+    fn exceeds_line(
+        &self,
+        value: &JsonValue,
+        output: &str,
+        path: &str,
+        depth: usize,
+        config: &JsonFixerConfig,
+        trailing: usize,
+    ) -> Result<bool, JsonFixerError> {
+        let max = match config.max_line_length {
+            Some(max) => max,
+            None => return Ok(false),
+        };
+        if value.is_scalar() {
+            // Strings and other scalars are never broken
+            return Ok(false);
+        }
+
+        let mut inline = String::new();
+        self.format_value_layout(value, &mut inline, path, depth, config, false)?;
+        let column = output.chars().rev().take_while(|ch| *ch != '\n').count();
+
+        Ok(column + inline.chars().count() + trailing > max)
+    }
+
+    /// Returns an error for the first line longer than the maximum line length, unless lines
+    /// are wrapped.
+    fn check_line_length(
+        &self,
+        output: &str,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        let max = match config.max_line_length {
+            Some(max) if config.line_length_policy == LineLengthPolicy::Error => max,
+            _ => return Ok(()),
+        };
+
+        for (i, line) in output.lines().enumerate() {
+            let length = line.chars().count();
+            if length > max {
+                return Err(JsonFixerError::Format(JsonFormatError::LineTooLong {
+                    line: i + 1,
+                    length,
+                    max,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a string between double quotes, escaping the characters JSON requires. Strings
+    /// are kept unescaped in the parsed values, so they are escaped exactly once here.
+    fn escaped_string(&self, output: &mut String, s: &str) -> Result<(), JsonFixerError> {
+        output.push('"');
+        jsonescape::escape_into(output, s, EscapePolicy::Json);
+        output.push('"');
+        Ok(())
+    }
+
+    /// Writes a number as written in the input, or normalized as set by `normalize_numbers`,
+    /// `float_precision` and `expand_exponents`.
+    fn write_number(
+        &self,
+        output: &mut String,
+        n: &JsonNumber,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        if !config.normalize_numbers {
+            return write!(output, "{}", n).map_err(JsonFixerError::IO);
+        }
+        let normalized = match config.float_precision() {
+            Some(decimals) => n.rounded(decimals),
+            None => n.normalized(),
+        };
+        if config.expand_exponents {
+            output.push_str(&jsonnumber::expand_exponent(&normalized));
+        } else {
+            output.push_str(&normalized);
+        }
+        Ok(())
+    }
+
+    /// Writes the key of a member, unquoted when `quote_keys` allows it.
+    fn write_key(
+        &self,
+        output: &mut String,
+        entry: &JsonEntryValue,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        let key = entry.get_key();
+        if config.writes_unquoted_key(&key, entry.unquoted_key) {
+            output.push_str(&key);
+            Ok(())
+        } else {
+            self.escaped_string(output, &key)
+        }
+    }
+
+    /// Writes the comment of the member or element at `path` as `//` lines, the output being
+    /// indented for it at `depth`.
+    fn write_comment(
+        &self,
+        output: &mut String,
+        path: &str,
+        depth: usize,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        let Some(comment) = config.comment_at(path) else {
+            return Ok(());
+        };
+        for line in comment.lines() {
+            output.push_str("//");
+            if !line.is_empty() {
+                output.push(' ');
+                output.push_str(line);
             }
+            self.write_newline(output, depth, config)?;
+            self.write_indent(output, depth, config)?;
         }
         Ok(())
     }
 
+    /// Writes the comment found on the line of the value of `entry`, after the value or its
+    /// comma, when the comments are kept, see `JsonFixerConfig::keep_comments`.
+    fn write_trailing_comment(
+        &self,
+        output: &mut String,
+        entry: &JsonEntryValue,
+        config: &JsonFixerConfig,
+    ) {
+        if let Some(comment) = entry.comment.as_ref().filter(|_| config.keeps_comments()) {
+            output.push(' ');
+            output.push_str(comment);
+        }
+    }
+
+    /// Writes the comments found before `entry`, one per line, the output being indented for
+    /// them at `depth`, when the comments are kept.
+    fn write_leading_comments(
+        &self,
+        output: &mut String,
+        entry: &JsonEntryValue,
+        depth: usize,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        if !config.keeps_comments() {
+            return Ok(());
+        }
+        for comment in &entry.leading_comments {
+            output.push_str(comment);
+            self.write_newline(output, depth, config)?;
+            self.write_indent(output, depth, config)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the comments found before the closing bracket of a container, held by its
+    /// entries without value, each on its own line indented at `depth`.
+    fn write_closing_comments(
+        &self,
+        output: &mut String,
+        entries: &[JsonEntryValue],
+        depth: usize,
+        config: &JsonFixerConfig,
+    ) -> Result<(), JsonFixerError> {
+        if !has_closing_comments(entries, config) {
+            return Ok(());
+        }
+        let comments = entries
+            .iter()
+            .filter(|entry| entry.value.is_none())
+            .flat_map(|entry| &entry.leading_comments);
+        for comment in comments {
+            self.write_indent(output, depth, config)?;
+            output.push_str(comment);
+            self.write_newline(output, depth, config)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the comment of `entry` in the preserved layout, `follows` being the whitespaces
+    /// written after it. A line comment is ended by a line break unless they start with one,
+    /// the entries following it having been sorted.
+    fn write_preserved_comment(
+        &self,
+        output: &mut String,
+        entry: &JsonEntryValue,
+        follows: &str,
+        config: &JsonFixerConfig,
+    ) {
+        if let Some(comment) = entry.comment.as_ref().filter(|_| config.keeps_comments()) {
+            output.push(' ');
+            output.push_str(comment);
+            if comment.starts_with("//") && !follows.starts_with(['\r', '\n']) {
+                output.push('\n');
+            }
+        }
+    }
+
     fn write_newline(
         &self,
         output: &mut String,
@@ -120,44 +552,77 @@ impl JsonFormatter {
         &self,
         arr: &[JsonEntryValue],
         output: &mut String,
+        path: &str,
         depth: usize,
         config: &JsonFixerConfig,
+        expand: bool,
     ) -> Result<(), JsonFixerError> {
-        if arr.is_empty() {
+        // Whitespaces only entries are not written
+        let entries: Vec<&JsonEntryValue> =
+            arr.iter().filter(|entry| entry.value.is_some()).collect();
+        let multiline = config.beautify() || expand;
+
+        if entries.is_empty() && !(multiline && has_closing_comments(arr, config)) {
             output.push_str("[]");
             return Ok(());
         }
+        let spaced = config.space_between() && !expand;
+        let trailing_comma = multiline && config.trailing_commas();
 
         output.push('[');
-        if config.beautify() {
+        if multiline {
             self.write_newline(output, depth + 1, config)?;
         }
-        if config.space_between() {
+        if spaced {
             output.push(' ');
         }
 
-        for (i, entry) in arr.iter().enumerate() {
-            if entry.value.is_some() {
-                if i > 0 {
-                    output.push(',');
-                    if config.beautify() {
-                        self.write_newline(output, depth + 1, config)?;
-                    }
-                    if config.space_between() {
-                        output.push(' ');
-                    }
+        for (i, entry) in entries.iter().enumerate() {
+            if i > 0 {
+                output.push(',');
+                if multiline {
+                    self.write_trailing_comment(output, entries[i - 1], config);
+                    self.write_newline(output, depth + 1, config)?;
                 }
-                if config.beautify() {
-                    self.write_indent(output, depth + 1, config)?;
+                if spaced {
+                    output.push(' ');
                 }
-                self.format_value(&entry.get_value(), output, depth + 1, config)?;
             }
+            if multiline {
+                self.write_indent(output, depth + 1, config)?;
+            }
+            let child_path = child_path(config, || jsonpath::index(path, i));
+            if multiline {
+                self.write_leading_comments(output, entry, depth + 1, config)?;
+                self.write_comment(output, &child_path, depth + 1, config)?;
+            }
+            let trailing = if i + 1 < entries.len() || trailing_comma {
+                1
+            } else {
+                0
+            };
+            self.format_child(
+                &entry.get_value(),
+                output,
+                &child_path,
+                depth + 1,
+                config,
+                expand,
+                trailing,
+            )?;
         }
-        if config.beautify() {
-            self.write_newline(output, depth, config)?;
+        if trailing_comma && !entries.is_empty() {
+            output.push(',');
+        }
+        if multiline {
+            if let Some(last) = entries.last() {
+                self.write_trailing_comment(output, last, config);
+                self.write_newline(output, depth, config)?;
+            }
+            self.write_closing_comments(output, arr, depth + 1, config)?;
             self.write_indent(output, depth, config)?;
         }
-        if config.space_between() {
+        if spaced {
             output.push(' ');
         }
 
@@ -169,6 +634,7 @@ impl JsonFormatter {
         &self,
         arr: &[JsonEntryValue],
         output: &mut String,
+        path: &str,
         depth: usize,
         config: &JsonFixerConfig,
     ) -> Result<(), JsonFixerError> {
@@ -179,15 +645,32 @@ impl JsonFormatter {
 
         output.push('[');
 
+        let mut index = 0;
+        // The comma before the element was written before the comment of the previous one
+        let mut comma_written = false;
         for (i, entry) in arr.iter().enumerate() {
-            if i > 0 && entry.value.is_some() {
+            if i > 0 && entry.value.is_some() && !std::mem::take(&mut comma_written) {
                 output.push(',');
             }
 
             output.push_str(&entry.get_sp_bf_val());
 
             if entry.value.is_some() {
-                self.format_value(&entry.get_value(), output, depth + 1, config)?;
+                let child_path = child_path(config, || jsonpath::index(path, index));
+                self.format_value(&entry.get_value(), output, &child_path, depth + 1, config)?;
+                index += 1;
+                if entry.comment.is_some() {
+                    if arr[i + 1..].iter().any(|next| next.value.is_some()) {
+                        output.push(',');
+                        comma_written = true;
+                    }
+                    let follows = match entry.get_sp_af_val() {
+                        sp if sp.is_empty() => arr.get(i + 1).map(|e| e.get_sp_bf_val()),
+                        sp => Some(sp),
+                    };
+                    let follows = follows.unwrap_or_default();
+                    self.write_preserved_comment(output, entry, &follows, config);
+                }
             }
             output.push_str(&entry.get_sp_af_val());
         }
@@ -198,51 +681,57 @@ impl JsonFormatter {
 
     fn format_object(
         &self,
-        obj: &Vec<JsonEntryValue>,
+        obj: &[JsonEntryValue],
         output: &mut String,
+        path: &str,
         depth: usize,
         config: &JsonFixerConfig,
+        expand: bool,
     ) -> Result<(), JsonFixerError> {
         let mut entries = obj.to_vec();
         entries.retain(|val| val.value.is_some());
+        let multiline = config.beautify() || expand;
 
-        if entries.is_empty() {
+        if entries.is_empty() && !(multiline && has_closing_comments(obj, config)) {
             output.push_str("{}");
             return Ok(());
         }
+        let spaced = config.space_between() && !expand;
+        let trailing_comma = multiline && config.trailing_commas();
 
         output.push('{');
-        if config.beautify() {
+        if multiline {
             self.write_newline(output, depth + 1, config)?;
         }
 
-        if config.sort_keys {
-            entries.sort_by(|a, b| a.key.cmp(&b.key));
-        }
+        order_entries(&mut entries, path, config);
 
-        if config.space_between() {
+        if spaced {
             output.push(' ');
         }
 
         for (i, entry) in entries.iter().enumerate() {
             if i > 0 {
                 output.push(',');
-                if config.beautify() {
+                if multiline {
+                    self.write_trailing_comment(output, &entries[i - 1], config);
                     self.write_newline(output, depth + 1, config)?;
                 }
-                if config.space_between() {
+                if spaced {
                     output.push(' ');
                 }
             }
 
-            if config.beautify() {
+            if multiline {
                 self.write_indent(output, depth + 1, config)?;
             }
+            let child_path = child_path(config, || jsonpath::key(path, &entry.get_key()));
+            if multiline {
+                self.write_leading_comments(output, entry, depth + 1, config)?;
+                self.write_comment(output, &child_path, depth + 1, config)?;
+            }
 
-            output.push('"');
-            //self.escaped_string(output, &entry.clone().key.unwrap())?;
-            output.push_str(&entry.get_key());
-            output.push('"');
+            self.write_key(output, entry, config)?;
 
             output.push(':');
 
@@ -250,15 +739,35 @@ impl JsonFormatter {
                 output.push(' ');
             }
 
-            self.format_value(&entry.get_value(), output, depth + 1, config)?;
+            let trailing = if i + 1 < entries.len() || trailing_comma {
+                1
+            } else {
+                0
+            };
+            self.format_child(
+                &entry.get_value(),
+                output,
+                &child_path,
+                depth + 1,
+                config,
+                expand,
+                trailing,
+            )?;
+        }
+        if trailing_comma && !entries.is_empty() {
+            output.push(',');
         }
 
-        if config.beautify() {
-            self.write_newline(output, depth, config)?;
+        if multiline {
+            if let Some(last) = entries.last() {
+                self.write_trailing_comment(output, last, config);
+                self.write_newline(output, depth, config)?;
+            }
+            self.write_closing_comments(output, obj, depth + 1, config)?;
             self.write_indent(output, depth, config)?;
         }
 
-        if config.space_between() {
+        if spaced {
             output.push(' ');
         }
 
@@ -269,12 +778,13 @@ impl JsonFormatter {
 
     fn format_object_preserved(
         &self,
-        obj: &Vec<JsonEntryValue>,
+        obj: &[JsonEntryValue],
         output: &mut String,
+        path: &str,
         depth: usize,
         config: &JsonFixerConfig,
     ) -> Result<(), JsonFixerError> {
-        let entries = self.clean_middle_spaces_and_sort(&obj, config);
+        let entries = self.clean_middle_spaces_and_sort(obj, path, config);
         if entries.is_empty() {
             output.push_str("{}");
             return Ok(());
@@ -282,7 +792,8 @@ impl JsonFormatter {
 
         output.push('{');
 
-        for (_i, entry) in entries.iter().enumerate() {
+        let last = entries.iter().rposition(|entry| entry.value.is_some());
+        for (i, entry) in entries.iter().enumerate() {
             //println!("Entry {i}: {:?}", entry);
             if entry.value.is_none() {
                 output.push_str(&entry.get_sp_bf_key());
@@ -292,10 +803,7 @@ impl JsonFormatter {
             } else {
                 output.push_str(&entry.get_sp_bf_key());
 
-                output.push('"');
-                output.push_str(&entry.get_key());
-                //self.escaped_string(output, &entry.clone().key.unwrap())?;
-                output.push('"');
+                self.write_key(output, entry, config)?;
 
                 output.push_str(&entry.get_sp_af_key());
 
@@ -303,31 +811,27 @@ impl JsonFormatter {
 
                 output.push_str(&entry.get_sp_bf_val());
 
-                self.format_value(&entry.get_value(), output, depth + 1, config)?;
+                let child_path = child_path(config, || jsonpath::key(path, &entry.get_key()));
+                self.format_value(&entry.get_value(), output, &child_path, depth + 1, config)?;
                 let last_space = entry.get_sp_af_val();
-
-                if last_space.contains('\n') {
-                    output.push(',');
+                let comma = if Some(i) == last { "" } else { "," };
+
+                if last_space.contains('\n') || entry.comment.is_some() {
+                    output.push_str(comma);
+                    let follows = match last_space.is_empty() {
+                        true => entries.get(i + 1).map(|e| e.get_sp_bf_key()),
+                        false => Some(last_space.clone()),
+                    };
+                    let follows = follows.unwrap_or_default();
+                    self.write_preserved_comment(output, entry, &follows, config);
                     output.push_str(&last_space);
                 } else {
                     output.push_str(&last_space);
-                    output.push(',');
+                    output.push_str(comma);
                 }
             }
         }
 
-        let found = output
-            .chars()
-            .rev()
-            .enumerate()
-            .find(|(_i, ch)| !ch.is_whitespace());
-        if found.is_some() {
-            let (i, ch) = found.unwrap();
-            if ch == ',' {
-                output.remove(output.len() - i - 1);
-            }
-        }
-
         output.push('}');
 
         Ok(())
@@ -335,7 +839,8 @@ impl JsonFormatter {
 
     fn clean_middle_spaces_and_sort(
         &self,
-        obj: &Vec<JsonEntryValue>,
+        obj: &[JsonEntryValue],
+        path: &str,
         config: &JsonFixerConfig,
     ) -> Vec<JsonEntryValue> {
         // Keep first and last whitespaces
@@ -350,13 +855,7 @@ impl JsonFormatter {
         cleaned_obj.retain(|entry| entry.value.is_some());
 
         // Sort the cleaned obj entries
-        if config.sort_keys {
-            cleaned_obj.sort_by(|a, b| {
-                let key_a = a.get_key();
-                let key_b = b.get_key();
-                key_a.cmp(&key_b)
-            });
-        }
+        order_entries(&mut cleaned_obj, path, config);
 
         if let Some(entry) = first_whitespaces {
             if entry.value.is_none() {
@@ -373,3 +872,132 @@ impl JsonFormatter {
         cleaned_obj
     }
 }
+
+/// Normalizes the whitespaces of a preserved output, see `preserve_normalize`. Each line is
+/// indented once per enclosing array or object, lines starting with a closing bracket like the
+/// line that opened it.
+fn normalize_whitespaces(output: &str, config: &JsonFixerConfig) -> String {
+    let indent = config.indent_style.with_size(Some(config.indent_size));
+    let mut normalized = String::with_capacity(output.len());
+    let mut depth: usize = 0;
+    // Block comment spanning lines, see `keep_comments`
+    let mut in_comment = false;
+
+    for (i, line) in output.split('\n').enumerate() {
+        if i > 0 {
+            normalized.push('\n');
+        }
+        // Keep the line endings as found
+        let (line, cr) = match line.strip_suffix('\r') {
+            Some(line) => (line, "\r"),
+            None => (line, ""),
+        };
+        let line = line.trim_matches([' ', '\t', '\r']);
+        if line.starts_with(['}', ']']) {
+            normalized.push_str(&indent.repeat(depth.saturating_sub(1)));
+        } else if !line.is_empty() {
+            normalized.push_str(&indent.repeat(depth));
+        }
+
+        // The strings of the output are escaped, so they never span lines
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut space = false;
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if in_comment {
+                normalized.push(ch);
+                if ch == '*' && chars.next_if_eq(&'/').is_some() {
+                    normalized.push('/');
+                    in_comment = false;
+                }
+                continue;
+            }
+            if in_string {
+                normalized.push(ch);
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == '"' {
+                    in_string = false;
+                }
+                continue;
+            }
+            if matches!(ch, ' ' | '\t' | '\r') {
+                space = true;
+                continue;
+            }
+            if space {
+                normalized.push(' ');
+                space = false;
+            }
+            match ch {
+                // The comments are kept as written
+                '/' if chars.peek() == Some(&'/') => {
+                    normalized.push(ch);
+                    normalized.extend(chars.by_ref());
+                    break;
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    normalized.push(ch);
+                    normalized.extend(chars.next());
+                    in_comment = true;
+                    continue;
+                }
+                '"' => in_string = true,
+                '{' | '[' => depth += 1,
+                '}' | ']' => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+            normalized.push(ch);
+        }
+        normalized.push_str(cr);
+    }
+    normalized
+}
+
+/// Path of a child value, only built when the configuration orders keys or comments values
+/// by path.
+fn child_path(config: &JsonFixerConfig, build: impl FnOnce() -> String) -> String {
+    if config.key_order.is_empty() && config.comments.is_empty() {
+        String::new()
+    } else {
+        build()
+    }
+}
+
+/// Returns `true` if comments found before the closing bracket of `entries` are kept.
+fn has_closing_comments(entries: &[JsonEntryValue], config: &JsonFixerConfig) -> bool {
+    config.keeps_comments()
+        && entries
+            .iter()
+            .any(|entry| entry.value.is_none() && !entry.leading_comments.is_empty())
+}
+
+/// Orders the members of the object at `path`: the keys of its `key_order` first, in that
+/// order, then the other keys sorted when `sort_keys` is set, or in input order.
+fn order_entries(entries: &mut [JsonEntryValue], path: &str, config: &JsonFixerConfig) {
+    let order = config.key_order_at(path);
+    if order.is_none() && !config.sort_keys {
+        return;
+    }
+
+    let rank = |entry: &JsonEntryValue| {
+        let key = entry.key.as_deref().unwrap_or_default();
+        order
+            .and_then(|order| order.iter().position(|k| k == key))
+            .unwrap_or(usize::MAX)
+    };
+    entries.sort_by(|a, b| {
+        rank(a).cmp(&rank(b)).then_with(|| {
+            if config.sort_keys {
+                let key_a = a.key.as_deref().unwrap_or_default();
+                let key_b = b.key.as_deref().unwrap_or_default();
+                config.compare_keys(key_a, key_b)
+            } else {
+                Ordering::Equal
+            }
+        })
+    });
+}
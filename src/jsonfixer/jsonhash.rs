@@ -0,0 +1,130 @@
+//! Hashing of the canonical form of fixed documents, to find the documents that only differ
+//! in their formatting, key order or repairs.
+//!
+//! The canonical form is the JSON Canonicalization Scheme of RFC 8785: no whitespace, the
+//! members sorted by the UTF-16 code units of their keys, the numbers written like
+//! JavaScript does and the strings escaped as little as possible. It is fed to the hasher
+//! as it is written, so that it is never held whole in memory.
+
+use sha2::{Digest, Sha256};
+
+use super::{
+    jsonfixer_config::{DuplicateKeys, JsonFixerConfig},
+    jsonfixer_error::JsonFixerError,
+    jsonnumber::JsonNumber,
+    jsonparser::{JsonParser, JsonValue},
+};
+
+/// Fixes `input` and returns the SHA-256 hash of the canonical form of the fixed value.
+///
+/// The input is fixed with the default configuration, except that only the last occurrence
+/// of a duplicated key is kept, like `JSON.parse` does.
+pub fn canonical_hash(input: &str) -> Result<[u8; 32], JsonFixerError> {
+    let config = JsonFixerConfig {
+        duplicate_keys: DuplicateKeys::KeepLast,
+        ..Default::default()
+    };
+    let (value, _) = JsonParser::with_borrowed_config(input, &config).parse_document()?;
+
+    let mut hasher = Sha256::new();
+    write_canonical(&value, &mut |bytes| hasher.update(bytes));
+    Ok(hasher.finalize().into())
+}
+
+/// Passes the canonical form of `value` to `sink`, piece by piece.
+fn write_canonical(value: &JsonValue, sink: &mut impl FnMut(&[u8])) {
+    match value {
+        JsonValue::Null => sink(b"null"),
+        JsonValue::Boolean(true) => sink(b"true"),
+        JsonValue::Boolean(false) => sink(b"false"),
+        JsonValue::Number(n) => sink(canonical_number(n).as_bytes()),
+        JsonValue::String(s) => write_string(s, sink),
+        JsonValue::Array(entries) => {
+            sink(b"[");
+            let values = entries.iter().filter_map(|entry| entry.value.as_ref());
+            for (i, value) in values.filter(|v| !is_space(v)).enumerate() {
+                if i > 0 {
+                    sink(b",");
+                }
+                write_canonical(value, sink);
+            }
+            sink(b"]");
+        }
+        JsonValue::Object(entries) => {
+            let mut members: Vec<(&str, &JsonValue)> = entries
+                .iter()
+                .filter_map(|entry| Some((entry.key.as_deref()?, entry.value.as_ref()?)))
+                .filter(|(_, value)| !is_space(value))
+                .collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            sink(b"{");
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    sink(b",");
+                }
+                write_string(key, sink);
+                sink(b":");
+                write_canonical(value, sink);
+            }
+            sink(b"}");
+        }
+        JsonValue::Space(_) => (),
+    }
+}
+
+fn is_space(value: &JsonValue) -> bool {
+    matches!(value, JsonValue::Space(_))
+}
+
+/// Writes a string between quotes, escaping only the quotes, the backslashes and the
+/// characters below U+0020, with their short escape when they have one.
+fn write_string(s: &str, sink: &mut impl FnMut(&[u8])) {
+    sink(b"\"");
+    let mut start = 0;
+    for (i, byte) in s.bytes().enumerate() {
+        let short = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 => "\\b",
+            0x0C => "\\f",
+            byte if byte < 0x20 => "",
+            _ => continue,
+        };
+        sink(&s.as_bytes()[start..i]);
+        if short.is_empty() {
+            sink(format!("\\u{:04x}", byte).as_bytes());
+        } else {
+            sink(short.as_bytes());
+        }
+        start = i + 1;
+    }
+    sink(&s.as_bytes()[start..]);
+    sink(b"\"");
+}
+
+/// Writes a number like JavaScript's `Number.prototype.toString`, eg. `1.50e2` to `150` and
+/// `1e21` to `1e+21`. The numbers out of the range of `f64` are kept as written.
+fn canonical_number(n: &JsonNumber) -> String {
+    let Some(n) = n.as_f64() else {
+        return n.to_string();
+    };
+    let magnitude = n.abs();
+    if magnitude == 0.0 {
+        // No negative zero
+        "0".to_string()
+    } else if (1e-6..1e21).contains(&magnitude) {
+        format!("{}", n)
+    } else {
+        let exponent = format!("{:e}", n);
+        match exponent.split_once('e') {
+            Some((mantissa, power)) if !power.starts_with('-') => {
+                format!("{}e+{}", mantissa, power)
+            }
+            _ => exponent,
+        }
+    }
+}
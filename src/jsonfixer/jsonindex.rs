@@ -0,0 +1,131 @@
+//! Flat index of the paths of a document, to query broken documents by path.
+
+use std::fmt;
+
+use super::{
+    json_tokenizer::Position,
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonparser::{JsonParser, JsonValue},
+};
+
+/// The kind of a value of the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Null,
+    Boolean,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Null => "null",
+            Self::Boolean => "boolean",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ValueKind {
+    /// Returns the kind of `value`, `None` for whitespaces.
+    pub fn of(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Null => Some(Self::Null),
+            JsonValue::Boolean(_) => Some(Self::Boolean),
+            JsonValue::Number(_) => Some(Self::Number),
+            JsonValue::String(_) => Some(Self::String),
+            JsonValue::Array(_) => Some(Self::Array),
+            JsonValue::Object(_) => Some(Self::Object),
+            JsonValue::Space(_) => None,
+        }
+    }
+}
+
+/// Location of a value in the input, before it was fixed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// Byte offset of the first character of the value.
+    pub start: usize,
+    /// Byte offset following the last character of the value, so that `&input[start..end]` is
+    /// the value as written. The end of an unclosed array or object is the end of its last
+    /// entry.
+    pub end: usize,
+    /// Line and column of the first character of the value.
+    pub position: Position,
+}
+
+/// A value of the index, see [`index_paths`].
+pub type IndexEntry = (String, ValueKind, Span);
+
+/// Fixes `input` with the default configuration and lists the path, kind and span of every
+/// value, parents first.
+pub fn index_paths(input: &str) -> Result<Vec<IndexEntry>, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let mut parser = JsonParser::with_borrowed_config(input, &config).with_index();
+    parser.parse_document()?;
+    Ok(parser.take_index())
+}
+
+/// Fixes the values at `paths` of `input` with the default configuration, whether or not the
+/// rest of the document can be fixed. `None` stands for the paths that are missing or whose
+/// value is broken itself.
+pub fn extract_fields(input: &str, paths: &[&str]) -> Vec<Option<String>> {
+    let config = JsonFixerConfig {
+        salvage: true,
+        ..Default::default()
+    };
+    let mut parser = JsonParser::with_borrowed_config(input, &config).with_partial_index();
+    // The values parsed before an unrepairable section are indexed all the same
+    let _ = parser.parse_document();
+    let index = parser.take_index();
+
+    let config = JsonFixerConfig::default();
+    paths
+        .iter()
+        .map(|&path| {
+            // The last occurrence of a duplicated key wins, like in most parsers
+            let (_, _, span) = index.iter().rev().find(|(p, _, _)| p == path)?;
+            let value = &input[span.start..span.end];
+            JsonParser::with_borrowed_config(value, &config)
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+/// Formats the value at `path` of `input` with `config`, the rest of the input being kept as
+/// written. Returns `None` if `path` is missing.
+///
+/// The whole input is fixed with the default configuration to locate the value, so it has to
+/// be fixable, but only the value is replaced by its fixed output. The lines following the
+/// first one of the value are indented like the line the value starts on.
+pub fn format_at(
+    input: &str,
+    path: &str,
+    config: &JsonFixerConfig,
+) -> Result<Option<String>, JsonFixerError> {
+    let index = index_paths(input)?;
+    // The last occurrence of a duplicated key wins, like in `extract_fields`
+    let Some((_, _, span)) = index.iter().rev().find(|(p, _, _)| p == path) else {
+        return Ok(None);
+    };
+
+    let value = JsonParser::with_borrowed_config(&input[span.start..span.end], config).parse()?;
+    let line_start = input[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line = &input[line_start..span.start];
+    let indent = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+
+    let mut output = String::with_capacity(input.len() + value.len());
+    output.push_str(&input[..span.start]);
+    output.push_str(&value.replace('\n', &format!("\n{}", indent)));
+    output.push_str(&input[span.end..]);
+    Ok(Some(output))
+}
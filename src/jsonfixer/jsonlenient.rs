@@ -0,0 +1,113 @@
+//! Wrapper type fixing malformed JSON while deserializing.
+
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{jsonfixer_config::JsonFixerConfig, jsonfixer_error::JsonFixerError, JsonFixer};
+
+/// A value deserialized from JSON that may be malformed.
+///
+/// When deserialized, a string holding a malformed JSON document, eg. a field filled by an
+/// upstream service with `"{name: 'John'}"`, is fixed with the default configuration and
+/// deserialized into `T`. Any other value, and strings that `T` accepts as is, are
+/// deserialized into `T` directly. Raw documents are fixed by parsing them with
+/// [`str::parse`], eg. the body of a request. `Lenient<T>` is serialized like `T`.
+///
+/// This type is only available when the `serde` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::Lenient;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Person {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     person: Lenient<Person>,
+/// }
+///
+/// let event: Event = serde_json::from_str(r#"{"person": "{name: 'John', tags: ['a' 'b']}"}"#)
+///     .unwrap();
+/// assert_eq!(event.person.name, "John");
+///
+/// let person: Lenient<Person> = "{name: 'Jane', tags: [],}".parse().unwrap();
+/// assert_eq!(person.into_inner().name, "Jane");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Lenient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Lenient<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Lenient<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let s = match value {
+            serde_json::Value::String(s) => s,
+            value => {
+                return serde_json::from_value(value)
+                    .map(Self)
+                    .map_err(D::Error::custom)
+            }
+        };
+
+        match serde_json::from_value(serde_json::Value::String(s.clone())) {
+            Ok(value) => Ok(Self(value)),
+            Err(err) => match s.parse() {
+                Ok(fixed) => Ok(fixed),
+                // The string is not JSON, `T` rejected it as a string
+                Err(JsonFixerError::Syntax(_)) => Err(D::Error::custom(err)),
+                // The fixed JSON does not match `T`
+                Err(fix_err) => Err(D::Error::custom(fix_err)),
+            },
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Lenient<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Fixes a raw JSON document with the default configuration and deserializes it.
+impl<T: DeserializeOwned> FromStr for Lenient<T> {
+    type Err = JsonFixerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        JsonFixer::from_fixed(s, Some(JsonFixerConfig::default())).map(Self)
+    }
+}
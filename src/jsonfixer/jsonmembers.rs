@@ -0,0 +1,54 @@
+//! Streaming of the members of huge flat objects, eg. maps of records by id.
+
+use super::{
+    json_tokenizer::{JsonTokenizer, Position, Token},
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::{JsonFixerError, SyntaxError},
+    jsonformatter::{Formatter, JsonFormatter},
+    jsonparser::JsonParser,
+};
+
+/// Fixes `input`, an object, with the default configuration and passes each of its members
+/// to `f` as soon as it is parsed, with the key unescaped and the value as fixed JSON text.
+///
+/// The members are not kept, so the object is never held whole in memory. They are passed
+/// as found in the input, duplicated keys included, and the members found before an error
+/// have been passed when the error is returned.
+pub fn for_each_member<F>(input: &str, mut f: F) -> Result<(), JsonFixerError>
+where
+    F: FnMut(&str, &str),
+{
+    expect_container(input, '{')?;
+
+    let config = JsonFixerConfig::default();
+    JsonParser::with_borrowed_config(input, &config)
+        .with_member_sink(|entry| {
+            if let (Some(key), Some(value)) = (&entry.key, &entry.value) {
+                f(key, &JsonFormatter.format(value, &config)?);
+            }
+            Ok(())
+        })
+        .parse_document()?;
+    Ok(())
+}
+
+/// Fails unless the first value of `input` opens with the `opening` bracket, `{` or `[`,
+/// returns the position of the bracket.
+pub(crate) fn expect_container(input: &str, opening: char) -> Result<Position, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let mut tokenizer = JsonTokenizer::with_config(input, &config);
+    loop {
+        return match tokenizer.next_token()? {
+            Some(Token::Whitespace(_, _)) => continue,
+            Some(Token::LeftBrace(pos)) if opening == '{' => Ok(pos),
+            Some(Token::LeftBracket(pos)) if opening == '[' => Ok(pos),
+            Some(token) => Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
+                format!("\nExpected '{}' but found {}", opening, token.get()),
+                token.pos().clone(),
+            ))),
+            None => Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(
+                tokenizer.current_position(),
+            ))),
+        };
+    }
+}
@@ -0,0 +1,308 @@
+//! Typed JSON numbers.
+
+use std::fmt;
+
+#[cfg(feature = "bignum")]
+use std::{cmp::Ordering, str::FromStr};
+
+#[cfg(feature = "bignum")]
+use bigdecimal::BigDecimal;
+#[cfg(feature = "bignum")]
+use num_bigint::BigInt;
+
+/// A JSON number of the parsed document.
+///
+/// Numbers are typed when they can be, yet always written back exactly as found in the
+/// fixed input: a variant other than `BigRaw` is only used when its canonical form is the
+/// number as written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonNumber {
+    /// A negative or positive integer fitting an `i64`, eg. `-42`
+    I64(i64),
+    /// A positive integer too large for an `i64` but fitting a `u64`
+    U64(u64),
+    /// A number with a fraction or an exponent written in its shortest form, eg. `3.14`
+    F64(f64),
+    /// Any other number, kept as written, eg. big integers, `1e5` or `1.50`
+    BigRaw(String),
+}
+
+impl JsonNumber {
+    /// Creates the number of a valid JSON number literal.
+    pub fn parse(literal: &str) -> Self {
+        if let Ok(n) = literal.parse::<i64>() {
+            if n.to_string() == literal {
+                return Self::I64(n);
+            }
+        }
+        if let Ok(n) = literal.parse::<u64>() {
+            if n.to_string() == literal {
+                return Self::U64(n);
+            }
+        }
+        if let Ok(n) = literal.parse::<f64>() {
+            if n.is_finite() && shortest(n) == literal {
+                return Self::F64(n);
+            }
+        }
+        Self::BigRaw(literal.to_string())
+    }
+
+    /// Returns the number as an `i64` if it is an integer in the range of `i64`, eg. `1e5`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::I64(n) => Some(*n),
+            Self::U64(n) => i64::try_from(*n).ok(),
+            Self::F64(_) => exact_integer(self.as_f64()?).map(|n| n as i64),
+            Self::BigRaw(literal) => literal
+                .parse()
+                .ok()
+                .or_else(|| exact_integer(self.as_f64()?).map(|n| n as i64)),
+        }
+    }
+
+    /// Returns the number as a `u64` if it is a positive integer in the range of `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::I64(n) => u64::try_from(*n).ok(),
+            Self::U64(n) => Some(*n),
+            Self::F64(_) => exact_integer(self.as_f64()?)
+                .filter(|n| *n >= 0.0)
+                .map(|n| n as u64),
+            Self::BigRaw(literal) => literal.parse().ok().or_else(|| {
+                exact_integer(self.as_f64()?)
+                    .filter(|n| *n >= 0.0)
+                    .map(|n| n as u64)
+            }),
+        }
+    }
+
+    /// Returns the closest `f64` of the number, `None` if it is out of the range of `f64`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::I64(n) => Some(*n as f64),
+            Self::U64(n) => Some(*n as f64),
+            Self::F64(n) => Some(*n),
+            Self::BigRaw(literal) => literal.parse().ok().filter(|n: &f64| n.is_finite()),
+        }
+    }
+
+    /// Returns the number in its shortest form parsing back to the same `f64`, eg.
+    /// `0.10000000000000001` to `0.1` or `1.50e2` to `150`. Integers are kept as is so that
+    /// they never lose precision, and so are the numbers out of the range of `f64`.
+    pub fn normalized(&self) -> String {
+        match self {
+            Self::BigRaw(literal) if literal.contains(['.', 'e', 'E']) => match self.as_f64() {
+                Some(n) => shortest(n),
+                None => literal.clone(),
+            },
+            number => number.to_string(),
+        }
+    }
+
+    /// Returns the number rounded to `decimals` decimals, in its shortest form, eg.
+    /// `3.14159` to `3.14` for 2 decimals. Integers are kept as is, and so are the numbers out
+    /// of the range of `f64`.
+    pub fn rounded(&self, decimals: u8) -> String {
+        match self {
+            Self::F64(_) | Self::BigRaw(_) if self.to_string().contains(['.', 'e', 'E']) => {
+                let Some(n) = self.as_f64() else {
+                    return self.to_string();
+                };
+                // Formatting rounds the decimal expansion exactly, unlike scaling by 10^n
+                let rounded: f64 = format!("{:.*}", decimals as usize, n).parse().unwrap_or(n);
+                // No negative zero, eg. for -0.001
+                shortest(if rounded == 0.0 { 0.0 } else { rounded })
+            }
+            number => number.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I64(n) => write!(f, "{}", n),
+            Self::U64(n) => write!(f, "{}", n),
+            Self::F64(n) => write!(f, "{}", shortest(*n)),
+            Self::BigRaw(literal) => write!(f, "{}", literal),
+        }
+    }
+}
+
+/// Largest exponent, in absolute value, that [`expand_exponent`] expands.
+pub const MAX_EXPANDED_EXPONENT: u32 = 21;
+
+/// Writes a number literal without its exponent, eg. `1.2e-3` to `0.0012` or `1e5` to
+/// `100000`. The digits are moved rather than computed, so that no precision is lost, and the
+/// literals with an exponent over [`MAX_EXPANDED_EXPONENT`] are kept as is.
+pub fn expand_exponent(literal: &str) -> String {
+    let Some((mantissa, exponent)) = literal.split_once(['e', 'E']) else {
+        return literal.to_string();
+    };
+    let exponent = match exponent.parse::<i32>() {
+        Ok(exponent) if exponent.unsigned_abs() <= MAX_EXPANDED_EXPONENT => exponent,
+        _ => return literal.to_string(),
+    };
+
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction);
+    // Position of the decimal point in the digits
+    let point = integer.len() as i32 + exponent;
+
+    let (integer, fraction) = if point <= 0 {
+        (
+            String::new(),
+            format!("{}{}", "0".repeat(-point as usize), digits),
+        )
+    } else if point as usize >= digits.len() {
+        (
+            format!("{}{}", digits, "0".repeat(point as usize - digits.len())),
+            String::new(),
+        )
+    } else {
+        let (integer, fraction) = digits.split_at(point as usize);
+        (integer.to_string(), fraction.to_string())
+    };
+
+    let integer = match integer.trim_start_matches('0') {
+        "" => "0",
+        integer => integer,
+    };
+    match fraction.trim_end_matches('0') {
+        "" => format!("{}{}", sign, integer),
+        fraction => format!("{}{}.{}", sign, integer, fraction),
+    }
+}
+
+/// Shortest representation of `n` parsing back to the same value. Exponents are used below
+/// 1e-6 and from 1e21, like JavaScript does, and integers have no fraction.
+fn shortest(n: f64) -> String {
+    let magnitude = n.abs();
+    if magnitude != 0.0 && !(1e-6..1e21).contains(&magnitude) {
+        format!("{:e}", n)
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Returns `n` if it is an integer that `f64` represents exactly.
+fn exact_integer(n: f64) -> Option<f64> {
+    const MAX_EXACT: f64 = 9_007_199_254_740_992.0; // 2^53
+    (n.fract() == 0.0 && n.abs() <= MAX_EXACT).then_some(n)
+}
+
+/*
+************************** Gated behind bignum *************************
+*/
+
+/// Largest exponent, in absolute value, of the numbers expanded to all their digits by
+/// [`JsonNumber::as_big_int`] and the exact arithmetic, so that eg. `1e100000` from an
+/// untrusted input is not expanded to 100001 digits.
+#[cfg(feature = "bignum")]
+const MAX_BIG_EXPONENT: i64 = 4096;
+
+#[cfg(feature = "bignum")]
+impl JsonNumber {
+    /// Returns the exact value of the number, whatever its size or precision.
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn as_big_decimal(&self) -> Option<BigDecimal> {
+        match self {
+            Self::I64(n) => Some(BigDecimal::from(*n)),
+            Self::U64(n) => Some(BigDecimal::from(*n)),
+            number => BigDecimal::from_str(&number.to_string()).ok(),
+        }
+    }
+
+    /// Returns the number as a `BigInt` if it is an integer, eg. `1.5e3` but not `1.5`.
+    ///
+    /// The integers written with an exponent above 4096, eg. `1e100000`, are not expanded to
+    /// all their digits and return `None`.
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        match self {
+            Self::I64(n) => Some(BigInt::from(*n)),
+            Self::U64(n) => Some(BigInt::from(*n)),
+            number => {
+                let decimal = number.bounded_decimal()?;
+                if !decimal.is_integer() {
+                    return None;
+                }
+                let (digits, _) = decimal.with_scale(0).into_bigint_and_exponent();
+                Some(digits)
+            }
+        }
+    }
+
+    /// Compares the exact values of two numbers, so that `1e2`, `100` and `100.0` are equal
+    /// while `9007199254740993` is greater than `9007199254740992`.
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn exact_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Self::I64(a), Self::I64(b)) => Some(a.cmp(b)),
+            (Self::U64(a), Self::U64(b)) => Some(a.cmp(b)),
+            _ => Some(self.as_big_decimal()?.cmp(&other.as_big_decimal()?)),
+        }
+    }
+
+    /// Returns `true` if both numbers have the same exact value, see [`JsonNumber::exact_cmp`].
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn exact_eq(&self, other: &Self) -> bool {
+        self.exact_cmp(other) == Some(Ordering::Equal)
+    }
+
+    /// Adds two numbers exactly, eg. `0.1` and `0.2` to `0.3`.
+    ///
+    /// Returns `None` if an exponent is above 4096 in absolute value, eg. for `1e100000`,
+    /// whose sum with `1` would be expanded to all its digits.
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn exact_add(&self, other: &Self) -> Option<Self> {
+        let sum = self.bounded_decimal()? + other.bounded_decimal()?;
+        Some(Self::from(sum))
+    }
+
+    /// Subtracts `other` exactly, see [`JsonNumber::exact_add`].
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn exact_sub(&self, other: &Self) -> Option<Self> {
+        let difference = self.bounded_decimal()? - other.bounded_decimal()?;
+        Some(Self::from(difference))
+    }
+
+    /// Multiplies two numbers exactly, see [`JsonNumber::exact_add`].
+    ///
+    /// This method is only available when the `bignum` feature is enabled.
+    pub fn exact_mul(&self, other: &Self) -> Option<Self> {
+        let product = self.bounded_decimal()? * other.bounded_decimal()?;
+        Some(Self::from(product))
+    }
+
+    /// Returns the exact value of the number if its exponent is small enough to be expanded.
+    fn bounded_decimal(&self) -> Option<BigDecimal> {
+        let decimal = self.as_big_decimal()?;
+        (decimal.fractional_digit_count().abs() <= MAX_BIG_EXPONENT).then_some(decimal)
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl From<BigInt> for JsonNumber {
+    fn from(n: BigInt) -> Self {
+        Self::parse(&n.to_string())
+    }
+}
+
+#[cfg(feature = "bignum")]
+impl From<BigDecimal> for JsonNumber {
+    fn from(n: BigDecimal) -> Self {
+        Self::parse(&n.to_string())
+    }
+}
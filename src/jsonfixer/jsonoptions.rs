@@ -0,0 +1,211 @@
+//! Configuration options set by name, so that the applications embedding the fixer can let
+//! their users tune it from environment variables or command line flags.
+//!
+//! The options are named after the fields of the configuration, eg. `sort_keys`, with the
+//! `pretty` and `indent` shorthands of `beautify` and `indent_size`:
+//!
+//! | Option | Value |
+//! |---|---|
+//! | `pretty`, `beautify`, `space_between`, `sort_keys`, `preserve` | boolean |
+//! | `indent`, `indent_size` | number of spaces |
+//! | `indent_style` | `spaces` or `tabs` |
+//! | `output_style` | `json`, `json5` or `jsonc` |
+//! | `emit_trailing_commas`, `verify_output`, `normalize_numbers` | boolean |
+//! | `strict`, `python_compat`, `strip_comments`, `salvage`, `unescape_quotes` | boolean |
+//! | `keep_comments`, `trim_keys` | boolean |
+//! | `quote_values` | boolean, the repair of the same name |
+//! | `quote_bare_root` | boolean |
+//! | `bare_words` | `error`, `quote`, `drop` or `null` |
+//! | `non_finite_numbers` | `bare_word`, `error`, `null`, `string` or `keep` |
+//! | `undefined` | `bare_word`, `null` or `drop` |
+//! | `hex_numbers` | boolean |
+//! | `max_line_length`, `max_repairs` | number, `none` for no limit |
+//! | `min_confidence` | `certain`, `likely` or `speculative` |
+//! | `header` | text |
+//!
+//! Booleans are written `1`, `true`, `yes` or `on`, and `0`, `false`, `no` or `off`.
+
+use std::fmt;
+
+use super::{
+    jsonfixer_config::{BareWords, JsonFixerConfig, NonFiniteNumbers, OutputStyle, Undefined},
+    jsonfixer_report::Confidence,
+    jsonformatter::IndentStyle,
+};
+
+/// An option that could not be set, see [`JsonFixerConfig::set`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionError {
+    /// Name of the option, or of the environment variable
+    pub option: String,
+    pub value: String,
+    pub message: String,
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={:?}: {}", self.option, self.value, self.message)
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+impl JsonFixerConfig {
+    /// Reads the configuration from the environment variables starting with `prefix`, eg.
+    /// `JSON_FIXER_PRETTY=1` and `JSON_FIXER_INDENT=2` for the `JSON_FIXER` prefix. See the
+    /// [module documentation](super::jsonoptions) for the options.
+    ///
+    /// The options not set keep their default, and an unknown variable starting with the
+    /// prefix is an error so that typos are not silently ignored.
+    pub fn from_env(prefix: &str) -> Result<Self, OptionError> {
+        Self::from_vars(prefix, std::env::vars())
+    }
+
+    /// Reads the configuration from `vars`, pairs of environment variable names and values,
+    /// like [`JsonFixerConfig::from_env`] does from the environment.
+    pub fn from_vars<K, V>(
+        prefix: &str,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self, OptionError>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let prefix = format!("{}_", prefix.trim_end_matches('_'));
+        let mut options: Vec<(String, String, String)> = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let option = name.as_ref().strip_prefix(&prefix)?.to_lowercase();
+                Some((
+                    name.as_ref().to_string(),
+                    option,
+                    value.as_ref().to_string(),
+                ))
+            })
+            .collect();
+        // The strict preset first, so that the other options refine it
+        options.sort_by_key(|(_, option, _)| option != "strict");
+
+        let mut config = Self::default();
+        for (name, option, value) in options {
+            config.set(&option, &value).map_err(|err| OptionError {
+                option: name,
+                ..err
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Sets the option named `option`, eg. `indent` for a `--indent` command line flag. See
+    /// the [module documentation](super::jsonoptions) for the options.
+    pub fn set(&mut self, option: &str, value: &str) -> Result<(), OptionError> {
+        let error = |message: &str| OptionError {
+            option: option.to_string(),
+            value: value.to_string(),
+            message: message.to_string(),
+        };
+        let boolean = || parse_bool(value).ok_or_else(|| error("expected a boolean"));
+        let number = || {
+            value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| error("expected a number"))
+        };
+        let limit = || match value.trim() {
+            "none" => Ok(None),
+            _ => number().map(Some),
+        };
+
+        match option {
+            "pretty" | "beautify" => self.beautify = boolean()?,
+            "space_between" => self.space_between = boolean()?,
+            "sort_keys" => self.sort_keys = boolean()?,
+            "preserve" => self.preserve = boolean()?,
+            "indent" | "indent_size" => self.indent_size = number()?,
+            "indent_style" => {
+                self.indent_style = match value.trim() {
+                    "spaces" => IndentStyle::Spaces,
+                    "tabs" => IndentStyle::Tabs,
+                    _ => return Err(error("expected spaces or tabs")),
+                }
+            }
+            "output_style" => {
+                self.output_style = match value.trim() {
+                    "json" => OutputStyle::Json,
+                    "json5" => OutputStyle::Json5,
+                    "jsonc" => OutputStyle::Jsonc,
+                    _ => return Err(error("expected json, json5 or jsonc")),
+                }
+            }
+            "emit_trailing_commas" => self.emit_trailing_commas = boolean()?,
+            "verify_output" => self.verify_output = boolean()?,
+            "normalize_numbers" => self.normalize_numbers = boolean()?,
+            "strict" if boolean()? => {
+                let strict = Self::strict();
+                self.strict = true;
+                self.repairs = strict.repairs;
+                self.identifier_chars = strict.identifier_chars;
+                self.whitespaces = strict.whitespaces;
+            }
+            "strict" => self.strict = false,
+            "python_compat" => self.python_compat = boolean()?,
+            "strip_comments" => self.strip_comments = boolean()?,
+            "keep_comments" => self.keep_comments = boolean()?,
+            "salvage" => self.salvage = boolean()?,
+            "unescape_quotes" => self.unescape_quotes = boolean()?,
+            "trim_keys" => self.trim_keys = boolean()?,
+            "quote_values" => self.repairs.quote_values = boolean()?,
+            "quote_bare_root" => self.quote_bare_root = boolean()?,
+            "bare_words" => {
+                self.bare_words = match value.trim() {
+                    "error" => BareWords::Error,
+                    "quote" => BareWords::Quote,
+                    "drop" => BareWords::Drop,
+                    "null" => BareWords::Null,
+                    _ => return Err(error("expected error, quote, drop or null")),
+                }
+            }
+            "non_finite_numbers" => {
+                self.non_finite_numbers = match value.trim() {
+                    "bare_word" => NonFiniteNumbers::BareWord,
+                    "error" => NonFiniteNumbers::Error,
+                    "null" => NonFiniteNumbers::Null,
+                    "string" => NonFiniteNumbers::String,
+                    "keep" => NonFiniteNumbers::Keep,
+                    _ => return Err(error("expected bare_word, error, null, string or keep")),
+                }
+            }
+            "undefined" => {
+                self.undefined = match value.trim() {
+                    "bare_word" => Undefined::BareWord,
+                    "null" => Undefined::Null,
+                    "drop" => Undefined::Drop,
+                    _ => return Err(error("expected bare_word, null or drop")),
+                }
+            }
+            "hex_numbers" => self.hex_numbers = boolean()?,
+            "max_line_length" => self.max_line_length = limit()?,
+            "max_repairs" => self.max_repairs = limit()?,
+            "min_confidence" => {
+                self.min_confidence = match value.trim() {
+                    "certain" => Confidence::Certain,
+                    "likely" => Confidence::Likely,
+                    "speculative" => Confidence::Speculative,
+                    _ => return Err(error("expected certain, likely or speculative")),
+                }
+            }
+            "header" => self.header = Some(value.to_string()),
+            _ => return Err(error("unknown option")),
+        }
+        Ok(())
+    }
+}
+
+/// Parses the usual spellings of a boolean, case insensitively.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
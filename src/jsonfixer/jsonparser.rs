@@ -4,52 +4,99 @@
 //! such as missing commas, extra commas, or unquoted identifiers. It attempts to produce valid
 //! JSON output while maintaining the original data structure.
 
-use std::fmt::Write;
+use std::borrow::Cow;
 
 use super::{
-    json_tokenizer::{JsonTokenizer, Token},
-    jsonfixer_config::JsonFixerConfig,
-    jsonfixer_error::{JsonFixerError, SyntaxError},
+    json_tokenizer::{self, byte_offset, JsonTokenizer, Position, Token, TokenAction, TokenHook},
+    jsonescape::{self, EscapePolicy},
+    jsonfixer_config::{
+        BareWords, JsonFixerConfig, NonFiniteNumbers, OutputStyle, RepeatedColons, Undefined,
+    },
+    jsonfixer_error::{JsonFixerError, LimitError, SyntaxError},
+    jsonfixer_report::{Confidence, Dropped, FixReport, Repair, RepairKind},
     jsonformatter::{Formatter, JsonFormatter},
+    jsonindex::{IndexEntry, Span, ValueKind},
+    jsonnumber::JsonNumber,
+    jsonpath,
+    jsonreplay::{Replay, TokenBuffer},
+    jsonscan::StructureScan,
+    jsontransformer::{self, JsonTransformer, Transform},
+    jsonvalidator::JsonValidator,
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum JsonValue {
     Null,
     Boolean(bool),
-    Number(String),
+    Number(JsonNumber),
     String(String),
     Array(Vec<JsonEntryValue>),
     Object(Vec<JsonEntryValue>),
     Space(String),
 }
 
+impl JsonValue {
+    /// Returns `true` for null, booleans, numbers and strings.
+    pub fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            Self::Null | Self::Boolean(_) | Self::Number(_) | Self::String(_)
+        )
+    }
+}
+
 /*
 ************************** JsonParser *************************
 */
 
+/// Deepest nesting of arrays and objects accepted by the parser, deeper documents fail with
+/// `LimitError::TooDeep` instead of overflowing the stack. Such documents fit in the 2 MiB
+/// stack of the spawned threads in release builds, and in the 8 MiB stack of the main thread
+/// in debug builds.
+pub const MAX_DEPTH: usize = 512;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct JsonEntryValue {
     pub space_bf_key: Option<String>,
     pub key: Option<String>,
+    /// The key was an unquoted identifier in the input, eg. `{name: 1}`
+    pub unquoted_key: bool,
     pub space_af_key: Option<String>,
     pub space_bf_val: Option<String>,
     pub value: Option<JsonValue>,
     pub space_af_val: Option<String>,
+    /// Comment on the line where the value ends, after the value or its comma, eg. `// count`
+    /// for `"a": 1, // count`, see `JsonFixerConfig::keep_comments`.
+    pub comment: Option<String>,
+    /// Comments before the entry, eg. `// ports` on the line above `"port": 8080`. Those of an
+    /// entry without value are the comments before the closing bracket.
+    pub leading_comments: Vec<String>,
+    /// Span of a member in the input, from its key to the end of its value, when the tokens
+    /// were read from the input as is, see `Dropped`.
+    pub span: Option<Span>,
 }
 
 impl JsonEntryValue {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             space_bf_key: None,
             key: None,
+            unquoted_key: false,
             space_af_key: None,
             space_bf_val: None,
             value: None,
             space_af_val: None,
+            comment: None,
+            leading_comments: Vec::new(),
+            span: None,
         }
     }
 
+    /// Takes the entry, leaving an empty one in its place.
+    pub(crate) fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::new())
+    }
+
     pub fn get_sp_bf_key(&self) -> String {
         let sp = self.space_bf_key.clone();
         sp.unwrap_or_default()
@@ -77,52 +124,452 @@ impl JsonEntryValue {
     }
 }
 
+/// State of the array or object being parsed, kept across its entries.
+#[derive(Default)]
+struct ContainerState {
+    /// Separating comma waiting for the next entry
+    pending_comma: Option<Span>,
+    /// Closed before the closing bracket of an enclosing container eg. [{"a": 1]
+    closed_implicitly: bool,
+    /// Index of the next element of an array
+    index: usize,
+    /// An element of the array was found
+    has_values: bool,
+}
+
+/// What the parsing of an array or object does after one of its entries.
+enum Step {
+    Next,
+    End,
+    /// The object turned out to be a Python set, eg. `{1, 2}`
+    Set,
+}
+
+/// Callback receiving the members of the root object, see `with_member_sink`.
+type MemberSink<'a> = Box<dyn FnMut(JsonEntryValue) -> Result<(), JsonFixerError> + 'a>;
+
+/// Callback receiving the elements of the root array, see `with_element_sink`.
+type ElementSink<'a> = Box<dyn FnMut(JsonValue) -> Result<(), JsonFixerError> + 'a>;
+
 /// Internal parser that handles the actual JSON parsing and fixing.
 pub struct JsonParser<'a> {
+    input: &'a str,
     tokenizer: JsonTokenizer<'a>,
     current_token: Option<Token>,
-    config: JsonFixerConfig,
+    config: Cow<'a, JsonFixerConfig>,
+    repairs: Vec<Repair>,
+    /// Repairs applied by the tokenizer to the current token
+    token_repairs: Vec<Repair>,
+    /// Path of the value being parsed
+    path: String,
+    /// Emptied entry vectors reused for the parsed arrays and objects
+    pool: Vec<Vec<JsonEntryValue>>,
+    /// Hook rewriting the tokens before they are parsed
+    token_hook: Option<Box<dyn TokenHook + 'a>>,
+    /// Tokens parsed instead of the ones of the input, see `from_tokens` and `replay`
+    tokens: Option<Replay<'a>>,
+    /// Balance of the input, scanned on the first mismatched closing bracket. The brackets
+    /// inserted before a mismatched one are counted as closing ones.
+    structure: Option<StructureScan>,
+    /// Number of objects and arrays being parsed, see `salvage`
+    depth: usize,
+    /// Byte offsets of the start and end of the current token
+    token_span: (usize, usize),
+    /// Byte offset following the last consumed token, whitespaces aside
+    consumed_end: usize,
+    /// Paths, kinds and spans of the parsed values, see `with_index`
+    index: Option<Vec<IndexEntry>>,
+    /// The values parsed inside a value that failed are kept in the index, see
+    /// `with_partial_index`
+    partial_index: bool,
+    /// Receives the members of the root object instead of the object, see `with_member_sink`
+    member_sink: Option<MemberSink<'a>>,
+    /// Receives the elements of the root array instead of the array, see `with_element_sink`
+    element_sink: Option<ElementSink<'a>>,
 }
 
 impl<'a> JsonParser<'a> {
     /// Creates a new parser instance and advances to the first token.
     pub fn new(input: &'a str, config: JsonFixerConfig) -> Self {
+        Self::with_cow_config(input, Cow::Owned(config))
+    }
+
+    /// Creates a new parser instance borrowing its configuration and advances to the first
+    /// token.
+    pub fn with_borrowed_config(input: &'a str, config: &'a JsonFixerConfig) -> Self {
+        Self::with_cow_config(input, Cow::Borrowed(config))
+    }
+
+    fn with_cow_config(input: &'a str, config: Cow<'a, JsonFixerConfig>) -> Self {
         let mut parser = Self {
-            tokenizer: JsonTokenizer::new(input),
+            input,
+            tokenizer: JsonTokenizer::with_config(input, &config),
             current_token: None,
-            config: config,
+            config,
+            repairs: Vec::new(),
+            token_repairs: Vec::new(),
+            path: String::new(),
+            pool: Vec::new(),
+            token_hook: None,
+            tokens: None,
+            structure: None,
+            depth: 0,
+            token_span: (0, 0),
+            consumed_end: 0,
+            index: None,
+            partial_index: false,
+            member_sink: None,
+            element_sink: None,
         };
 
         let _ = parser.advance();
         parser
     }
 
+    /// Creates a new parser instance parsing the given tokens instead of tokenizing an input,
+    /// and advances to the first token.
+    ///
+    /// The tokens are parsed as if the tokenizer had read them, so the parser repairs apply
+    /// to them. As there is no input text, the `strict` validation is skipped.
+    pub fn from_tokens(tokens: impl IntoIterator<Item = Token>, config: JsonFixerConfig) -> Self {
+        let mut parser = Self::new("", config);
+        parser.tokens = Some(Replay::new(tokens.into_iter().collect()));
+        let _ = parser.advance();
+        parser
+    }
+
+    /// Creates a new parser instance parsing the tokens captured by `buffer`, along with the
+    /// repairs applied while capturing them, and advances to the first token.
+    ///
+    /// Like with `from_tokens`, the `strict` validation is skipped.
+    pub fn replay(buffer: &'a TokenBuffer, config: &'a JsonFixerConfig) -> Self {
+        let mut parser = Self::with_borrowed_config("", config);
+        parser.tokens = Some(Replay::of(buffer));
+        parser.structure = Some(buffer.structure().clone());
+        let _ = parser.advance();
+        parser
+    }
+
+    /// Uses the given emptied vectors for the parsed arrays and objects.
+    pub fn with_pool(mut self, pool: Vec<Vec<JsonEntryValue>>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Passes every token through `hook` before parsing it.
+    pub fn with_token_hook(mut self, hook: impl TokenHook + 'a) -> Self {
+        let mut hook: Box<dyn TokenHook + 'a> = Box::new(hook);
+        // The first token was read without the hook
+        let action = self
+            .current_token
+            .as_ref()
+            .map(|token| hook.on_token(token));
+        self.token_hook = Some(hook);
+        match action {
+            Some(TokenAction::Replace(token)) => self.current_token = Some(token),
+            Some(TokenAction::Drop) => {
+                self.token_repairs.clear();
+                let _ = self.advance();
+            }
+            _ => (),
+        }
+        self
+    }
+
+    /// Records the path, kind and span of every parsed value, parents first, see
+    /// `jsonindex::index_paths`.
+    pub(crate) fn with_index(mut self) -> Self {
+        self.index = Some(Vec::new());
+        self
+    }
+
+    /// Records the values like `with_index`, keeping the ones parsed before an error inside
+    /// the values that failed, see `jsonindex::extract_fields`.
+    pub(crate) fn with_partial_index(mut self) -> Self {
+        self.partial_index = true;
+        self.with_index()
+    }
+
+    /// Passes the members of the root object to `sink` as soon as they are parsed, with
+    /// their key unescaped, instead of keeping them in the object. See
+    /// `jsonmembers::for_each_member`.
+    pub(crate) fn with_member_sink(
+        mut self,
+        sink: impl FnMut(JsonEntryValue) -> Result<(), JsonFixerError> + 'a,
+    ) -> Self {
+        self.member_sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Passes the elements of the root array to `sink` as soon as they are parsed instead of
+    /// keeping them in the array. See `jsonsplit::split_array`.
+    pub(crate) fn with_element_sink(
+        mut self,
+        sink: impl FnMut(JsonValue) -> Result<(), JsonFixerError> + 'a,
+    ) -> Self {
+        self.element_sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Returns the values recorded since `with_index`.
+    pub(crate) fn take_index(&mut self) -> Vec<IndexEntry> {
+        self.index.take().unwrap_or_default()
+    }
+
+    /// Returns the vectors of the pool that were not used.
+    pub fn take_pool(&mut self) -> Vec<Vec<JsonEntryValue>> {
+        std::mem::take(&mut self.pool)
+    }
+
     /// Advances to the next token in the input stream.
     fn advance(&mut self) -> Result<(), JsonFixerError> {
-        self.current_token = self.tokenizer.next_token()?;
+        // The repairs of the consumed token relate to the value being parsed
+        for repair in std::mem::take(&mut self.token_repairs) {
+            self.push_repair(repair);
+        }
+        // Aborts as soon as the document needs too many repairs
+        self.check_repair_count(self.repairs.len())?;
+        if !matches!(self.current_token, None | Some(Token::Whitespace(_, _))) {
+            self.consumed_end = self.token_span.1;
+        }
+        let start = self.tokenizer.offset();
+        self.current_token = self.next_token()?;
+        self.token_span = match self.tokens.as_ref() {
+            Some(tokens) => tokens.span().unwrap_or_default(),
+            None => (start, self.tokenizer.offset()),
+        };
+        self.token_repairs = self.take_tokenizer_repairs();
 
         Ok(())
     }
 
+    /// Reads the next token, rewritten by the token hook if any.
+    fn next_token(&mut self) -> Result<Option<Token>, JsonFixerError> {
+        loop {
+            let token = match self.tokens.as_mut() {
+                Some(tokens) => tokens.next_token(),
+                None => self.tokenizer.next_token()?,
+            };
+            let (token, hook) = match (token, self.token_hook.as_mut()) {
+                (Some(token), Some(hook)) => (token, hook),
+                (token, _) => return Ok(token),
+            };
+            match hook.on_token(&token) {
+                TokenAction::Keep => return Ok(Some(token)),
+                TokenAction::Replace(token) => return Ok(Some(token)),
+                TokenAction::Drop => {
+                    self.take_tokenizer_repairs();
+                }
+            }
+        }
+    }
+
+    /// Returns the position of the last character read by the tokenizer, or of the last
+    /// token replayed.
+    fn input_position(&self) -> Position {
+        match self.tokens.as_ref() {
+            Some(tokens) => tokens.current_position(),
+            None => self.tokenizer.current_position(),
+        }
+    }
+
+    /// Returns the position following the last character of the input, where the brackets
+    /// left open are closed.
+    fn end_position(&self) -> Position {
+        let position = self.input_position();
+        Position {
+            column: position.column + 1,
+            ..position
+        }
+    }
+
+    /// Returns the repairs applied by the tokenizer, or replayed with the tokens, to the
+    /// tokens read so far.
+    fn take_tokenizer_repairs(&mut self) -> Vec<Repair> {
+        match self.tokens.as_mut() {
+            Some(tokens) => tokens.take_repairs(),
+            None => self.tokenizer.take_repairs(),
+        }
+    }
+
+    /// Fails if `count` repairs exceed the `max_repairs` of the configuration.
+    fn check_repair_count(&self, count: usize) -> Result<(), JsonFixerError> {
+        match self.config.max_repairs {
+            Some(max) if count > max => Err(JsonFixerError::Limit(LimitError::TooManyRepairs {
+                count,
+                max,
+            })),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails with `error` if repairs of the given kind are disabled in the configuration.
+    fn check_repair(&self, kind: RepairKind, error: SyntaxError) -> Result<(), JsonFixerError> {
+        if self.config.repairs.allows(kind) && self.config.accepts_repair(kind, kind.confidence()) {
+            Ok(())
+        } else {
+            Err(JsonFixerError::Syntax(error))
+        }
+    }
+
+    /// Records a repair applied at the given position of the value being parsed.
+    fn repair(&mut self, kind: RepairKind, position: Position, message: impl Into<String>) {
+        self.push_repair(Repair::at(kind, position, message));
+    }
+
+    /// Records a repair discarding `text`, the content of the input at `span`.
+    fn repair_dropping(
+        &mut self,
+        kind: RepairKind,
+        text: &str,
+        span: Span,
+        message: impl Into<String>,
+    ) {
+        let repair = Repair::at(kind, span.position.clone(), message);
+        let dropped = Dropped {
+            text: text.to_string(),
+            span: self.knows_offsets().then_some(span),
+        };
+        self.push_repair(repair.with_dropped(dropped));
+    }
+
+    /// Returns `true` if the byte offsets of the tokens are the ones of the input, which
+    /// they are not for unescaped quotes or tokens replayed without their offsets.
+    fn knows_offsets(&self) -> bool {
+        match self.tokens.as_ref() {
+            Some(tokens) => tokens.has_spans(),
+            None => !self.tokenizer.unescapes(),
+        }
+    }
+
+    /// Returns `true` if the byte offsets of the tokens locate them in `input`, which they do
+    /// not for unescaped quotes or replayed tokens.
+    fn slices_input(&self) -> bool {
+        self.tokens.is_none() && !self.tokenizer.unescapes()
+    }
+
+    /// Reports the member `entry`, dropped along with its bare word, as the content discarded
+    /// by the repair of the word, recorded after the `repairs` first ones if not suppressed.
+    fn drop_member(&mut self, entry: &JsonEntryValue, repairs: usize) {
+        let dropped = self.repairs[repairs..]
+            .iter_mut()
+            .rev()
+            .find_map(|repair| repair.dropped.as_mut());
+        let Some(dropped) = dropped else {
+            return;
+        };
+        match &entry.span {
+            Some(span) => {
+                dropped.text = self.input[span.start..span.end].to_string();
+                dropped.span = Some(span.clone());
+            }
+            None => {
+                let key = jsonescape::escape_str(&entry.get_key(), EscapePolicy::Json);
+                dropped.text = format!("\"{}\":{}", key, dropped.text);
+            }
+        }
+    }
+
+    /// Returns the span of the current token, found at `position`.
+    fn current_span(&self, position: Position) -> Span {
+        Span {
+            start: self.token_span.0,
+            end: self.token_span.1,
+            position,
+        }
+    }
+
+    /// Records `repair` at the path of the value being parsed, unless its kind is suppressed.
+    fn push_repair(&mut self, repair: Repair) {
+        if self.config.suppresses(repair.kind) {
+            return;
+        }
+        self.repairs.push(repair.with_path(&self.path));
+    }
+
+    /// Returns `true` if the sections that cannot be repaired are skipped, see `salvage`.
+    fn salvages(&self) -> bool {
+        let kind = RepairKind::Salvaged;
+        self.config.salvage && self.config.accepts_repair(kind, kind.confidence())
+    }
+
     /// Parses the entire JSON input and returns the fixed JSON string.
     pub fn parse(&mut self) -> Result<String, JsonFixerError> {
-        let mut output = String::new();
+        self.parse_with_report().map(|report| report.output)
+    }
+
+    /// Parses the entire JSON input and returns the fixed JSON string along with the
+    /// repairs that were applied.
+    pub fn parse_with_report(&mut self) -> Result<FixReport, JsonFixerError> {
+        let (value, repairs) = self.parse_document()?;
+        let output = self.format_document(&value)?;
+
+        Ok(FixReport { output, repairs })
+    }
+
+    /// Parses the entire JSON input and returns a preview of it where the strings longer
+    /// than `max_string_display` are truncated. The preview is meant for display only.
+    pub fn parse_preview(&mut self) -> Result<String, JsonFixerError> {
+        let (value, _) = self.parse_document()?;
+        let value = match self.config.max_string_display {
+            Some(max) => jsontransformer::truncate_strings(value, max),
+            None => value,
+        };
+
+        self.format_document(&value)
+    }
+
+    /// Parses the entire JSON input into a value with the configured transformations applied,
+    /// along with the repairs that were applied.
+    pub fn parse_document(&mut self) -> Result<(JsonValue, Vec<Repair>), JsonFixerError> {
+        self.config.check()?;
+        if self.config.strict && self.tokens.is_none() {
+            JsonValidator::validate_with(self.input, &self.config.compliance)
+                .map_err(JsonFixerError::Syntax)?;
+        }
+
         // Input can be whitespace-value-whitespace
         // Handle white space if any
         if let Some(Token::Whitespace(_sp, _)) = &self.current_token {
             // Ignore spaces before an actual value
             self.advance()?; // Consume spaces
         }
-        let config = self.config.clone();
         // Handle JsonValue
+        let bare_word = match &self.current_token {
+            Some(Token::UnquotedString(_, pos)) if self.config.quote_bare_root => Some(pos.clone()),
+            _ => None,
+        };
+        let repairs = self.repairs.len();
+        let value = match (self.parse_root(), bare_word) {
+            (Err(JsonFixerError::Syntax(_)), Some(pos)) if self.tokens.is_none() => {
+                self.repairs.truncate(repairs);
+                self.quote_bare_root(pos)
+            }
+            (result, _) => result?,
+        };
+
+        // Repairs found while tokenizing and parsing, in the input order
+        let mut repairs = std::mem::take(&mut self.repairs);
+        repairs.sort_by_key(|r| r.position.as_ref().map(|p| (p.line, p.column)));
+
+        let mut transformer = JsonTransformer::new(&self.config).with_input(self.input);
+        let value = transformer.transform(value)?;
+        let mut transformed = transformer.into_repairs();
+        transformed.retain(|r| !self.config.suppresses(r.kind));
+        repairs.append(&mut transformed);
+        self.check_repair_count(repairs.len())?;
+        let value = jsontransformer::insert_provenance(value, &self.config, repairs.len())?;
+
+        Ok((value, repairs))
+    }
+
+    /// Parses the root value, the whitespaces around it aside, and fails if anything else
+    /// follows it.
+    fn parse_root(&mut self) -> Result<JsonValue, JsonFixerError> {
+        self.skip_stray_words()?;
         let value = self.parse_value()?;
         self.advance()?; // Consume value
 
-        // Format the output
-        let formetter = JsonFormatter;
-        write!(output, "{}", formetter.format(&value, &config)?)
-            .map_err(|err| JsonFixerError::IO(err))?;
-
         loop {
             match &self.current_token {
                 Some(Token::Whitespace(_sp, _)) => {
@@ -140,35 +587,162 @@ impl<'a> JsonParser<'a> {
                 None => break, // EOF
             }
         }
+        Ok(value)
+    }
+
+    /// Returns the input as a string, the bare text starting with the word at `pos` that
+    /// could not be parsed, see `quote_bare_root`. Replayed tokens have no input text to quote.
+    fn quote_bare_root(&mut self, pos: Position) -> JsonValue {
+        let text = self.input.trim();
+        let message = format!("Quoted the bare text '{}'", text);
+        self.token_repairs.clear();
+        self.path.clear();
+        if let Some(index) = self.index.as_mut() {
+            index.clear();
+        }
+        self.repair(RepairKind::QuotedValue, pos, message);
+        JsonValue::String(text.to_string())
+    }
+
+    /// Formats a parsed value using the parser configuration.
+    fn format_document(&self, value: &JsonValue) -> Result<String, JsonFixerError> {
+        let mut output = String::new();
+        self.format_document_into(value, &mut output)?;
+        Ok(output)
+    }
+
+    /// Formats a parsed value using the parser configuration at the end of `output`, which
+    /// is expected to be empty.
+    pub fn format_document_into(
+        &self,
+        value: &JsonValue,
+        output: &mut String,
+    ) -> Result<(), JsonFixerError> {
+        // Format the output
+        let formetter = JsonFormatter;
+        formetter.format_into(value, &self.config, output)?;
+        self.verify_output(output)
+    }
 
+    /// Parses the entire JSON input and returns it formatted by `formatter` instead of the
+    /// default `JsonFormatter`.
+    pub fn parse_with_formatter<F: Formatter + ?Sized>(
+        &mut self,
+        formatter: &F,
+    ) -> Result<String, JsonFixerError> {
+        let (value, _) = self.parse_document()?;
+        let mut output = formatter.format(&value, &self.config)?;
+        self.verify_output(&mut output)?;
         Ok(output)
     }
 
-    /// Parses a JSON value (object, array, string, number, boolean, or null).
+    /// Parses the entire JSON input and returns the fixed JSON string, with `transform`
+    /// applied to every value before it is formatted.
+    pub fn parse_with_transform<T: Transform + ?Sized>(
+        &mut self,
+        transform: &mut T,
+    ) -> Result<String, JsonFixerError> {
+        let (value, _) = self.parse_document()?;
+        let value = jsontransformer::apply_transform(value, transform);
+        self.format_document(&value)
+    }
+
+    /// Re-validates the output with the strict validator when `verify_output` is set, the
+    /// output is moved into the error when it is not valid JSON.
+    fn verify_output(&self, output: &mut String) -> Result<(), JsonFixerError> {
+        if self.config.verify_output && self.config.output_style == OutputStyle::Json {
+            if let Err(diagnostic) = JsonValidator::validate_with(output, &self.config.compliance) {
+                let output = std::mem::take(output);
+                return Err(JsonFixerError::Internal { output, diagnostic });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses a JSON value, recorded in the index if any.
     fn parse_value(&mut self) -> Result<JsonValue, JsonFixerError> {
+        if self.index.is_none() {
+            return self.parse_any_value();
+        }
+        self.parse_indexed_value()
+    }
+
+    /// Parses a JSON value recorded in the index. Kept out of `parse_value` so that its
+    /// recursion uses little stack when the index is disabled.
+    #[inline(never)]
+    fn parse_indexed_value(&mut self) -> Result<JsonValue, JsonFixerError> {
+        // Recorded before parsing so that parents come first
+        let (start, token_end) = self.token_span;
+        let position = match &self.current_token {
+            Some(token) => token.pos().clone(),
+            None => self.input_position(),
+        };
+        let span = Span {
+            start,
+            end: token_end,
+            position,
+        };
+        let index = self.index.as_mut().expect("index enabled");
+        let slot = index.len();
+        index.push((self.path.clone(), ValueKind::Null, span));
+
+        let first_token = self.current_token.clone();
+        let result = self.parse_any_value();
+        // Scalars are consumed by the caller
+        let end = if self.current_token == first_token {
+            token_end
+        } else {
+            self.consumed_end
+        };
+
+        let index = self.index.as_mut().expect("index enabled");
+        match result.as_ref().ok().and_then(ValueKind::of) {
+            Some(kind) => {
+                index[slot].1 = kind;
+                index[slot].2.end = end;
+            }
+            None if self.partial_index => {
+                index.remove(slot);
+            }
+            None => index.truncate(slot),
+        }
+        result
+    }
+
+    /// Parses a JSON value (object, array, string, number, boolean, or null).
+    fn parse_any_value(&mut self) -> Result<JsonValue, JsonFixerError> {
         match &self.current_token {
             Some(Token::LeftBrace(_)) => self.parse_object(),
             Some(Token::LeftBracket(_)) => self.parse_array(),
-            Some(Token::String(s, _)) => Ok(JsonValue::String(s.replace('"', "\\\""))),
+            Some(Token::LeftParen(_)) => self.parse_tuple(),
+            Some(Token::String(s, _)) => Ok(JsonValue::String(s.clone())),
             Some(Token::Number(n, pos)) => {
-                let _result: f64 = n.parse().map_err(|_| {
-                    JsonFixerError::Syntax(SyntaxError::InvalidNumber(n.clone(), pos.clone()))
-                })?;
+                // The tokenizer only reads valid numbers, unlike the token hooks
+                if !json_tokenizer::is_number(n, self.config.repairs.fix_numbers) {
+                    return Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(
+                        n.clone(),
+                        pos.clone(),
+                    )));
+                }
 
-                Ok(JsonValue::Number(n.to_string()))
+                Ok(JsonValue::Number(JsonNumber::parse(n)))
             }
             Some(Token::Boolean(b, _)) => Ok(JsonValue::Boolean(*b)),
             Some(Token::Null(_)) => Ok(JsonValue::Null),
 
             Some(Token::UnquotedString(s, pos)) => {
-                //println!("Here....");
-                Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
-                    s.to_string(),
-                    pos.clone(),
-                )))
+                let (word, pos) = (s.to_string(), pos.clone());
+                if self.at_non_finite() {
+                    return self.parse_non_finite(word, pos);
+                }
+                if self.at_undefined() {
+                    return self.parse_undefined(word, pos);
+                }
+                self.parse_bare_word(word, pos)
             }
             None => Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(
-                self.tokenizer.current_position(),
+                self.input_position(),
             ))),
 
             // Should be reached
@@ -184,183 +758,1107 @@ impl<'a> JsonParser<'a> {
 
     /// Parses a JSON object, handling potential formatting issues.
     /// Supports unquoted keys and trailing/multiple commas.
+    #[inline(never)]
     fn parse_object(&mut self) -> Result<JsonValue, JsonFixerError> {
-        let mut obj = Vec::new();
+        let mut obj = self.pool.pop().unwrap_or_default();
+        let obj_path = self.path.clone();
+        let open_pos = match &self.current_token {
+            Some(token) => token.pos().clone(),
+            None => self.input_position(),
+        };
+        self.enter_container()?;
+        let depth = self.depth;
         self.advance()?; // Consume {
 
-        //let go_next_token = true;
-        while !self.current_token.is_none() {
-            let mut entry = JsonEntryValue::new();
-            //println!("Obj: {:?}", obj);
-            //println!("Current_token: {:?}", &self.current_token);
+        let mut state = ContainerState::default();
 
-            match &self.current_token {
-                Some(Token::RightBrace(_)) => break,
-                Some(Token::Comma(_)) => {
-                    // Empty entry
-                    // Consume consecutive commas (e.g., {,,})
-                    self.advance()?;
-                    continue;
-                }
-                Some(Token::Whitespace(sp, _)) => {
-                    // Consume spaces before 'Key' if any
-                    entry.space_bf_key = Some(sp.to_string());
-                    self.advance()?;
+        while self.current_token.is_some() {
+            self.flush_members(&mut obj, depth)?;
+            let mut entry = JsonEntryValue::new();
+            match self.parse_member(&mut obj, &mut entry, &obj_path, &mut state) {
+                Ok(Step::Next) => (),
+                Ok(Step::End) => break,
+                Ok(Step::Set) => return self.parse_set(obj_path, open_pos, obj),
+                Err(JsonFixerError::Syntax(error)) if self.salvages() => {
+                    self.path = match &entry.key {
+                        Some(key) => jsonpath::key(&obj_path, key),
+                        None => obj_path.clone(),
+                    };
+                    state.pending_comma = self.salvage(error, depth)?;
+                    self.path = obj_path.clone();
+                    // The member is dropped when its key is unknown
+                    if entry.key.is_some() {
+                        entry.value.get_or_insert(JsonValue::Null);
+                        obj.push(entry);
+                    }
                 }
-                _ => (),
+                Err(error) => return Err(error),
             }
+        }
+        self.flush_members(&mut obj, depth)?;
+        if self.config.keeps_comments() {
+            attach_comments(&mut obj, true);
+        }
 
-            // parse key
-            match &self.current_token {
-                Some(Token::RightBrace(_)) => {
-                    // Empty object with inside spaces eg. {   }
-                    entry.value = None;
-                    obj.push(entry);
-                    break;
-                }
-                Some(Token::Comma(_)) => {
-                    // Empty entry
-                    // Consume consecutive commas (e.g., {,,})
+        if let Some(span) = state.pending_comma {
+            let error = SyntaxError::UnexpectedToken("','".to_string(), span.position.clone());
+            self.check_repair(RepairKind::RemovedComma, error)?;
+            let message = "Removed trailing ','";
+            self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+        }
+        if self.current_token.is_none() {
+            let error = SyntaxError::UnexpectedEndOfInput(self.input_position());
+            self.check_repair(RepairKind::ClosedBracket, error)?;
+            let pos = self.end_position();
+            self.repair(RepairKind::ClosedBracket, pos, "Inserted missing '}'");
+        }
+
+        self.depth -= 1;
+        if !state.closed_implicitly {
+            self.advance()?; // Consume }
+        }
+        Ok(JsonValue::Object(obj))
+    }
+
+    /// Passes the members parsed so far to the member sink, if any, when `obj` is the root
+    /// object.
+    fn flush_members(
+        &mut self,
+        obj: &mut Vec<JsonEntryValue>,
+        depth: usize,
+    ) -> Result<(), JsonFixerError> {
+        let Some(sink) = self.member_sink.as_mut().filter(|_| depth == 1) else {
+            return Ok(());
+        };
+        for entry in obj.drain(..) {
+            if entry.key.is_some() && entry.value.is_some() {
+                sink(entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the key of a member of an object into `entry` up to its colon. Returns the step
+    /// of the object when there is no member to parse the value of, eg. at its end. Kept out
+    /// of `parse_member` so that its recursion uses little stack.
+    #[inline(never)]
+    fn parse_member_key(
+        &mut self,
+        obj: &mut Vec<JsonEntryValue>,
+        entry: &mut JsonEntryValue,
+        obj_path: &str,
+        state: &mut ContainerState,
+    ) -> Result<Option<Step>, JsonFixerError> {
+        //println!("Obj: {:?}", obj);
+        //println!("Current_token: {:?}", &self.current_token);
+
+        match &self.current_token {
+            Some(Token::RightBrace(_)) => return Ok(Some(Step::End)),
+            Some(Token::Comma(pos)) => {
+                // Empty entry
+                // Consume consecutive commas (e.g., {,,})
+                let error = SyntaxError::UnexpectedToken("','".to_string(), pos.clone());
+                self.check_repair(RepairKind::RemovedComma, error)?;
+                let span = self.current_span(pos.clone());
+                let message = "Removed extra ','";
+                self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+                self.advance()?;
+                return Ok(Some(Step::Next));
+            }
+            Some(Token::Whitespace(sp, _)) => {
+                // Consume spaces before 'Key' if any
+                entry.space_bf_key = Some(sp.to_string());
+                self.advance()?;
+            }
+            _ => (),
+        }
+
+        let quoted_key = matches!(self.current_token, Some(Token::String(_, _)));
+        // Value and position of a `true`, `false` or `null` key
+        let mut keyword_key = None;
+
+        // Python sets are recognized by their first element
+        let is_first = !obj.iter().any(|e: &JsonEntryValue| e.value.is_some());
+        if self.config.python_compat && is_first && self.at_set_element() {
+            // Set starting with a value that cannot be a key eg. {1, 2}
+            entry.space_bf_val = entry.space_bf_key.take();
+            obj.push(entry.take());
+            return Ok(Some(Step::Set));
+        }
+
+        if self.closes_implicitly('}')? {
+            if entry.space_bf_key.is_some() {
+                obj.push(entry.take());
+            }
+            // Unless the mismatched bracket was replaced by a '}'
+            state.closed_implicitly = !matches!(self.current_token, Some(Token::RightBrace(_)));
+            return Ok(Some(Step::End));
+        }
+
+        // The member starts at its key
+        if self.slices_input() {
+            let pos = self.current_token.as_ref().map(|token| token.pos().clone());
+            entry.span = pos.map(|pos| self.current_span(pos));
+        }
+
+        // parse key
+        match &self.current_token {
+            Some(Token::RightBrace(_)) => {
+                // Empty object with inside spaces eg. {   }
+                entry.value = None;
+                obj.push(entry.take());
+                return Ok(Some(Step::End));
+            }
+            Some(Token::Comma(pos)) => {
+                // Empty entry
+                // Consume consecutive commas (e.g., {,,})
+                let error = SyntaxError::UnexpectedToken("','".to_string(), pos.clone());
+                self.check_repair(RepairKind::RemovedComma, error)?;
+                let span = self.current_span(pos.clone());
+                let message = "Removed extra ','";
+                self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+                entry.value = None;
+                obj.push(entry.take());
+                self.advance()?;
+                return Ok(Some(Step::Next));
+            }
+            Some(Token::String(k, _)) => {
+                entry.key = Some(k.to_string());
+                self.path = jsonpath::key(obj_path, k);
+
+                self.advance()?; // Consume the key
+            }
+            Some(Token::UnquotedString(k, pos)) => {
+                let error = SyntaxError::UnexpectedToken(k.to_string(), pos.clone());
+                self.check_repair(RepairKind::QuotedKey, error)?;
+                entry.key = Some(k.to_string());
+                entry.unquoted_key = true;
+                self.path = jsonpath::key(obj_path, k);
+                let message = format!("Quoted key '{}'", k);
+                self.repair(RepairKind::QuotedKey, pos.clone(), message);
+
+                self.advance()?; // Consume the key
+            }
+            Some(token @ (Token::Boolean(_, _) | Token::Null(_))) => {
+                // Keyword in key position eg. {null: 1}
+                let (k, literal) = match token {
+                    Token::Boolean(b, _) => (b.to_string(), JsonValue::Boolean(*b)),
+                    _ => ("null".to_string(), JsonValue::Null),
+                };
+                let pos = token.pos().clone();
+                let error = SyntaxError::UnexpectedToken(k.clone(), pos.clone());
+                self.check_repair(RepairKind::QuotedKey, error)?;
+                self.path = jsonpath::key(obj_path, &k);
+                entry.key = Some(k);
+                entry.unquoted_key = true;
+                keyword_key = Some((literal, pos));
+
+                self.advance()?; // Consume the key
+            }
+            Some(Token::Number(n, pos)) if self.config.quote_numeric_keys => {
+                let error = SyntaxError::UnexpectedToken(n.to_string(), pos.clone());
+                self.check_repair(RepairKind::QuotedKey, error)?;
+                entry.key = Some(n.to_string());
+                self.path = jsonpath::key(obj_path, n);
+                let message = format!("Quoted numeric key '{}'", n);
+                self.repair(RepairKind::QuotedKey, pos.clone(), message);
+
+                self.advance()?; // Consume the key
+            }
+            token => {
+                if let Some(t) = &token {
+                    return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
+                        format!("\nExpected a 'Key' after '{}' but found {}", '{', t.get()),
+                        t.pos().clone(),
+                    )));
+                } else {
+                    // Reach the EOF with no closing } a no key
+                    // Empty object with inside spaces and not closed eg. {
                     entry.value = None;
-                    obj.push(entry);
-                    self.advance()?;
-                    continue;
+                    obj.push(entry.take());
+                    return Ok(Some(Step::End));
                 }
-                Some(Token::String(k, _)) | Some(Token::UnquotedString(k, _)) => {
-                    entry.key = Some(k.to_string());
+            }
+        }
 
-                    self.advance()?; // Consume the key
-                }
-                token => {
-                    if let Some(t) = &token {
-                        return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
-                            format!("\nExpected a 'Key' after '{}' but found {}", '{', t.get()),
-                            t.pos().clone(),
-                        )));
-                    } else {
-                        // Reach the EOF with no closing } a no key
-                        // Empty object with inside spaces and not closed eg. {
-                        entry.value = None;
-                        obj.push(entry);
-                        break;
-                    }
+        // Consume spaces before ':' if any
+        if let Some(Token::Whitespace(sp, _)) = &self.current_token {
+            entry.space_af_key = Some(sp.to_string());
+            self.advance()?;
+        }
+
+        if self.config.python_compat
+            && is_first
+            && (quoted_key || keyword_key.is_some())
+            && matches!(
+                self.current_token,
+                Some(Token::Comma(_)) | Some(Token::RightBrace(_))
+            )
+        {
+            // Set of strings eg. {'a', 'b'} or of keywords eg. {true, false}, the key is the
+            // first element
+            let element_path = jsonpath::index(obj_path, 0);
+            for repair in self.repairs.iter_mut() {
+                if repair.path.as_ref() == Some(&self.path) {
+                    repair.path = Some(element_path.clone());
                 }
             }
+            let mut element = JsonEntryValue::new();
+            element.value = match keyword_key {
+                Some((literal, _)) => Some(literal),
+                None => Some(JsonValue::String(entry.get_key())),
+            };
+            element.space_bf_val = entry.space_bf_key.take();
+            element.space_af_val = entry.space_af_key.take();
+            obj.push(element);
+            return Ok(Some(Step::Set));
+        }
 
-            // Consume spaces before ':' if any
-            if let Some(Token::Whitespace(sp, _)) = &self.current_token {
-                entry.space_af_key = Some(sp.to_string());
-                self.advance()?;
+        if let Some((_, pos)) = keyword_key {
+            let message = format!("Quoted keyword key '{}'", entry.get_key());
+            self.repair(RepairKind::QuotedKey, pos, message);
+        }
+
+        // Expect colon
+        match &self.current_token {
+            Some(Token::Colon(_)) => {
+                self.advance()?; // Consume the :
+            }
+            Some(unexped_token) => {
+                return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
+                    format!(
+                        "\nExpected ':' after a 'key' but found {}",
+                        unexped_token.get()
+                    ),
+                    unexped_token.pos().clone(),
+                )));
+            }
+            None => {
+                // Unexpected end of the input
+                return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(
+                    self.input_position(),
+                )));
             }
+        }
+        Ok(None)
+    }
 
-            // Expect colon
-            match &self.current_token {
-                Some(Token::Colon(_)) => {
-                    self.advance()?; // Consume the : 
+    /// Parses a member of an object into `entry`, pushed to `obj` once complete.
+    fn parse_member(
+        &mut self,
+        obj: &mut Vec<JsonEntryValue>,
+        entry: &mut JsonEntryValue,
+        obj_path: &str,
+        state: &mut ContainerState,
+    ) -> Result<Step, JsonFixerError> {
+        if let Some(step) = self.parse_member_key(obj, entry, obj_path, state)? {
+            return Ok(step);
+        }
+
+        // Consume spaces before Value if any
+        if let Some(Token::Whitespace(sp, _)) = &self.current_token {
+            entry.space_bf_val = Some(sp.to_string());
+            self.advance()?;
+        }
+
+        // Parse value
+        let comma = state.pending_comma.take();
+        self.skip_stray_words()?;
+        let dropped = self.at_dropped_word();
+        let curr_t = self.current_token.clone();
+        let repairs = self.repairs.len();
+        entry.value = Some(self.parse_value()?);
+
+        // Primitive value needs to be consumed after parse value
+        if curr_t == self.current_token {
+            self.advance()?;
+        }
+        self.path = obj_path.to_string();
+        if let Some(span) = entry.span.as_mut() {
+            span.end = self.consumed_end;
+        }
+        if dropped {
+            // The member is dropped, the comma before it separates the next one
+            self.drop_member(entry, repairs);
+            entry.key = None;
+            entry.value = None;
+            entry.span = None;
+            state.pending_comma = comma;
+        }
+
+        // Consume spaces After Value if any
+        if let Some(Token::Whitespace(sp, _)) = &self.current_token {
+            entry.space_af_val = Some(sp.to_string());
+            self.advance()?;
+        }
+
+        if let Some(Token::Colon(_)) = &self.current_token {
+            let entry_path = jsonpath::key(obj_path, &entry.get_key());
+            self.parse_repeated_colons(entry, entry_path)?;
+            self.path = obj_path.to_string();
+        }
+
+        if self.closes_implicitly('}')? {
+            obj.push(entry.take());
+            // Unless the mismatched bracket was replaced by a '}'
+            state.closed_implicitly = !matches!(self.current_token, Some(Token::RightBrace(_)));
+            return Ok(Step::End);
+        }
+
+        // Consume the comma separating the entries if any
+        match &self.current_token {
+            Some(Token::Comma(pos)) => {
+                let span = self.current_span(pos.clone());
+                // The comma of a dropped member is the one before it
+                if state.pending_comma.is_some() {
+                    let error = SyntaxError::UnexpectedToken("','".to_string(), pos.clone());
+                    self.check_repair(RepairKind::RemovedComma, error)?;
+                    let message = "Removed extra ','";
+                    self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+                } else {
+                    state.pending_comma = Some(span);
                 }
-                Some(unexped_token) => {
+                self.advance()?;
+            }
+            Some(Token::RightBrace(_)) | None => (),
+            Some(token) => {
+                let pos = token.pos().clone();
+                let error = SyntaxError::MissingComma(pos.clone());
+                self.check_repair(RepairKind::InsertedComma, error)?;
+                self.repair(RepairKind::InsertedComma, pos, "Inserted missing ','");
+            }
+        }
+        // Push the entry
+        obj.push(entry.take());
+        Ok(Step::Next)
+    }
+
+    /// Repairs the colons repeated after the value of `entry` according to `repeated_colons`,
+    /// eg. `{"a": "b": 1}`. The current token is the first repeated colon.
+    #[inline(never)]
+    fn parse_repeated_colons(
+        &mut self,
+        entry: &mut JsonEntryValue,
+        mut path: String,
+    ) -> Result<(), JsonFixerError> {
+        let mut value = entry.value.take().unwrap_or(JsonValue::Null);
+        let mut keys = Vec::new();
+
+        while let Some(Token::Colon(pos)) = &self.current_token {
+            let pos = pos.clone();
+            let key = match &value {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Number(n) => n.to_string(),
+                JsonValue::Boolean(b) => b.to_string(),
+                JsonValue::Null => "null".to_string(),
+                _ => break, // Reported as a missing comma
+            };
+
+            self.path = path.clone();
+            // Repairing a repeated colon is a guess, see `min_confidence`
+            let kind = RepairKind::RepeatedColon;
+            let policy = match self.config.accepts_repair(kind, kind.confidence()) {
+                true => self.config.repeated_colons.clone(),
+                false => RepeatedColons::Error,
+            };
+            match policy {
+                RepeatedColons::Error => {
                     return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(
-                        format!(
-                            "\nExpected ':' after a 'key' but found {}",
-                            unexped_token.get()
-                        ),
-                        unexped_token.pos().clone(),
+                        format!("\nRepeated ':' after the value of '{}'", entry.get_key()),
+                        pos,
                     )));
                 }
-                None => {
-                    // Unexpected end of the input
-                    return Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(
-                        self.tokenizer.current_position(),
-                    )));
+                RepeatedColons::Nest => {
+                    let message = format!("Nested the value after '{}:' in an object", key);
+                    self.repair(RepairKind::RepeatedColon, pos, message);
+                    path = jsonpath::key(&path, &key);
+                    keys.push(key);
+                }
+                RepeatedColons::Drop => {
+                    let message = format!("Dropped '{}:'", key);
+                    self.repair(RepairKind::RepeatedColon, pos, message);
                 }
             }
-
-            // Consume spaces before Value if any
-            if let Some(Token::Whitespace(sp, _)) = &self.current_token {
-                entry.space_bf_val = Some(sp.to_string());
+            self.advance()?; // Consume the :
+            if let Some(Token::Whitespace(_, _)) = &self.current_token {
                 self.advance()?;
             }
 
-            // Parse value
-            entry.value = Some(self.parse_value()?);
+            self.path = path.clone();
+            let curr_t = self.current_token.clone();
+            value = self.parse_value()?;
+            if curr_t == self.current_token {
+                self.advance()?;
+            }
 
-            // Consume spaces After Value if any
+            entry.space_af_val = None;
             if let Some(Token::Whitespace(sp, _)) = &self.current_token {
                 entry.space_af_val = Some(sp.to_string());
                 self.advance()?;
             }
-
-            self.advance()?;
-            // Push the entry
-            obj.push(entry);
         }
 
-        self.advance()?; // Consume }
-        Ok(JsonValue::Object(obj))
+        for key in keys.into_iter().rev() {
+            let mut nested = self.pool.pop().unwrap_or_default();
+            let mut member = JsonEntryValue::new();
+            member.key = Some(key);
+            member.value = Some(value);
+            nested.push(member);
+            value = JsonValue::Object(nested);
+        }
+        entry.value = Some(value);
+        Ok(())
     }
 
     /// Parses a JSON array, handling trailing/multiple commas.
     fn parse_array(&mut self) -> Result<JsonValue, JsonFixerError> {
-        let mut arr = Vec::new();
+        self.enter_container()?;
         self.advance()?; // Consume [
+        let arr = self.pool.pop().unwrap_or_default();
+        self.parse_elements(arr, ']')
+    }
+
+    /// Counts the array or object being entered, fails when it is nested deeper than
+    /// [`MAX_DEPTH`].
+    fn enter_container(&mut self) -> Result<(), JsonFixerError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(JsonFixerError::Limit(LimitError::TooDeep {
+                path: self.path.clone(),
+                max: MAX_DEPTH,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Parses a Python tuple into an array, eg. `('a', 'b')` to `["a","b"]`.
+    #[inline(never)]
+    fn parse_tuple(&mut self) -> Result<JsonValue, JsonFixerError> {
+        if let Some(token) = &self.current_token {
+            let pos = token.pos().clone();
+            self.repair(
+                RepairKind::ConvertedToArray,
+                pos,
+                "Converted tuple to array",
+            );
+        }
+        self.enter_container()?;
+        self.advance()?; // Consume (
+        let arr = self.pool.pop().unwrap_or_default();
+        self.parse_elements(arr, ')')
+    }
+
+    /// Parses the remaining elements of a Python set into an array, eg. `{'a', 'b'}` to
+    /// `["a","b"]`. `entries` holds the elements parsed while looking for a key.
+    #[inline(never)]
+    fn parse_set(
+        &mut self,
+        obj_path: String,
+        open_pos: Position,
+        entries: Vec<JsonEntryValue>,
+    ) -> Result<JsonValue, JsonFixerError> {
+        self.path = obj_path;
+        self.repair(
+            RepairKind::ConvertedToArray,
+            open_pos,
+            "Converted set to array",
+        );
+        self.parse_elements(entries, '}')
+    }
 
-        while !self.current_token.is_none() {
+    /// Parses the elements of an array up to the `closing` bracket, handling trailing/multiple
+    /// commas. `arr` holds the elements already parsed.
+    fn parse_elements(
+        &mut self,
+        mut arr: Vec<JsonEntryValue>,
+        closing: char,
+    ) -> Result<JsonValue, JsonFixerError> {
+        let arr_path = self.path.clone();
+        let depth = self.depth;
+
+        let index = arr.iter().filter(|e| e.value.is_some()).count();
+        let mut state = ContainerState {
+            index,
+            has_values: index > 0,
+            ..Default::default()
+        };
+
+        while self.current_token.is_some() {
+            self.flush_elements(&mut arr, depth)?;
             let mut entry = JsonEntryValue::new();
+            match self.parse_element(&mut arr, &mut entry, &arr_path, closing, &mut state) {
+                Ok(Step::Next) => (),
+                Ok(Step::End | Step::Set) => break,
+                Err(JsonFixerError::Syntax(error)) if self.salvages() => {
+                    if entry.value.is_none() {
+                        entry.value = Some(JsonValue::Null);
+                        state.index += 1;
+                    }
+                    self.path = jsonpath::index(&arr_path, state.index - 1);
+                    state.pending_comma = self.salvage(error, depth)?;
+                    self.path = arr_path.clone();
+                    arr.push(entry);
+                    state.has_values = true;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        self.flush_elements(&mut arr, depth)?;
+        if self.config.keeps_comments() {
+            attach_comments(&mut arr, false);
+        }
 
-            match &self.current_token {
-                Some(Token::RightBracket(_)) => break, // Empty array without spaces
-                Some(Token::Comma(_)) => {
-                    // Consume consecutive commas (e.g., [,,])
-                    self.advance()?;
-                    continue;
+        if let Some(span) = state.pending_comma {
+            let error = SyntaxError::UnexpectedToken("','".to_string(), span.position.clone());
+            self.check_repair(RepairKind::RemovedComma, error)?;
+            let message = "Removed trailing ','";
+            self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+        }
+        if self.current_token.is_none() {
+            let error = SyntaxError::UnexpectedEndOfInput(self.input_position());
+            self.check_repair(RepairKind::ClosedBracket, error)?;
+            let pos = self.end_position();
+            let message = format!("Inserted missing '{}'", closing);
+            self.repair(RepairKind::ClosedBracket, pos, message);
+        }
+
+        self.depth -= 1;
+        if !state.closed_implicitly {
+            self.advance()?; // Consume ]
+        }
+
+        Ok(JsonValue::Array(arr))
+    }
+
+    /// Passes the elements parsed so far to the element sink, if any, when `arr` is the root
+    /// array.
+    fn flush_elements(
+        &mut self,
+        arr: &mut Vec<JsonEntryValue>,
+        depth: usize,
+    ) -> Result<(), JsonFixerError> {
+        let Some(sink) = self.element_sink.as_mut().filter(|_| depth == 1) else {
+            return Ok(());
+        };
+        for value in arr.drain(..).filter_map(|entry| entry.value) {
+            sink(value)?;
+        }
+        Ok(())
+    }
+
+    /// Parses an element of an array into `entry`, pushed to `arr` once complete.
+    fn parse_element(
+        &mut self,
+        arr: &mut Vec<JsonEntryValue>,
+        entry: &mut JsonEntryValue,
+        arr_path: &str,
+        closing: char,
+        state: &mut ContainerState,
+    ) -> Result<Step, JsonFixerError> {
+        match &self.current_token {
+            // Empty array without spaces
+            Some(token) if token.closes(closing) => return Ok(Step::End),
+            Some(Token::Comma(pos)) => {
+                // Consume consecutive commas (e.g., [,,])
+                let pos = pos.clone();
+                self.handle_array_comma(&mut state.pending_comma, state.has_values, pos)?;
+                self.advance()?;
+                return Ok(Step::Next);
+            }
+            Some(Token::Whitespace(sp, _)) => {
+                // Consume spaces
+                entry.space_bf_val = Some(sp.to_string());
+                self.advance()?;
+            }
+            _ => (),
+        }
+
+        match &self.current_token {
+            Some(token) if token.closes(closing) => {
+                // Empty array with spaces inside it
+                entry.value = None;
+                arr.push(entry.take());
+                Ok(Step::End)
+            }
+            Some(Token::Comma(pos)) => {
+                // Empty array with spaces inside it and commas
+                // Consume consecutive commas (e.g., [,,])
+                let pos = pos.clone();
+                self.handle_array_comma(&mut state.pending_comma, state.has_values, pos)?;
+                entry.value = None;
+                arr.push(entry.take());
+                self.advance()?;
+                Ok(Step::Next)
+            }
+            _ => {
+                if self.closes_implicitly(closing)? {
+                    if entry.space_bf_val.is_some() {
+                        arr.push(entry.take());
+                    }
+                    // Unless the mismatched bracket was replaced by the closing one
+                    state.closed_implicitly = !self
+                        .current_token
+                        .as_ref()
+                        .is_some_and(|token| token.closes(closing));
+                    return Ok(Step::End);
                 }
-                Some(Token::Whitespace(sp, _)) => {
-                    // Consume spaces
-                    entry.space_bf_val = Some(sp.to_string());
-                    self.advance()?;
+                if state.has_values && state.pending_comma.is_none() {
+                    if let Some(token) = &self.current_token {
+                        let pos = token.pos().clone();
+                        let error = SyntaxError::MissingComma(pos.clone());
+                        self.check_repair(RepairKind::InsertedComma, error)?;
+                        self.repair(RepairKind::InsertedComma, pos, "Inserted missing ','");
+                    }
                 }
-                _ => (),
-            }
+                let (comma, had_values) = (state.pending_comma.take(), state.has_values);
+                state.has_values = true;
 
-            match &self.current_token {
-                Some(Token::RightBracket(_)) => {
-                    // Empty array with spaces inside it
+                //println!("current_token : {:?}", self.current_token);
+                // Get the value
+                self.path = jsonpath::index(arr_path, state.index);
+                let dropped = self.at_dropped_word();
+                let curr_t = self.current_token.clone();
+                entry.value = Some(self.parse_value()?);
+                if dropped {
+                    // The element is dropped, the comma before it separates the next one
                     entry.value = None;
-                    arr.push(entry);
-                    break;
+                    state.pending_comma = comma;
+                    state.has_values = had_values;
+                } else {
+                    state.index += 1;
                 }
-                Some(Token::Comma(_)) => {
-                    // Empty array with spaces inside it and commas
-                    // Consume consecutive commas (e.g., [,,])
-                    entry.value = None;
-                    arr.push(entry);
+
+                // Primitive value needs to be consumed after parse value
+                if curr_t == self.current_token {
                     self.advance()?;
-                    continue;
                 }
-                _ => {
-                    //println!("current_token : {:?}", self.current_token);
-                    // Get the value
-                    let curr_t = self.current_token.clone();
-                    entry.value = Some(self.parse_value()?);
-
-                    // Primitive value needs to be consumed after parse value
-                    if curr_t == self.current_token {
-                        self.advance()?;
-                    }
+                self.path = arr_path.to_string();
 
-                    // Consume spaces After Value if any
-                    if let Some(Token::Whitespace(sp, _)) = &self.current_token {
-                        entry.space_af_val = Some(sp.to_string());
-                        self.advance()?;
-                    }
+                // Consume spaces After Value if any
+                if let Some(Token::Whitespace(sp, _)) = &self.current_token {
+                    entry.space_af_val = Some(sp.to_string());
+                    self.advance()?;
+                }
 
-                    arr.push(entry);
+                arr.push(entry.take());
+                Ok(Step::Next)
+            }
+        }
+    }
+
+    /// Skips the section of the input that failed with `error` up to the next boundary of the
+    /// entries of the container parsed at `depth`, see `JsonFixerConfig::salvage`, and reads
+    /// the token following it. Returns the span of the comma separating the section from the
+    /// next entry, if any.
+    #[inline(never)]
+    fn salvage(
+        &mut self,
+        mut error: SyntaxError,
+        depth: usize,
+    ) -> Result<Option<Span>, JsonFixerError> {
+        loop {
+            // Brackets opened by the section that are left unclosed
+            let open = self.depth - depth;
+            self.depth = depth;
+
+            // The token that failed is kept when it is the boundary itself eg. {"a": }
+            let at_boundary = open == 0
+                && match &self.current_token {
+                    Some(
+                        token @ (Token::Comma(_)
+                        | Token::RightBrace(_)
+                        | Token::RightBracket(_)
+                        | Token::RightParen(_)),
+                    ) => token.pos() == error.position(),
+                    _ => false,
+                };
+            let comma = if at_boundary {
+                None
+            } else {
+                self.token_repairs.clear();
+                self.take_tokenizer_repairs();
+                self.tokenizer.skip_corrupted(open)
+            };
+
+            // The skipped section ends before the comma and the whitespaces around it, the
+            // offsets are the ones of the input as replayed tokens are not salvaged
+            let start = byte_offset(self.input, error.position());
+            let skipped = match self.tokenizer.unescapes() {
+                true => "",
+                false => self
+                    .input
+                    .get(start..self.tokenizer.offset())
+                    .unwrap_or_default(),
+            }
+            .trim_end();
+            let text = match comma {
+                Some(_) => skipped.strip_suffix(',').unwrap_or(skipped).trim_end(),
+                None => skipped,
+            };
+            let section = Span {
+                start,
+                end: start + text.len(),
+                position: error.position().clone(),
+            };
+            let comma = comma.map(|position| Span {
+                start: (start + skipped.len()).saturating_sub(1),
+                end: start + skipped.len(),
+                position,
+            });
+
+            let end = self.input_position();
+            let message = format!(
+                "Skipped the corrupted section up to line {}, column {} ({})",
+                end.line, end.column, error
+            );
+            if at_boundary || text.is_empty() {
+                self.repair(RepairKind::Salvaged, section.position, message);
+            } else {
+                self.repair_dropping(RepairKind::Salvaged, text, section, message);
+            }
+
+            let read = if at_boundary {
+                match &self.current_token {
+                    Some(Token::Comma(pos)) => {
+                        let span = self.current_span(pos.clone());
+                        self.advance().map(|_| Some(span))
+                    }
+                    _ => Ok(None),
                 }
+            } else {
+                self.advance().map(|_| comma)
+            };
+            match read {
+                Err(JsonFixerError::Syntax(next_error)) => error = next_error,
+                read => return read,
             }
         }
+    }
 
-        self.advance()?; // Consume ]
+    /// Parses a bare word in value position according to `bare_words`, eg. the `oops` of
+    /// `{"a": oops}`. A dropped word is parsed as `null`, its member or element being dropped
+    /// by the caller, see `at_dropped_word`.
+    #[inline(never)]
+    fn parse_bare_word(
+        &mut self,
+        word: String,
+        pos: Position,
+    ) -> Result<JsonValue, JsonFixerError> {
+        let error = SyntaxError::UnexpectedToken(word.clone(), pos.clone());
+        let (kind, message) = match self.config.bare_words {
+            BareWords::Error => return Err(JsonFixerError::Syntax(error)),
+            BareWords::Quote => {
+                self.check_repair(RepairKind::QuotedValue, error)?;
+                let message = format!("Quoted value '{}'", word);
+                self.repair(RepairKind::QuotedValue, pos, message);
+                return Ok(JsonValue::String(word));
+            }
+            BareWords::Drop => (
+                RepairKind::StrayWord,
+                format!("Dropped bare word '{}'", word),
+            ),
+            BareWords::Null => (
+                RepairKind::StrayWord,
+                format!("Replaced bare word '{}' by null", word),
+            ),
+        };
+        self.check_repair(kind, error)?;
+        let span = self.current_span(pos);
+        self.repair_dropping(kind, &word, span, message);
+        Ok(JsonValue::Null)
+    }
 
-        Ok(JsonValue::Array(arr))
+    /// Returns `true` if the current token is a bare word dropped along with its member or
+    /// element, see `BareWords::Drop` and `Undefined::Drop`.
+    fn at_dropped_word(&self) -> bool {
+        if self.at_undefined() {
+            return self.config.undefined == Undefined::Drop;
+        }
+        self.config.bare_words == BareWords::Drop
+            && matches!(self.current_token, Some(Token::UnquotedString(_, _)))
+            && !self.at_non_finite()
+    }
+
+    /// Returns `true` if the current token is the `undefined` of JavaScript rather than a
+    /// bare word, see `JsonFixerConfig::undefined`.
+    fn at_undefined(&self) -> bool {
+        let Some(Token::UnquotedString(word, _)) = &self.current_token else {
+            return false;
+        };
+        self.config.undefined != Undefined::BareWord && word == "undefined"
+    }
+
+    /// Parses the `undefined` of JavaScript according to `JsonFixerConfig::undefined`. A
+    /// dropped one is parsed as `null`, its member or element being dropped by the caller.
+    #[inline(never)]
+    fn parse_undefined(
+        &mut self,
+        word: String,
+        pos: Position,
+    ) -> Result<JsonValue, JsonFixerError> {
+        if self.config.undefined == Undefined::Drop {
+            let error = SyntaxError::UnexpectedToken(word.clone(), pos.clone());
+            self.check_repair(RepairKind::StrayWord, error)?;
+            let span = self.current_span(pos);
+            self.repair_dropping(RepairKind::StrayWord, &word, span, "Dropped 'undefined'");
+        } else {
+            self.repair(RepairKind::CoercedNull, pos, "Replaced 'undefined' by null");
+        }
+        Ok(JsonValue::Null)
+    }
+
+    /// Returns `true` if the current token is a non-finite number rather than a bare word,
+    /// see `JsonFixerConfig::non_finite_numbers`.
+    fn at_non_finite(&self) -> bool {
+        let Some(Token::UnquotedString(word, _)) = &self.current_token else {
+            return false;
+        };
+        self.config.non_finite_numbers != NonFiniteNumbers::BareWord
+            && matches!(word.trim_start_matches(['+', '-']), "Infinity" | "NaN")
+    }
+
+    /// Parses a non-finite number, `NaN`, `Infinity` or `-Infinity`, according to
+    /// `non_finite_numbers`.
+    #[inline(never)]
+    fn parse_non_finite(
+        &mut self,
+        word: String,
+        pos: Position,
+    ) -> Result<JsonValue, JsonFixerError> {
+        let error = SyntaxError::InvalidNumber(word.clone(), pos.clone());
+        let (value, message) = match self.config.non_finite_numbers {
+            NonFiniteNumbers::Keep => return Ok(JsonValue::Number(JsonNumber::BigRaw(word))),
+            NonFiniteNumbers::BareWord | NonFiniteNumbers::Error => {
+                return Err(JsonFixerError::Syntax(error))
+            }
+            NonFiniteNumbers::Null => (JsonValue::Null, format!("Replaced '{}' by null", word)),
+            NonFiniteNumbers::String => {
+                let message = format!("Replaced '{}' by a string", word);
+                let string = word.strip_prefix('+').unwrap_or(&word).to_string();
+                (JsonValue::String(string), message)
+            }
+        };
+        self.check_repair(RepairKind::FixedNumber, error)?;
+        self.repair(RepairKind::FixedNumber, pos, message);
+        Ok(value)
+    }
+
+    /// Skips the bare words followed by a value, the strays of `{"a": oops 1}`, unless
+    /// `bare_words` makes them errors.
+    fn skip_stray_words(&mut self) -> Result<(), JsonFixerError> {
+        if self.config.bare_words == BareWords::Error {
+            return Ok(());
+        }
+        while let Some(Token::UnquotedString(word, pos)) = &self.current_token {
+            if !self.value_follows() || self.at_non_finite() || self.at_undefined() {
+                break;
+            }
+            let (word, pos) = (word.clone(), pos.clone());
+            let error = SyntaxError::UnexpectedToken(word.clone(), pos.clone());
+            self.check_repair(RepairKind::StrayWord, error)?;
+            let span = self.current_span(pos);
+            let message = format!("Dropped stray word '{}'", word);
+            self.repair_dropping(RepairKind::StrayWord, &word, span, message);
+
+            self.advance()?; // Consume the word
+            if let Some(Token::Whitespace(_, _)) = &self.current_token {
+                self.advance()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if a value follows the current token, whitespaces aside.
+    fn value_follows(&self) -> bool {
+        match self.tokens.as_ref() {
+            Some(tokens) => tokens.value_follows(),
+            None => self.tokenizer.value_follows(),
+        }
+    }
+
+    /// Returns `true` if the current token closes another kind of container while the input
+    /// lacks a `closing` bracket, eg. the `]` of `[{"a": 1]`. The missing bracket is then
+    /// inserted before the current token, which is left to the enclosing container.
+    ///
+    /// When the input also has one bracket of the current token kind too many, the current
+    /// token is a typo and is replaced by the `closing` bracket instead, eg. the `}` of
+    /// `{"a": [1}, "b": 2}`.
+    #[inline(never)]
+    fn closes_implicitly(&mut self, closing: char) -> Result<bool, JsonFixerError> {
+        let pos = match &self.current_token {
+            Some(
+                token @ (Token::RightBrace(_) | Token::RightBracket(_) | Token::RightParen(_)),
+            ) if !token.closes(closing) => token.pos().clone(),
+            _ => return Ok(false),
+        };
+
+        let input = self.input;
+        let structure = self
+            .structure
+            .get_or_insert_with(|| StructureScan::scan(input));
+        let missing = match closing {
+            '}' => structure.unclosed_objects(),
+            ']' => structure.unclosed_arrays(),
+            _ => 0,
+        };
+        // Where the missing brackets belong is a guess
+        if missing == 0
+            || !self.config.repairs.allows(RepairKind::ClosedBracket)
+            || !self
+                .config
+                .accepts_repair(RepairKind::ClosedBracket, Confidence::Speculative)
+        {
+            return Ok(false);
+        }
+        match closing {
+            '}' => structure.close_braces += 1,
+            _ => structure.close_brackets += 1,
+        }
+
+        let found = self
+            .current_token
+            .as_ref()
+            .map(Token::get)
+            .unwrap_or_default();
+        let typo = match &self.current_token {
+            Some(Token::RightBrace(_)) => structure.close_braces > structure.open_braces,
+            Some(Token::RightBracket(_)) => structure.close_brackets > structure.open_brackets,
+            _ => false,
+        };
+        if typo {
+            match &self.current_token {
+                Some(Token::RightBrace(_)) => structure.close_braces -= 1,
+                _ => structure.close_brackets -= 1,
+            }
+            let message = format!("Replaced {} by '{}'", found, closing);
+            let repair = Repair::at(RepairKind::ClosedBracket, pos.clone(), message);
+            self.push_repair(repair.with_confidence(Confidence::Speculative));
+            self.current_token = Some(match closing {
+                '}' => Token::RightBrace(pos),
+                _ => Token::RightBracket(pos),
+            });
+        } else {
+            let message = format!("Inserted missing '{}' before {}", closing, found);
+            let repair = Repair::at(RepairKind::ClosedBracket, pos, message);
+            self.push_repair(repair.with_confidence(Confidence::Speculative));
+        }
+        Ok(true)
+    }
+
+    /// Returns `true` if the current token starts a value that cannot be a key, meaning the
+    /// enclosing braces are a Python set.
+    fn at_set_element(&self) -> bool {
+        match &self.current_token {
+            Some(Token::Number(_, _)) => !self.config.quote_numeric_keys,
+            Some(Token::LeftBrace(_)) | Some(Token::LeftBracket(_)) | Some(Token::LeftParen(_)) => {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Keeps track of a comma found between array values, extra commas are removed.
+    fn handle_array_comma(
+        &mut self,
+        pending_comma: &mut Option<Span>,
+        has_values: bool,
+        pos: Position,
+    ) -> Result<(), JsonFixerError> {
+        let span = self.current_span(pos);
+        if pending_comma.is_some() || !has_values {
+            let error = SyntaxError::UnexpectedToken("','".to_string(), span.position.clone());
+            self.check_repair(RepairKind::RemovedComma, error)?;
+            let message = "Removed extra ','";
+            self.repair_dropping(RepairKind::RemovedComma, ",", span, message);
+        } else {
+            *pending_comma = Some(span);
+        }
+        Ok(())
+    }
+}
+
+/// Moves the comment found on the line where each value of `entries` ends, after the value or
+/// its comma, from the whitespaces to the `comment` of its entry, so that it follows the entry
+/// when the entries are sorted or reformatted. The whitespaces following a value are the ones
+/// after it, then the ones before the key of the next member of an `object` or before the
+/// next element of an array.
+///
+/// The other comments before each entry are copied to its `leading_comments`, the ones
+/// before the closing bracket to an entry without value appended if there is none.
+fn attach_comments(entries: &mut Vec<JsonEntryValue>, object: bool) {
+    for i in 0..entries.len() {
+        let (entry, rest) = entries[i..].split_first_mut().expect("entry in range");
+        if entry.value.is_none() {
+            continue;
+        }
+        entry.comment = take_same_line_comment(&mut entry.space_af_val);
+        if entry.comment.is_some() || entry.get_sp_af_val().contains('\n') {
+            continue;
+        }
+        if let Some(next) = rest.first_mut() {
+            let whitespaces = match object {
+                true => &mut next.space_bf_key,
+                false => &mut next.space_bf_val,
+            };
+            entry.comment = take_same_line_comment(whitespaces);
+        }
+    }
+
+    for entry in entries.iter_mut() {
+        let whitespaces = match object {
+            true => entry.get_sp_bf_key(),
+            false => entry.get_sp_bf_val(),
+        };
+        entry.leading_comments = comments(&whitespaces);
+    }
+    // Comments before the closing bracket, without trailing comma
+    let closing = match entries.last() {
+        Some(last) if last.value.is_some() => comments(&last.get_sp_af_val()),
+        _ => Vec::new(),
+    };
+    if !closing.is_empty() {
+        entries.push(JsonEntryValue {
+            leading_comments: closing,
+            ..JsonEntryValue::new()
+        });
+    }
+}
+
+/// Returns the comments of `whitespaces`.
+fn comments(whitespaces: &str) -> Vec<String> {
+    let mut comments = Vec::new();
+    let mut rest = whitespaces;
+    // Whitespaces only hold comments besides whitespaces
+    while let Some(start) = rest.find('/') {
+        let end = comment_end(rest, start);
+        comments.push(rest[start..end].to_string());
+        rest = &rest[end..];
+    }
+    comments
+}
+
+/// Returns the end of the comment starting at `start` in `sp`, the line break ending a line
+/// comment excluded.
+fn comment_end(sp: &str, start: usize) -> usize {
+    match sp[start..].strip_prefix("/*") {
+        Some(body) => body.find("*/").map_or(sp.len(), |i| start + 2 + i + 2),
+        None => sp[start..]
+            .find(['\r', '\n'])
+            .map_or(sp.len(), |i| start + i),
+    }
+}
+
+/// Removes the first comment of `whitespaces` along with the spaces before it if no line break
+/// comes before it, and returns it. The line break ending a line comment is left in place.
+fn take_same_line_comment(whitespaces: &mut Option<String>) -> Option<String> {
+    let sp = whitespaces.as_mut()?;
+    // Whitespaces only hold comments besides whitespaces
+    let start = sp.find('/')?;
+    if sp[..start].contains('\n') {
+        return None;
     }
+    let end = comment_end(sp, start);
+    let comment = sp[start..end].to_string();
+    sp.replace_range(..end, "");
+    Some(comment)
 }
@@ -0,0 +1,135 @@
+//! Deserialization of fixed documents tolerating the members that do not match the type.
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    jsonfixer_config::JsonFixerConfig, jsonfixer_error::JsonFixerError, jsonparser::JsonParser,
+    jsonpath,
+};
+
+/// Result of [`JsonFixer::from_fixed_partial`](super::JsonFixer::from_fixed_partial).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialFix<T> {
+    /// The deserialized value, with the default value of the fields that failed.
+    pub value: T,
+    /// The members of the document that failed to deserialize.
+    pub errors: Vec<FieldError>,
+}
+
+impl<T> PartialFix<T> {
+    /// Returns `true` if every member of the document was deserialized.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A member of the document that failed to deserialize and was replaced by its default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// Path of the member, eg. `user.age`, empty for the root value, see `jsonpath`.
+    pub path: String,
+    /// The deserialization error of the member.
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at path '{}'", self.message, self.path)
+    }
+}
+
+/// Fixes `input` and deserializes it into `T`, replacing the members that fail by their
+/// default value.
+///
+/// The document is built from the serialized default of `T`, then the members of the input
+/// are added one by one, keeping the ones `T` still deserializes with. Members that are
+/// objects in both are merged the same way, so only the failing nested members are dropped.
+pub fn from_fixed_partial<T>(
+    input: &str,
+    config: &JsonFixerConfig,
+) -> Result<PartialFix<T>, JsonFixerError>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    let fixed = JsonParser::with_borrowed_config(input, config).parse()?;
+    let value: Value = serde_json::from_str(&fixed).map_err(serde_error)?;
+    let error = match serde_json::from_value(value.clone()) {
+        Ok(value) => {
+            return Ok(PartialFix {
+                value,
+                errors: Vec::new(),
+            })
+        }
+        Err(error) => error,
+    };
+
+    let mut document = serde_json::to_value(T::default()).map_err(serde_error)?;
+    let mut errors = Vec::new();
+    match value {
+        Value::Object(members) if document.is_object() => {
+            merge_members::<T>(&mut document, &mut Vec::new(), members, &mut errors);
+        }
+        _ => errors.push(FieldError {
+            path: String::new(),
+            message: error.to_string(),
+        }),
+    }
+
+    let value = serde_json::from_value(document).map_err(serde_error)?;
+    Ok(PartialFix { value, errors })
+}
+
+/// Adds `members` to the object of `document` at the path of `keys`, dropping the members
+/// that `T` fails to deserialize with.
+fn merge_members<T: DeserializeOwned>(
+    document: &mut Value,
+    keys: &mut Vec<String>,
+    members: Map<String, Value>,
+    errors: &mut Vec<FieldError>,
+) {
+    for (key, member) in members {
+        let previous = object_at(document, keys).insert(key.clone(), member.clone());
+        let error = match serde_json::from_value::<T>(document.clone()) {
+            Ok(_) => continue,
+            Err(error) => error,
+        };
+
+        let object = object_at(document, keys);
+        match (previous, member) {
+            (Some(Value::Object(default)), Value::Object(members)) => {
+                object.insert(key.clone(), Value::Object(default));
+                keys.push(key);
+                merge_members::<T>(document, keys, members, errors);
+                keys.pop();
+            }
+            (previous, _) => {
+                match previous {
+                    Some(previous) => object.insert(key.clone(), previous),
+                    None => object.remove(&key),
+                };
+                let parent = keys
+                    .iter()
+                    .fold(String::new(), |path, k| jsonpath::key(&path, k));
+                errors.push(FieldError {
+                    path: jsonpath::key(&parent, &key),
+                    message: error.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Returns the object of `document` at the path of `keys`, which must be an object.
+fn object_at<'a>(document: &'a mut Value, keys: &[String]) -> &'a mut Map<String, Value> {
+    let value = keys
+        .iter()
+        .fold(document, |value, key| &mut value[key.as_str()]);
+    value.as_object_mut().expect("merged members are objects")
+}
+
+fn serde_error(error: serde_json::Error) -> JsonFixerError {
+    JsonFixerError::SerdeError(error.to_string())
+}
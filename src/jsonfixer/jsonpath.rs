@@ -0,0 +1,136 @@
+//! Dotted paths used to target parts of a document from the configuration.
+//!
+//! The root value has the empty path `""`, object members are joined with a dot
+//! (`user.name`) and array elements use brackets (`users[0].name`). Patterns may use
+//! `*` to match any single key or index (`users[*].name`, `*.id`).
+//!
+//! Keys that are empty or contain a `.`, `[` or `]` are quoted in brackets, with their
+//! quotes and backslashes escaped, eg. `hosts["example.com"].port`.
+
+use std::borrow::Cow;
+
+/// Returns the path of the member `key` of the object at `parent`.
+pub fn key(parent: &str, key: &str) -> String {
+    if key.is_empty() || key.contains(['.', '[', ']']) {
+        let mut path = format!("{}[\"", parent);
+        for ch in key.chars() {
+            if matches!(ch, '"' | '\\') {
+                path.push('\\');
+            }
+            path.push(ch);
+        }
+        path.push_str("\"]");
+        path
+    } else if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Returns the path of the element `index` of the array at `parent`.
+pub fn index(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+/// Splits a path into its segments, e.g. `a.b[0]` into `a`, `b`, `[0]`. Quoted keys are
+/// kept as written, e.g. `a["b.c"]` into `a`, `["b.c"]`, see `segment_key`.
+pub fn segments(path: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut chars = path.char_indices();
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '.' => {
+                if i > start {
+                    segments.push(&path[start..i]);
+                }
+                start = i + 1;
+            }
+            '[' => {
+                if i > start {
+                    segments.push(&path[start..i]);
+                }
+                start = i;
+                if !path[i + 1..].starts_with('"') {
+                    continue;
+                }
+                // Skip the quoted key up to its closing quote
+                chars.next();
+                let mut escaped = false;
+                for (_, ch) in chars.by_ref() {
+                    match ch {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            ']' => {
+                segments.push(&path[start..=i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if start < path.len() {
+        segments.push(&path[start..]);
+    }
+
+    segments
+}
+
+/// Returns the key of a member segment, e.g. `b` for `b` and `b.c` for `["b.c"]`, or `None`
+/// for an element segment, e.g. `[0]`.
+pub fn segment_key(segment: &str) -> Option<Cow<'_, str>> {
+    let Some(bracketed) = segment.strip_prefix('[') else {
+        return Some(Cow::Borrowed(segment));
+    };
+    let quoted = bracketed
+        .strip_suffix(']')?
+        .strip_prefix('"')?
+        .strip_suffix('"')?;
+    if !quoted.contains('\\') {
+        return Some(Cow::Borrowed(quoted));
+    }
+    let mut key = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => key.extend(chars.next()),
+            ch => key.push(ch),
+        }
+    }
+    Some(Cow::Owned(key))
+}
+
+/// Checks whether `path` matches `pattern`.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let pattern = segments(pattern);
+    let path = segments(path);
+
+    pattern.len() == path.len()
+        && pattern
+            .iter()
+            .zip(path.iter())
+            .all(|(p, s)| segment_matches(p, s))
+}
+
+/// Checks whether `path` matches `pattern`, leads to a path matching it or lies within a
+/// path matching it, eg. `user`, `user.name` and `user.name.first` for `user.name`.
+pub fn overlaps(pattern: &str, path: &str) -> bool {
+    segments(pattern)
+        .iter()
+        .zip(segments(path).iter())
+        .all(|(p, s)| segment_matches(p, s))
+}
+
+fn segment_matches(pattern: &str, segment: &str) -> bool {
+    match pattern {
+        "*" => true,
+        "[*]" => segment.starts_with('[') && !segment.starts_with("[\""),
+        _ => pattern == segment,
+    }
+}
@@ -0,0 +1,30 @@
+//! Relaxing of valid JSON into human friendly JSON5 or JSONC, the inverse of fixing.
+
+use super::{
+    jsonfixer_config::{JsonFixerConfig, OutputStyle, QuoteKeys},
+    jsonfixer_error::JsonFixerError,
+    jsonparser::JsonParser,
+};
+
+/// Rewrites `input`, which must be valid JSON, as an editable configuration file in the
+/// given style: pretty printed with 2 spaces, with a comment before the members and elements
+/// matching the path patterns of `comments`, see `JsonFixerConfig::comments`.
+///
+/// JSON5 output also leaves the keys that are identifiers unquoted and ends arrays and objects
+/// with a trailing comma. Invalid input fails with the syntax error of the strict validator.
+pub fn relax(
+    input: &str,
+    style: OutputStyle,
+    comments: &[(String, String)],
+) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig {
+        output_style: style,
+        beautify: true,
+        indent_size: 2,
+        quote_keys: QuoteKeys::OnlyWhenNeeded,
+        emit_trailing_commas: true,
+        comments: comments.to_vec(),
+        ..JsonFixerConfig::strict()
+    };
+    JsonParser::new(input, config).parse()
+}
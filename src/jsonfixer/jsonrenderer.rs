@@ -0,0 +1,534 @@
+//! Renderers producing syntax highlighted output of fixed JSON.
+
+use std::{
+    cmp::Reverse,
+    fmt::{self, Write},
+};
+
+use super::{
+    json_tokenizer::{byte_offset, JsonTokenizer, Position, Token},
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::{JsonFixerError, SyntaxError},
+    jsonfixer_report::{Repair, RepairKind},
+    jsonparser::JsonParser,
+    jsonpath,
+};
+
+/// The kinds of highlighted elements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Highlight {
+    Key,
+    String,
+    Number,
+    Boolean,
+    Null,
+    Punctuation,
+    Whitespace,
+}
+
+/// A highlighted slice of a fixed JSON output.
+struct Slice<'a> {
+    highlight: Highlight,
+    text: &'a str,
+    /// Path of the member or value starting with this slice, if any
+    path: Option<String>,
+}
+
+/// An array or object enclosing the slices being highlighted.
+struct Container {
+    is_object: bool,
+    path: String,
+    index: usize,
+}
+
+/// Splits a fixed JSON output into highlighted slices.
+fn highlights(output: &str) -> Result<Vec<Slice<'_>>, JsonFixerError> {
+    let mut tokenizer = JsonTokenizer::new(output);
+    let mut slices = Vec::new();
+    let mut containers: Vec<Container> = Vec::new();
+    let mut expect_key = false;
+    // Path of the next value
+    let mut value_path = Some(String::new());
+
+    loop {
+        let start = tokenizer.offset();
+        let token = match tokenizer.next_token()? {
+            Some(token) => token,
+            None => break,
+        };
+        let text = &output[start..tokenizer.offset()];
+
+        let mut path = None;
+        let highlight = match token {
+            Token::LeftBrace(_) => {
+                path = value_path.take();
+                containers.push(Container {
+                    is_object: true,
+                    path: path.clone().unwrap_or_default(),
+                    index: 0,
+                });
+                expect_key = true;
+                Highlight::Punctuation
+            }
+            Token::LeftBracket(_) => {
+                path = value_path.take();
+                let container = Container {
+                    is_object: false,
+                    path: path.clone().unwrap_or_default(),
+                    index: 0,
+                };
+                value_path = Some(jsonpath::index(&container.path, 0));
+                containers.push(container);
+                Highlight::Punctuation
+            }
+            Token::RightBrace(_) | Token::RightBracket(_) => {
+                containers.pop();
+                expect_key = false;
+                value_path = None;
+                Highlight::Punctuation
+            }
+            Token::Comma(_) => {
+                if let Some(container) = containers.last_mut() {
+                    expect_key = container.is_object;
+                    if !container.is_object {
+                        container.index += 1;
+                        value_path = Some(jsonpath::index(&container.path, container.index));
+                    }
+                }
+                Highlight::Punctuation
+            }
+            Token::String(key, _) if expect_key => {
+                expect_key = false;
+                let parent = containers
+                    .last()
+                    .map(|c| c.path.as_str())
+                    .unwrap_or_default();
+                path = Some(jsonpath::key(parent, &key));
+                value_path = path.clone();
+                Highlight::Key
+            }
+            Token::Whitespace(_, _) => Highlight::Whitespace,
+            Token::Colon(_) | Token::LeftParen(_) | Token::RightParen(_) => Highlight::Punctuation,
+            token => {
+                path = value_path.take();
+                match token {
+                    Token::Number(_, _) => Highlight::Number,
+                    Token::Boolean(_, _) => Highlight::Boolean,
+                    Token::Null(_) => Highlight::Null,
+                    _ => Highlight::String,
+                }
+            }
+        };
+        slices.push(Slice {
+            highlight,
+            text,
+            path,
+        });
+    }
+
+    Ok(slices)
+}
+
+/*
+************************** HTML *************************
+*/
+
+/// Escapes the characters having a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Returns the CSS class of a kind of highlighted element.
+fn html_class(highlight: Highlight) -> Option<&'static str> {
+    match highlight {
+        Highlight::Key => Some("jf-key"),
+        Highlight::String => Some("jf-string"),
+        Highlight::Number => Some("jf-number"),
+        Highlight::Boolean => Some("jf-boolean"),
+        Highlight::Null => Some("jf-null"),
+        Highlight::Punctuation => Some("jf-punctuation"),
+        Highlight::Whitespace => None,
+    }
+}
+
+/// Returns the kind of a token compared when aligning the input with the output, the
+/// scalars being compared regardless of how they were fixed, eg. an unquoted key with its
+/// quoted output. The whitespaces and comments are not aligned.
+fn alignment_kind(token: &Token) -> Option<char> {
+    match token {
+        Token::Whitespace(_, _) => None,
+        Token::LeftBrace(_) => Some('{'),
+        Token::RightBrace(_) => Some('}'),
+        Token::LeftBracket(_) | Token::LeftParen(_) => Some('['),
+        Token::RightBracket(_) | Token::RightParen(_) => Some(']'),
+        Token::Colon(_) => Some(':'),
+        Token::Comma(_) => Some(','),
+        _ => Some('v'),
+    }
+}
+
+/// Aligns the tokens of the input with the ones of the output, returning the index of the
+/// output token matching each input token, `None` for the tokens dropped by the fixer.
+///
+/// The repairs being local, the tokens are matched in order and, past a token of either side
+/// without match, the sides resume on the nearest tokens agreeing again, the longest
+/// agreement being kept among the nearest ones.
+fn align(input: &[char], output: &[char]) -> Vec<Option<usize>> {
+    const LOOKAHEAD: usize = 8;
+    let mut aligned = vec![None; input.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < input.len() && j < output.len() {
+        if input[i] == output[j] {
+            aligned[i] = Some(j);
+            i += 1;
+            j += 1;
+            continue;
+        }
+        // Number of tokens agreeing once `di` input and `dj` output tokens are skipped
+        let agreement = |di: usize, dj: usize| {
+            (0..LOOKAHEAD)
+                .take_while(|&k| input.get(i + di + k) == output.get(j + dj + k))
+                .count()
+        };
+        // The fewest tokens to skip, inserted ones first on a tie
+        let skip = (1..=2 * LOOKAHEAD).find_map(|skipped| {
+            (0..=skipped)
+                .map(|di| (di, skipped - di))
+                .filter(|&(di, dj)| di <= LOOKAHEAD && dj <= LOOKAHEAD)
+                .map(|(di, dj)| (agreement(di, dj), di, dj))
+                .filter(|&(agreed, _, _)| agreed > 0)
+                .min_by_key(|&(agreed, _, _)| Reverse(agreed))
+        });
+        let (_, di, dj) = skip.unwrap_or((0, 1, 1));
+        i += di;
+        j += dj;
+    }
+    aligned
+}
+
+/// Returns the repairs to mark on each slice of the output.
+///
+/// The repairs located in the input are marked on the output token matching the input token
+/// at their position, or preceding it when that token was dropped. The commas and brackets
+/// inserted are marked on the inserted tokens, in order. The repairs applied on the parsed
+/// document, and all of them when the input cannot be tokenized, are marked on the first
+/// slice of their path.
+fn repair_marks<'r>(
+    input: &str,
+    config: &JsonFixerConfig,
+    repairs: &'r [Repair],
+    slices: &[Slice],
+) -> Vec<Vec<&'r Repair>> {
+    let mut marks = vec![Vec::new(); slices.len()];
+
+    // Start offsets and kinds of the input tokens
+    let mut tokenizer = JsonTokenizer::with_config(input, config);
+    let mut starts = Vec::new();
+    let mut input_kinds = Vec::new();
+    let mut start = 0;
+    let tokenized = loop {
+        match tokenizer.next_token() {
+            Ok(Some(token)) => {
+                if let Some(kind) = alignment_kind(&token) {
+                    starts.push(start);
+                    input_kinds.push(kind);
+                }
+                start = tokenizer.offset();
+            }
+            Ok(None) => break true,
+            Err(_) => break false,
+        }
+    };
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .copied()
+        .chain([input.len()])
+        .collect();
+
+    // Indexes and kinds of the output slices, whitespaces aside
+    let mut indexes = Vec::new();
+    let mut output_kinds = Vec::new();
+    for (index, slice) in slices.iter().enumerate() {
+        let kind = match slice.highlight {
+            Highlight::Whitespace => continue,
+            Highlight::Punctuation => match slice.text.chars().next() {
+                Some('(') => '[',
+                Some(')') => ']',
+                Some(ch) => ch,
+                None => continue,
+            },
+            _ => 'v',
+        };
+        indexes.push(index);
+        output_kinds.push(kind);
+    }
+
+    let aligned = align(&input_kinds, &output_kinds);
+    let mut inserted = vec![true; output_kinds.len()];
+    for &j in aligned.iter().flatten() {
+        inserted[j] = false;
+    }
+    // Insertions marked so far before each output token, several brackets being closed
+    // before the same token or at the end
+    let mut insertions = vec![0; output_kinds.len() + 1];
+
+    for repair in repairs {
+        let target = match &repair.position {
+            Some(position) if tokenized && !output_kinds.is_empty() => {
+                let offset = byte_offset(input, position);
+                // The input token at the position, or the first one following it
+                let t = ends
+                    .iter()
+                    .position(|&end| offset < end)
+                    .unwrap_or(ends.len());
+                let next = aligned[t..].iter().flatten().next().copied();
+                let next = next.unwrap_or(output_kinds.len());
+                let last = aligned[..(t + 1).min(aligned.len())]
+                    .iter()
+                    .flatten()
+                    .last();
+                // The tokens of the kind inserted by the repair among the ones inserted
+                // right before the next output token
+                let inserting: &[char] = match repair.kind {
+                    RepairKind::InsertedComma => &[','],
+                    RepairKind::ClosedBracket => &['}', ']'],
+                    _ => &[],
+                };
+                let first = (0..next).rev().take_while(|&j| inserted[j]).last();
+                let mut candidates =
+                    (first.unwrap_or(next)..next).filter(|&j| inserting.contains(&output_kinds[j]));
+                let j = match candidates.nth(insertions[next]) {
+                    Some(j) => {
+                        insertions[next] += 1;
+                        j
+                    }
+                    None => last.copied().unwrap_or(next).min(output_kinds.len() - 1),
+                };
+                indexes[j]
+            }
+            _ => {
+                let path = repair.path.as_deref().unwrap_or_default();
+                match slices.iter().position(|s| s.path.as_deref() == Some(path)) {
+                    Some(index) => index,
+                    None => continue,
+                }
+            }
+        };
+        marks[target].push(repair);
+    }
+    marks
+}
+
+/// Fixes the input and renders it as highlighted HTML.
+///
+/// The repairs are marked with a `<mark class="jf-repair">` whose title describes them, on
+/// the token found at their position in the input, or on the member or value of their path
+/// for the repairs applied on the parsed document. When the input cannot be fixed, it is
+/// rendered as is with the line of the error marked with `<mark class="jf-error">`.
+pub fn render_html(input: &str, config: &JsonFixerConfig) -> Result<String, JsonFixerError> {
+    let report = match JsonParser::with_borrowed_config(input, config).parse_with_report() {
+        Ok(report) => report,
+        Err(JsonFixerError::Syntax(err)) => {
+            return Ok(render_html_error(input, &err, config.error_window))
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut rendered = String::from("<pre class=\"json-fixer\">");
+    let slices = highlights(&report.output)?;
+    let marks = repair_marks(input, config, &report.repairs, &slices);
+
+    for (slice, repairs) in slices.iter().zip(marks) {
+        if !repairs.is_empty() {
+            let title: Vec<String> = repairs.iter().map(|r| r.to_string()).collect();
+            write!(
+                rendered,
+                "<mark class=\"jf-repair\" title=\"{}\">",
+                escape_html(&title.join("\n"))
+            )
+            .map_err(JsonFixerError::IO)?;
+        }
+        match html_class(slice.highlight) {
+            Some(class) => write!(
+                rendered,
+                "<span class=\"{}\">{}</span>",
+                class,
+                escape_html(slice.text)
+            )
+            .map_err(JsonFixerError::IO)?,
+            None => rendered.push_str(slice.text),
+        }
+        if !repairs.is_empty() {
+            rendered.push_str("</mark>");
+        }
+    }
+
+    rendered.push_str("</pre>");
+    Ok(rendered)
+}
+
+/// Renders an input that could not be fixed with the line of the error marked, windowed
+/// around the column of the error when `radius` is set.
+fn render_html_error(input: &str, err: &SyntaxError, radius: Option<usize>) -> String {
+    let Position {
+        line: error_line,
+        column,
+    } = *err.position();
+    let mut rendered = String::from("<pre class=\"json-fixer jf-invalid\">");
+
+    for (i, line) in input.split('\n').enumerate() {
+        if i > 0 {
+            rendered.push('\n');
+        }
+        if i + 1 == error_line {
+            let line = match radius {
+                Some(radius) => window(line, column, radius).to_string(),
+                None => line.to_string(),
+            };
+            rendered.push_str(&format!(
+                "<mark class=\"jf-error\" title=\"{}\">{}</mark>",
+                escape_html(&err.to_string()),
+                escape_html(&line)
+            ));
+        } else {
+            rendered.push_str(&escape_html(line));
+        }
+    }
+
+    rendered.push_str("</pre>");
+    rendered
+}
+
+/// A slice of a line around a column, see [`window`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window<'a> {
+    pub text: &'a str,
+    /// 1-based column of the first character of the text in the line
+    pub column: usize,
+    /// Whether characters of the line are elided before the text
+    pub elided_before: bool,
+    /// Whether characters of the line are elided after the text
+    pub elided_after: bool,
+}
+
+/// Writes the text with a '…' on the sides where characters are elided.
+impl fmt::Display for Window<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ellipsis = |elided: bool| if elided { "…" } else { "" };
+        write!(
+            f,
+            "{}{}{}",
+            ellipsis(self.elided_before),
+            self.text,
+            ellipsis(self.elided_after)
+        )
+    }
+}
+
+/// Returns the characters of `line` at most `radius` characters away from the 1-based
+/// `column`, so that an error in a minified line is shown without the whole line. The
+/// columns past the end of the line are windowed as the one right after its last character.
+pub fn window(line: &str, column: usize, radius: usize) -> Window<'_> {
+    let length = line.chars().count();
+    let center = column.saturating_sub(1).min(length);
+    let first = center.saturating_sub(radius);
+    let last = center.saturating_add(radius).saturating_add(1).min(length);
+
+    // Byte offsets of the characters, the slice being cut on character boundaries
+    let offset = |index: usize| {
+        line.char_indices()
+            .nth(index)
+            .map_or(line.len(), |(i, _)| i)
+    };
+    Window {
+        text: &line[offset(first)..offset(last)],
+        column: first + 1,
+        elided_before: first > 0,
+        elided_after: last < length,
+    }
+}
+
+/*
+************************** ANSI *************************
+*/
+
+/// ANSI escape sequences used to color each kind of element.
+#[cfg(feature = "terminal")]
+#[derive(Debug, Clone)]
+pub struct AnsiTheme {
+    pub key: String,
+    pub string: String,
+    pub number: String,
+    pub boolean: String,
+    pub null: String,
+    pub punctuation: String,
+}
+
+/// Colors matching the defaults of `jq`.
+#[cfg(feature = "terminal")]
+impl Default for AnsiTheme {
+    fn default() -> Self {
+        Self {
+            key: "\x1b[34;1m".to_string(),
+            string: "\x1b[0;32m".to_string(),
+            number: "\x1b[0;39m".to_string(),
+            boolean: "\x1b[0;39m".to_string(),
+            null: "\x1b[1;30m".to_string(),
+            punctuation: "\x1b[1;39m".to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "terminal")]
+impl AnsiTheme {
+    const RESET: &'static str = "\x1b[0m";
+
+    fn color(&self, highlight: Highlight) -> Option<&str> {
+        match highlight {
+            Highlight::Key => Some(&self.key),
+            Highlight::String => Some(&self.string),
+            Highlight::Number => Some(&self.number),
+            Highlight::Boolean => Some(&self.boolean),
+            Highlight::Null => Some(&self.null),
+            Highlight::Punctuation => Some(&self.punctuation),
+            Highlight::Whitespace => None,
+        }
+    }
+}
+
+/// Fixes the input and renders it pretty printed with ANSI colors.
+#[cfg(feature = "terminal")]
+pub fn render_ansi(input: &str, theme: &AnsiTheme) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig {
+        beautify: true,
+        indent_size: 2,
+        ..Default::default()
+    };
+    let output = JsonParser::new(input, config).parse()?;
+
+    let mut rendered = String::with_capacity(output.len() * 2);
+    for slice in highlights(&output)? {
+        match theme.color(slice.highlight) {
+            Some(color) if !color.is_empty() => {
+                rendered.push_str(color);
+                rendered.push_str(slice.text);
+                rendered.push_str(AnsiTheme::RESET);
+            }
+            _ => rendered.push_str(slice.text),
+        }
+    }
+
+    Ok(rendered)
+}
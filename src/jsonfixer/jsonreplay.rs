@@ -0,0 +1,343 @@
+//! Token streams captured once and parsed several times.
+
+use std::borrow::Cow;
+
+use super::{
+    json_tokenizer::{JsonTokenizer, Position, Token},
+    jsonfixer_config::{JsonFixerConfig, NonFiniteNumbers, Whitespaces},
+    jsonfixer_error::JsonFixerError,
+    jsonfixer_report::{FixReport, Repair},
+    jsonformatter::Formatter,
+    jsonparser::JsonParser,
+    jsonscan::StructureScan,
+};
+
+/// The tokens of an input along with the repairs applied while tokenizing it, to fix the
+/// input several times without tokenizing it again, eg. to write both a compact and a
+/// pretty output.
+///
+/// The input is tokenized with the lexing options of the capture configuration, eg.
+/// `python_compat` or `identifier_chars`, the options of the replay configuration only
+/// apply to the parsing and the formatting. The input text is not kept, so the `strict`
+/// validation and the `salvage` of corrupted sections are not available on replays. Replay
+/// configurations setting them, or setting a lexing option differently, are rejected with
+/// `JsonFixerError::Unreplayable`.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::{JsonFixerConfig, TokenBuffer};
+///
+/// let tokens = TokenBuffer::capture("{a: 1, b: [1 2]}", &JsonFixerConfig::default()).unwrap();
+///
+/// let compact = tokens.fix_with_config(&JsonFixerConfig::default()).unwrap();
+/// assert_eq!(compact, r#"{"a":1,"b":[1,2]}"#);
+///
+/// let mut config = JsonFixerConfig::default();
+/// config.space_between = true;
+/// let report = tokens.fix_with_report(&config).unwrap();
+/// assert_eq!(report.output, r#"{ "a": 1, "b": [ 1, 2 ] }"#);
+/// assert_eq!(report.repairs.len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBuffer {
+    tokens: Vec<Token>,
+    /// Byte offsets of the start and end of the tokens in the input, none when the quotes
+    /// of the input were unescaped
+    spans: Vec<(usize, usize)>,
+    /// Repairs of the tokenizer, with the index of the token they were applied to
+    repairs: Vec<(usize, Repair)>,
+    /// Balance of the input, guiding the repair of mismatched brackets
+    structure: StructureScan,
+    /// Position of the last character of the input
+    end: Position,
+    /// Lexing options of the capture configuration
+    lexing: LexingOptions,
+}
+
+impl TokenBuffer {
+    /// Tokenizes `input` with the lexing options of `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JsonFixerError` if the input cannot be tokenized, as fixing it would.
+    pub fn capture(input: &str, config: &JsonFixerConfig) -> Result<Self, JsonFixerError> {
+        let mut tokenizer = JsonTokenizer::with_config(input, config);
+        let mut tokens = Vec::new();
+        let mut spans = Vec::new();
+        let mut repairs = Vec::new();
+        let mut start = tokenizer.offset();
+        while let Some(token) = tokenizer.next_token()? {
+            let index = tokens.len();
+            repairs.extend(tokenizer.take_repairs().into_iter().map(|r| (index, r)));
+            tokens.push(token);
+            spans.push((start, tokenizer.offset()));
+            start = tokenizer.offset();
+        }
+        tokens.shrink_to_fit();
+        if tokenizer.unescapes() {
+            spans = Vec::new();
+        }
+        spans.shrink_to_fit();
+
+        Ok(Self {
+            tokens,
+            spans,
+            repairs,
+            structure: StructureScan::scan(input),
+            end: tokenizer.current_position(),
+            lexing: LexingOptions::of(config),
+        })
+    }
+
+    /// Returns the captured tokens.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Returns the balance of the captured input.
+    pub(crate) fn structure(&self) -> &StructureScan {
+        &self.structure
+    }
+
+    /// Returns the number of captured tokens.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns `true` if no token was captured, ie. the input was empty.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Fails with `JsonFixerError::Unreplayable` when `config` sets an option needing the
+    /// input text, or a lexing option differently from the capture configuration.
+    fn check_replay(&self, config: &JsonFixerConfig) -> Result<(), JsonFixerError> {
+        let option = if config.strict {
+            Some("strict")
+        } else if config.salvage {
+            Some("salvage")
+        } else {
+            self.lexing.changed_option(&LexingOptions::of(config))
+        };
+        match option {
+            Some(option) => Err(JsonFixerError::Unreplayable(option)),
+            None => Ok(()),
+        }
+    }
+
+    /// Fixes the captured tokens using `config`, see
+    /// [`JsonFixer::fix_with_config`](crate::JsonFixer::fix_with_config).
+    ///
+    /// # Errors
+    ///
+    /// Returns `JsonFixerError::Unreplayable` if `config` sets `strict`, `salvage` or a lexing
+    /// option differently from the capture configuration, eg. `strip_comments`.
+    pub fn fix_with_config(&self, config: &JsonFixerConfig) -> Result<String, JsonFixerError> {
+        self.check_replay(config)?;
+        JsonParser::replay(self, config).parse()
+    }
+
+    /// Fixes the captured tokens using `config` and reports every repair that was applied,
+    /// including the ones applied while capturing them, see
+    /// [`JsonFixer::fix_with_report`](crate::JsonFixer::fix_with_report).
+    pub fn fix_with_report(&self, config: &JsonFixerConfig) -> Result<FixReport, JsonFixerError> {
+        self.check_replay(config)?;
+        JsonParser::replay(self, config).parse_with_report()
+    }
+
+    /// Fixes the captured tokens using `config` and formats them with `formatter`, see
+    /// [`JsonFixer::fix_with_formatter`](crate::JsonFixer::fix_with_formatter).
+    pub fn fix_with_formatter<F: Formatter + ?Sized>(
+        &self,
+        config: &JsonFixerConfig,
+        formatter: &F,
+    ) -> Result<String, JsonFixerError> {
+        self.check_replay(config)?;
+        JsonParser::replay(self, config).parse_with_formatter(formatter)
+    }
+}
+
+/// The options of a configuration read while tokenizing, see `JsonTokenizer::with_config`.
+#[derive(Debug, Clone, PartialEq)]
+struct LexingOptions {
+    unescape_quotes: bool,
+    identifier_chars: String,
+    whitespaces: Whitespaces,
+    python_compat: bool,
+    strip_comments: bool,
+    keep_comments: bool,
+    hex_numbers: bool,
+    strip_leading_zeros: bool,
+    non_finite_numbers: bool,
+    normalize_quotes: bool,
+    fix_numbers: bool,
+    fix_escapes: bool,
+    leading_plus: bool,
+    lone_surrogates: bool,
+    decode_html_entities: bool,
+}
+
+impl LexingOptions {
+    fn of(config: &JsonFixerConfig) -> Self {
+        Self {
+            unescape_quotes: config.unescape_quotes,
+            identifier_chars: config.identifier_chars.clone(),
+            whitespaces: config.whitespaces.clone(),
+            python_compat: config.python_compat,
+            strip_comments: config.strip_comments,
+            keep_comments: config.keeps_comments(),
+            hex_numbers: config.hex_numbers,
+            strip_leading_zeros: config.strip_leading_zeros,
+            non_finite_numbers: config.non_finite_numbers != NonFiniteNumbers::BareWord,
+            normalize_quotes: config.repairs.normalize_quotes,
+            fix_numbers: config.repairs.fix_numbers,
+            fix_escapes: config.repairs.fix_escapes,
+            leading_plus: config.compliance.leading_plus,
+            lone_surrogates: config.compliance.lone_surrogates,
+            decode_html_entities: config.decode_html_entities,
+        }
+    }
+
+    /// Returns the name of the first option set differently in `other`.
+    fn changed_option(&self, other: &Self) -> Option<&'static str> {
+        let options = [
+            (
+                "unescape_quotes",
+                self.unescape_quotes != other.unescape_quotes,
+            ),
+            (
+                "identifier_chars",
+                self.identifier_chars != other.identifier_chars,
+            ),
+            ("whitespaces", self.whitespaces != other.whitespaces),
+            ("python_compat", self.python_compat != other.python_compat),
+            (
+                "strip_comments",
+                self.strip_comments != other.strip_comments,
+            ),
+            ("keep_comments", self.keep_comments != other.keep_comments),
+            ("hex_numbers", self.hex_numbers != other.hex_numbers),
+            (
+                "strip_leading_zeros",
+                self.strip_leading_zeros != other.strip_leading_zeros,
+            ),
+            (
+                "non_finite_numbers",
+                self.non_finite_numbers != other.non_finite_numbers,
+            ),
+            (
+                "repairs.normalize_quotes",
+                self.normalize_quotes != other.normalize_quotes,
+            ),
+            ("repairs.fix_numbers", self.fix_numbers != other.fix_numbers),
+            ("repairs.fix_escapes", self.fix_escapes != other.fix_escapes),
+            (
+                "compliance.leading_plus",
+                self.leading_plus != other.leading_plus,
+            ),
+            (
+                "compliance.lone_surrogates",
+                self.lone_surrogates != other.lone_surrogates,
+            ),
+            (
+                "decode_html_entities",
+                self.decode_html_entities != other.decode_html_entities,
+            ),
+        ];
+        options
+            .into_iter()
+            .find(|(_, changed)| *changed)
+            .map(|(option, _)| option)
+    }
+}
+
+/// Tokens read by the parser instead of the ones of a tokenizer.
+pub(crate) struct Replay<'a> {
+    tokens: Cow<'a, [Token]>,
+    /// Byte offsets of the tokens in the input, if known
+    spans: Cow<'a, [(usize, usize)]>,
+    repairs: Cow<'a, [(usize, Repair)]>,
+    /// Position of the last character of the input, if known
+    end: Option<Position>,
+    /// Index of the next token
+    next: usize,
+    /// Index of the next repair
+    next_repair: usize,
+}
+
+impl<'a> Replay<'a> {
+    /// Replays tokens read without repairs, see `JsonParser::from_tokens`.
+    pub(crate) fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens: Cow::Owned(tokens),
+            spans: Cow::Owned(Vec::new()),
+            repairs: Cow::Owned(Vec::new()),
+            end: None,
+            next: 0,
+            next_repair: 0,
+        }
+    }
+
+    /// Replays the tokens of `buffer`.
+    pub(crate) fn of(buffer: &'a TokenBuffer) -> Self {
+        Self {
+            tokens: Cow::Borrowed(&buffer.tokens),
+            spans: Cow::Borrowed(&buffer.spans),
+            repairs: Cow::Borrowed(&buffer.repairs),
+            end: Some(buffer.end.clone()),
+            next: 0,
+            next_repair: 0,
+        }
+    }
+
+    pub(crate) fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).cloned()?;
+        self.next += 1;
+        Some(token)
+    }
+
+    /// Returns the byte offsets of the start and end of the last token replayed in the
+    /// input, if known.
+    pub(crate) fn span(&self) -> Option<(usize, usize)> {
+        self.spans.get(self.next.checked_sub(1)?).copied()
+    }
+
+    /// Returns `true` if the next token, whitespaces aside, starts a value, like
+    /// `JsonTokenizer::value_follows`.
+    pub(crate) fn value_follows(&self) -> bool {
+        self.tokens[self.next.min(self.tokens.len())..]
+            .iter()
+            .find(|token| !matches!(token, Token::Whitespace(_, _)))
+            .is_some_and(Token::starts_value)
+    }
+
+    /// Returns `true` if the byte offsets of the tokens in the input are known.
+    pub(crate) fn has_spans(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    /// Returns the position of the end of the input once every token is replayed, or the
+    /// position of the last token replayed, like `JsonTokenizer::current_position`.
+    pub(crate) fn current_position(&self) -> Position {
+        match (&self.end, self.next) {
+            (Some(end), next) if next >= self.tokens.len() => end.clone(),
+            (_, 0) => Position { line: 1, column: 1 },
+            (_, next) => self.tokens[next - 1].pos().clone(),
+        }
+    }
+
+    /// Returns the repairs of the tokens replayed so far, like `JsonTokenizer::take_repairs`.
+    pub(crate) fn take_repairs(&mut self) -> Vec<Repair> {
+        let start = self.next_repair;
+        let replayed = self.repairs[start..]
+            .iter()
+            .take_while(|(index, _)| *index < self.next)
+            .count();
+        self.next_repair += replayed;
+        self.repairs[start..self.next_repair]
+            .iter()
+            .map(|(_, repair)| repair.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,128 @@
+//! Fast structural pre-scan of an input.
+
+use std::fmt;
+
+/// Balance of the strings, braces and brackets of an input.
+///
+/// The scan only looks at the quotes, braces and brackets, so it is much faster than
+/// tokenizing and is done on inputs of any size. Braces and brackets inside strings are
+/// ignored, the strings being delimited by double or single quotes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructureScan {
+    pub open_braces: usize,
+    pub close_braces: usize,
+    pub open_brackets: usize,
+    pub close_brackets: usize,
+    /// Number of strings, including an unclosed one
+    pub strings: usize,
+    /// The input ends inside a string, eg. a truncated `{"a": "x`
+    pub unclosed_string: bool,
+    /// Deepest nesting of objects and arrays
+    pub max_depth: usize,
+}
+
+impl StructureScan {
+    /// Scans `input`.
+    pub fn scan(input: &str) -> Self {
+        let mut scan = Self::default();
+        let mut depth: usize = 0;
+        let mut quote = None;
+        let mut escaped = false;
+
+        for &byte in input.as_bytes() {
+            if let Some(quote_byte) = quote {
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == quote_byte {
+                    quote = None;
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' | b'\'' => {
+                    quote = Some(byte);
+                    scan.strings += 1;
+                }
+                b'{' | b'[' => {
+                    if byte == b'{' {
+                        scan.open_braces += 1;
+                    } else {
+                        scan.open_brackets += 1;
+                    }
+                    depth += 1;
+                    scan.max_depth = scan.max_depth.max(depth);
+                }
+                b'}' | b']' => {
+                    if byte == b'}' {
+                        scan.close_braces += 1;
+                    } else {
+                        scan.close_brackets += 1;
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                _ => (),
+            }
+        }
+
+        scan.unclosed_string = quote.is_some();
+        scan
+    }
+
+    /// Number of `{` without a matching `}`.
+    pub fn unclosed_objects(&self) -> usize {
+        self.open_braces.saturating_sub(self.close_braces)
+    }
+
+    /// Number of `[` without a matching `]`.
+    pub fn unclosed_arrays(&self) -> usize {
+        self.open_brackets.saturating_sub(self.close_brackets)
+    }
+
+    /// Returns `true` if every string, object and array is closed, and nothing more is.
+    pub fn is_balanced(&self) -> bool {
+        self.open_braces == self.close_braces
+            && self.open_brackets == self.close_brackets
+            && !self.unclosed_string
+    }
+
+    /// Describes the imbalances found, eg. `["2 unclosed arrays"]`.
+    pub fn hints(&self) -> Vec<String> {
+        let plural = |count: usize, name: &str| match count {
+            1 => format!("1 {}", name),
+            count => format!("{} {}s", count, name),
+        };
+
+        let mut hints = Vec::new();
+        if self.unclosed_string {
+            hints.push("unclosed string".to_string());
+        }
+        if self.unclosed_objects() > 0 {
+            hints.push(plural(self.unclosed_objects(), "unclosed object"));
+        }
+        if self.unclosed_arrays() > 0 {
+            hints.push(plural(self.unclosed_arrays(), "unclosed array"));
+        }
+        if self.close_braces > self.open_braces {
+            let extra = self.close_braces - self.open_braces;
+            hints.push(format!("{} extra '}}'", extra));
+        }
+        if self.close_brackets > self.open_brackets {
+            let extra = self.close_brackets - self.open_brackets;
+            hints.push(format!("{} extra ']'", extra));
+        }
+        hints
+    }
+}
+
+impl fmt::Display for StructureScan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_balanced() {
+            write!(f, "balanced")
+        } else {
+            write!(f, "{}", self.hints().join(", "))
+        }
+    }
+}
@@ -0,0 +1,569 @@
+//! Validation of fixed documents against a JSON Schema.
+//!
+//! A subset of JSON Schema draft 2020-12 is supported:
+//!
+//! * `type`, `enum` and `const`
+//! * `minimum`, `maximum`, `exclusiveMinimum` and `exclusiveMaximum`
+//! * `minLength` and `maxLength`
+//! * `prefixItems`, `items`, `minItems`, `maxItems` and `uniqueItems`
+//! * `properties`, `required`, `additionalProperties`, `minProperties` and `maxProperties`
+//! * `allOf`, `anyOf`, `oneOf` and `not`
+//! * `$ref` to a JSON Pointer of the same schema, eg. `#/$defs/address`
+//!
+//! The other keywords, like `pattern` or `format`, are ignored.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::{
+    jsoncompare,
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonnumber::JsonNumber,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+    jsonpath,
+};
+
+/// Maximum number of nested `$ref` followed while validating a value, so that a reference
+/// cycle like `{"$ref": "#"}` ends with an error.
+const MAX_REF_DEPTH: usize = 64;
+
+/// A bound keyword, the orderings of the numbers within the bound and the relation of the
+/// numbers out of bounds.
+type NumberBound = (&'static str, fn(Ordering) -> bool, &'static str);
+
+const NUMBER_BOUNDS: [NumberBound; 4] = [
+    ("minimum", Ordering::is_ge, "less than"),
+    ("maximum", Ordering::is_le, "greater than"),
+    ("exclusiveMinimum", Ordering::is_gt, "less than or equal to"),
+    (
+        "exclusiveMaximum",
+        Ordering::is_lt,
+        "greater than or equal to",
+    ),
+];
+
+/// Result of [`JsonFixer::fix_and_validate`](super::JsonFixer::fix_and_validate).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaReport {
+    /// The fixed JSON string.
+    pub output: String,
+    /// The schema violations of the fixed value, in document order.
+    pub errors: Vec<SchemaError>,
+}
+
+impl SchemaReport {
+    /// Returns `true` if the fixed value is valid against the schema.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A schema violation of the fixed value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError {
+    /// JSON Pointer of the invalid value, eg. `/users/0/name`, empty for the root value.
+    pub pointer: String,
+    /// The schema keyword the value violates, eg. `required`.
+    pub keyword: String,
+    /// Human readable description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at '{}' ({})",
+            self.message, self.pointer, self.keyword
+        )
+    }
+}
+
+/// Fixes `input` and `schema` with the default configuration, then validates the fixed
+/// value against the schema.
+pub fn fix_and_validate(input: &str, schema: &str) -> Result<SchemaReport, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let (schema, _) = JsonParser::with_borrowed_config(schema, &config).parse_document()?;
+
+    let mut parser = JsonParser::with_borrowed_config(input, &config);
+    let (value, _) = parser.parse_document()?;
+    let mut output = String::new();
+    parser.format_document_into(&value, &mut output)?;
+
+    let mut validator = SchemaValidator::new(&schema);
+    validator.validate(&value, &schema, &mut String::new(), 0);
+    Ok(SchemaReport {
+        output,
+        errors: validator.errors,
+    })
+}
+
+/// Returns the path patterns of the values whose schema allows the `type_name` type, eg.
+/// `users[*].admin` for a `boolean` property of the items of a `users` array.
+///
+/// The schema is fixed with the default configuration, and walked through `properties`,
+/// `additionalProperties`, `prefixItems`, `items`, `allOf`, `anyOf`, `oneOf` and `$ref`. A
+/// recursive reference is not followed again from within itself.
+pub fn paths_of_type(schema: &str, type_name: &str) -> Result<Vec<String>, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let (schema, _) = JsonParser::with_borrowed_config(schema, &config).parse_document()?;
+
+    let mut collector = PathCollector {
+        root: &schema,
+        type_name,
+        references: Vec::new(),
+        paths: Vec::new(),
+    };
+    collector.collect(&schema, "");
+    Ok(collector.paths)
+}
+
+/// Collects the paths of [`paths_of_type`].
+struct PathCollector<'s> {
+    root: &'s JsonValue,
+    type_name: &'s str,
+    /// The references being followed
+    references: Vec<&'s str>,
+    paths: Vec<String>,
+}
+
+impl<'s> PathCollector<'s> {
+    fn collect(&mut self, schema: &'s JsonValue, path: &str) {
+        let JsonValue::Object(schema) = schema else {
+            return;
+        };
+
+        if let Some(JsonValue::String(reference)) = get(schema, "$ref") {
+            if !self.references.contains(&reference.as_str()) {
+                if let Some(target) = resolve(self.root, reference) {
+                    self.references.push(reference);
+                    self.collect(target, path);
+                    self.references.pop();
+                }
+            }
+        }
+
+        let allowed = match get(schema, "type") {
+            Some(JsonValue::String(name)) => name == self.type_name,
+            Some(JsonValue::Array(names)) => entry_values(names)
+                .any(|name| matches!(name, JsonValue::String(name) if name == self.type_name)),
+            _ => false,
+        };
+        if allowed && !self.paths.iter().any(|known| known == path) {
+            self.paths.push(path.to_string());
+        }
+
+        for keyword in ["allOf", "anyOf", "oneOf"] {
+            if let Some(JsonValue::Array(schemas)) = get(schema, keyword) {
+                for subschema in entry_values(schemas) {
+                    self.collect(subschema, path);
+                }
+            }
+        }
+        if let Some(JsonValue::Object(properties)) = get(schema, "properties") {
+            for entry in properties {
+                if let (Some(key), Some(property_schema)) = (&entry.key, &entry.value) {
+                    self.collect(property_schema, &jsonpath::key(path, key));
+                }
+            }
+        }
+        if let Some(additional) = get(schema, "additionalProperties") {
+            self.collect(additional, &jsonpath::key(path, "*"));
+        }
+        if let Some(JsonValue::Array(prefix)) = get(schema, "prefixItems") {
+            for (index, item_schema) in entry_values(prefix).enumerate() {
+                self.collect(item_schema, &jsonpath::index(path, index));
+            }
+        }
+        if let Some(item_schema) = get(schema, "items") {
+            self.collect(item_schema, &format!("{}[*]", path));
+        }
+    }
+}
+
+struct SchemaValidator<'s> {
+    root: &'s JsonValue,
+    errors: Vec<SchemaError>,
+}
+
+impl<'s> SchemaValidator<'s> {
+    fn new(root: &'s JsonValue) -> Self {
+        Self {
+            root,
+            errors: Vec::new(),
+        }
+    }
+
+    fn error(&mut self, pointer: &str, keyword: &str, message: String) {
+        self.errors.push(SchemaError {
+            pointer: pointer.to_string(),
+            keyword: keyword.to_string(),
+            message,
+        });
+    }
+
+    /// Returns `true` if `value` is valid against `schema`, without reporting anything.
+    fn is_valid(&self, value: &JsonValue, schema: &'s JsonValue, depth: usize) -> bool {
+        let mut validator = SchemaValidator::new(self.root);
+        validator.validate(value, schema, &mut String::new(), depth);
+        validator.errors.is_empty()
+    }
+
+    fn validate(
+        &mut self,
+        value: &JsonValue,
+        schema: &'s JsonValue,
+        pointer: &mut String,
+        depth: usize,
+    ) {
+        let schema = match schema {
+            JsonValue::Boolean(true) => return,
+            JsonValue::Boolean(false) => {
+                self.error(pointer, "false", "No value is allowed here".to_string());
+                return;
+            }
+            JsonValue::Object(members) => members,
+            _ => return, // Not a schema
+        };
+
+        if let Some(JsonValue::String(reference)) = get(schema, "$ref") {
+            match resolve(self.root, reference) {
+                Some(_) if depth >= MAX_REF_DEPTH => self.error(
+                    pointer,
+                    "$ref",
+                    format!("Too many nested references at '{}'", reference),
+                ),
+                Some(target) => self.validate(value, target, pointer, depth + 1),
+                None => self.error(
+                    pointer,
+                    "$ref",
+                    format!("Unresolved reference '{}'", reference),
+                ),
+            }
+        }
+
+        self.validate_type(value, schema, pointer);
+        self.validate_enum(value, schema, pointer);
+        match value {
+            JsonValue::Number(_) => self.validate_number(value, schema, pointer),
+            JsonValue::String(s) => self.validate_string(s, schema, pointer),
+            JsonValue::Array(entries) => self.validate_array(entries, schema, pointer, depth),
+            JsonValue::Object(entries) => self.validate_object(entries, schema, pointer, depth),
+            _ => (),
+        }
+        self.validate_combinators(value, schema, pointer, depth);
+    }
+
+    fn validate_type(&mut self, value: &JsonValue, schema: &[JsonEntryValue], pointer: &str) {
+        let allowed: Vec<&str> = match get(schema, "type") {
+            Some(JsonValue::String(name)) => vec![name],
+            Some(JsonValue::Array(names)) => entry_values(names)
+                .filter_map(|name| match name {
+                    JsonValue::String(name) => Some(name.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            _ => return,
+        };
+
+        let found = type_name(value);
+        let matches = allowed
+            .iter()
+            .any(|name| *name == found || (*name == "number" && found == "integer"));
+        if !matches {
+            let message = format!("Expected {} but found {}", allowed.join(" or "), found);
+            self.error(pointer, "type", message);
+        }
+    }
+
+    fn validate_enum(&mut self, value: &JsonValue, schema: &[JsonEntryValue], pointer: &str) {
+        if let Some(JsonValue::Array(values)) = get(schema, "enum") {
+            if !entry_values(values).any(|allowed| equal(value, allowed)) {
+                self.error(
+                    pointer,
+                    "enum",
+                    "Value is not one of the allowed values".to_string(),
+                );
+            }
+        }
+        if let Some(constant) = get(schema, "const") {
+            if !equal(value, constant) {
+                self.error(
+                    pointer,
+                    "const",
+                    "Value is not the expected constant".to_string(),
+                );
+            }
+        }
+    }
+
+    fn validate_number(&mut self, value: &JsonValue, schema: &[JsonEntryValue], pointer: &str) {
+        let JsonValue::Number(n) = value else {
+            return;
+        };
+
+        for (keyword, is_valid, relation) in NUMBER_BOUNDS {
+            let Some(JsonValue::Number(bound)) = get(schema, keyword) else {
+                continue;
+            };
+            if compare_numbers(n, bound).is_some_and(|ordering| !is_valid(ordering)) {
+                let message = format!("{} is {} {}", n, relation, bound);
+                self.error(pointer, keyword, message);
+            }
+        }
+    }
+
+    fn validate_string(&mut self, s: &str, schema: &[JsonEntryValue], pointer: &str) {
+        let length = s.chars().count();
+        if let Some(min) = get(schema, "minLength").and_then(count) {
+            if length < min {
+                let message = format!("String of {} characters is shorter than {}", length, min);
+                self.error(pointer, "minLength", message);
+            }
+        }
+        if let Some(max) = get(schema, "maxLength").and_then(count) {
+            if length > max {
+                let message = format!("String of {} characters is longer than {}", length, max);
+                self.error(pointer, "maxLength", message);
+            }
+        }
+    }
+
+    fn validate_array(
+        &mut self,
+        entries: &[JsonEntryValue],
+        schema: &'s [JsonEntryValue],
+        pointer: &mut String,
+        depth: usize,
+    ) {
+        let items: Vec<&JsonValue> = entry_values(entries).collect();
+
+        let mut prefix_len = 0;
+        if let Some(JsonValue::Array(prefix)) = get(schema, "prefixItems") {
+            for (index, item_schema) in entry_values(prefix).enumerate().take(items.len()) {
+                self.validate_child(
+                    items[index],
+                    item_schema,
+                    pointer,
+                    &index.to_string(),
+                    depth,
+                );
+                prefix_len += 1;
+            }
+        }
+        if let Some(item_schema) = get(schema, "items") {
+            for (index, item) in items.iter().enumerate().skip(prefix_len) {
+                self.validate_child(item, item_schema, pointer, &index.to_string(), depth);
+            }
+        }
+
+        if let Some(min) = get(schema, "minItems").and_then(count) {
+            if items.len() < min {
+                let message = format!("Array of {} items has fewer than {}", items.len(), min);
+                self.error(pointer, "minItems", message);
+            }
+        }
+        if let Some(max) = get(schema, "maxItems").and_then(count) {
+            if items.len() > max {
+                let message = format!("Array of {} items has more than {}", items.len(), max);
+                self.error(pointer, "maxItems", message);
+            }
+        }
+        if let Some(JsonValue::Boolean(true)) = get(schema, "uniqueItems") {
+            let duplicate = (1..items.len())
+                .find(|&i| items[..i].iter().any(|previous| equal(previous, items[i])));
+            if let Some(index) = duplicate {
+                let message = format!("Item {} is a duplicate of a previous item", index);
+                self.error(pointer, "uniqueItems", message);
+            }
+        }
+    }
+
+    fn validate_object(
+        &mut self,
+        entries: &[JsonEntryValue],
+        schema: &'s [JsonEntryValue],
+        pointer: &mut String,
+        depth: usize,
+    ) {
+        let properties = match get(schema, "properties") {
+            Some(JsonValue::Object(properties)) => Some(properties),
+            _ => None,
+        };
+        let additional = get(schema, "additionalProperties");
+
+        for entry in entries {
+            let (key, value) = match (&entry.key, &entry.value) {
+                (Some(key), Some(value)) => (key, value),
+                _ => continue,
+            };
+            match properties.and_then(|properties| get(properties, key)) {
+                Some(property_schema) => {
+                    self.validate_child(value, property_schema, pointer, key, depth)
+                }
+                None => {
+                    if let Some(additional) = additional {
+                        self.validate_child(value, additional, pointer, key, depth);
+                    }
+                }
+            }
+        }
+
+        if let Some(JsonValue::Array(required)) = get(schema, "required") {
+            for name in entry_values(required) {
+                if let JsonValue::String(name) = name {
+                    if get(entries, name).is_none() {
+                        let message = format!("Missing required property '{}'", name);
+                        self.error(pointer, "required", message);
+                    }
+                }
+            }
+        }
+
+        let len = entry_values(entries).count();
+        if let Some(min) = get(schema, "minProperties").and_then(count) {
+            if len < min {
+                let message = format!("Object of {} properties has fewer than {}", len, min);
+                self.error(pointer, "minProperties", message);
+            }
+        }
+        if let Some(max) = get(schema, "maxProperties").and_then(count) {
+            if len > max {
+                let message = format!("Object of {} properties has more than {}", len, max);
+                self.error(pointer, "maxProperties", message);
+            }
+        }
+    }
+
+    fn validate_combinators(
+        &mut self,
+        value: &JsonValue,
+        schema: &'s [JsonEntryValue],
+        pointer: &mut String,
+        depth: usize,
+    ) {
+        if let Some(JsonValue::Array(schemas)) = get(schema, "allOf") {
+            for sub_schema in entry_values(schemas) {
+                self.validate(value, sub_schema, pointer, depth);
+            }
+        }
+        if let Some(JsonValue::Array(schemas)) = get(schema, "anyOf") {
+            if !entry_values(schemas).any(|sub_schema| self.is_valid(value, sub_schema, depth)) {
+                let message = "Value is not valid against any schema".to_string();
+                self.error(pointer, "anyOf", message);
+            }
+        }
+        if let Some(JsonValue::Array(schemas)) = get(schema, "oneOf") {
+            let valid = entry_values(schemas)
+                .filter(|sub_schema| self.is_valid(value, sub_schema, depth))
+                .count();
+            if valid != 1 {
+                let message = format!("Value is valid against {} schemas instead of 1", valid);
+                self.error(pointer, "oneOf", message);
+            }
+        }
+        if let Some(sub_schema) = get(schema, "not") {
+            if self.is_valid(value, sub_schema, depth) {
+                let message = "Value is valid against the excluded schema".to_string();
+                self.error(pointer, "not", message);
+            }
+        }
+    }
+
+    /// Validates a member or an item, `token` being its key or index.
+    fn validate_child(
+        &mut self,
+        value: &JsonValue,
+        schema: &'s JsonValue,
+        pointer: &mut String,
+        token: &str,
+        depth: usize,
+    ) {
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+        self.validate(value, schema, pointer, depth);
+        pointer.truncate(len);
+    }
+}
+
+/// Returns the value of the last member named `key`.
+fn get<'v>(entries: &'v [JsonEntryValue], key: &str) -> Option<&'v JsonValue> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| entry.key.as_deref() == Some(key))
+        .and_then(|entry| entry.value.as_ref())
+}
+
+fn entry_values(entries: &[JsonEntryValue]) -> impl Iterator<Item = &JsonValue> {
+    entries.iter().filter_map(|entry| entry.value.as_ref())
+}
+
+/// Resolves a reference to a JSON Pointer of the root schema, eg. `#/$defs/name`.
+fn resolve<'s>(root: &'s JsonValue, reference: &str) -> Option<&'s JsonValue> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+
+    let mut target = root;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        target = match target {
+            JsonValue::Object(entries) => get(entries, &token)?,
+            JsonValue::Array(entries) => entry_values(entries).nth(token.parse().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(target)
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Boolean(_) => "boolean",
+        JsonValue::Number(_) if number(value).is_some_and(|n| n.fract() == 0.0) => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+        JsonValue::Space(_) => "whitespace",
+    }
+}
+
+fn number(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Number(n) => n.as_f64(),
+        _ => None,
+    }
+}
+
+/// Compares a number to a bound exactly.
+#[cfg(feature = "bignum")]
+fn compare_numbers(n: &JsonNumber, bound: &JsonNumber) -> Option<Ordering> {
+    n.exact_cmp(bound)
+}
+
+/// Compares a number to a bound as `f64`, exactly with the `bignum` feature.
+#[cfg(not(feature = "bignum"))]
+fn compare_numbers(n: &JsonNumber, bound: &JsonNumber) -> Option<Ordering> {
+    n.as_f64()?.partial_cmp(&bound.as_f64()?)
+}
+
+/// Non-negative integer of a keyword like `minLength`.
+fn count(value: &JsonValue) -> Option<usize> {
+    match value {
+        JsonValue::Number(n) => n.as_u64().and_then(|n| usize::try_from(n).ok()),
+        _ => None,
+    }
+}
+
+/// Equality of JSON Schema: object members in any order, numbers by value.
+fn equal(a: &JsonValue, b: &JsonValue) -> bool {
+    let options = jsoncompare::CompareOptions {
+        ignore_number_format: true,
+    };
+    jsoncompare::values_equal(a, b, &options)
+}
@@ -0,0 +1,143 @@
+//! Conversions between the parsed values and the values of `serde_json`, so that a fixed
+//! document can be handed to the serde ecosystem and back without writing it as text.
+//!
+//! Numbers keep their value whenever `serde_json` can hold it: the integers of `i64` and
+//! `u64` and the finite `f64` are converted as is, and the other numbers the way `serde_json`
+//! parses them, eg. big integers kept exact with its `arbitrary_precision` feature and
+//! rounded to the closest `f64` without. The numbers no `serde_json::Number` can hold, eg.
+//! `1e400` or a kept `NaN`, are converted to `null`, like `serde_json` converts non-finite
+//! floats.
+//!
+//! The lone surrogates kept in strings, eg. `"\ud800"`, are replaced by U+FFFD as
+//! `serde_json` strings cannot hold them.
+//!
+//! The conversions fail on the values nested deeper than `jsonparser::MAX_DEPTH`, which the
+//! parser rejects as well, rather than overflowing the stack.
+//!
+//! This module is only available when the `serde` feature is enabled.
+
+use serde_json::{Map, Number, Value};
+
+use super::{
+    jsonescape::replace_lone_surrogates,
+    jsonfixer_error::{JsonFixerError, LimitError},
+    jsonnumber::JsonNumber,
+    jsonparser::{JsonEntryValue, JsonValue, MAX_DEPTH},
+    jsonpath,
+};
+
+impl TryFrom<JsonValue> for Value {
+    type Error = JsonFixerError;
+
+    /// Converts a parsed value, the entries without value and the whitespaces left out. Only
+    /// the last occurrence of a duplicated key is kept. Fails with `LimitError::TooDeep` when
+    /// the arrays and objects are nested deeper than [`MAX_DEPTH`].
+    fn try_from(value: JsonValue) -> Result<Self, Self::Error> {
+        to_serde(value, "", 0)
+    }
+}
+
+impl TryFrom<Value> for JsonValue {
+    type Error = JsonFixerError;
+
+    /// Converts a `serde_json` value, its numbers written back as `serde_json` writes them.
+    /// Fails with `LimitError::TooDeep` when the arrays and objects are nested deeper than
+    /// [`MAX_DEPTH`].
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        from_serde(value, "", 0)
+    }
+}
+
+/// Converts the parsed value at `path`, nested in `depth` arrays and objects.
+fn to_serde(value: JsonValue, path: &str, depth: usize) -> Result<Value, JsonFixerError> {
+    let value = match value {
+        JsonValue::Null | JsonValue::Space(_) => Value::Null,
+        JsonValue::Boolean(b) => Value::Bool(b),
+        JsonValue::Number(n) => number_to_serde(n),
+        JsonValue::String(s) => Value::String(replace_lone_surrogates(s)),
+        JsonValue::Array(entries) => {
+            let depth = enter(path, depth)?;
+            let mut values = Vec::with_capacity(entries.len());
+            let elements = entries
+                .into_iter()
+                .filter_map(|entry| entry.value)
+                .filter(|value| !matches!(value, JsonValue::Space(_)));
+            for value in elements {
+                let child_path = jsonpath::index(path, values.len());
+                values.push(to_serde(value, &child_path, depth)?);
+            }
+            Value::Array(values)
+        }
+        JsonValue::Object(entries) => {
+            let depth = enter(path, depth)?;
+            let mut map = Map::new();
+            for entry in entries {
+                if let (Some(key), Some(value)) = (entry.key, entry.value) {
+                    let value = to_serde(value, &jsonpath::key(path, &key), depth)?;
+                    map.insert(replace_lone_surrogates(key), value);
+                }
+            }
+            Value::Object(map)
+        }
+    };
+    Ok(value)
+}
+
+/// Converts the `serde_json` value at `path`, nested in `depth` arrays and objects.
+fn from_serde(value: Value, path: &str, depth: usize) -> Result<JsonValue, JsonFixerError> {
+    let value = match value {
+        Value::Null => JsonValue::Null,
+        Value::Bool(b) => JsonValue::Boolean(b),
+        Value::Number(n) => JsonValue::Number(JsonNumber::parse(&n.to_string())),
+        Value::String(s) => JsonValue::String(s),
+        Value::Array(values) => {
+            let depth = enter(path, depth)?;
+            let mut entries = Vec::with_capacity(values.len());
+            for value in values {
+                let child_path = jsonpath::index(path, entries.len());
+                entries.push(JsonEntryValue {
+                    value: Some(from_serde(value, &child_path, depth)?),
+                    ..JsonEntryValue::new()
+                });
+            }
+            JsonValue::Array(entries)
+        }
+        Value::Object(map) => {
+            let depth = enter(path, depth)?;
+            let mut entries = Vec::with_capacity(map.len());
+            for (key, value) in map {
+                let value = from_serde(value, &jsonpath::key(path, &key), depth)?;
+                entries.push(JsonEntryValue {
+                    key: Some(key),
+                    value: Some(value),
+                    ..JsonEntryValue::new()
+                });
+            }
+            JsonValue::Object(entries)
+        }
+    };
+    Ok(value)
+}
+
+/// Returns the depth of the array or object at `path` nested in `depth` others, fails when it
+/// is deeper than [`MAX_DEPTH`].
+fn enter(path: &str, depth: usize) -> Result<usize, JsonFixerError> {
+    if depth == MAX_DEPTH {
+        return Err(JsonFixerError::Limit(LimitError::TooDeep {
+            path: path.to_string(),
+            max: MAX_DEPTH,
+        }));
+    }
+    Ok(depth + 1)
+}
+
+/// Converts a number, `null` if `serde_json` cannot hold it.
+fn number_to_serde(n: JsonNumber) -> Value {
+    let number = match n {
+        JsonNumber::I64(n) => Some(Number::from(n)),
+        JsonNumber::U64(n) => Some(Number::from(n)),
+        JsonNumber::F64(n) => Number::from_f64(n),
+        JsonNumber::BigRaw(literal) => literal.parse().ok(),
+    };
+    number.map_or(Value::Null, Value::Number)
+}
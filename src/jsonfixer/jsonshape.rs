@@ -0,0 +1,105 @@
+//! Lightweight expectations on the shape of fixed values, for ingestion gates.
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::{JsonFixerError, ShapeError, ShapeViolation},
+    jsonindex::ValueKind,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+    jsonpath,
+};
+
+/// The structure a fixed value is expected to have, see `fix_expecting`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// An object having at least these keys
+    Object { required_keys: Vec<String> },
+    /// An array of any values
+    Array,
+    /// An array whose elements are objects having at least these keys
+    ArrayOfObjects { required_keys: Vec<String> },
+}
+
+/// Fixes `input` with the default configuration and checks that the fixed value has the
+/// expected shape, failing with `JsonFixerError::Shape` listing every violation otherwise.
+pub fn fix_expecting(input: &str, shape: &Shape) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let mut parser = JsonParser::with_borrowed_config(input, &config);
+    let (value, _) = parser.parse_document()?;
+
+    let mut violations = Vec::new();
+    match shape {
+        Shape::Object { required_keys } => {
+            check_object(&value, "", required_keys, &mut violations);
+        }
+        Shape::Array => {
+            expect_kind(&value, "", ValueKind::Array, &mut violations);
+        }
+        Shape::ArrayOfObjects { required_keys } => {
+            if let Some(entries) = expect_kind(&value, "", ValueKind::Array, &mut violations) {
+                let elements = entries.iter().filter_map(|entry| entry.value.as_ref());
+                for (i, element) in elements.enumerate() {
+                    let path = jsonpath::index("", i);
+                    check_object(element, &path, required_keys, &mut violations);
+                }
+            }
+        }
+    }
+    if !violations.is_empty() {
+        return Err(JsonFixerError::Shape(ShapeError { violations }));
+    }
+
+    let mut output = String::new();
+    parser.format_document_into(&value, &mut output)?;
+    Ok(output)
+}
+
+/// Checks that `value` is an object having the required keys.
+fn check_object(
+    value: &JsonValue,
+    path: &str,
+    required_keys: &[String],
+    violations: &mut Vec<ShapeViolation>,
+) {
+    let Some(entries) = expect_kind(value, path, ValueKind::Object, violations) else {
+        return;
+    };
+    for key in required_keys {
+        if !entries.iter().any(|entry| entry.key.as_ref() == Some(key)) {
+            violations.push(ShapeViolation {
+                path: path.to_string(),
+                message: format!("Missing required key '{}'", key),
+            });
+        }
+    }
+}
+
+/// Returns the entries of `value` if it is of the expected kind, an array or an object,
+/// otherwise records the violation.
+fn expect_kind<'v>(
+    value: &'v JsonValue,
+    path: &str,
+    expected: ValueKind,
+    violations: &mut Vec<ShapeViolation>,
+) -> Option<&'v [JsonEntryValue]> {
+    match (value, expected) {
+        (JsonValue::Array(entries), ValueKind::Array)
+        | (JsonValue::Object(entries), ValueKind::Object) => Some(entries),
+        _ => {
+            let found = ValueKind::of(value).map_or("nothing".to_string(), article);
+            violations.push(ShapeViolation {
+                path: path.to_string(),
+                message: format!("Expected {} but found {}", article(expected), found),
+            });
+            None
+        }
+    }
+}
+
+/// Names a kind of value with its article, eg. `an array`.
+fn article(kind: ValueKind) -> String {
+    match kind {
+        ValueKind::Null => kind.to_string(),
+        ValueKind::Array | ValueKind::Object => format!("an {}", kind),
+        _ => format!("a {}", kind),
+    }
+}
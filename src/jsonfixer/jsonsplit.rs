@@ -0,0 +1,67 @@
+//! Splitting of huge arrays into chunks, eg. to shard a dump into smaller files.
+
+use std::io;
+
+use super::{
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonformatter::{Formatter, JsonFormatter},
+    jsonmembers::expect_container,
+    jsonparser::JsonParser,
+};
+
+/// How the chunks of `split_array` are written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkFormat {
+    /// A JSON array of the elements, eg. `[1,2]`
+    Array,
+    /// One element per line, each line ending with a line break, eg. `1\n2\n`
+    NdJson,
+}
+
+/// Fixes `input`, an array, with the default configuration and passes its elements to `sink`
+/// by chunks of `chunk_size` as soon as they are parsed, the last chunk holding the remaining
+/// elements. Returns the number of chunks written.
+///
+/// Only the elements of the chunk being filled are kept, so the array is never held whole
+/// in memory. An error of `sink` aborts the split and is returned as
+/// `JsonFixerError::IoError`.
+pub fn split_array<F>(
+    input: &str,
+    chunk_size: usize,
+    format: ChunkFormat,
+    mut sink: F,
+) -> Result<usize, JsonFixerError>
+where
+    F: FnMut(&str) -> io::Result<()>,
+{
+    expect_container(input, '[')?;
+
+    let chunk_size = chunk_size.max(1);
+    let config = JsonFixerConfig::default();
+    let mut elements = Vec::with_capacity(chunk_size);
+    let mut chunks = 0;
+    let mut write = |elements: &mut Vec<String>| {
+        let chunk = match format {
+            ChunkFormat::Array => format!("[{}]", elements.join(",")),
+            ChunkFormat::NdJson => elements.iter().map(|e| format!("{}\n", e)).collect(),
+        };
+        elements.clear();
+        chunks += 1;
+        sink(&chunk).map_err(JsonFixerError::IoError)
+    };
+
+    JsonParser::with_borrowed_config(input, &config)
+        .with_element_sink(|value| {
+            elements.push(JsonFormatter.format(&value, &config)?);
+            if elements.len() == chunk_size {
+                write(&mut elements)?;
+            }
+            Ok(())
+        })
+        .parse_document()?;
+    if !elements.is_empty() {
+        write(&mut elements)?;
+    }
+    Ok(chunks)
+}
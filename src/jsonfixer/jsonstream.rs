@@ -0,0 +1,250 @@
+//! Streaming of the fixed output to a writer, one top-level member at a time, eg. to relay an
+//! endless NDJSON stream with bounded memory.
+
+use std::cell::{Cell, RefCell};
+use std::io::{BufRead, Write};
+
+use super::{
+    jsonfixer_config::{JsonFixerConfig, OutputStyle},
+    jsonfixer_error::JsonFixerError,
+    jsonformatter::{Formatter, JsonFormatter},
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+    jsontransformer::{uninsertable_header, RootTransformer},
+    jsonvalidator::JsonValidator,
+};
+
+/// Options of [`JsonFixer::fix_to_writer`](super::JsonFixer::fix_to_writer) and
+/// [`JsonFixer::fix_ndjson`](super::JsonFixer::fix_ndjson).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamOptions {
+    /// Bytes of output buffered before they are written and the writer is flushed, checked
+    /// after each top-level member: `0` flushes after every member for timely delivery, a
+    /// larger cap batches small members into fewer writes. The buffer never holds more than
+    /// the cap and one member.
+    pub buffer_cap: usize,
+}
+
+/// Fixes `input` with `config` and writes it to `writer`, the members or elements of the root
+/// object or array being written as soon as they are parsed. Returns the number of members or
+/// elements written, `0` for the other roots.
+///
+/// The members are transformed and formatted with `config` as `JsonFixer::fix_with_config`
+/// does, see `RootTransformer` for the options needing the whole root. The members written
+/// before an error have been flushed when it is returned.
+pub fn fix_to_writer<W: Write>(
+    input: &str,
+    writer: W,
+    config: &JsonFixerConfig,
+    options: &StreamOptions,
+) -> Result<usize, JsonFixerError> {
+    let output = RefCell::new(MemberWriter::new(writer, options.buffer_cap));
+    let result = write_document(input, &output, config);
+    let mut output = output.into_inner();
+    output.flush()?;
+    result.map(|_| output.members)
+}
+
+/// Fixes each line of `reader`, a document of an NDJSON stream, with `config` and writes it
+/// to `writer` followed by a line break. Returns the number of documents written.
+///
+/// Only the line being fixed is held in memory, so endless streams can be relayed. The blank
+/// lines are skipped, and the documents written before an error have been flushed when it is
+/// returned. The documents are written compact whatever `beautify`, and the positions of the
+/// syntax errors are the ones in the stream, eg. line 3 for an error in the third line.
+pub fn fix_ndjson<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    config: &JsonFixerConfig,
+    options: &StreamOptions,
+) -> Result<usize, JsonFixerError> {
+    let mut output = MemberWriter::new(writer, options.buffer_cap);
+    let result = write_lines(reader, &mut output, config);
+    output.flush()?;
+    result.map(|_| output.members)
+}
+
+fn write_document<W: Write>(
+    input: &str,
+    output: &RefCell<MemberWriter<W>>,
+    config: &JsonFixerConfig,
+) -> Result<(), JsonFixerError> {
+    let root = RefCell::new(RootTransformer::new(config, input));
+    // Bracket of the root container, once its first member is parsed
+    let opening = Cell::new(None);
+
+    let write_member = |entry: &JsonEntryValue| -> Result<(), JsonFixerError> {
+        let mut output = output.borrow_mut();
+        let (start, index) = (output.buffer.len(), output.members);
+        JsonFormatter.format_root_member(entry, index, config, &mut output.buffer)?;
+        if config.verify_output {
+            // The member alone, without the bracket or the comma preceding it
+            let (open, close) = match entry.key {
+                Some(_) => ('{', '}'),
+                None => ('[', ']'),
+            };
+            let member = format!("{}{}{}", open, &output.buffer[start + 1..], close);
+            if let Err(error) = verify(&member, config) {
+                output.buffer.truncate(start);
+                return Err(error);
+            }
+        }
+        output.member()
+    };
+    let open = |bracket: char| -> Result<(), JsonFixerError> {
+        if opening.get().is_some() {
+            return Ok(());
+        }
+        let root = root.borrow();
+        root.check(bracket == '{')?;
+        opening.set(Some(bracket));
+        match root.header() {
+            Some(header) if bracket == '{' => write_member(&header),
+            Some(_) => Err(uninsertable_header()),
+            None => Ok(()),
+        }
+    };
+
+    let (value, _) = JsonParser::with_borrowed_config(input, config)
+        .with_member_sink(|entry| {
+            open('{')?;
+            let member = root.borrow_mut().member(entry)?;
+            member.map_or(Ok(()), |member| write_member(&member))
+        })
+        .with_element_sink(|value| {
+            open('[')?;
+            let element = root.borrow_mut().element(value)?;
+            element.map_or(Ok(()), |value| {
+                let mut element = JsonEntryValue::new();
+                element.value = Some(value);
+                write_member(&element)
+            })
+        })
+        .parse_document()?;
+
+    // The root is transformed whole by the parser when it is not streamed, ie. when it is
+    // empty or a scalar
+    let Some(bracket) = opening.get() else {
+        let mut output = output.borrow_mut();
+        let text = JsonFormatter.format(&value, config)?;
+        verify(&text, config)?;
+        output.buffer.push_str(&text);
+        return Ok(());
+    };
+    let mut root = root.into_inner();
+    let is_object = bracket == '{';
+    if is_object {
+        for entry in root.defaults()? {
+            write_member(&entry)?;
+        }
+    }
+    root.finish(is_object)?;
+
+    let mut output = output.borrow_mut();
+    if output.members > 0 {
+        let closing = if is_object { '}' } else { ']' };
+        JsonFormatter.close_root(closing, config, &mut output.buffer)
+    } else {
+        // Every member was dropped
+        let empty = if is_object {
+            JsonValue::Object(Vec::new())
+        } else {
+            JsonValue::Array(Vec::new())
+        };
+        JsonFormatter.format_into(&empty, config, &mut output.buffer)
+    }
+}
+
+/// Re-validates fixed `text` with the strict validator when `verify_output` is set, like
+/// `JsonParser::parse` does.
+fn verify(text: &str, config: &JsonFixerConfig) -> Result<(), JsonFixerError> {
+    if config.verify_output && config.output_style == OutputStyle::Json {
+        if let Err(diagnostic) = JsonValidator::validate_with(text, &config.compliance) {
+            let output = text.to_string();
+            return Err(JsonFixerError::Internal { output, diagnostic });
+        }
+    }
+    Ok(())
+}
+
+fn write_lines<R: BufRead, W: Write>(
+    mut reader: R,
+    output: &mut MemberWriter<W>,
+    config: &JsonFixerConfig,
+) -> Result<(), JsonFixerError> {
+    // A document pretty printed over several lines would break the stream
+    let compact;
+    let config = match config.beautify {
+        true => {
+            compact = JsonFixerConfig {
+                beautify: false,
+                ..config.clone()
+            };
+            &compact
+        }
+        false => config,
+    };
+
+    let mut line = String::new();
+    let mut number = 0;
+    while reader
+        .read_line(&mut line)
+        .map_err(JsonFixerError::IoError)?
+        > 0
+    {
+        number += 1;
+        if !line.trim().is_empty() {
+            let document = line.trim_end_matches(['\r', '\n']);
+            let fixed = JsonParser::with_borrowed_config(document, config)
+                .parse()
+                .map_err(|error| match error {
+                    JsonFixerError::Syntax(mut error) => {
+                        error.position_mut().line += number - 1;
+                        JsonFixerError::Syntax(error)
+                    }
+                    error => error,
+                })?;
+            output.buffer.push_str(&fixed);
+            output.buffer.push('\n');
+            output.member()?;
+        }
+        line.clear();
+    }
+    Ok(())
+}
+
+/// Output buffered until a top-level member is complete and the buffer holds `cap` bytes.
+struct MemberWriter<W: Write> {
+    writer: W,
+    buffer: String,
+    cap: usize,
+    members: usize,
+}
+
+impl<W: Write> MemberWriter<W> {
+    fn new(writer: W, cap: usize) -> Self {
+        Self {
+            writer,
+            buffer: String::new(),
+            cap,
+            members: 0,
+        }
+    }
+
+    /// Counts the top-level member ending the buffer, and writes the buffer once it reaches
+    /// the cap.
+    fn member(&mut self) -> Result<(), JsonFixerError> {
+        self.members += 1;
+        if self.buffer.len() >= self.cap {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), JsonFixerError> {
+        self.writer
+            .write_all(self.buffer.as_bytes())
+            .map_err(JsonFixerError::IoError)?;
+        self.buffer.clear();
+        self.writer.flush().map_err(JsonFixerError::IoError)
+    }
+}
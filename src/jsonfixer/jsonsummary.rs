@@ -0,0 +1,123 @@
+//! Short human readable summaries of documents, for log messages.
+
+use std::fmt::Write;
+
+use super::{
+    jsonescape::{self, EscapePolicy},
+    jsonfixer_config::JsonFixerConfig,
+    jsonfixer_error::JsonFixerError,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+};
+
+/// Maximum number of members written for each object of a summary.
+pub const SUMMARY_KEYS: usize = 5;
+
+/// Maximum number of elements written for each array of a summary.
+pub const SUMMARY_ITEMS: usize = 3;
+
+/// Maximum number of characters of the strings of a summary.
+pub const PREVIEW_LENGTH: usize = 24;
+
+/// Fixes `input` with the default configuration and summarizes the fixed value on a single
+/// line, the objects and arrays nested deeper than `depth` being summarized by their size.
+pub fn summarize(input: &str, depth: usize) -> Result<String, JsonFixerError> {
+    let config = JsonFixerConfig::default();
+    let (value, _) = JsonParser::with_borrowed_config(input, &config).parse_document()?;
+
+    let mut summary = String::new();
+    write_summary(&mut summary, &value, depth);
+    Ok(summary)
+}
+
+fn write_summary(output: &mut String, value: &JsonValue, depth: usize) {
+    match value {
+        JsonValue::Null => output.push_str("null"),
+        JsonValue::Boolean(b) => {
+            let _ = write!(output, "{}", b);
+        }
+        JsonValue::Number(n) => {
+            let _ = write!(output, "{}", n);
+        }
+        JsonValue::String(s) => write_preview(output, s),
+        JsonValue::Array(entries) => write_array(output, entries, depth),
+        JsonValue::Object(entries) => write_object(output, entries, depth),
+        JsonValue::Space(_) => (),
+    }
+}
+
+/// Writes the length of an array and its first elements, eg. `[100 items: 1, 2, 3, …]`.
+fn write_array(output: &mut String, entries: &[JsonEntryValue], depth: usize) {
+    let items: Vec<&JsonValue> = entries.iter().filter_map(|e| e.value.as_ref()).collect();
+    match items.len() {
+        0 => return output.push_str("[]"),
+        1 => output.push_str("[1 item"),
+        length => {
+            let _ = write!(output, "[{} items", length);
+        }
+    }
+    if depth == 0 {
+        return output.push(']');
+    }
+
+    output.push_str(": ");
+    for (index, item) in items.iter().take(SUMMARY_ITEMS).enumerate() {
+        if index > 0 {
+            output.push_str(", ");
+        }
+        write_summary(output, item, depth - 1);
+    }
+    if items.len() > SUMMARY_ITEMS {
+        output.push_str(", …");
+    }
+    output.push(']');
+}
+
+/// Writes the first members of an object, eg. `{id: 1, name: "Ann", … 3 more keys}`, or
+/// its number of keys, eg. `{5 keys}`.
+fn write_object(output: &mut String, entries: &[JsonEntryValue], depth: usize) {
+    let members: Vec<(&str, &JsonValue)> = entries
+        .iter()
+        .filter_map(|entry| Some((entry.key.as_deref()?, entry.value.as_ref()?)))
+        .collect();
+    if members.is_empty() {
+        return output.push_str("{}");
+    }
+    if depth == 0 {
+        let _ = match members.len() {
+            1 => write!(output, "{{1 key}}"),
+            length => write!(output, "{{{} keys}}", length),
+        };
+        return;
+    }
+
+    output.push('{');
+    for (index, (key, value)) in members.iter().take(SUMMARY_KEYS).enumerate() {
+        if index > 0 {
+            output.push_str(", ");
+        }
+        output.push_str(key);
+        output.push_str(": ");
+        write_summary(output, value, depth - 1);
+    }
+    match members.len().saturating_sub(SUMMARY_KEYS) {
+        0 => (),
+        1 => output.push_str(", … 1 more key"),
+        more => {
+            let _ = write!(output, ", … {} more keys", more);
+        }
+    }
+    output.push('}');
+}
+
+/// Writes a string quoted and escaped, cut to `PREVIEW_LENGTH` characters.
+fn write_preview(output: &mut String, s: &str) {
+    output.push('"');
+    match s.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => {
+            jsonescape::escape_into(output, &s[..end], EscapePolicy::Json);
+            output.push('…');
+        }
+        None => jsonescape::escape_into(output, s, EscapePolicy::Json),
+    }
+    output.push('"');
+}
@@ -0,0 +1,1241 @@
+//! Passes applied to the parsed value tree before it is formatted.
+
+use std::collections::HashSet;
+
+use super::{
+    jsonfixer_config::{
+        DuplicateKeys, HomogenizeArrays, JsonFixerConfig, LengthPolicy, NormalizeNulls, SizePolicy,
+    },
+    jsonfixer_error::{JsonFixerError, LimitError},
+    jsonfixer_report::{Confidence, Dropped, Repair, RepairKind},
+    jsonformatter::{Formatter, JsonFormatter},
+    jsonnumber::JsonNumber,
+    jsonparser::{JsonEntryValue, JsonParser, JsonValue},
+    jsonpath,
+    jsonvalidator::JsonValidator,
+};
+
+/// Key of the header written in the root object of JSON output, see `JsonFixerConfig::header`.
+pub const HEADER_KEY: &str = "_generated_by";
+
+/// What to do with a value visited by a [`Transform`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformAction {
+    /// Keep the value, along with the changes made to it and to its key
+    Keep,
+    /// Remove the member or element, a dropped root value is written as `null`
+    Drop,
+}
+
+/// A user transformation applied to every value of the fixed document before it is written.
+///
+/// Values are visited parents first, with their path and the key of object members. The path
+/// is built from the keys of the parents as written in the output, followed by the key of the
+/// value before it is transformed. The key and the value can be modified in place, eg. to
+/// rename keys or rewrite values, and the children of the value are visited once it was
+/// transformed.
+/// Closures with the same signature as [`Transform::transform`] are transforms, and
+/// transforms are chained with [`Transform::then`].
+pub trait Transform {
+    fn transform(
+        &mut self,
+        path: &str,
+        key: Option<&mut String>,
+        value: &mut JsonValue,
+    ) -> TransformAction;
+
+    /// Chains `next` after this transform, `next` is not called on dropped values.
+    fn then<T: Transform>(self, next: T) -> Chain<Self, T>
+    where
+        Self: Sized,
+    {
+        Chain(self, next)
+    }
+}
+
+impl<F> Transform for F
+where
+    F: FnMut(&str, Option<&mut String>, &mut JsonValue) -> TransformAction,
+{
+    fn transform(
+        &mut self,
+        path: &str,
+        key: Option<&mut String>,
+        value: &mut JsonValue,
+    ) -> TransformAction {
+        self(path, key, value)
+    }
+}
+
+/// Two transforms applied one after the other, see [`Transform::then`].
+pub struct Chain<A, B>(pub A, pub B);
+
+impl<A: Transform, B: Transform> Transform for Chain<A, B> {
+    fn transform(
+        &mut self,
+        path: &str,
+        mut key: Option<&mut String>,
+        value: &mut JsonValue,
+    ) -> TransformAction {
+        match self.0.transform(path, key.as_deref_mut(), value) {
+            TransformAction::Keep => self.1.transform(path, key, value),
+            TransformAction::Drop => TransformAction::Drop,
+        }
+    }
+}
+
+/// Applies a user transform to the root value and all its children.
+pub fn apply_transform<T: Transform + ?Sized>(
+    mut value: JsonValue,
+    transform: &mut T,
+) -> JsonValue {
+    match transform.transform("", None, &mut value) {
+        TransformAction::Keep => apply_transform_children(value, "", transform),
+        TransformAction::Drop => JsonValue::Null,
+    }
+}
+
+fn apply_transform_children<T: Transform + ?Sized>(
+    value: JsonValue,
+    path: &str,
+    transform: &mut T,
+) -> JsonValue {
+    let (mut entries, is_object) = match value {
+        JsonValue::Object(entries) => (entries, true),
+        JsonValue::Array(entries) => (entries, false),
+        value => return value,
+    };
+
+    let mut index = 0;
+    entries.retain_mut(|entry| {
+        let mut value = match entry.value.take() {
+            Some(value) => value,
+            None => return true, // Whitespaces only entry
+        };
+        let action = if is_object {
+            let mut key = entry.get_key();
+            let child_path = jsonpath::key(path, &key);
+            let action = transform.transform(&child_path, Some(&mut key), &mut value);
+            // The children are located under the new key
+            let child_path = jsonpath::key(path, &key);
+            entry.key = Some(key);
+            if action == TransformAction::Keep {
+                value = apply_transform_children(value, &child_path, transform);
+            }
+            action
+        } else {
+            let child_path = jsonpath::index(path, index);
+            let action = transform.transform(&child_path, None, &mut value);
+            if action == TransformAction::Keep {
+                value = apply_transform_children(value, &child_path, transform);
+                index += 1;
+            }
+            action
+        };
+        entry.value = Some(value);
+        action == TransformAction::Keep
+    });
+
+    if is_object {
+        JsonValue::Object(entries)
+    } else {
+        JsonValue::Array(entries)
+    }
+}
+
+/// Applies the configured transformations to a parsed value.
+pub struct JsonTransformer<'a> {
+    config: &'a JsonFixerConfig,
+    repairs: Vec<Repair>,
+    /// Input the value was parsed from, located by the spans of its members
+    input: &'a str,
+}
+
+impl<'a> JsonTransformer<'a> {
+    pub fn new(config: &'a JsonFixerConfig) -> Self {
+        Self {
+            config,
+            repairs: Vec::new(),
+            input: "",
+        }
+    }
+
+    /// Reports the members dropped from the value as written in `input`, the input it was
+    /// parsed from, when their spans are known, see `JsonEntryValue::span`.
+    pub fn with_input(mut self, input: &'a str) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Returns the repairs applied by the transformations.
+    pub fn into_repairs(self) -> Vec<Repair> {
+        self.repairs
+    }
+
+    /// Transforms the root value and all its children, fails when a limit is exceeded and
+    /// its policy is to error.
+    pub fn transform(&mut self, value: JsonValue) -> Result<JsonValue, JsonFixerError> {
+        let value = self.transform_value(value, "")?;
+        self.insert_header(value)
+    }
+
+    /// Writes the header as the first key of a root object, fails if the root is not an
+    /// object or already has a key of the same name.
+    fn insert_header(&self, value: JsonValue) -> Result<JsonValue, JsonFixerError> {
+        let Some(header) = self.config.header_key() else {
+            return Ok(value);
+        };
+        let mut entries = match value {
+            JsonValue::Object(entries) => entries,
+            _ => return Err(uninsertable_header()),
+        };
+
+        if entries
+            .iter()
+            .any(|entry| entry.key.as_deref() == Some(HEADER_KEY))
+        {
+            return Err(uninsertable_header());
+        }
+        entries.insert(0, member(HEADER_KEY, JsonValue::String(header.to_string())));
+        Ok(JsonValue::Object(entries))
+    }
+
+    fn transform_value(
+        &mut self,
+        value: JsonValue,
+        path: &str,
+    ) -> Result<JsonValue, JsonFixerError> {
+        let value = match value {
+            JsonValue::Object(entries) => {
+                let entries = self.transform_members(entries, path)?;
+                let entries = entries
+                    .into_iter()
+                    .map(|mut entry| {
+                        if let Some(val) = entry.value.take() {
+                            let child_path = jsonpath::key(path, &entry.get_key());
+                            entry.value = Some(self.transform_value(val, &child_path)?);
+                        }
+                        Ok(entry)
+                    })
+                    .collect::<Result<_, JsonFixerError>>()?;
+                JsonValue::Object(entries)
+            }
+            JsonValue::Array(entries) => {
+                let entries = self.transform_elements(entries, path)?;
+                let mut index = 0;
+                let entries = entries
+                    .into_iter()
+                    .map(|mut entry| {
+                        if let Some(val) = entry.value.take() {
+                            let child_path = jsonpath::index(path, index);
+                            entry.value = Some(self.transform_value(val, &child_path)?);
+                            index += 1;
+                        }
+                        Ok(entry)
+                    })
+                    .collect::<Result<_, JsonFixerError>>()?;
+                JsonValue::Array(entries)
+            }
+            JsonValue::String(s) => return self.transform_string(s, path),
+            value => value,
+        };
+        Ok(value)
+    }
+
+    /// Applies the transformations of the members of an object, its values left as is. Kept
+    /// out of `transform_value` so that its recursion uses little stack.
+    #[inline(never)]
+    fn transform_members(
+        &mut self,
+        entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Result<Vec<JsonEntryValue>, JsonFixerError> {
+        let entries = self.clean_keys(entries, path);
+        let entries = self.prune_keys(entries, path);
+        let entries = self.insert_defaults(entries, path);
+        let entries = self.limit_keys(entries, path)?;
+        let entries = self.handle_duplicate_keys(entries, path);
+        self.limit_size(entries, path, true)
+    }
+
+    /// Applies the transformations of the elements of an array, its values left as is. Kept
+    /// out of `transform_value` so that its recursion uses little stack.
+    #[inline(never)]
+    fn transform_elements(
+        &mut self,
+        entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Result<Vec<JsonEntryValue>, JsonFixerError> {
+        let entries = self.homogenize_array(entries, path);
+        self.limit_size(entries, path, false)
+    }
+
+    /// Coerces a string value to `null` or to a boolean when its field is hinted as such, or
+    /// unwraps the JSON it holds when `unwrap_stringified_json` is set.
+    #[inline(never)]
+    fn transform_string(&mut self, s: String, path: &str) -> Result<JsonValue, JsonFixerError> {
+        if self.is_null_field(path) && is_null_string(&s) {
+            self.repairs.push(Repair::at_path(
+                RepairKind::CoercedNull,
+                path,
+                format!("Coerced \"{}\" to null", s),
+            ));
+            return Ok(JsonValue::Null);
+        }
+        let hint = self
+            .boolean_hint(path)
+            .filter(|c| self.config.accepts_repair(RepairKind::CoercedBoolean, *c));
+        if let (Some(confidence), Some(b)) = (hint, truthiness(&s)) {
+            let message = format!("Coerced \"{}\" to {}", s, b);
+            let repair = Repair::at_path(RepairKind::CoercedBoolean, path, message);
+            self.repairs.push(repair.with_confidence(confidence));
+            return Ok(JsonValue::Boolean(b));
+        }
+        if self.config.unwrap_stringified_json {
+            return self.unwrap_stringified_json(s, path);
+        }
+        Ok(JsonValue::String(self.string_value(s, path)?))
+    }
+
+    /// Returns how confident the hint that the value at `path` is a boolean is, when
+    /// `normalize_booleans` is set: certain when it matches `boolean_paths`, likely when only
+    /// its key hints it.
+    fn boolean_hint(&self, path: &str) -> Option<Confidence> {
+        if !self.config.normalize_booleans {
+            return None;
+        }
+        let config = self.config;
+        if config
+            .boolean_paths
+            .iter()
+            .any(|p| jsonpath::matches(p, path))
+        {
+            return Some(Confidence::Certain);
+        }
+        jsonpath::segments(path)
+            .last()
+            .and_then(|segment| jsonpath::segment_key(segment))
+            .filter(|key| is_boolean_key(key))
+            .map(|_| Confidence::Likely)
+    }
+
+    /// Returns `true` if the strings standing for a missing value are converted to `null` at
+    /// `path`.
+    fn is_null_field(&self, path: &str) -> bool {
+        match self.config.normalize_nulls {
+            NormalizeNulls::Never => false,
+            NormalizeNulls::Hinted => self
+                .config
+                .null_paths
+                .iter()
+                .any(|pattern| jsonpath::matches(pattern, path)),
+            NormalizeNulls::Always => true,
+        }
+    }
+
+    /// Compacts the whitespaces of a string value when `compact_strings_whitespace` is set,
+    /// then applies the length policy to it.
+    fn string_value(&mut self, s: String, path: &str) -> Result<String, JsonFixerError> {
+        if !self.config.compact_strings_whitespace {
+            return self.limit_length(s, path, false);
+        }
+
+        let s = match compact_whitespace(&s) {
+            Some(compacted) => {
+                self.repairs.push(Repair::at_path(
+                    RepairKind::CompactedWhitespace,
+                    path,
+                    "Collapsed the whitespace runs of a string",
+                ));
+                compacted
+            }
+            None => s,
+        };
+        self.limit_length(s, path, false)
+    }
+
+    /// Applies the length policy to a string value, or to a key when `is_key` is set, longer
+    /// than the configured maximum.
+    fn limit_length(
+        &mut self,
+        s: String,
+        path: &str,
+        is_key: bool,
+    ) -> Result<String, JsonFixerError> {
+        let (max, name) = if is_key {
+            (self.config.max_key_length, "key")
+        } else {
+            (self.config.max_string_length, "string")
+        };
+        let max = match max {
+            Some(max) => max,
+            None => return Ok(s),
+        };
+        let length = s.chars().count();
+        if length <= max {
+            return Ok(s);
+        }
+
+        match &self.config.length_policy {
+            LengthPolicy::Error => {
+                let path = path.to_string();
+                let error = if is_key {
+                    LimitError::KeyTooLong { path, length, max }
+                } else {
+                    LimitError::StringTooLong { path, length, max }
+                };
+                Err(JsonFixerError::Limit(error))
+            }
+            LengthPolicy::Truncate(marker) => {
+                // The marker counts in the maximum length, and is itself truncated beyond it
+                let marker: String = marker.chars().take(max).collect();
+                let kept = max - marker.chars().count();
+                let mut truncated: String = s.chars().take(kept).collect();
+                let dropped = Dropped {
+                    text: s[truncated.len()..].to_string(),
+                    span: None,
+                };
+                truncated.push_str(&marker);
+
+                let message = format!("Truncated {} of {} characters to {}", name, length, max);
+                let repair = Repair::at_path(RepairKind::TruncatedString, path, message);
+                self.repairs.push(repair.with_dropped(dropped));
+                Ok(truncated)
+            }
+        }
+    }
+
+    /// Applies the size policy to an object, or to an array when `is_object` is not set, with
+    /// more members or elements than the configured maximum.
+    fn limit_size(
+        &mut self,
+        mut entries: Vec<JsonEntryValue>,
+        path: &str,
+        is_object: bool,
+    ) -> Result<Vec<JsonEntryValue>, JsonFixerError> {
+        let (max, name) = if is_object {
+            (self.config.max_object_members, "members")
+        } else {
+            (self.config.max_array_elements, "elements")
+        };
+        let max = match max {
+            Some(max) => max,
+            None => return Ok(entries),
+        };
+        let count = entries.iter().filter(|e| e.value.is_some()).count();
+        if count <= max {
+            return Ok(entries);
+        }
+
+        match self.config.size_policy {
+            SizePolicy::Error => {
+                let path = path.to_string();
+                let error = if is_object {
+                    LimitError::TooManyMembers { path, count, max }
+                } else {
+                    LimitError::TooManyElements { path, count, max }
+                };
+                Err(JsonFixerError::Limit(error))
+            }
+            SizePolicy::Truncate => {
+                // Keep the first values along with the whitespaces only entries before them
+                let mut kept = 0;
+                let split = entries
+                    .iter()
+                    .position(|e| {
+                        let full = kept == max;
+                        if e.value.is_some() {
+                            kept += 1;
+                        }
+                        full
+                    })
+                    .unwrap_or(entries.len());
+                let dropped = entries.split_off(split);
+
+                let message = format!("Dropped {} of {} {}", count - max, count, name);
+                let repair = Repair::at_path(RepairKind::TruncatedContainer, path, message);
+                self.repairs
+                    .push(repair.with_dropped(dropped_entries(dropped, is_object)));
+                Ok(entries)
+            }
+        }
+    }
+
+    /// Cleans up the whitespaces and invisible characters of the keys of an object, as set
+    /// by `trim_keys` and `normalize_key_whitespace`.
+    fn clean_keys(&mut self, mut entries: Vec<JsonEntryValue>, path: &str) -> Vec<JsonEntryValue> {
+        if !self.config.trim_keys && !self.config.normalize_key_whitespace {
+            return entries;
+        }
+
+        for entry in entries.iter_mut() {
+            let Some(key) = &entry.key else {
+                continue;
+            };
+            let mut cleaned = key.clone();
+            if self.config.normalize_key_whitespace {
+                cleaned = normalize_whitespace(&cleaned);
+            }
+            if self.config.trim_keys {
+                cleaned = cleaned.trim().to_string();
+            }
+            if cleaned != *key {
+                let message = format!("Cleaned key {:?} to {:?}", key, cleaned);
+                let member_path = jsonpath::key(path, &cleaned);
+                self.repairs.push(Repair::at_path(
+                    RepairKind::CleanedKey,
+                    &member_path,
+                    message,
+                ));
+                entry.key = Some(cleaned);
+            }
+        }
+        entries
+    }
+
+    /// Drops the members of an object that are not allowed by `allowed_keys` or are denied
+    /// by `denied_keys`.
+    fn prune_keys(&mut self, entries: Vec<JsonEntryValue>, path: &str) -> Vec<JsonEntryValue> {
+        let config = self.config;
+        if config.allowed_keys.is_empty() && config.denied_keys.is_empty() {
+            return entries;
+        }
+
+        let (kept, pruned): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| {
+            let Some(key) = &entry.key else {
+                return true;
+            };
+            let member_path = jsonpath::key(path, key);
+            let allowed = config.allowed_keys.is_empty()
+                || config
+                    .allowed_keys
+                    .iter()
+                    .any(|pattern| jsonpath::overlaps(pattern, &member_path));
+            let denied = config
+                .denied_keys
+                .iter()
+                .any(|pattern| jsonpath::matches(pattern, &member_path));
+            allowed && !denied
+        });
+
+        for entry in pruned {
+            let key = entry.get_key();
+            let message = format!("Dropped member {:?}", key);
+            let repair =
+                Repair::at_path(RepairKind::PrunedKey, &jsonpath::key(path, &key), message);
+            self.repairs
+                .push(repair.with_dropped(dropped_entries(vec![entry], true)));
+        }
+        kept
+    }
+
+    /// Inserts the members of the object at `path` that have a default value in `defaults`
+    /// and are missing.
+    fn insert_defaults(
+        &mut self,
+        mut entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Vec<JsonEntryValue> {
+        let mut missing = self.missing_defaults(path, |key| {
+            entries
+                .iter()
+                .any(|entry| entry.key.as_deref() == Some(key))
+        });
+        entries.append(&mut missing);
+        entries
+    }
+
+    /// Returns the members of the object at `path` that have a default value in `defaults`
+    /// and for which `has_key` is not set.
+    fn missing_defaults(
+        &mut self,
+        path: &str,
+        has_key: impl Fn(&str) -> bool,
+    ) -> Vec<JsonEntryValue> {
+        let mut missing: Vec<JsonEntryValue> = Vec::new();
+        for (pattern, value) in &self.config.defaults {
+            let segments = jsonpath::segments(pattern);
+            let Some(key) = segments.last().and_then(|s| jsonpath::segment_key(s)) else {
+                continue;
+            };
+            let key = key.as_ref();
+            if key == "*" {
+                continue;
+            }
+            let member_path = jsonpath::key(path, key);
+            let inserted = missing
+                .iter()
+                .any(|entry| entry.key.as_deref() == Some(key));
+            if !jsonpath::matches(pattern, &member_path) || has_key(key) || inserted {
+                continue;
+            }
+            self.repairs.push(Repair::at_path(
+                RepairKind::InsertedDefault,
+                &member_path,
+                format!("Inserted default value of missing member {:?}", key),
+            ));
+            missing.push(member(key, value.clone()));
+        }
+        missing
+    }
+
+    /// Applies the length policy to the keys of the object at `path`. The truncated keys equal
+    /// to another key are reported, and then handled by `duplicate_keys`.
+    fn limit_keys(
+        &mut self,
+        entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Result<Vec<JsonEntryValue>, JsonFixerError> {
+        let Some(max) = self.config.max_key_length else {
+            return Ok(entries);
+        };
+        // Indexes of the truncated keys
+        let mut truncated = Vec::new();
+        let entries: Vec<JsonEntryValue> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut entry)| {
+                if let Some(key) = entry.key.take() {
+                    if key.chars().count() > max {
+                        truncated.push(i);
+                    }
+                    entry.key = Some(self.limit_length(key, path, true)?);
+                }
+                Ok(entry)
+            })
+            .collect::<Result<_, JsonFixerError>>()?;
+
+        let mut reported = Vec::new();
+        for i in truncated {
+            let key = entries[i].key.as_deref().unwrap_or_default();
+            let duplicated = entries
+                .iter()
+                .enumerate()
+                .any(|(j, entry)| j != i && entry.key.as_deref() == Some(key));
+            if duplicated && !reported.contains(&key) {
+                reported.push(key);
+                let message = format!("Truncated key {:?} equals another key", key);
+                self.repairs
+                    .push(Repair::at_path(RepairKind::TruncatedString, path, message));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Replaces a string holding a valid JSON array or object, eg. `"{\"a\": 1}"`, by the
+    /// value it holds. Any other string is kept as is.
+    fn unwrap_stringified_json(
+        &mut self,
+        s: String,
+        path: &str,
+    ) -> Result<JsonValue, JsonFixerError> {
+        let content = s.trim();
+        let stringified = (content.starts_with('{') && content.ends_with('}'))
+            || (content.starts_with('[') && content.ends_with(']'));
+        if !stringified || JsonValidator::validate(content).is_err() {
+            return Ok(JsonValue::String(self.string_value(s, path)?));
+        }
+
+        let parsed = JsonParser::new(content, JsonFixerConfig::default()).parse_document();
+        let value = match parsed {
+            Ok((value, _)) => value,
+            Err(_) => return Ok(JsonValue::String(self.string_value(s, path)?)),
+        };
+        self.repairs.push(Repair::at_path(
+            RepairKind::UnwrappedJsonString,
+            path,
+            "Parsed the JSON held by a string",
+        ));
+        // The embedded JSON may hold stringified JSON as well
+        self.transform_value(value, path)
+    }
+
+    /// Returns the duplicate keys policy of the object at `path`.
+    fn duplicate_keys_policy(&self, path: &str) -> &DuplicateKeys {
+        self.config
+            .duplicate_keys_by_path
+            .iter()
+            .find(|(pattern, _)| jsonpath::matches(pattern, path))
+            .map(|(_, policy)| policy)
+            .unwrap_or(&self.config.duplicate_keys)
+    }
+
+    /// Normalizes arrays mixing scalars and single key objects, eg. `[1, {"value": 2}, 3]`.
+    fn homogenize_array(
+        &mut self,
+        entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Vec<JsonEntryValue> {
+        if self.config.homogenize_arrays == HomogenizeArrays::Leave {
+            return entries;
+        }
+
+        let mut scalars = 0;
+        let mut wrapper_keys: Vec<String> = Vec::new();
+        for value in entries.iter().filter_map(|e| e.value.as_ref()) {
+            match single_member(value) {
+                Some(member) => wrapper_keys.push(member.get_key()),
+                None if value.is_scalar() => scalars += 1,
+                None => return entries,
+            }
+        }
+        if scalars == 0 || wrapper_keys.is_empty() {
+            return entries;
+        }
+        let wrappers = wrapper_keys.len();
+        wrapper_keys.dedup();
+        if wrapper_keys.len() > 1 {
+            // The objects are not wrappers of a common key but records
+            return entries;
+        }
+
+        let message = match self.config.homogenize_arrays {
+            HomogenizeArrays::Unwrap => format!("Unwrapped {} single key objects", wrappers),
+            _ => format!("Wrapped {} scalars with key '{}'", scalars, wrapper_keys[0]),
+        };
+
+        let entries = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.value = match (entry.value.take(), &self.config.homogenize_arrays) {
+                    (Some(value), HomogenizeArrays::Unwrap) => match single_member(&value) {
+                        Some(member) => Some(member.get_value()),
+                        None => Some(value),
+                    },
+                    (Some(value), _) if value.is_scalar() => {
+                        let mut member = JsonEntryValue::new();
+                        member.key = Some(wrapper_keys[0].clone());
+                        member.value = Some(value);
+                        Some(JsonValue::Object(vec![member]))
+                    }
+                    (value, _) => value,
+                };
+                entry
+            })
+            .collect();
+
+        self.repairs
+            .push(Repair::at_path(RepairKind::HomogenizedArray, path, message));
+        entries
+    }
+
+    fn handle_duplicate_keys(
+        &mut self,
+        entries: Vec<JsonEntryValue>,
+        path: &str,
+    ) -> Vec<JsonEntryValue> {
+        let policy = self.duplicate_keys_policy(path).clone();
+        if policy == DuplicateKeys::KeepAll {
+            return entries;
+        }
+
+        let mut result: Vec<JsonEntryValue> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            if entry.value.is_none() {
+                // Whitespaces only entry
+                result.push(entry);
+                continue;
+            }
+
+            let existing = result
+                .iter()
+                .position(|e| e.value.is_some() && e.key == entry.key);
+
+            match (existing, &policy) {
+                (None, _) | (_, DuplicateKeys::KeepAll) => result.push(entry),
+                (Some(_), DuplicateKeys::KeepFirst) => self.remove_duplicate(entry, path),
+                (Some(i), DuplicateKeys::KeepLast) => {
+                    let removed = result.remove(i);
+                    self.remove_duplicate(removed, path);
+                    result.push(entry);
+                }
+                (Some(i), DuplicateKeys::MergeObjects) => {
+                    let existing_value = result[i].value.take().unwrap();
+                    result[i].value = Some(merge_values(existing_value, entry.get_value()));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Reports the member `entry` of the object at `path` dropped as a duplicate.
+    fn remove_duplicate(&mut self, entry: JsonEntryValue, path: &str) {
+        let key = entry.get_key();
+        let message = format!("Dropped duplicate member {:?}", key);
+        let repair = Repair::at_path(
+            RepairKind::RemovedDuplicate,
+            &jsonpath::key(path, &key),
+            message,
+        );
+        let written = entry.span.as_ref().and_then(|span| {
+            let text = self.input.get(span.start..span.end)?;
+            Some(Dropped {
+                text: text.to_string(),
+                span: Some(span.clone()),
+            })
+        });
+        let dropped = written.unwrap_or_else(|| dropped_entries(vec![entry], true));
+        self.repairs.push(repair.with_dropped(dropped));
+    }
+}
+
+/// Applies the configured transformations to the members of the root object, or to the
+/// elements of the root array, one at a time as they are parsed, like
+/// `JsonTransformer::transform` does to the whole root, see `jsonstream`.
+///
+/// The members are not kept, only their count and, when `defaults` are set or the duplicate
+/// keys are dropped, their keys.
+pub(crate) struct RootTransformer<'a> {
+    transformer: JsonTransformer<'a>,
+    /// Keys of the members parsed, checked for the missing `defaults`
+    keys: HashSet<String>,
+    /// Keys of the members kept, checked for the duplicates
+    kept: HashSet<String>,
+    /// Members or elements counted against the size limit
+    count: usize,
+}
+
+impl<'a> RootTransformer<'a> {
+    pub(crate) fn new(config: &'a JsonFixerConfig, input: &'a str) -> Self {
+        Self {
+            transformer: JsonTransformer::new(config).with_input(input),
+            keys: HashSet::new(),
+            kept: HashSet::new(),
+            count: 0,
+        }
+    }
+
+    /// Fails with the first option of the configuration that needs the whole root object,
+    /// or the whole root array when `is_object` is not set.
+    pub(crate) fn check(&self, is_object: bool) -> Result<(), JsonFixerError> {
+        let config = self.transformer.config;
+        let policy = self.transformer.duplicate_keys_policy("");
+        let options = [
+            (config.preserve(), "preserve"),
+            (config.keeps_comments(), "keep_comments"),
+            (config.max_line_length.is_some(), "max_line_length"),
+            (config.max_repairs.is_some(), "max_repairs"),
+            (config.provenance_path.is_some(), "provenance_path"),
+            (is_object && config.sort_keys, "sort_keys"),
+            (is_object && config.key_order_at("").is_some(), "key_order"),
+            (
+                is_object && !matches!(policy, DuplicateKeys::KeepAll | DuplicateKeys::KeepFirst),
+                "duplicate_keys",
+            ),
+            (
+                !is_object && config.homogenize_arrays != HomogenizeArrays::Leave,
+                "homogenize_arrays",
+            ),
+        ];
+        match options.into_iter().find(|(needed, _)| *needed) {
+            Some((_, option)) => Err(JsonFixerError::Unstreamable(option)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the header written as the first member of the root object, if any.
+    pub(crate) fn header(&self) -> Option<JsonEntryValue> {
+        let header = self.transformer.config.header_key()?;
+        Some(member(HEADER_KEY, JsonValue::String(header.to_string())))
+    }
+
+    /// Transforms a member of the root object, returns `None` if it is dropped.
+    pub(crate) fn member(
+        &mut self,
+        entry: JsonEntryValue,
+    ) -> Result<Option<JsonEntryValue>, JsonFixerError> {
+        let entries = self.transformer.clean_keys(vec![entry], "");
+        let Some(entry) = self.transformer.prune_keys(entries, "").pop() else {
+            return Ok(None);
+        };
+        if !self.transformer.config.defaults.is_empty() {
+            self.keys.insert(entry.get_key());
+        }
+        self.keep_member(entry)
+    }
+
+    /// Returns the members of the root object that have a default value and were missing,
+    /// transformed, once all its members are parsed.
+    pub(crate) fn defaults(&mut self) -> Result<Vec<JsonEntryValue>, JsonFixerError> {
+        let keys = std::mem::take(&mut self.keys);
+        let missing = self
+            .transformer
+            .missing_defaults("", |key| keys.contains(key));
+        let mut kept = Vec::new();
+        for entry in missing {
+            kept.extend(self.keep_member(entry)?);
+        }
+        Ok(kept)
+    }
+
+    /// Transforms an element of the root array, returns `None` if it is dropped.
+    pub(crate) fn element(
+        &mut self,
+        value: JsonValue,
+    ) -> Result<Option<JsonValue>, JsonFixerError> {
+        if !self.count(false) {
+            return Ok(None);
+        }
+        let path = jsonpath::index("", self.count - 1);
+        self.transformer.transform_value(value, &path).map(Some)
+    }
+
+    /// Fails if the root object, or the root array when `is_object` is not set, has more
+    /// members or elements than the configured maximum and the size policy is to error.
+    pub(crate) fn finish(&self, is_object: bool) -> Result<(), JsonFixerError> {
+        let config = self.transformer.config;
+        let max = match is_object {
+            true => config.max_object_members,
+            false => config.max_array_elements,
+        };
+        let (count, path) = (self.count, String::new());
+        match max {
+            Some(max) if count > max && config.size_policy == SizePolicy::Error => {
+                let error = match is_object {
+                    true => LimitError::TooManyMembers { path, count, max },
+                    false => LimitError::TooManyElements { path, count, max },
+                };
+                Err(JsonFixerError::Limit(error))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Applies the transformations following the insertion of the defaults to a member of
+    /// the root object.
+    fn keep_member(
+        &mut self,
+        entry: JsonEntryValue,
+    ) -> Result<Option<JsonEntryValue>, JsonFixerError> {
+        let Some(mut entry) = self.transformer.limit_keys(vec![entry], "")?.pop() else {
+            return Ok(None);
+        };
+        let key = entry.get_key();
+        let policy = self.transformer.duplicate_keys_policy("");
+        if *policy == DuplicateKeys::KeepFirst && !self.kept.insert(key.clone()) {
+            self.transformer.remove_duplicate(entry, "");
+            return Ok(None);
+        }
+        if key == HEADER_KEY && self.header().is_some() {
+            return Err(uninsertable_header());
+        }
+        if !self.count(true) {
+            return Ok(None);
+        }
+        if let Some(value) = entry.value.take() {
+            let path = jsonpath::key("", &key);
+            entry.value = Some(self.transformer.transform_value(value, &path)?);
+        }
+        Ok(Some(entry))
+    }
+
+    /// Counts a member, or an element when `is_object` is not set, returns `false` if it
+    /// is beyond the maximum.
+    fn count(&mut self, is_object: bool) -> bool {
+        let config = self.transformer.config;
+        let max = match is_object {
+            true => config.max_object_members,
+            false => config.max_array_elements,
+        };
+        self.count += 1;
+        match max {
+            Some(max) => self.count <= max,
+            None => true,
+        }
+    }
+}
+
+/// Returns the members of an object, or the elements of an array when `is_object` is not
+/// set, dropped from the parsed document, written as fixed JSON without the enclosing
+/// brackets, eg. `"id":2`.
+fn dropped_entries(mut entries: Vec<JsonEntryValue>, is_object: bool) -> Dropped {
+    entries.retain(|entry| entry.value.is_some());
+    let value = match is_object {
+        true => JsonValue::Object(entries),
+        false => JsonValue::Array(entries),
+    };
+    let text = JsonFormatter
+        .format(&value, &JsonFixerConfig::default())
+        .unwrap_or_default();
+    // Without the enclosing brackets
+    let text = text
+        .get(1..text.len().saturating_sub(1))
+        .unwrap_or_default();
+    Dropped {
+        text: text.to_string(),
+        span: None,
+    }
+}
+
+/// Truncates the strings longer than `max` characters, the truncated strings end with the
+/// number of characters that were removed.
+pub fn truncate_strings(value: JsonValue, max: usize) -> JsonValue {
+    match value {
+        JsonValue::String(s) => {
+            let length = s.chars().count();
+            if length <= max {
+                return JsonValue::String(s);
+            }
+
+            let truncated: String = s.chars().take(max).collect();
+            JsonValue::String(format!("{}… (+{} chars)", truncated, length - max))
+        }
+        JsonValue::Object(entries) => JsonValue::Object(truncate_entries(entries, max)),
+        JsonValue::Array(entries) => JsonValue::Array(truncate_entries(entries, max)),
+        value => value,
+    }
+}
+
+fn truncate_entries(entries: Vec<JsonEntryValue>, max: usize) -> Vec<JsonEntryValue> {
+    entries
+        .into_iter()
+        .map(|mut entry| {
+            entry.value = entry.value.map(|value| truncate_strings(value, max));
+            entry
+        })
+        .collect()
+}
+
+/// Returns the only member of a single key object.
+fn single_member(value: &JsonValue) -> Option<&JsonEntryValue> {
+    match value {
+        JsonValue::Object(entries) => {
+            let mut members = entries.iter().filter(|e| e.value.is_some());
+            match (members.next(), members.next()) {
+                (Some(member), None) => Some(member),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Deep merges `incoming` into `existing`: objects are merged member by member, arrays are
+/// concatenated and any other value is replaced by the incoming one.
+fn merge_values(existing: JsonValue, incoming: JsonValue) -> JsonValue {
+    match (existing, incoming) {
+        (JsonValue::Object(mut entries), JsonValue::Object(incoming_entries)) => {
+            for incoming_entry in incoming_entries {
+                if incoming_entry.value.is_none() {
+                    continue;
+                }
+                let existing = entries
+                    .iter_mut()
+                    .find(|e| e.value.is_some() && e.key == incoming_entry.key);
+
+                match existing {
+                    Some(entry) => {
+                        let value = entry.value.take().unwrap();
+                        entry.value = Some(merge_values(value, incoming_entry.get_value()));
+                    }
+                    None => entries.push(incoming_entry),
+                }
+            }
+            JsonValue::Object(entries)
+        }
+        (JsonValue::Array(mut entries), JsonValue::Array(incoming_entries)) => {
+            entries.extend(incoming_entries.into_iter().filter(|e| e.value.is_some()));
+            JsonValue::Array(entries)
+        }
+        (_, incoming) => incoming,
+    }
+}
+
+/// Inserts the provenance object `{"repairs": N, "version": "..."}` at the configured
+/// `provenance_path` of a fixed document, `repairs` being the number of repairs applied.
+///
+/// The missing objects of the path are created. Fails with `JsonFixerError::Uninsertable`
+/// when a member of the document already has the key, or when the path goes through a
+/// value that is not an object or an array element that does not exist, eg. in an array
+/// root with the default path.
+pub fn insert_provenance(
+    mut value: JsonValue,
+    config: &JsonFixerConfig,
+    repairs: usize,
+) -> Result<JsonValue, JsonFixerError> {
+    let path = match &config.provenance_path {
+        Some(path) => path,
+        None => return Ok(value),
+    };
+
+    let provenance = JsonValue::Object(vec![
+        member(
+            "repairs",
+            JsonValue::Number(JsonNumber::parse(&repairs.to_string())),
+        ),
+        member(
+            "version",
+            JsonValue::String(env!("CARGO_PKG_VERSION").to_string()),
+        ),
+    ]);
+    match insert_at(&mut value, &jsonpath::segments(path), provenance) {
+        true => Ok(value),
+        false => Err(JsonFixerError::Uninsertable {
+            option: "provenance_path",
+            path: path.clone(),
+        }),
+    }
+}
+
+/// Inserts `inserted` at the path of `segments` under `value`, returns `false` if it cannot
+/// be inserted there without replacing a value.
+fn insert_at(value: &mut JsonValue, segments: &[&str], inserted: JsonValue) -> bool {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => return false,
+    };
+
+    match value {
+        JsonValue::Array(entries) => {
+            let index = segment
+                .strip_prefix('[')
+                .and_then(|s| s.strip_suffix(']'))
+                .and_then(|s| s.parse::<usize>().ok());
+            let element = index.and_then(|index| {
+                entries
+                    .iter_mut()
+                    .filter_map(|e| e.value.as_mut())
+                    .nth(index)
+            });
+            match element {
+                Some(element) if !rest.is_empty() => insert_at(element, rest, inserted),
+                _ => false,
+            }
+        }
+        JsonValue::Object(entries) => {
+            let Some(key) = jsonpath::segment_key(segment) else {
+                return false;
+            };
+            let key = key.as_ref();
+            let mut keys = entries.iter().filter_map(|entry| entry.key.as_deref());
+            if rest.is_empty() && keys.any(|k| k == key) {
+                return false;
+            }
+            if rest.is_empty() {
+                entries.push(member(key, inserted));
+                return true;
+            }
+
+            let existing = entries
+                .iter_mut()
+                .filter(|entry| entry.key.as_deref() == Some(key))
+                .find_map(|entry| entry.value.as_mut());
+            match existing {
+                Some(existing) => insert_at(existing, rest, inserted),
+                None => {
+                    let mut created = JsonValue::Object(Vec::new());
+                    let done = insert_at(&mut created, rest, inserted);
+                    if done {
+                        entries.push(member(key, created));
+                    }
+                    done
+                }
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Returns the error of a header that cannot be written as the first member of the root.
+pub(crate) fn uninsertable_header() -> JsonFixerError {
+    JsonFixerError::Uninsertable {
+        option: "header",
+        path: HEADER_KEY.to_string(),
+    }
+}
+
+/// Returns the object member `key` with the given value.
+pub(crate) fn member(key: &str, value: JsonValue) -> JsonEntryValue {
+    let mut member = JsonEntryValue::new();
+    member.key = Some(key.to_string());
+    member.value = Some(value);
+    member
+}
+
+/// Collapses the runs of whitespaces between the words of `s` into single spaces, eg.
+/// `"a \n\t b"` to `"a b"`, returns `None` if it has none. The leading and trailing
+/// whitespaces are kept.
+fn compact_whitespace(s: &str) -> Option<String> {
+    let start = s.len() - s.trim_start().len();
+    let end = s.trim_end().len().max(start);
+
+    let mut compacted = String::with_capacity(s.len());
+    compacted.push_str(&s[..start]);
+    let mut found = false;
+    let mut in_run = false;
+    for ch in s[start..end].chars() {
+        if !ch.is_whitespace() {
+            compacted.push(ch);
+            in_run = false;
+            continue;
+        }
+        // A single space is not a run
+        found |= in_run || ch != ' ';
+        if !in_run {
+            compacted.push(' ');
+            in_run = true;
+        }
+    }
+    compacted.push_str(&s[end..]);
+
+    found.then_some(compacted)
+}
+
+/// Returns `true` if `key` reads as a yes or no question, ie. starts with `is`, `has`, `can` or
+/// `should` followed by a `_`, a `-` or an uppercase letter, eg. `is_active` or `hasItems`.
+fn is_boolean_key(key: &str) -> bool {
+    ["is", "has", "can", "should"].iter().any(|prefix| {
+        key.strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|next| next == '_' || next == '-' || next.is_uppercase())
+    })
+}
+
+/// Boolean of a truthy or falsey string, eg. `"Yes"` or `"off"`, `None` for any other string.
+fn truthiness(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "yes" | "y" | "true" | "on" | "1" => Some(true),
+        "no" | "n" | "false" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns `true` if `s` stands for a missing value, eg. `"NULL"`, `"None"` or `""`.
+fn is_null_string(s: &str) -> bool {
+    matches!(
+        s.trim().to_lowercase().as_str(),
+        "null" | "nil" | "none" | ""
+    )
+}
+
+/// Removes the invisible characters of `s` and collapses its runs of whitespaces into single
+/// spaces, eg. `"a\u{200b}\u{a0} b"` to `"a b"`.
+fn normalize_whitespace(s: &str) -> String {
+    let mut normalized = String::with_capacity(s.len());
+    let mut in_run = false;
+    for ch in s.chars() {
+        if is_invisible(ch) {
+            continue;
+        }
+        if ch.is_whitespace() {
+            if !in_run {
+                normalized.push(' ');
+            }
+            in_run = true;
+        } else {
+            normalized.push(ch);
+            in_run = false;
+        }
+    }
+    normalized
+}
+
+/// Returns `true` for the format characters that render as nothing, eg. zero width spaces,
+/// joiners, soft hyphens and byte order marks.
+fn is_invisible(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{00ad}' | '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{2064}' | '\u{feff}'
+    )
+}
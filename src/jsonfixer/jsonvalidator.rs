@@ -0,0 +1,578 @@
+//! Strict RFC 8259 validator.
+//!
+//! Unlike the parser, the validator never repairs anything: it walks the input once and
+//! reports the first construct that is not valid JSON. It is used to double-check the
+//! output produced by the fixer.
+
+use super::{
+    json_tokenizer::Position, jsonfixer_config::Compliance, jsonfixer_error::SyntaxError,
+};
+
+/// Validates that an input is strictly conformant JSON text.
+pub struct JsonValidator<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    line: usize,
+    column: usize,
+    compliance: Compliance,
+}
+
+impl<'a> JsonValidator<'a> {
+    /// Creates a new validator over the given input.
+    pub fn new(input: &'a str) -> Self {
+        Self::with_compliance(input, Compliance::default())
+    }
+
+    /// Creates a new validator accepting the gray areas allowed by `compliance`.
+    pub fn with_compliance(input: &'a str, compliance: Compliance) -> Self {
+        Self {
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            line: 1,
+            column: 0,
+            compliance,
+        }
+    }
+
+    /// Validates the whole input: a single value surrounded by optional whitespace.
+    pub fn validate(input: &str) -> Result<(), SyntaxError> {
+        Self::validate_with(input, &Compliance::default())
+    }
+
+    /// Validates the whole input, accepting the gray areas allowed by `compliance`.
+    pub fn validate_with(input: &str, compliance: &Compliance) -> Result<(), SyntaxError> {
+        let mut validator = JsonValidator::with_compliance(input, compliance.clone());
+        validator.skip_whitespaces();
+        if !compliance.scalar_root && !matches!(validator.peek(), Some(b'{' | b'[')) {
+            return Err(validator.unexpected_char());
+        }
+        validator.validate_value()?;
+        validator.skip_whitespaces();
+
+        match validator.peek() {
+            None => Ok(()),
+            Some(_) => Err(validator.unexpected_char()),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn advance(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        // Only count the first byte of each UTF-8 sequence as a column
+        if byte & 0xC0 != 0x80 {
+            self.column += 1;
+        }
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 0;
+        }
+        Some(byte)
+    }
+
+    fn current_position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column.max(1),
+        }
+    }
+
+    fn unexpected_char(&mut self) -> SyntaxError {
+        match self.input[self.pos..].chars().next() {
+            Some(ch) => {
+                self.advance();
+                SyntaxError::UnexpectedCharacter(ch, self.current_position())
+            }
+            None => SyntaxError::UnexpectedEndOfInput(self.current_position()),
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.advance();
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SyntaxError> {
+        if self.peek() == Some(byte) {
+            self.advance();
+            Ok(())
+        } else {
+            Err(self.unexpected_char())
+        }
+    }
+
+    fn validate_value(&mut self) -> Result<(), SyntaxError> {
+        match self.peek() {
+            Some(b'{') => self.validate_object(),
+            Some(b'[') => self.validate_array(),
+            Some(b'"') => self.validate_string(),
+            Some(b'-' | b'0'..=b'9') => self.validate_number(),
+            Some(b'+') if self.compliance.leading_plus => self.validate_number(),
+            Some(b't') => self.validate_literal("true"),
+            Some(b'f') => self.validate_literal("false"),
+            Some(b'n') => self.validate_literal("null"),
+            _ => Err(self.unexpected_char()),
+        }
+    }
+
+    fn validate_literal(&mut self, literal: &str) -> Result<(), SyntaxError> {
+        for byte in literal.bytes() {
+            self.expect(byte)?;
+        }
+        Ok(())
+    }
+
+    fn validate_object(&mut self) -> Result<(), SyntaxError> {
+        self.advance(); // Consume {
+        self.skip_whitespaces();
+        if self.peek() == Some(b'}') {
+            self.advance();
+            return Ok(());
+        }
+
+        // Keys found so far, only tracked when duplicates are rejected
+        let mut keys: Vec<String> = Vec::new();
+
+        loop {
+            self.skip_whitespaces();
+            if self.peek() != Some(b'"') {
+                return Err(self.unexpected_char());
+            }
+            let start = self.pos;
+            let start_pos = self.current_position();
+            self.validate_string()?;
+            if !self.compliance.duplicate_keys {
+                let key = decode_string(&self.input[start + 1..self.pos - 1]);
+                if keys.contains(&key) {
+                    return Err(SyntaxError::DuplicateKey(key, start_pos));
+                }
+                keys.push(key);
+            }
+            self.skip_whitespaces();
+            self.expect(b':')?;
+            self.skip_whitespaces();
+            self.validate_value()?;
+            self.skip_whitespaces();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.advance();
+                }
+                Some(b'}') => {
+                    self.advance();
+                    return Ok(());
+                }
+                _ => return Err(self.unexpected_char()),
+            }
+        }
+    }
+
+    fn validate_array(&mut self) -> Result<(), SyntaxError> {
+        self.advance(); // Consume [
+        self.skip_whitespaces();
+        if self.peek() == Some(b']') {
+            self.advance();
+            return Ok(());
+        }
+
+        loop {
+            self.skip_whitespaces();
+            self.validate_value()?;
+            self.skip_whitespaces();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.advance();
+                }
+                Some(b']') => {
+                    self.advance();
+                    return Ok(());
+                }
+                _ => return Err(self.unexpected_char()),
+            }
+        }
+    }
+
+    fn validate_string(&mut self) -> Result<(), SyntaxError> {
+        let start_pos = self.current_position();
+        self.advance(); // Consume "
+
+        // Escaped high surrogate waiting for its low surrogate
+        let mut high_surrogate: Option<(u32, Position)> = None;
+
+        loop {
+            let escape_pos = self.current_position();
+            let code = match self.peek() {
+                None => return Err(SyntaxError::UnmatchedQuotes(start_pos)),
+                Some(b'"') => {
+                    if let Some((code, pos)) = high_surrogate {
+                        return Err(lone_surrogate(code, pos));
+                    }
+                    self.advance();
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.advance();
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.advance();
+                            None
+                        }
+                        Some(b'u') => {
+                            self.advance();
+                            let start = self.pos;
+                            for _ in 0..4 {
+                                match self.peek() {
+                                    Some(h) if h.is_ascii_hexdigit() => {
+                                        self.advance();
+                                    }
+                                    _ => return Err(self.unexpected_char()),
+                                }
+                            }
+                            u32::from_str_radix(&self.input[start..self.pos], 16).ok()
+                        }
+                        _ => return Err(self.unexpected_char()),
+                    }
+                }
+                // Control characters must be escaped
+                Some(0x00..=0x1F) => return Err(self.unexpected_char()),
+                Some(_) => {
+                    self.advance();
+                    None
+                }
+            };
+
+            if self.compliance.lone_surrogates {
+                continue;
+            }
+            match (high_surrogate.take(), code) {
+                (None, Some(code @ 0xD800..=0xDBFF)) => high_surrogate = Some((code, escape_pos)),
+                (None, Some(code @ 0xDC00..=0xDFFF)) => {
+                    return Err(lone_surrogate(code, escape_pos))
+                }
+                (Some(_), Some(0xDC00..=0xDFFF)) | (None, _) => (),
+                (Some((code, pos)), _) => return Err(lone_surrogate(code, pos)),
+            }
+        }
+    }
+
+    fn validate_number(&mut self) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        let start_pos = self.current_position();
+
+        if let Some(b'-' | b'+') = self.peek() {
+            self.advance();
+        }
+
+        // Integer part: a single 0 or a non zero digit followed by digits
+        match self.peek() {
+            Some(b'0') => {
+                self.advance();
+            }
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.invalid_number(start, start_pos)),
+        }
+
+        if self.peek() == Some(b'.') {
+            self.advance();
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.invalid_number(start, start_pos));
+            }
+            self.skip_digits();
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.advance();
+            if let Some(b'+' | b'-') = self.peek() {
+                self.advance();
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.invalid_number(start, start_pos));
+            }
+            self.skip_digits();
+        }
+
+        Ok(())
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.advance();
+        }
+    }
+
+    fn invalid_number(&mut self, start: usize, start_pos: Position) -> SyntaxError {
+        // Include the rest of the number-like characters in the diagnostic
+        while let Some(b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-') = self.peek() {
+            self.advance();
+        }
+        SyntaxError::InvalidNumber(self.input[start..self.pos].to_string(), start_pos)
+    }
+}
+
+/// Deepest nesting of objects and arrays accepted by [`is_valid_json`].
+pub const MAX_VALID_DEPTH: usize = 1024;
+
+/// Returns `true` if `input` is strictly conformant JSON text, without allocating.
+///
+/// The grammar is the one of [`JsonValidator::validate`] with the default [`Compliance`],
+/// except that documents nested deeper than [`MAX_VALID_DEPTH`] are rejected. As a `const
+/// fn` it neither recurses nor allocates, which makes it a cheap check on hot paths before
+/// deciding whether the input needs fixing.
+///
+/// # Examples
+///
+/// ```
+/// use json_fixer::is_valid_json;
+///
+/// const VALID: bool = is_valid_json(r#"{"a": [1, 2]}"#);
+/// assert!(VALID);
+/// assert!(!is_valid_json("{a: [1 2]}"));
+/// ```
+pub const fn is_valid_json(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    // One bit per nesting level, set for the objects and clear for the arrays
+    let mut objects = [0u64; MAX_VALID_DEPTH / 64];
+    let mut depth = 0;
+    let mut i = skip_whitespaces(bytes, 0);
+
+    loop {
+        // A value starts at `i`
+        if i >= bytes.len() {
+            return false;
+        }
+        let end = match bytes[i] {
+            open @ (b'{' | b'[') => {
+                if depth == MAX_VALID_DEPTH {
+                    return false;
+                }
+                let is_object = open == b'{';
+                if is_object {
+                    objects[depth / 64] |= 1 << (depth % 64);
+                } else {
+                    objects[depth / 64] &= !(1 << (depth % 64));
+                }
+                depth += 1;
+
+                i = skip_whitespaces(bytes, i + 1);
+                let close = if is_object { b'}' } else { b']' };
+                if i < bytes.len() && bytes[i] == close {
+                    depth -= 1;
+                    Some(i + 1)
+                } else if is_object {
+                    match member_value(bytes, i) {
+                        Some(value) => {
+                            i = value;
+                            continue;
+                        }
+                        None => return false,
+                    }
+                } else {
+                    continue;
+                }
+            }
+            b'"' => string_end(bytes, i),
+            b'-' | b'0'..=b'9' => number_end(bytes, i),
+            b't' => literal_end(bytes, i, b"true"),
+            b'f' => literal_end(bytes, i, b"false"),
+            b'n' => literal_end(bytes, i, b"null"),
+            _ => None,
+        };
+        i = match end {
+            Some(end) => end,
+            None => return false,
+        };
+
+        // After a value: close the containers until one continues with a comma
+        loop {
+            i = skip_whitespaces(bytes, i);
+            if depth == 0 {
+                return i == bytes.len();
+            }
+            if i >= bytes.len() {
+                return false;
+            }
+            let is_object = objects[(depth - 1) / 64] & (1 << ((depth - 1) % 64)) != 0;
+            match (bytes[i], is_object) {
+                (b',', true) => {
+                    i = match member_value(bytes, skip_whitespaces(bytes, i + 1)) {
+                        Some(value) => value,
+                        None => return false,
+                    };
+                    break;
+                }
+                (b',', false) => {
+                    i = skip_whitespaces(bytes, i + 1);
+                    break;
+                }
+                (b'}', true) | (b']', false) => {
+                    depth -= 1;
+                    i += 1;
+                }
+                _ => return false,
+            }
+        }
+    }
+}
+
+const fn skip_whitespaces(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && matches!(bytes[i], b' ' | b'\t' | b'\n' | b'\r') {
+        i += 1;
+    }
+    i
+}
+
+/// Skips the key and colon of a member starting at `i`, returning where its value starts.
+const fn member_value(bytes: &[u8], i: usize) -> Option<usize> {
+    if i >= bytes.len() || bytes[i] != b'"' {
+        return None;
+    }
+    let i = match string_end(bytes, i) {
+        Some(end) => skip_whitespaces(bytes, end),
+        None => return None,
+    };
+    if i >= bytes.len() || bytes[i] != b':' {
+        return None;
+    }
+    Some(skip_whitespaces(bytes, i + 1))
+}
+
+/// End of the string starting at `i`, the input being valid UTF-8 already.
+const fn string_end(bytes: &[u8], mut i: usize) -> Option<usize> {
+    i += 1; // Skip "
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => return Some(i + 1),
+            b'\\' => {
+                if i + 1 >= bytes.len() {
+                    return None;
+                }
+                match bytes[i + 1] {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => i += 2,
+                    b'u' => {
+                        if i + 6 > bytes.len() {
+                            return None;
+                        }
+                        let mut digit = i + 2;
+                        while digit < i + 6 {
+                            if !bytes[digit].is_ascii_hexdigit() {
+                                return None;
+                            }
+                            digit += 1;
+                        }
+                        i += 6;
+                    }
+                    _ => return None,
+                }
+            }
+            // Control characters must be escaped
+            0x00..=0x1F => return None,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+const fn number_end(bytes: &[u8], mut i: usize) -> Option<usize> {
+    if bytes[i] == b'-' {
+        i += 1;
+    }
+    // Integer part: a single 0 or a non zero digit followed by digits
+    if i < bytes.len() && bytes[i] == b'0' {
+        i += 1;
+    } else if i < bytes.len() && matches!(bytes[i], b'1'..=b'9') {
+        i = skip_digits(bytes, i);
+    } else {
+        return None;
+    }
+
+    if i < bytes.len() && bytes[i] == b'.' {
+        let start = i + 1;
+        i = skip_digits(bytes, start);
+        if i == start {
+            return None;
+        }
+    }
+    if i < bytes.len() && matches!(bytes[i], b'e' | b'E') {
+        i += 1;
+        if i < bytes.len() && matches!(bytes[i], b'+' | b'-') {
+            i += 1;
+        }
+        let start = i;
+        i = skip_digits(bytes, start);
+        if i == start {
+            return None;
+        }
+    }
+    Some(i)
+}
+
+const fn skip_digits(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        i += 1;
+    }
+    i
+}
+
+const fn literal_end(bytes: &[u8], i: usize, literal: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    while offset < literal.len() {
+        if i + offset >= bytes.len() || bytes[i + offset] != literal[offset] {
+            return None;
+        }
+        offset += 1;
+    }
+    Some(i + literal.len())
+}
+
+pub(crate) fn lone_surrogate(code: u32, pos: Position) -> SyntaxError {
+    SyntaxError::UnexpectedToken(format!("\\u{:04x} (lone surrogate)", code), pos)
+}
+
+/// Decodes the content of a validated string.
+pub(crate) fn decode_string(content: &str) -> String {
+    let mut decoded = String::with_capacity(content.len());
+    let mut chars = content.chars();
+    // Escaped high surrogate waiting for its low surrogate
+    let mut high_surrogate: Option<u32> = None;
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            decoded.push(ch);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('b') => '\x08',
+            Some('f') => '\x0C',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let code = u32::from_str_radix(&hex, 16).unwrap_or_default();
+                let code = match (high_surrogate.take(), code) {
+                    (_, 0xD800..=0xDBFF) => {
+                        high_surrogate = Some(code);
+                        continue;
+                    }
+                    (Some(high), 0xDC00..=0xDFFF) => {
+                        0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00)
+                    }
+                    (_, code) => code,
+                };
+                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            Some(ch) => ch,
+            None => break,
+        };
+        decoded.push(escaped);
+    }
+
+    decoded
+}
@@ -1,14 +1,77 @@
 pub mod json_tokenizer;
+pub mod jsonannotate;
+#[cfg(feature = "arena")]
+pub mod jsonarena;
+pub mod jsonbatch;
+pub mod jsoncapabilities;
+pub mod jsoncode;
+pub mod jsoncompare;
+pub mod jsonconcat;
+#[cfg(feature = "encoding")]
+pub mod jsonencoding;
+pub mod jsonengine;
+pub mod jsonescape;
 pub mod jsonfixer_config;
 pub mod jsonfixer_error;
+pub mod jsonfixer_report;
+pub mod jsonflatten;
 pub mod jsonformatter;
+#[cfg(feature = "hash")]
+pub mod jsonhash;
+pub mod jsonindex;
+#[cfg(feature = "serde")]
+pub mod jsonlenient;
+pub mod jsonmembers;
+pub mod jsonnumber;
+pub mod jsonoptions;
+#[cfg(feature = "serde")]
+pub mod jsonpartial;
 pub mod jsonparser;
+pub mod jsonpath;
+pub mod jsonrelax;
+pub mod jsonrenderer;
+pub mod jsonreplay;
+pub mod jsonscan;
+pub mod jsonshape;
+pub mod jsonsplit;
+pub mod jsonstream;
+pub mod jsonsummary;
+#[cfg(feature = "schema")]
+pub mod jsonschema;
+#[cfg(feature = "serde")]
+pub mod jsonserde;
+pub mod jsontransformer;
+pub mod jsonvalidator;
 
-pub use json_tokenizer::{JsonTokenizer, Token};
-pub use jsonfixer_config::JsonFixerConfig;
-pub use jsonfixer_error::JsonFixerError;
-pub use jsonformatter::JsonFormatter;
-pub use jsonparser::JsonParser;
+pub use json_tokenizer::{JsonTokenizer, Token, TokenAction, TokenHook};
+pub use jsonbatch::{BatchReport, FailureSample};
+pub use jsoncapabilities::{capabilities, Capabilities};
+pub use jsoncode::Code;
+pub use jsoncompare::CompareOptions;
+pub use jsonconcat::ConcatOptions;
+pub use jsonengine::JsonFixerEngine;
+pub use jsonescape::{escape_str, fix_string_literal, unescape_str, EscapePolicy};
+pub use jsonfixer_config::{
+    BareWords, Compliance, DuplicateKeys, HomogenizeArrays, JsonFixerConfig, LengthPolicy,
+    LineLengthPolicy, NonFiniteNumbers, NormalizeNulls, OutputStyle, QuoteKeys, Repairs,
+    RepeatedColons, SizePolicy, Undefined, Whitespaces,
+};
+pub use jsonfixer_error::{
+    JsonFixerError, JsonFormatError, LimitError, ShapeError, ShapeViolation,
+};
+pub use jsonfixer_report::{Confidence, Dropped, FixReport, Repair, RepairKind};
+pub use jsonformatter::{Formatter, JsonFormatter};
+pub use jsonindex::{IndexEntry, Span, ValueKind};
+pub use jsonnumber::JsonNumber;
+pub use jsonoptions::OptionError;
+pub use jsonparser::{JsonParser, JsonValue};
+pub use jsonreplay::TokenBuffer;
+pub use jsonscan::StructureScan;
+pub use jsonshape::Shape;
+pub use jsonsplit::ChunkFormat;
+pub use jsonstream::StreamOptions;
+pub use jsontransformer::{Chain, Transform, TransformAction};
+pub use jsonvalidator::{is_valid_json, JsonValidator};
 
 /// A utility for parsing and fixing malformed JSON input.
 ///
@@ -49,10 +112,1230 @@ pub use jsonparser::JsonParser;
 pub struct JsonFixer;
 
 impl JsonFixer {
-    /// Fixes JSON input using custom configuration options.
+    /// Fixes JSON input using custom configuration options.
+    ///
+    /// This method allows full control over the fixing and formatting process through
+    /// the provided configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = r#"{
+    ///     c: 3,
+    ///     a: 1,
+    ///     b: 2
+    /// }"#;
+    ///
+    /// let mut config = JsonFixerConfig::default();
+    /// config.sort_keys = true;
+    /// config.beautify = true;
+    ///
+    /// let result = JsonFixer::fix_with_config(input, config).unwrap();
+    /// ```
+    pub fn fix_with_config(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<String, JsonFixerError> {
+        let mut parser = JsonParser::with_borrowed_config(input, config.as_ref());
+        parser.parse()
+    }
+    /// Fixes JSON input and reports every repair that was applied.
+    ///
+    /// This method behaves like [`JsonFixer::fix_with_config`] but also returns the list of
+    /// repairs (quoted keys, inserted commas, closed brackets, ...) needed to produce the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FixReport)` - The fixed JSON string and the applied repairs
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, RepairKind};
+    ///
+    /// let input = r#"{ name: 'John', age: 30, }"#;
+    /// let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+    ///
+    /// assert_eq!(report.output, r#"{"name":"John","age":30}"#);
+    /// assert_eq!(report.count(RepairKind::QuotedKey), 2);
+    /// assert_eq!(report.count(RepairKind::RemovedComma), 1);
+    /// ```
+    pub fn fix_with_report(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<FixReport, JsonFixerError> {
+        let mut parser = JsonParser::with_borrowed_config(input, config.as_ref());
+        parser.parse_with_report()
+    }
+
+    /// Fixes JSON input and writes it with a custom formatter.
+    ///
+    /// The input is fixed and transformed like with [`JsonFixer::fix_with_config`], then the
+    /// fixed value is written by `formatter` instead of the default JSON formatter. When
+    /// `config.verify_output` is set the formatter output must be valid JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    /// * `formatter` - The formatter writing the fixed value
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The output of the formatter
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed or the formatter fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{Formatter, JsonFixer, JsonFixerConfig, JsonFixerError, JsonValue};
+    ///
+    /// /// Writes the type of the root value.
+    /// struct TypeFormatter;
+    ///
+    /// impl Formatter for TypeFormatter {
+    ///     fn format(
+    ///         &self,
+    ///         value: &JsonValue,
+    ///         _config: &JsonFixerConfig,
+    ///     ) -> Result<String, JsonFixerError> {
+    ///         Ok(if value.is_scalar() { "scalar" } else { "container" }.to_string())
+    ///     }
+    /// }
+    ///
+    /// let config = JsonFixerConfig::default();
+    /// let output = JsonFixer::fix_with_formatter("[1 2]", config, &TypeFormatter).unwrap();
+    /// assert_eq!(output, "container");
+    /// ```
+    pub fn fix_with_formatter<F: Formatter + ?Sized>(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+        formatter: &F,
+    ) -> Result<String, JsonFixerError> {
+        let mut parser = JsonParser::with_borrowed_config(input, config.as_ref());
+        parser.parse_with_formatter(formatter)
+    }
+
+    /// Fixes JSON input, passing every token through a hook before it is parsed.
+    ///
+    /// The hook can keep, replace or drop each token read from the input, which allows
+    /// bespoke repairs on top of the ones of the fixer. Whitespaces are tokens as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    /// * `hook` - The hook called for every token, usually a closure
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, Token, TokenAction};
+    ///
+    /// // Replace the NaN values by null
+    /// let hook = |token: &Token| match token {
+    ///     Token::UnquotedString(s, pos) if s == "NaN" => {
+    ///         TokenAction::Replace(Token::Null(pos.clone()))
+    ///     }
+    ///     _ => TokenAction::Keep,
+    /// };
+    ///
+    /// let output = JsonFixer::fix_with_token_hook("[1, NaN]", JsonFixerConfig::default(), hook);
+    /// assert_eq!(output.unwrap(), "[1,null]");
+    /// ```
+    pub fn fix_with_token_hook(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+        hook: impl TokenHook,
+    ) -> Result<String, JsonFixerError> {
+        let mut parser =
+            JsonParser::with_borrowed_config(input, config.as_ref()).with_token_hook(hook);
+        parser.parse()
+    }
+
+    /// Fixes JSON input and applies a transform to every value before writing it.
+    ///
+    /// The transform is applied once the input is fixed and the configured transformations
+    /// are applied, see [`Transform`] for how values are visited.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    /// * `transform` - The transform applied to every value, usually a closure or a chain of
+    ///   transforms
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed and transformed JSON string
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, JsonValue, Transform, TransformAction};
+    ///
+    /// let redact = |_: &str, key: Option<&mut String>, value: &mut JsonValue| {
+    ///     if key.is_some_and(|k| k == "password") {
+    ///         *value = JsonValue::String("***".to_string());
+    ///     }
+    ///     TransformAction::Keep
+    /// };
+    /// let drop_nulls = |_: &str, _: Option<&mut String>, value: &mut JsonValue| match value {
+    ///     JsonValue::Null => TransformAction::Drop,
+    ///     _ => TransformAction::Keep,
+    /// };
+    ///
+    /// let input = "{user: 'john', password: 'secret', email: null}";
+    /// let config = JsonFixerConfig::default();
+    /// let output = JsonFixer::fix_with_transform(input, config, redact.then(drop_nulls));
+    /// assert_eq!(output.unwrap(), r#"{"user":"john","password":"***"}"#);
+    /// ```
+    pub fn fix_with_transform(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+        mut transform: impl Transform,
+    ) -> Result<String, JsonFixerError> {
+        let mut parser = JsonParser::with_borrowed_config(input, config.as_ref());
+        parser.parse_with_transform(&mut transform)
+    }
+
+    /// Checks the indentation of a JSON input without fixing it.
+    ///
+    /// Every line is expected to be indented once per enclosing array or object using the
+    /// `indent_style` and `indent_size` of the configuration. This is meant for lint-only
+    /// workflows where the input must not be rewritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to check
+    /// * `config` - Configuration holding the expected indentation
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<JsonFormatError>)` - An `InvalidIndentation` error per badly indented line
+    /// * `Err(JsonFixerError)` - If the input cannot be tokenized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, JsonFormatError};
+    ///
+    /// let input = "{\n  \"a\": 1,\n   \"b\": 2\n}";
+    ///
+    /// let mut config = JsonFixerConfig::default();
+    /// config.indent_size = 2;
+    ///
+    /// let errors = JsonFixer::check_indentation(input, config).unwrap();
+    /// assert_eq!(errors, vec![JsonFormatError::InvalidIndentation { line: 3 }]);
+    /// ```
+    pub fn check_indentation(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<Vec<JsonFormatError>, JsonFixerError> {
+        JsonFormatter::check_indentation(input, config.as_ref())
+    }
+
+    /// Fixes JSON input and renders a preview of it for display purposes.
+    ///
+    /// The preview is formatted like [`JsonFixer::fix_with_config`], except that strings
+    /// longer than `config.max_string_display` characters are truncated. This is meant for
+    /// log viewers and other displays, the output of the other methods is never truncated.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string with truncated strings
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = r#"{ id: 1, payload: "a very long payload" }"#;
+    ///
+    /// let mut config = JsonFixerConfig::default();
+    /// config.max_string_display = Some(6);
+    ///
+    /// let preview = JsonFixer::preview(input, config).unwrap();
+    /// assert_eq!(preview, r#"{"id":1,"payload":"a very… (+13 chars)"}"#);
+    /// ```
+    pub fn preview(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<String, JsonFixerError> {
+        let mut parser = JsonParser::with_borrowed_config(input, config.as_ref());
+        parser.parse_preview()
+    }
+
+    /// Fixes a double encoded JSON document, ie. a JSON string whose content is JSON.
+    ///
+    /// Logging pipelines often encode a JSON document into a string more than once, eg.
+    /// `"{\"a\":1}"` or `"\"{\\\"a\\\":1}\""`. Up to `max_levels` string encodings are removed
+    /// before the innermost document is fixed like with [`JsonFixer::fix_with_report`]. A
+    /// string whose content is not an array, an object or another string is never unwrapped.
+    /// The encodings are removed with the default configuration, `config` applies to the
+    /// innermost document only, eg. its `max_string_length` does not limit the encoded strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `config` - Configuration options for fixing and formatting
+    /// * `max_levels` - The maximum number of encodings removed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FixReport)` - The fixed innermost document and the applied repairs, starting with
+    ///   one [`RepairKind::UnwrappedJsonString`] repair per removed encoding. The positions of
+    ///   the other repairs are relative to the innermost document
+    /// * `Err(JsonFixerError)` - If the innermost document is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, RepairKind};
+    ///
+    /// let input = r#""\"{a: 1, b: [1 2]}\"""#;
+    /// let report = JsonFixer::fix_double_encoded(input, JsonFixerConfig::default(), 5).unwrap();
+    ///
+    /// assert_eq!(report.output, r#"{"a":1,"b":[1,2]}"#);
+    /// assert_eq!(report.count(RepairKind::UnwrappedJsonString), 2);
+    /// ```
+    pub fn fix_double_encoded(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+        max_levels: usize,
+    ) -> Result<FixReport, JsonFixerError> {
+        let config = config.as_ref();
+        let unwrap_config = JsonFixerConfig::default();
+        let mut document = input.to_string();
+        let mut levels = 0;
+
+        while levels < max_levels {
+            let encoded = document.trim();
+            if !encoded.starts_with(['"', '\'']) {
+                break;
+            }
+            let parsed = JsonParser::with_borrowed_config(encoded, &unwrap_config).parse_document();
+            let content = match parsed {
+                Ok((JsonValue::String(content), _)) => content,
+                _ => break,
+            };
+            if !content.trim_start().starts_with(['{', '[', '"', '\'']) {
+                break;
+            }
+            document = content;
+            levels += 1;
+        }
+
+        let mut report = JsonParser::with_borrowed_config(&document, config).parse_with_report()?;
+        let unwrapped = (1..=levels).map(|level| {
+            let message = format!("Removed string encoding level {}", level);
+            Repair::at_path(RepairKind::UnwrappedJsonString, "", message)
+        });
+        report.repairs.splice(0..0, unwrapped);
+        Ok(report)
+    }
+
+    /// Fixes two JSON documents and compares them structurally.
+    ///
+    /// Both inputs are fixed with the default configuration, then compared ignoring
+    /// whitespace and the order of object members. Numbers are compared as written unless
+    /// `options.ignore_number_format` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first JSON string
+    /// * `b` - The second JSON string
+    /// * `options` - The comparison options
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - Whether both documents hold the same values
+    /// * `Err(JsonFixerError)` - If either input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{CompareOptions, JsonFixer};
+    ///
+    /// let options = CompareOptions::default();
+    /// assert!(JsonFixer::semantically_equal("{a: 1, b: [1 2]}", r#"{"b":[1,2],"a":1}"#, &options)
+    ///     .unwrap());
+    /// assert!(!JsonFixer::semantically_equal("[1, 2]", "[2, 1]", &options).unwrap());
+    ///
+    /// let options = CompareOptions { ignore_number_format: true };
+    /// assert!(JsonFixer::semantically_equal("[1e2, 0.50]", "[100, 0.5]", &options).unwrap());
+    /// ```
+    pub fn semantically_equal(
+        a: &str,
+        b: &str,
+        options: &CompareOptions,
+    ) -> Result<bool, JsonFixerError> {
+        jsoncompare::semantically_equal(a, b, options)
+    }
+
+    /// Fixes a batch of documents and aggregates the repairs and failures of the batch.
+    ///
+    /// Each input is fixed like with [`JsonFixer::fix_with_report`], a document that cannot
+    /// be fixed does not stop the batch. The returned [`BatchReport`] counts the repairs of
+    /// each kind, keeps samples of the failures and the sizes of the documents, to monitor
+    /// the data quality of their producers.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - The JSON strings to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// The result of each input, in order, and the report of the batch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, RepairKind};
+    ///
+    /// let inputs = ["{a: 1}", r#"{"b": 2}"#, "[1 2", "{a 1}"];
+    /// let (results, mut report) = JsonFixer::fix_batch(inputs, JsonFixerConfig::default());
+    ///
+    /// assert_eq!(results[2].as_ref().unwrap().output, "[1,2]");
+    /// assert_eq!((report.documents, report.clean, report.failures), (4, 1, 1));
+    /// assert_eq!(report.count(RepairKind::QuotedKey), 1);
+    /// assert_eq!(report.failure_samples[0].index, 3);
+    /// assert_eq!(report.size_percentile(50.0), Some(5));
+    /// ```
+    pub fn fix_batch<'a>(
+        inputs: impl IntoIterator<Item = &'a str>,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> (Vec<Result<FixReport, JsonFixerError>>, BatchReport) {
+        jsonbatch::fix_batch(inputs, config.as_ref())
+    }
+
+    /// Fixes a stream of concatenated documents separated by byte order marks (U+FEFF) or
+    /// form feeds, and aggregates the repairs and failures of the documents.
+    ///
+    /// Such separators are left by the tools concatenating files or paging their output.
+    /// They are treated as document boundaries rather than errors, unless they are found
+    /// inside a string, and a byte order mark starting the stream is skipped. Each document
+    /// is then fixed like with [`JsonFixer::fix_batch`], blank documents being left out, and
+    /// the positions of the separators are kept in [`BatchReport::separators`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The stream of JSON documents to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// The result of each document, in order, and the report of the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::jsonfixer::json_tokenizer::Position;
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = "\u{FEFF}{a: 1}\u{FEFF}[1 2]\n\x0C\n{b: 'x\u{FEFF}'}";
+    /// let (results, report) = JsonFixer::fix_documents(input, JsonFixerConfig::default());
+    ///
+    /// assert_eq!(results.len(), 3);
+    /// assert_eq!(results[1].as_ref().unwrap().output, "[1,2]");
+    /// assert_eq!(results[2].as_ref().unwrap().output, "{\"b\":\"x\u{FEFF}\"}");
+    /// assert_eq!(report.separators[0], Position { line: 1, column: 8 });
+    /// assert_eq!(report.separators[1], Position { line: 2, column: 1 });
+    /// ```
+    pub fn fix_documents(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> (Vec<Result<FixReport, JsonFixerError>>, BatchReport) {
+        jsonbatch::fix_documents(input, config.as_ref())
+    }
+
+    /// Fixes many documents and concatenates them into one array, or merges them into one
+    /// object, written to `writer`.
+    ///
+    /// Each document is read, fixed with `config` and written as soon as it is fixed, so that
+    /// only one document is held in memory at a time. The array or object is formatted with
+    /// `config` as if it were formatted whole. When merging objects, every document must be an
+    /// object and its members are written as the members of the merged object.
+    /// `options.deduplicate` leaves out the documents, or the keys, written before.
+    ///
+    /// # Arguments
+    ///
+    /// * `readers` - The readers of the documents, eg. files
+    /// * `writer` - Where the array or object is written
+    /// * `config` - Configuration options for fixing and formatting each document
+    /// * `options` - Whether the documents are merged and deduplicated
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of elements or members written
+    /// * `Err(JsonFixerError)` - If a document cannot be read, is too malformed to be fixed or
+    ///   is not an object when merging objects, or if writing fails. The documents before it
+    ///   have been written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{ConcatOptions, JsonFixer, JsonFixerConfig};
+    ///
+    /// let files = ["{id: 1}", "{'id': 2,}", "{id: 1}"];
+    /// let options = ConcatOptions {
+    ///     deduplicate: true,
+    ///     ..Default::default()
+    /// };
+    /// let mut output = Vec::new();
+    /// let readers = files.iter().map(|file| file.as_bytes());
+    /// let count = JsonFixer::concat(readers, &mut output, JsonFixerConfig::default(), &options);
+    ///
+    /// assert_eq!(count.unwrap(), 2);
+    /// assert_eq!(String::from_utf8(output).unwrap(), r#"[{"id":1},{"id":2}]"#);
+    /// ```
+    pub fn concat<R: std::io::Read, W: std::io::Write>(
+        readers: impl IntoIterator<Item = R>,
+        writer: W,
+        config: impl AsRef<JsonFixerConfig>,
+        options: &ConcatOptions,
+    ) -> Result<usize, JsonFixerError> {
+        jsonconcat::concat(readers, writer, config.as_ref(), options)
+    }
+
+    /// Fixes malformed JSON and checks that the fixed value has the expected shape, a cheap
+    /// alternative to a JSON Schema for ingestion gates.
+    ///
+    /// The input is fixed with the default configuration. Every violation of the shape is
+    /// listed in the error, with the path of the value, eg. each element of an array of
+    /// objects missing a required key.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `shape` - The structure the fixed value is expected to have
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string
+    /// * `Err(JsonFixerError::Shape)` - If the fixed value does not have the expected shape
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerError, Shape};
+    ///
+    /// let shape = Shape::ArrayOfObjects {
+    ///     required_keys: vec!["id".to_string()],
+    /// };
+    /// assert_eq!(
+    ///     JsonFixer::fix_expecting("[{id: 1}, {id: 2}]", &shape).unwrap(),
+    ///     r#"[{"id":1},{"id":2}]"#
+    /// );
+    ///
+    /// let Err(JsonFixerError::Shape(err)) = JsonFixer::fix_expecting("[{id: 1}, {}]", &shape)
+    /// else {
+    ///     panic!("expected a shape error");
+    /// };
+    /// assert_eq!(err.violations[0].path, "[1]");
+    /// assert_eq!(err.violations[0].message, "Missing required key 'id'");
+    /// ```
+    pub fn fix_expecting(input: &str, shape: &Shape) -> Result<String, JsonFixerError> {
+        jsonshape::fix_expecting(input, shape)
+    }
+
+    /// Scans the structure of an input without fixing it.
+    ///
+    /// The scan counts the strings, braces and brackets of the input, which tells whether it
+    /// was truncated or has mismatched brackets. It is much faster than fixing, and is the
+    /// scan the parser relies on to repair mismatched brackets.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to scan
+    ///
+    /// # Returns
+    ///
+    /// The balance of the strings, braces and brackets of the input
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let scan = JsonFixer::inspect(r#"{"a": [[1, 2], [3"#);
+    /// assert_eq!(scan.unclosed_arrays(), 2);
+    /// assert_eq!(scan.hints(), vec!["1 unclosed object", "2 unclosed arrays"]);
+    /// ```
+    pub fn inspect(input: &str) -> StructureScan {
+        StructureScan::scan(input)
+    }
+
+    /// Fixes a huge flat object and streams its members one at a time, eg. a map of records by
+    /// id.
+    ///
+    /// Each member is passed to `f` as soon as it is parsed, with its key unescaped and its
+    /// value as fixed JSON text, and is then dropped. Neither the object nor its fixed output
+    /// are held whole in memory. The members are passed as found, duplicated keys included,
+    /// and the ones found before an error have already been passed when it is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON object to fix
+    /// * `f` - The callback receiving the key and fixed value of each member
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every member was passed
+    /// * `Err(JsonFixerError)` - If the input is not an object or is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = "{'u1': {name: 'Ann', tags: ['a' 'b']}, 'u2': {name: 'Bob'},";
+    /// let mut members = Vec::new();
+    /// JsonFixer::for_each_member(input, |key, value| {
+    ///     members.push(format!("{} => {}", key, value));
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     members,
+    ///     vec![
+    ///         r#"u1 => {"name":"Ann","tags":["a","b"]}"#,
+    ///         r#"u2 => {"name":"Bob"}"#,
+    ///     ]
+    /// );
+    /// ```
+    pub fn for_each_member<F>(input: &str, f: F) -> Result<(), JsonFixerError>
+    where
+        F: FnMut(&str, &str),
+    {
+        jsonmembers::for_each_member(input, f)
+    }
+
+    /// Fixes a huge malformed array and splits it into chunks of `chunk_size` elements, each
+    /// one passed to `sink` as a valid JSON array or as NDJSON lines, eg. to write them to
+    /// numbered files.
+    ///
+    /// The elements are passed to `sink` as soon as a chunk is filled, so the array is never
+    /// held whole in memory. The elements of a chunk are written compact.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON array to split
+    /// * `chunk_size` - The number of elements of each chunk, the last one may hold fewer
+    /// * `format` - Whether the chunks are written as arrays or as NDJSON
+    /// * `sink` - The callback receiving each chunk, its error aborting the split
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of chunks written
+    /// * `Err(JsonFixerError)` - If the input is not an array, is too malformed to be fixed,
+    ///   or if `sink` failed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{ChunkFormat, JsonFixer};
+    ///
+    /// let mut chunks = Vec::new();
+    /// let count = JsonFixer::split_array("[1, 2, 3, {a: 4}", 2, ChunkFormat::Array, |chunk| {
+    ///     chunks.push(chunk.to_string());
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(count, 2);
+    /// assert_eq!(chunks, vec!["[1,2]", r#"[3,{"a":4}]"#]);
+    /// ```
+    pub fn split_array<F>(
+        input: &str,
+        chunk_size: usize,
+        format: ChunkFormat,
+        sink: F,
+    ) -> Result<usize, JsonFixerError>
+    where
+        F: FnMut(&str) -> std::io::Result<()>,
+    {
+        jsonsplit::split_array(input, chunk_size, format, sink)
+    }
+
+    /// Fixes malformed JSON and writes it to `writer`, each member or element of the root
+    /// object or array written as soon as it is parsed instead of building the whole output.
+    ///
+    /// The output is buffered and, after each member, written and flushed once the buffer
+    /// holds `options.buffer_cap` bytes, so memory stays bounded by the cap and one member,
+    /// along with the keys of the root object when `defaults` are set or duplicate keys are
+    /// dropped. The output is the one of [`JsonFixer::fix_with_config`], the other roots being
+    /// written whole. The limits are checked as the members are parsed, the root having too
+    /// many members failing once it is parsed.
+    ///
+    /// The options needing the whole root container fail before anything is written:
+    /// `preserve`, `keep_comments`, `max_line_length`, `max_repairs` and `provenance_path`,
+    /// `sort_keys`, `key_order` and the duplicate keys kept last or merged in a root object,
+    /// and `homogenize_arrays` in a root array.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `writer` - Where the fixed JSON is written
+    /// * `config` - Configuration options for fixing and formatting the members
+    /// * `options` - How much output is buffered between flushes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of members or elements written, `0` for the other roots
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed, if writing fails
+    ///   or, with `JsonFixerError::Unstreamable`, if an option needs the whole root. The
+    ///   members before it have been written and flushed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, StreamOptions};
+    ///
+    /// let mut output = Vec::new();
+    /// let (config, options) = (JsonFixerConfig::default(), StreamOptions::default());
+    /// let count = JsonFixer::fix_to_writer("{a: 1, b: [2,]", &mut output, config, &options);
+    ///
+    /// assert_eq!(count.unwrap(), 2);
+    /// assert_eq!(String::from_utf8(output).unwrap(), r#"{"a":1,"b":[2]}"#);
+    /// ```
+    pub fn fix_to_writer<W: std::io::Write>(
+        input: &str,
+        writer: W,
+        config: impl AsRef<JsonFixerConfig>,
+        options: &StreamOptions,
+    ) -> Result<usize, JsonFixerError> {
+        jsonstream::fix_to_writer(input, writer, config.as_ref(), options)
+    }
+
+    /// Fixes an NDJSON stream line by line and writes each fixed document to `writer`,
+    /// followed by a line break, eg. to relay an endless stream of log records.
+    ///
+    /// Only the line being fixed is held in memory. The output is buffered and, after each
+    /// document, written and flushed once the buffer holds `options.buffer_cap` bytes. The
+    /// blank lines are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The NDJSON stream, one document per line
+    /// * `writer` - Where the fixed documents are written
+    /// * `config` - Configuration options for fixing and formatting each document, written
+    ///   compact whatever `beautify` to keep one document per line
+    /// * `options` - How much output is buffered between flushes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of documents written, once the reader is exhausted
+    /// * `Err(JsonFixerError)` - If a line cannot be read or is too malformed to be fixed, or
+    ///   if writing fails. The documents before it have been written and flushed, and the
+    ///   syntax errors are located at their line in the stream
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig, StreamOptions};
+    ///
+    /// let input = "{id: 1}\n\n{'id': 2,}\n";
+    /// let mut output = Vec::new();
+    /// let (config, options) = (JsonFixerConfig::default(), StreamOptions::default());
+    /// let count = JsonFixer::fix_ndjson(input.as_bytes(), &mut output, config, &options);
+    ///
+    /// assert_eq!(count.unwrap(), 2);
+    /// assert_eq!(String::from_utf8(output).unwrap(), "{\"id\":1}\n{\"id\":2}\n");
+    /// ```
+    pub fn fix_ndjson<R: std::io::BufRead, W: std::io::Write>(
+        reader: R,
+        writer: W,
+        config: impl AsRef<JsonFixerConfig>,
+        options: &StreamOptions,
+    ) -> Result<usize, JsonFixerError> {
+        jsonstream::fix_ndjson(reader, writer, config.as_ref(), options)
+    }
+
+    /// Fixes malformed JSON and annotates the original input with the repairs applied, for
+    /// documentation and teaching tools.
+    ///
+    /// The input is returned as written, with a `/*<-- ...*/` comment after each repaired
+    /// token describing its repair. The repairs inserting a token are marked after the token
+    /// the insertion is made after. The repairs applied on the parsed document, eg. the
+    /// coerced booleans, have no position in the input and are not marked.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to annotate
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The input with a marker for each repair
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// assert_eq!(
+    ///     JsonFixer::annotate("[1 2,]").unwrap(),
+    ///     "[1/*<-- inserted missing ','*/ 2,/*<-- removed trailing ','*/]"
+    /// );
+    /// ```
+    pub fn annotate(input: &str) -> Result<String, JsonFixerError> {
+        jsonannotate::annotate(input)
+    }
+
+    /// Relaxes valid JSON into human friendly JSON5 or JSONC, eg. to generate editable
+    /// configuration files from machine output.
+    ///
+    /// The output is pretty printed with 2 spaces and every member or element matching a path
+    /// pattern of `comments` is preceded by its comment, written as `//` lines. JSON5 output
+    /// also leaves the keys that are identifiers unquoted and writes trailing commas.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The valid JSON string to relax
+    /// * `style` - `OutputStyle::Json5` or `OutputStyle::Jsonc`, strict JSON has no comments
+    /// * `comments` - Pairs of path patterns and comments, first match wins
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The relaxed document
+    /// * `Err(JsonFixerError)` - If the input is not valid JSON
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, OutputStyle};
+    ///
+    /// let comments = [("port".to_string(), "Port to listen on".to_string())];
+    /// let relaxed = JsonFixer::relax(r#"{"port": 80}"#, OutputStyle::Json5, &comments);
+    /// assert_eq!(relaxed.unwrap(), "{\n  // Port to listen on\n  port: 80,\n}");
+    /// ```
+    pub fn relax(
+        input: &str,
+        style: OutputStyle,
+        comments: &[(String, String)],
+    ) -> Result<String, JsonFixerError> {
+        jsonrelax::relax(input, style, comments)
+    }
+
+    /// Fixes malformed JSON and summarizes it on a single line, for log messages.
+    ///
+    /// The summary writes the first keys of the objects, the lengths and first elements of
+    /// the arrays and previews of the strings, down to `depth` nested levels. The deeper
+    /// objects and arrays are only summarized by their size, so that the structure of a
+    /// large document can be glanced at without pretty printing it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `depth` - The number of nested levels written, `0` only writes the size of the root
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The summary of the fixed value
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{users: [{id: 1, tags: ['a' 'b']}, {id: 2}], total: 2"#;
+    /// assert_eq!(
+    ///     JsonFixer::summarize(input, 2).unwrap(),
+    ///     "{users: [2 items: {2 keys}, {1 key}], total: 2}"
+    /// );
+    /// assert_eq!(JsonFixer::summarize(input, 0).unwrap(), "{2 keys}");
+    /// ```
+    pub fn summarize(input: &str, depth: usize) -> Result<String, JsonFixerError> {
+        jsonsummary::summarize(input, depth)
+    }
+
+    /// Fixes malformed JSON and lists the path, kind and span of every value, to make broken
+    /// documents searchable by path.
+    ///
+    /// Values are listed depth first, parents before their children, with the paths of the
+    /// configuration, eg. `users[0].name`. The spans locate the values in the input as
+    /// written, before it was fixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<IndexEntry>)` - The path, kind and span of every value of the fixed input
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, ValueKind};
+    ///
+    /// let input = "{users: [{name: 'Ann'}";
+    /// let index = JsonFixer::index_paths(input).unwrap();
+    ///
+    /// let paths: Vec<_> = index.iter().map(|(path, kind, _)| (path.as_str(), *kind)).collect();
+    /// assert_eq!(
+    ///     paths,
+    ///     vec![
+    ///         ("", ValueKind::Object),
+    ///         ("users", ValueKind::Array),
+    ///         ("users[0]", ValueKind::Object),
+    ///         ("users[0].name", ValueKind::String),
+    ///     ]
+    /// );
+    /// let span = &index[3].2;
+    /// assert_eq!(&input[span.start..span.end], "'Ann'");
+    /// ```
+    pub fn index_paths(input: &str) -> Result<Vec<IndexEntry>, JsonFixerError> {
+        jsonindex::index_paths(input)
+    }
+
+    /// Fixes the values at the given paths of a malformed JSON input, even when other parts
+    /// of it are broken beyond repair.
+    ///
+    /// The input is parsed skipping its corrupted sections like with `salvage`, and the
+    /// values parsed before a section that cannot be skipped are kept as well, so that a few
+    /// fields can be read out of a mangled payload. Each value found is then fixed on its
+    /// own with the default configuration. The paths use the syntax of the configuration,
+    /// eg. `users[0].name`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to read the fields from
+    /// * `paths` - The paths of the fields
+    ///
+    /// # Returns
+    ///
+    /// The fixed value of each path, `None` if it is missing from the input or if its value
+    /// is broken itself
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{"user": {"id": 42}, "event": {"type": 'click', "data": {"x": ?!#"#;
+    /// assert!(JsonFixer::fix(input).is_err());
+    ///
+    /// let fields = JsonFixer::extract_fields(input, &["user.id", "event.type", "event.data.x"]);
+    /// assert_eq!(
+    ///     fields,
+    ///     vec![Some("42".to_string()), Some(r#""click""#.to_string()), None]
+    /// );
+    /// ```
+    pub fn extract_fields(input: &str, paths: &[&str]) -> Vec<Option<String>> {
+        jsonindex::extract_fields(input, paths)
+    }
+
+    /// Reformats the value at a path of a document, the rest of the document being kept
+    /// byte for byte, eg. for tools editing one section of a large hand maintained config.
+    ///
+    /// The document is fixed to locate the value, so it has to be fixable, but only the value
+    /// is replaced, by its output fixed with `config`. The paths of the configuration, eg.
+    /// `key_order`, are relative to the value. The lines following the first one of the
+    /// value are indented like the line the value starts on.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON document
+    /// * `path` - The path of the value to format, eg. `server.routes`
+    /// * `config` - Configuration options for fixing and formatting the value
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(String))` - The document with the value formatted
+    /// * `Ok(None)` - If the document has no value at `path`
+    /// * `Err(JsonFixerError)` - If the document is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = "{\n    \"name\":   \"app\",\n    \"deps\": {b: 1, 'a': 2}\n}";
+    /// let config = JsonFixerConfig {
+    ///     beautify: true,
+    ///     indent_size: 4,
+    ///     sort_keys: true,
+    ///     ..Default::default()
+    /// };
+    /// let output = JsonFixer::format_at(input, "deps", &config).unwrap().unwrap();
+    /// assert_eq!(
+    ///     output,
+    ///     concat!(
+    ///         "{\n    \"name\":   \"app\",\n    \"deps\": {\n",
+    ///         "        \"a\": 2,\n        \"b\": 1\n    }\n}"
+    ///     )
+    /// );
+    /// ```
+    pub fn format_at(
+        input: &str,
+        path: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<Option<String>, JsonFixerError> {
+        jsonindex::format_at(input, path, config.as_ref())
+    }
+
+    /// Fixes malformed JSON and flattens it into a single level object keyed by paths.
+    ///
+    /// The keys are the paths of the scalars, empty objects and empty arrays of the fixed
+    /// value: object members are joined with `separator` and array elements use brackets, eg.
+    /// `users[0].name` with the `"."` separator. This suits tools that only handle flat
+    /// records, eg. spreadsheets or key value stores. A scalar is written as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `separator` - The string joining the keys of nested objects
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The flattened object
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = "{user: {name: 'Ann', tags: ['a' 'b']}, meta: {}}";
+    /// let flat = JsonFixer::flatten(input, ".", JsonFixerConfig::default()).unwrap();
+    /// assert_eq!(
+    ///     flat,
+    ///     r#"{"user.name":"Ann","user.tags[0]":"a","user.tags[1]":"b","meta":{}}"#
+    /// );
+    /// ```
+    pub fn flatten(
+        input: &str,
+        separator: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<String, JsonFixerError> {
+        jsonflatten::flatten(input, separator, config.as_ref())
+    }
+
+    /// Fixes malformed JSON and nests the members of its root object following their keys,
+    /// the inverse of [`JsonFixer::flatten`].
+    ///
+    /// Array elements are written in the order of their indices, missing indices being
+    /// skipped. When keys conflict, eg. `a` then `a.b`, the later key wins unless the earlier
+    /// value is an object or an array, which is merged with it. A value that is not an object
+    /// is written as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `separator` - The string joining the keys of nested objects
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The nested value
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let input = "{'user/name': 'Ann', 'user/tags[0]': 'a', 'user/tags[1]': 'b'}";
+    /// let nested = JsonFixer::unflatten(input, "/", JsonFixerConfig::default()).unwrap();
+    /// assert_eq!(nested, r#"{"user":{"name":"Ann","tags":["a","b"]}}"#);
+    /// ```
+    pub fn unflatten(
+        input: &str,
+        separator: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<String, JsonFixerError> {
+        jsonflatten::unflatten(input, separator, config.as_ref())
+    }
+
+    /// Fixes malformed JSON using default configuration.
+    ///
+    /// This method attempts to fix common JSON syntax errors while maintaining
+    /// a compact output format.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{ name: 'John', age: 30 hobbies: ['reading' 'coding'] }"#;
+    /// let result = JsonFixer::fix(input).unwrap();
+    /// assert_eq!(result, r#"{"name":"John","age":30,"hobbies":["reading","coding"]}"#);
+    /// ```
+    pub fn fix(input: &str) -> Result<String, JsonFixerError> {
+        let mut parser = JsonParser::new(input, JsonFixerConfig::default());
+        parser.parse()
+    }
+    /// Fixes JSON and adds spaces between keys, values, and punctuation.
+    ///
+    /// This method applies minimal formatting to make the JSON more readable
+    /// while keeping it on a single line.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string with added spacing
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{name:"John",age:30}"#;
+    /// let result = JsonFixer::fix_with_space_between(input).unwrap();
+    /// assert_eq!(result, r#"{ "name": "John", "age": 30 }"#);
+    /// ```
+    pub fn fix_with_space_between(input: &str) -> Result<String, JsonFixerError> {
+        let config = JsonFixerConfig {
+            space_between: true,
+            beautify: false,
+            preserve: false,
+            ..Default::default()
+        };
+        let mut parser = JsonParser::new(input, config);
+        parser.parse()
+    }
+     /// Fixes JSON and applies pretty printing with proper indentation.
+    ///
+    /// This method formats the JSON to be human-readable with proper indentation
+    /// and line breaks.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed and formatted JSON string
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{name:"John",age:30,hobbies:["reading","coding"]}"#;
+    /// let result = JsonFixer::fix_pretty(input).unwrap();
+    /// // Result will be:
+    /// // {
+    /// //     "name": "John",
+    /// //     "age": 30,
+    /// //     "hobbies": [
+    /// //         "reading",
+    /// //         "coding"
+    /// //     ]
+    /// // }
+    /// ```
+    pub fn fix_pretty(input: &str) -> Result<String, JsonFixerError> {
+        let config = JsonFixerConfig {
+            beautify: true,
+            preserve: false,
+            space_between: false,
+            ..Default::default()
+        };
+
+        let mut parser = JsonParser::new(input, config);
+        parser.parse()
+    }
+
+    /// Fixes JSON input once and returns it both compact and pretty printed, like
+    /// [`JsonFixer::fix`] and [`JsonFixer::fix_pretty`] do, without parsing it twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((String, String))` - The compact and the pretty printed JSON strings
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    ///
+    /// let input = r#"{name: "John", tags: ['a' 'b']}"#;
+    /// let (compact, pretty) = JsonFixer::fix_both(input).unwrap();
+    /// assert_eq!(compact, JsonFixer::fix(input).unwrap());
+    /// assert_eq!(pretty, JsonFixer::fix_pretty(input).unwrap());
+    /// ```
+    pub fn fix_both(input: &str) -> Result<(String, String), JsonFixerError> {
+        let config = JsonFixerConfig::default();
+        let pretty_config = JsonFixerConfig {
+            beautify: true,
+            preserve: false,
+            space_between: false,
+            ..Default::default()
+        };
+
+        let mut parser = JsonParser::with_borrowed_config(input, &config);
+        let (value, _) = parser.parse_document()?;
+        let compact = JsonFormatter.format(&value, &config)?;
+        let pretty = JsonFormatter.format(&value, &pretty_config)?;
+        Ok((compact, pretty))
+    }
+}
+
+impl JsonFixer {
+    /// Fixes JSON and renders it as highlighted HTML showing what was repaired.
     ///
-    /// This method allows full control over the fixing and formatting process through
-    /// the provided configuration.
+    /// Every token is wrapped in a `<span>` with a class depending on its kind (`jf-key`,
+    /// `jf-string`, `jf-number`, `jf-boolean`, `jf-null` or `jf-punctuation`) and the
+    /// whole output in a `<pre class="json-fixer">`. The members and values that were
+    /// repaired are wrapped in a `<mark class="jf-repair">` whose title describes the
+    /// repairs. If the input cannot be fixed, it is rendered as is with the line of the
+    /// error wrapped in a `<mark class="jf-error">`, and cut to the characters around the
+    /// error when it is longer than `config.error_window` allows.
     ///
     /// # Arguments
     ///
@@ -61,93 +1344,340 @@ impl JsonFixer {
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The fixed JSON string
-    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    /// * `Ok(String)` - The HTML rendering of the fixed JSON, or of the input with its error
+    /// * `Err(JsonFixerError)` - If an error other than a syntax error occurred
     ///
     /// # Examples
     ///
     /// ```
     /// use json_fixer::{JsonFixer, JsonFixerConfig};
     ///
-    /// let input = r#"{
-    ///     c: 3,
-    ///     a: 1,
-    ///     b: 2
-    /// }"#;
+    /// let html = JsonFixer::render_html("[1, 2,]", JsonFixerConfig::default()).unwrap();
+    /// assert!(html.contains("<mark class=\"jf-repair\" title=\"removed comma: "));
+    /// assert!(html.contains("<span class=\"jf-number\">2</span></mark>"));
+    /// ```
+    pub fn render_html(
+        input: &str,
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<String, JsonFixerError> {
+        jsonrenderer::render_html(input, config.as_ref())
+    }
+}
+
+/*
+************************** Gated behind terminal *************************
+*/
+
+#[cfg(feature = "terminal")]
+pub use jsonrenderer::AnsiTheme;
+
+#[cfg(feature = "terminal")]
+impl JsonFixer {
+    /// Fixes JSON and renders it pretty printed with ANSI colors for terminals.
     ///
-    /// let mut config = JsonFixerConfig::default();
-    /// config.sort_keys = true;
-    /// config.beautify = true;
+    /// This method is only available when the `terminal` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON string to fix
+    /// * `theme` - The ANSI escape sequences used for each kind of element
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The fixed JSON string, indented with 2 spaces and highlighted
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
     ///
-    /// let result = JsonFixer::fix_with_config(input, config).unwrap();
     /// ```
-    pub fn fix_with_config(input: &str, config: JsonFixerConfig) -> Result<String, JsonFixerError> {
-        let mut parser = JsonParser::new(input, config);
-        parser.parse()
+    /// use json_fixer::{AnsiTheme, JsonFixer};
+    ///
+    /// let rendered = JsonFixer::render_ansi("{ok: true}", &AnsiTheme::default()).unwrap();
+    /// println!("{}", rendered);
+    /// ```
+    pub fn render_ansi(input: &str, theme: &AnsiTheme) -> Result<String, JsonFixerError> {
+        jsonrenderer::render_ansi(input, theme)
     }
-    /// Fixes malformed JSON using default configuration.
+}
+
+/*
+************************** Gated behind memmap *************************
+*/
+
+/// Bytes of output buffered by `JsonFixer::fix_mmap` between writes.
+#[cfg(feature = "memmap")]
+const MMAP_BUFFER_CAP: usize = 64 * 1024;
+
+#[cfg(feature = "memmap")]
+impl JsonFixer {
+    /// Fixes a JSON file by memory mapping it and writes the fixed JSON to `writer`.
     ///
-    /// This method attempts to fix common JSON syntax errors while maintaining
-    /// a compact output format.
+    /// The tokenizer runs directly over the mapped bytes, so the file is never copied in
+    /// memory, and the members of the root object or array are written as they are fixed
+    /// like [`JsonFixer::fix_to_writer`] does, so the output is not held whole either. The
+    /// output is only built whole when an option of `config` needs the whole document, eg.
+    /// `sort_keys`. The file must not be modified while it is being fixed.
+    ///
+    /// This method is only available when the `memmap` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the JSON file to fix
+    /// * `config` - Configuration options for fixing and formatting
+    /// * `writer` - Where the fixed JSON is written
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the fixed JSON was written
+    /// * `Err(JsonFixerError)` - If the file cannot be read, is not UTF-8 or is too malformed
+    ///   to be fixed, or if writing fails. The members before it may have been written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let mut output = Vec::new();
+    /// JsonFixer::fix_mmap("data.json", JsonFixerConfig::default(), &mut output).unwrap();
+    /// ```
+    pub fn fix_mmap<P: AsRef<std::path::Path>, W: std::io::Write>(
+        path: P,
+        config: impl AsRef<JsonFixerConfig>,
+        mut writer: W,
+    ) -> Result<(), JsonFixerError> {
+        let config = config.as_ref();
+        let file = std::fs::File::open(path).map_err(JsonFixerError::IoError)?;
+        if file.metadata().map_err(JsonFixerError::IoError)?.len() == 0 {
+            // Empty files cannot be mapped
+            return JsonParser::with_borrowed_config("", config)
+                .parse()
+                .map(|_| ());
+        }
+
+        // SAFETY: the mapping is only read, the caller must not modify the file meanwhile
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(JsonFixerError::IoError)?;
+        let input = std::str::from_utf8(&mmap).map_err(|err| {
+            JsonFixerError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        })?;
+
+        // Nothing is written before an option is found to need the whole document
+        let options = StreamOptions {
+            buffer_cap: MMAP_BUFFER_CAP,
+        };
+        match jsonstream::fix_to_writer(input, &mut writer, config, &options) {
+            Err(JsonFixerError::Unstreamable(_)) => (),
+            result => return result.map(|_| ()),
+        }
+        let output = JsonParser::with_borrowed_config(input, config).parse()?;
+        writer
+            .write_all(output.as_bytes())
+            .map_err(JsonFixerError::IoError)
+    }
+}
+
+/*
+************************** Gated behind arena *************************
+*/
+
+#[cfg(feature = "arena")]
+pub use jsonarena::{Arena, ArenaValue};
+
+#[cfg(feature = "arena")]
+impl JsonFixer {
+    /// Fixes malformed JSON and builds its value in an arena.
+    ///
+    /// Every string, array and object of the value is allocated in `arena` instead of the
+    /// global allocator, so documents with many small nodes are built faster and freed at
+    /// once by dropping or resetting the arena. Inputs that are already valid JSON are built
+    /// directly, the others are fixed with the default configuration first.
+    ///
+    /// This method is only available when the `arena` feature is enabled.
     ///
     /// # Arguments
     ///
+    /// * `arena` - The arena the value is allocated in
     /// * `input` - The JSON string to fix
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The fixed JSON string
+    /// * `Ok(ArenaValue)` - The value of the fixed JSON, borrowed from the arena
     /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
     ///
     /// # Examples
     ///
     /// ```
-    /// use json_fixer::JsonFixer;
+    /// use json_fixer::{Arena, ArenaValue, JsonFixer};
     ///
-    /// let input = r#"{ name: 'John', age: 30 hobbies: ['reading' 'coding'] }"#;
-    /// let result = JsonFixer::fix(input).unwrap();
-    /// assert_eq!(result, r#"{"name":"John","age":30,"hobbies":["reading","coding"]}"#);
+    /// let arena = Arena::new();
+    /// let value = JsonFixer::fix_to_value_in(&arena, "{name: 'John', tags: [1 2]}").unwrap();
+    ///
+    /// assert_eq!(value.get("name"), Some(&ArenaValue::String("John")));
+    /// assert_eq!(
+    ///     value.get("tags"),
+    ///     Some(&ArenaValue::Array(&[ArenaValue::Number("1"), ArenaValue::Number("2")]))
+    /// );
     /// ```
-    pub fn fix(input: &str) -> Result<String, JsonFixerError> {
-        let mut parser = JsonParser::new(input, JsonFixerConfig::default());
-        parser.parse()
+    pub fn fix_to_value_in<'a>(
+        arena: &'a Arena,
+        input: &str,
+    ) -> Result<ArenaValue<'a>, JsonFixerError> {
+        jsonarena::fix_to_value_in(arena, input)
     }
-    /// Fixes JSON and adds spaces between keys, values, and punctuation.
+}
+
+/*
+************************** Gated behind schema *************************
+*/
+
+#[cfg(feature = "schema")]
+pub use jsonschema::{SchemaError, SchemaReport};
+
+#[cfg(feature = "schema")]
+impl JsonFixer {
+    /// Fixes malformed JSON and validates the fixed value against a JSON Schema.
     ///
-    /// This method applies minimal formatting to make the JSON more readable
-    /// while keeping it on a single line.
+    /// Both the input and the schema are fixed with the default configuration. A subset of
+    /// JSON Schema draft 2020-12 is supported, see the [`jsonschema`] module for the list of
+    /// keywords. Violations are reported with the JSON Pointer of the invalid value, they are
+    /// not errors: the fixed output is returned either way.
+    ///
+    /// This method is only available when the `schema` feature is enabled.
     ///
     /// # Arguments
     ///
     /// * `input` - The JSON string to fix
+    /// * `schema` - The JSON Schema the fixed value is validated against
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The fixed JSON string with added spacing
-    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    /// * `Ok(SchemaReport)` - The fixed JSON string and the schema violations
+    /// * `Err(JsonFixerError)` - If the input or the schema is too malformed to be fixed
     ///
     /// # Examples
     ///
     /// ```
     /// use json_fixer::JsonFixer;
     ///
-    /// let input = r#"{name:"John",age:30}"#;
-    /// let result = JsonFixer::fix_with_space_between(input).unwrap();
-    /// assert_eq!(result, r#"{ "name": "John", "age": 30 }"#);
+    /// let schema = r#"{
+    ///     "type": "object",
+    ///     "properties": {"age": {"type": "integer", "minimum": 0}},
+    ///     "required": ["name"]
+    /// }"#;
+    /// let report = JsonFixer::fix_and_validate("{age: -1}", schema).unwrap();
+    ///
+    /// assert_eq!(report.output, r#"{"age":-1}"#);
+    /// assert_eq!(report.errors.len(), 2);
+    /// assert_eq!(report.errors[0].pointer, "/age");
+    /// assert_eq!(report.errors[0].keyword, "minimum");
+    /// assert_eq!(report.errors[1].keyword, "required");
     /// ```
-    pub fn fix_with_space_between(input: &str) -> Result<String, JsonFixerError> {
-        let mut config = JsonFixerConfig::default();
+    pub fn fix_and_validate(input: &str, schema: &str) -> Result<SchemaReport, JsonFixerError> {
+        jsonschema::fix_and_validate(input, schema)
+    }
 
-        config.space_between = true;
-        config.beautify = false;
-        config.preserve = false;
-        let mut parser = JsonParser::new(input, config);
-        parser.parse()
+    /// Lists the paths of the values a JSON Schema types as `type_name`, eg. to hint the
+    /// boolean fields of [`JsonFixerConfig::boolean_paths`].
+    ///
+    /// The paths are patterns of the configuration paths, `[*]` standing for the items of an
+    /// array and `*` for the additional properties of an object. The same subset of JSON
+    /// Schema as [`JsonFixer::fix_and_validate`] is supported.
+    ///
+    /// This method is only available when the `schema` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The JSON Schema to walk
+    /// * `type_name` - The JSON Schema type of the values to list, eg. `"boolean"`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<String>)` - The paths of the values allowing the type, in schema order
+    /// * `Err(JsonFixerError)` - If the schema is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{JsonFixer, JsonFixerConfig};
+    ///
+    /// let schema = r#"{
+    ///     "properties": {
+    ///         "users": {"items": {"properties": {"admin": {"type": "boolean"}}}}
+    ///     }
+    /// }"#;
+    /// let mut config = JsonFixerConfig::default();
+    /// config.normalize_booleans = true;
+    /// config.boolean_paths = JsonFixer::paths_of_type(schema, "boolean").unwrap();
+    ///
+    /// assert_eq!(config.boolean_paths, vec!["users[*].admin"]);
+    /// let output = JsonFixer::fix_with_config("{users: [{admin: 'yes'}]}", config).unwrap();
+    /// assert_eq!(output, r#"{"users":[{"admin":true}]}"#);
+    /// ```
+    pub fn paths_of_type(schema: &str, type_name: &str) -> Result<Vec<String>, JsonFixerError> {
+        jsonschema::paths_of_type(schema, type_name)
     }
-     /// Fixes JSON and applies pretty printing with proper indentation.
+}
+
+/*
+************************** Gated behind encoding *************************
+*/
+
+#[cfg(feature = "encoding")]
+pub use jsonencoding::{Encoding, EncodingReport};
+
+#[cfg(feature = "encoding")]
+impl JsonFixer {
+    /// Fixes malformed JSON bytes of an unknown encoding.
     ///
-    /// This method formats the JSON to be human-readable with proper indentation
-    /// and line breaks.
+    /// Legacy exports are often encoded in ISO-8859-1 or Windows-1252 rather than UTF-8, see
+    /// [`Encoding::detect`] for how the encoding is detected. The input is transcoded to UTF-8
+    /// before being fixed, and the detected encoding is reported along with the output.
+    ///
+    /// This method is only available when the `encoding` feature is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The JSON bytes to fix
+    /// * `config` - Configuration options for fixing and formatting
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(EncodingReport)` - The fixed JSON string and the detected encoding
+    /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::{Encoding, JsonFixer, JsonFixerConfig};
+    ///
+    /// // "Café “Noël”" in Windows-1252
+    /// let input = b"{name: 'Caf\xe9 \x93No\xebl\x94'}";
+    /// let report = JsonFixer::fix_bytes(input, JsonFixerConfig::default()).unwrap();
+    ///
+    /// assert_eq!(report.output, r#"{"name":"Café “Noël”"}"#);
+    /// assert_eq!(report.encoding, Encoding::Windows1252);
+    /// ```
+    pub fn fix_bytes(
+        input: &[u8],
+        config: impl AsRef<JsonFixerConfig>,
+    ) -> Result<EncodingReport, JsonFixerError> {
+        jsonencoding::fix_bytes(input, config.as_ref())
+    }
+}
+
+/*
+************************** Gated behind hash *************************
+*/
+
+#[cfg(feature = "hash")]
+impl JsonFixer {
+    /// Fixes JSON and returns the SHA-256 hash of its canonical form, eg. to deduplicate
+    /// documents that only differ in their formatting, key order or repairs.
+    ///
+    /// The canonical form is the one of RFC 8785, see the [`jsonhash`] module. It is hashed
+    /// as it is written, so that it is never held whole in memory. Only the last occurrence
+    /// of a duplicated key is kept.
+    ///
+    /// This method is only available when the `hash` feature is enabled.
     ///
     /// # Arguments
     ///
@@ -155,7 +1685,7 @@ impl JsonFixer {
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The fixed and formatted JSON string
+    /// * `Ok([u8; 32])` - The hash of the canonical form of the fixed JSON
     /// * `Err(JsonFixerError)` - If the input is too malformed to be fixed
     ///
     /// # Examples
@@ -163,26 +1693,13 @@ impl JsonFixer {
     /// ```
     /// use json_fixer::JsonFixer;
     ///
-    /// let input = r#"{name:"John",age:30,hobbies:["reading","coding"]}"#;
-    /// let result = JsonFixer::fix_pretty(input).unwrap();
-    /// // Result will be:
-    /// // {
-    /// //     "name": "John",
-    /// //     "age": 30,
-    /// //     "hobbies": [
-    /// //         "reading",
-    /// //         "coding"
-    /// //     ]
-    /// // }
+    /// let hash = JsonFixer::canonical_hash("{b: 1.50e2, a: 'x',}").unwrap();
+    ///
+    /// assert_eq!(hash, JsonFixer::canonical_hash(r#"{"a":"x","b":150}"#).unwrap());
+    /// assert_ne!(hash, JsonFixer::canonical_hash(r#"{"a":"x","b":151}"#).unwrap());
     /// ```
-    pub fn fix_pretty(input: &str) -> Result<String, JsonFixerError> {
-        let mut config = JsonFixerConfig::default();
-        config.beautify = true;
-        config.preserve = false;
-        config.space_between = false;
-
-        let mut parser = JsonParser::new(input, config);
-        parser.parse()
+    pub fn canonical_hash(input: &str) -> Result<[u8; 32], JsonFixerError> {
+        jsonhash::canonical_hash(input)
     }
 }
 
@@ -190,6 +1707,11 @@ impl JsonFixer {
 ************************** Gated behind serde *************************
 */
 
+#[cfg(feature = "serde")]
+pub use jsonlenient::Lenient;
+#[cfg(feature = "serde")]
+pub use jsonpartial::{FieldError, PartialFix};
+
 
 #[cfg(feature = "serde")]
 impl JsonFixer {
@@ -243,6 +1765,9 @@ impl JsonFixer {
 
     /// Parses a JSON string into a Rust type without fixing.
     ///
+    /// The `&str` fields of `T` borrow the strings of `input` that have no escapes, so that
+    /// they are not copied.
+    ///
     /// This method is only available when the `serde` feature is enabled.
     ///
     /// # Type Parameters
@@ -273,7 +1798,8 @@ impl JsonFixer {
     /// let json = r#"{"name":"John","age":30}"#;
     /// let person: Person = JsonFixer::from_str(json).unwrap();
     /// ```
-    pub fn from_str<T: for<'de> serde::Deserialize<'de>>(input: &str) -> Result<T, JsonFixerError> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str<'a, T: serde::Deserialize<'a>>(input: &'a str) -> Result<T, JsonFixerError> {
         serde_json::from_str::<T>(input).map_err(|e| JsonFixerError::SerdeError(e.to_string()))
     }
 
@@ -318,4 +1844,113 @@ impl JsonFixer {
         let fixed = parser.parse()?;
         serde_json::from_str(&fixed).map_err(|e| JsonFixerError::SerdeError(e.to_string()))
     }
+
+    /// Fixes malformed JSON into `buffer` and then parses it into a Rust type borrowing from
+    /// it.
+    ///
+    /// The `&str` fields of `T` borrow the strings of the fixed JSON that have no escapes, so
+    /// that they are not copied once more. The fixed JSON replaces the content of `buffer`,
+    /// which must outlive the deserialized value.
+    ///
+    /// This method is only available when the `serde` feature is enabled.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `T` - The type to deserialize into, must implement `serde::Deserialize`
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The potentially malformed JSON string to fix and parse
+    /// * `config` - Optional configuration for JSON fixing
+    /// * `buffer` - Where the fixed JSON is written, borrowed by the deserialized value
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(T)` - The deserialized value
+    /// * `Err(JsonFixerError)` - If fixing or parsing fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Person<'a> {
+    ///     name: &'a str,
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let input = "{name: 'John', age: 30";
+    /// let person: Person = JsonFixer::from_fixed_borrowed(input, None, &mut buffer).unwrap();
+    ///
+    /// assert_eq!(person.name, "John");
+    /// ```
+    pub fn from_fixed_borrowed<'a, T: serde::Deserialize<'a>>(
+        input: &str,
+        config: Option<JsonFixerConfig>,
+        buffer: &'a mut String,
+    ) -> Result<T, JsonFixerError> {
+        let mut parser = JsonParser::new(input, config.unwrap_or_default());
+        *buffer = parser.parse()?;
+        serde_json::from_str(buffer).map_err(|e| JsonFixerError::SerdeError(e.to_string()))
+    }
+
+    /// Fixes malformed JSON and parses it into a Rust type, tolerating the fields that fail.
+    ///
+    /// Unlike [`JsonFixer::from_fixed`], a member of the document that does not match its
+    /// field, eg. `"age": "unknown"` for a `u32`, does not fail the whole document: the field
+    /// keeps its value of `T::default()` and the path of the member is reported. Nested
+    /// objects are handled member by member. This suits the ingestion of logs or events whose
+    /// producers are not always consistent.
+    ///
+    /// This method is only available when the `serde` feature is enabled.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `T` - The type to deserialize into, its default value is serialized to build the
+    ///   document the members are added to
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The potentially malformed JSON string to fix and parse
+    /// * `config` - Optional configuration for JSON fixing
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PartialFix<T>)` - The deserialized value and the members that failed
+    /// * `Err(JsonFixerError)` - If fixing fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use json_fixer::JsonFixer;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Deserialize, Serialize, Default)]
+    /// struct Entry {
+    ///     level: String,
+    ///     code: Option<u32>,
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// let input = "{level: 'warn', code: 'E12', tags: ['db']}";
+    /// let entry = JsonFixer::from_fixed_partial::<Entry>(input, None).unwrap();
+    ///
+    /// assert_eq!(entry.value.level, "warn");
+    /// assert_eq!(entry.value.code, None);
+    /// assert_eq!(entry.value.tags, vec!["db"]);
+    /// assert_eq!(entry.errors.len(), 1);
+    /// assert_eq!(entry.errors[0].path, "code");
+    /// ```
+    pub fn from_fixed_partial<T>(
+        input: &str,
+        config: Option<JsonFixerConfig>,
+    ) -> Result<PartialFix<T>, JsonFixerError>
+    where
+        T: for<'de> serde::Deserialize<'de> + serde::Serialize + Default,
+    {
+        jsonpartial::from_fixed_partial(input, &config.unwrap_or_default())
+    }
 }
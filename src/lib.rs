@@ -1,10 +1,82 @@
-
 pub mod jsonfixer;
 pub mod tests;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 pub use jsonfixer::{
     JsonFixer, 
     JsonFixerConfig, 
+    JsonFixerEngine,
+    CompareOptions,
+    ConcatOptions,
+    Compliance,
+    DuplicateKeys,
+    HomogenizeArrays,
+    LengthPolicy,
+    LineLengthPolicy,
+    NormalizeNulls,
+    OutputStyle,
+    QuoteKeys,
+    RepeatedColons,
+    BareWords,
+    NonFiniteNumbers,
+    Undefined,
+    Repairs,
+    SizePolicy,
+    Whitespaces,
+    OptionError,
+    FixReport,
+    Confidence,
+    Repair,
+    RepairKind,
+    Dropped,
+    Code,
     JsonFixerError,
+    JsonFormatError,
+    LimitError,
+    ShapeError,
+    ShapeViolation,
+    Shape,
+    JsonValue,
+    JsonNumber,
+    Formatter,
+    JsonFormatter,
+    IndexEntry,
+    Span,
+    ValueKind,
+    Token,
+    TokenAction,
+    TokenHook,
+    TokenBuffer,
+    Transform,
+    TransformAction,
+    Chain,
+    StructureScan,
+    ChunkFormat,
+    StreamOptions,
+    BatchReport,
+    FailureSample,
+    Capabilities,
+    capabilities,
+    is_valid_json,
+    escape_str,
+    fix_string_literal,
+    unescape_str,
+    EscapePolicy,
     jsonformatter::IndentStyle
-};
\ No newline at end of file
+};
+
+#[cfg(feature = "terminal")]
+pub use jsonfixer::AnsiTheme;
+
+#[cfg(feature = "arena")]
+pub use jsonfixer::{Arena, ArenaValue};
+
+#[cfg(feature = "schema")]
+pub use jsonfixer::{SchemaError, SchemaReport};
+
+#[cfg(feature = "encoding")]
+pub use jsonfixer::{Encoding, EncodingReport};
+
+#[cfg(feature = "serde")]
+pub use jsonfixer::{FieldError, Lenient, PartialFix};
\ No newline at end of file
@@ -0,0 +1,294 @@
+//! Helpers to write parser tests from token streams.
+//!
+//! Tests of the parser usually go through a string that is tokenized first, which makes it
+//! hard to check how the parser handles a given token sequence. [`TokenBuilder`] builds the
+//! token sequence directly, and its type checks at compile time that every object and array
+//! is closed by the matching token:
+//!
+//! ```
+//! use json_fixer::testing::{assert_fixes_to, TokenBuilder};
+//!
+//! let tokens = TokenBuilder::new()
+//!     .begin_object()
+//!     .key("a")
+//!     .begin_array()
+//!     .number("1")
+//!     .number("2") // Missing comma
+//!     .end_array()
+//!     .end_object()
+//!     .build();
+//! assert_fixes_to(tokens, r#"{"a":[1,2]}"#);
+//! ```
+//!
+//! This module is only available when the `testing` feature is enabled.
+
+pub mod corpus;
+
+use std::marker::PhantomData;
+
+use crate::jsonfixer::{
+    json_tokenizer::{JsonTokenizer, Position},
+    JsonParser,
+};
+use crate::{FixReport, JsonFixerConfig, JsonFixerError, Token};
+
+/// State of a [`TokenBuilder`] outside any object or array.
+pub struct Root;
+
+/// State of a [`TokenBuilder`] inside an object opened in the `P` state.
+pub struct InObject<P>(PhantomData<P>);
+
+/// State of a [`TokenBuilder`] inside an array opened in the `P` state.
+pub struct InArray<P>(PhantomData<P>);
+
+/// Builds a token sequence, placing each token after the previous one like the tokenizer
+/// would.
+///
+/// Every method adds a single token, except [`key`](TokenBuilder::key) adding a string and
+/// a colon, so that commas, colons and whitespaces can be left out to test the repairs. The
+/// `S` state tracks the opened objects and arrays: `end_object` and `end_array` are only
+/// available when they close the innermost one, and `build` once they are all closed.
+pub struct TokenBuilder<S = Root> {
+    tokens: Vec<Token>,
+    line: usize,
+    column: usize,
+    state: PhantomData<S>,
+}
+
+impl TokenBuilder<Root> {
+    /// Creates an empty token sequence.
+    pub fn new() -> Self {
+        Self {
+            tokens: Vec::new(),
+            line: 1,
+            column: 1,
+            state: PhantomData,
+        }
+    }
+
+    /// Returns the token sequence.
+    pub fn build(self) -> Vec<Token> {
+        self.tokens
+    }
+}
+
+impl Default for TokenBuilder<Root> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> TokenBuilder<S> {
+    /// Adds `token` at the current position, whatever its own position.
+    pub fn token(mut self, token: Token) -> Self {
+        let token = at(token, self.position());
+        match &token {
+            Token::Whitespace(s, _) => {
+                for ch in s.chars() {
+                    if ch == '\n' {
+                        self.line += 1;
+                        self.column = 1;
+                    } else {
+                        self.column += 1;
+                    }
+                }
+            }
+            token => self.column += width(token),
+        }
+        self.tokens.push(token);
+        self
+    }
+
+    /// Adds a string token, `s` being its decoded content.
+    pub fn string(self, s: &str) -> Self {
+        let token = Token::String(s.to_string(), self.position());
+        self.token(token)
+    }
+
+    /// Adds an unquoted word, eg. an unquoted key.
+    pub fn unquoted(self, word: &str) -> Self {
+        let token = Token::UnquotedString(word.to_string(), self.position());
+        self.token(token)
+    }
+
+    /// Adds a number token, kept as written like the tokenizer does.
+    pub fn number(self, n: &str) -> Self {
+        let token = Token::Number(n.to_string(), self.position());
+        self.token(token)
+    }
+
+    pub fn boolean(self, b: bool) -> Self {
+        let token = Token::Boolean(b, self.position());
+        self.token(token)
+    }
+
+    pub fn null(self) -> Self {
+        let token = Token::Null(self.position());
+        self.token(token)
+    }
+
+    pub fn comma(self) -> Self {
+        let token = Token::Comma(self.position());
+        self.token(token)
+    }
+
+    pub fn colon(self) -> Self {
+        let token = Token::Colon(self.position());
+        self.token(token)
+    }
+
+    pub fn whitespace(self, s: &str) -> Self {
+        let token = Token::Whitespace(s.to_string(), self.position());
+        self.token(token)
+    }
+
+    /// Adds a `{` token, to be closed by `end_object`.
+    pub fn begin_object(self) -> TokenBuilder<InObject<S>> {
+        let token = Token::LeftBrace(self.position());
+        self.token(token).into_state()
+    }
+
+    /// Adds a `[` token, to be closed by `end_array`.
+    pub fn begin_array(self) -> TokenBuilder<InArray<S>> {
+        let token = Token::LeftBracket(self.position());
+        self.token(token).into_state()
+    }
+
+    /// Returns the token sequence, leaving the opened objects and arrays unclosed.
+    pub fn build_unclosed(self) -> Vec<Token> {
+        self.tokens
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn into_state<T>(self) -> TokenBuilder<T> {
+        TokenBuilder {
+            tokens: self.tokens,
+            line: self.line,
+            column: self.column,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<P> TokenBuilder<InObject<P>> {
+    /// Adds a key, ie. a string and a colon.
+    pub fn key(self, key: &str) -> Self {
+        self.string(key).colon()
+    }
+
+    /// Adds the `}` token closing the object.
+    pub fn end_object(self) -> TokenBuilder<P> {
+        let token = Token::RightBrace(self.position());
+        self.token(token).into_state()
+    }
+}
+
+impl<P> TokenBuilder<InArray<P>> {
+    /// Adds the `]` token closing the array.
+    pub fn end_array(self) -> TokenBuilder<P> {
+        let token = Token::RightBracket(self.position());
+        self.token(token).into_state()
+    }
+}
+
+/// Tokenizes `input` with the default configuration.
+///
+/// # Panics
+///
+/// Panics if the input cannot be tokenized.
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokenizer = JsonTokenizer::new(input);
+    let mut tokens = Vec::new();
+    loop {
+        match tokenizer.next_token() {
+            Ok(Some(token)) => tokens.push(token),
+            Ok(None) => return tokens,
+            Err(err) => panic!("cannot tokenize {:?}: {}", input, err),
+        }
+    }
+}
+
+/// Parses `tokens` with `config` and returns the fixed JSON string along with the repairs
+/// that were applied, see [`JsonParser::from_tokens`].
+pub fn parse_tokens(
+    tokens: Vec<Token>,
+    config: &JsonFixerConfig,
+) -> Result<FixReport, JsonFixerError> {
+    JsonParser::from_tokens(tokens, config.clone()).parse_with_report()
+}
+
+/// Asserts that `tokens` are fixed to `expected` with the default configuration.
+///
+/// # Panics
+///
+/// Panics with the tokens if they cannot be fixed or are fixed to another output.
+pub fn assert_fixes_to(tokens: Vec<Token>, expected: &str) {
+    match parse_tokens(tokens.clone(), &JsonFixerConfig::default()) {
+        Ok(report) => assert_eq!(report.output, expected, "tokens: {:?}", tokens),
+        Err(err) => panic!("cannot fix tokens {:?}: {}", tokens, err),
+    }
+}
+
+/// Asserts that `tokens` cannot be fixed with the default configuration, and returns the
+/// error.
+///
+/// # Panics
+///
+/// Panics with the output if the tokens are fixed.
+pub fn assert_fix_fails(tokens: Vec<Token>) -> JsonFixerError {
+    match parse_tokens(tokens.clone(), &JsonFixerConfig::default()) {
+        Ok(report) => panic!("tokens {:?} were fixed to {}", tokens, report.output),
+        Err(err) => err,
+    }
+}
+
+/// Asserts that both token sequences are the same, ignoring the positions of the tokens.
+///
+/// # Panics
+///
+/// Panics with both sequences if they differ.
+pub fn assert_same_tokens(actual: &[Token], expected: &[Token]) {
+    let origin = || Position { line: 1, column: 1 };
+    let actual: Vec<Token> = actual.iter().map(|t| at(t.clone(), origin())).collect();
+    let expected: Vec<Token> = expected.iter().map(|t| at(t.clone(), origin())).collect();
+    assert_eq!(actual, expected);
+}
+
+/// Returns `token` moved to `pos`.
+fn at(token: Token, pos: Position) -> Token {
+    match token {
+        Token::LeftBrace(_) => Token::LeftBrace(pos),
+        Token::RightBrace(_) => Token::RightBrace(pos),
+        Token::LeftBracket(_) => Token::LeftBracket(pos),
+        Token::RightBracket(_) => Token::RightBracket(pos),
+        Token::Colon(_) => Token::Colon(pos),
+        Token::Comma(_) => Token::Comma(pos),
+        Token::LeftParen(_) => Token::LeftParen(pos),
+        Token::RightParen(_) => Token::RightParen(pos),
+        Token::String(s, _) => Token::String(s, pos),
+        Token::Number(n, _) => Token::Number(n, pos),
+        Token::Boolean(b, _) => Token::Boolean(b, pos),
+        Token::Null(_) => Token::Null(pos),
+        Token::Whitespace(s, _) => Token::Whitespace(s, pos),
+        Token::UnquotedString(s, _) => Token::UnquotedString(s, pos),
+    }
+}
+
+/// Number of characters of `token` once written, strings written without escapes.
+fn width(token: &Token) -> usize {
+    match token {
+        Token::String(s, _) => s.chars().count() + 2,
+        Token::Number(s, _) | Token::Whitespace(s, _) | Token::UnquotedString(s, _) => {
+            s.chars().count()
+        }
+        Token::Boolean(true, _) | Token::Null(_) => 4,
+        Token::Boolean(false, _) => 5,
+        _ => 1,
+    }
+}
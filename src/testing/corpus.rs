@@ -0,0 +1,282 @@
+//! Corpus of real world malformed JSON, and the harness checking how its cases are fixed.
+//!
+//! The built-in corpus gathers the usual sources of malformed JSON by category: `llm` for
+//! truncated or sloppy LLM output, `python` for Python reprs, `javascript` for JavaScript
+//! object literals and `logs` for records found in log lines. Each case records the output
+//! it is fixed to, or that it cannot be fixed, so that running the corpus catches any change
+//! in how the fixer repairs them:
+//!
+//! ```
+//! use json_fixer::testing::corpus::{assert_corpus, Corpus};
+//!
+//! let mut corpus = Corpus::builtin();
+//! let cases = "=== api/missing_comma\n{id: 1 name: 'a'}\n---\n{\"id\":1,\"name\":\"a\"}";
+//! corpus.extend(Corpus::parse(cases).unwrap());
+//! assert_corpus(&corpus);
+//! ```
+//!
+//! Cases are written one after the other, each one being a header, the input and the expected
+//! output separated by a `---` line, or `!error` when the input cannot be fixed:
+//!
+//! ```text
+//! # Comment lines are ignored between cases
+//! === python/set python_compat
+//! {'tags': {'a', 'b'}}
+//! ---
+//! {"tags":["a","b"]}
+//! ```
+//!
+//! The header names the case `category/name` and lists the options the case is fixed with,
+//! see [`OPTIONS`]. The input and output keep their line breaks, but not the last one.
+
+use std::{fmt, fs, io, path::Path};
+
+use crate::{JsonFixer, JsonFixerConfig, RepeatedColons};
+
+/// The corpus cases shipped with the crate, by category.
+const BUILTIN: [&str; 4] = [
+    include_str!("corpus/llm.txt"),
+    include_str!("corpus/python.txt"),
+    include_str!("corpus/javascript.txt"),
+    include_str!("corpus/logs.txt"),
+];
+
+/// The options a case header can enable, each one setting the configuration field of the
+/// same name, or the repair of the same name for `quote_values`. `repeated_colons` nests
+/// the values after the repeated colons.
+pub const OPTIONS: [&str; 6] = [
+    "python_compat",
+    "strip_comments",
+    "unescape_quotes",
+    "salvage",
+    "quote_values",
+    "repeated_colons",
+];
+
+/// What fixing the input of a case results in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    /// The input is fixed to this output
+    Output(String),
+    /// The input cannot be fixed
+    Error,
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Output(output) => write!(f, "{}", output),
+            Self::Error => write!(f, "!error"),
+        }
+    }
+}
+
+/// A malformed input of the corpus and the result of fixing it.
+#[derive(Debug, Clone)]
+pub struct CorpusCase {
+    /// Category of the case, eg. `llm`
+    pub category: String,
+    pub name: String,
+    pub input: String,
+    pub expected: Expected,
+    /// Configuration the input is fixed with
+    pub config: JsonFixerConfig,
+}
+
+impl CorpusCase {
+    /// Fixes the input and returns the result in the form of an expectation.
+    pub fn fix(&self) -> Expected {
+        match JsonFixer::fix_with_config(&self.input, &self.config) {
+            Ok(output) => Expected::Output(output),
+            Err(_) => Expected::Error,
+        }
+    }
+}
+
+/// A case fixed differently than expected, see [`Corpus::run`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// The `category/name` of the case
+    pub case: String,
+    pub expected: Expected,
+    pub actual: Expected,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: expected {} but got {}",
+            self.case, self.expected, self.actual
+        )
+    }
+}
+
+/// A syntax error of a corpus file, at a 1-based line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CorpusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for CorpusError {}
+
+/// A list of corpus cases, see the [module documentation](self) for the file format.
+#[derive(Debug, Clone, Default)]
+pub struct Corpus {
+    pub cases: Vec<CorpusCase>,
+}
+
+impl Corpus {
+    /// Returns the corpus shipped with the crate.
+    pub fn builtin() -> Self {
+        let mut corpus = Self::default();
+        for text in BUILTIN {
+            corpus.extend(Self::parse(text).expect("valid built-in corpus"));
+        }
+        corpus
+    }
+
+    /// Parses the cases written in `text`.
+    pub fn parse(text: &str) -> Result<Self, CorpusError> {
+        let mut cases = Vec::new();
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .peekable();
+
+        while let Some((number, line)) = lines.next() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| CorpusError {
+                line: number,
+                message,
+            };
+            let Some(header) = line.strip_prefix("=== ") else {
+                return Err(error(format!("expected a '=== ' header, found {:?}", line)));
+            };
+
+            let mut words = header.split_whitespace();
+            let id = words.next().unwrap_or_default();
+            let Some((category, name)) = id.split_once('/') else {
+                return Err(error(format!(
+                    "expected a 'category/name' case, found {:?}",
+                    id
+                )));
+            };
+            let mut config = JsonFixerConfig::default();
+            for option in words {
+                enable(&mut config, option)
+                    .ok_or_else(|| error(format!("unknown option {:?}", option)))?;
+            }
+
+            let mut input = Vec::new();
+            loop {
+                match lines.next() {
+                    Some((_, "---")) => break,
+                    Some((_, line)) => input.push(line),
+                    None => return Err(error(format!("case {:?} has no '---' line", id))),
+                }
+            }
+            let mut output = Vec::new();
+            while let Some((_, line)) = lines.next_if(|(_, line)| !line.starts_with("=== ")) {
+                output.push(line);
+            }
+            // Blank and comment lines before the next case are not part of the output
+            while output
+                .last()
+                .is_some_and(|line| line.trim().is_empty() || line.starts_with('#'))
+            {
+                output.pop();
+            }
+
+            let expected = match output.as_slice() {
+                ["!error"] => Expected::Error,
+                [] => return Err(error(format!("case {:?} has no expected output", id))),
+                lines => Expected::Output(lines.join("\n")),
+            };
+            cases.push(CorpusCase {
+                category: category.to_string(),
+                name: name.to_string(),
+                input: input.join("\n"),
+                expected,
+                config,
+            });
+        }
+
+        Ok(Self { cases })
+    }
+
+    /// Reads and parses a corpus file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Appends the cases of `other`.
+    pub fn extend(&mut self, other: Corpus) {
+        self.cases.extend(other.cases);
+    }
+
+    /// Returns the cases of a category.
+    pub fn category<'a>(&'a self, category: &'a str) -> impl Iterator<Item = &'a CorpusCase> {
+        self.cases
+            .iter()
+            .filter(move |case| case.category == category)
+    }
+
+    /// Fixes every case and returns the ones fixed differently than expected.
+    pub fn run(&self) -> Vec<Mismatch> {
+        self.cases
+            .iter()
+            .filter_map(|case| {
+                let actual = case.fix();
+                (actual != case.expected).then(|| Mismatch {
+                    case: format!("{}/{}", case.category, case.name),
+                    expected: case.expected.clone(),
+                    actual,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Asserts that every case of `corpus` is fixed as expected.
+///
+/// # Panics
+///
+/// Panics listing every case fixed differently than expected.
+pub fn assert_corpus(corpus: &Corpus) {
+    let mismatches = corpus.run();
+    if !mismatches.is_empty() {
+        let lines: Vec<String> = mismatches.iter().map(|m| m.to_string()).collect();
+        panic!(
+            "{} of {} corpus cases changed:\n{}",
+            mismatches.len(),
+            corpus.cases.len(),
+            lines.join("\n")
+        );
+    }
+}
+
+/// Enables the option of a case header, returns `None` if it is unknown.
+fn enable(config: &mut JsonFixerConfig, option: &str) -> Option<()> {
+    match option {
+        "python_compat" => config.python_compat = true,
+        "strip_comments" => config.strip_comments = true,
+        "unescape_quotes" => config.unescape_quotes = true,
+        "salvage" => config.salvage = true,
+        "quote_values" => config.repairs.quote_values = true,
+        "repeated_colons" => config.repeated_colons = RepeatedColons::Nest,
+        _ => return None,
+    }
+    Some(())
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use crate::JsonFixer;
+
+    #[test]
+    fn test_annotate_valid_input() {
+        let input = "{\"a\": [1, 2],\n \"b\": null}";
+        assert_eq!(JsonFixer::annotate(input).unwrap(), input);
+    }
+
+    #[test]
+    fn test_annotate() {
+        assert_eq!(
+            JsonFixer::annotate("{a: 'b'}").unwrap(),
+            "{a/*<-- quoted key 'a'*/: 'b'/*<-- converted single quotes to double quotes*/}"
+        );
+        assert_eq!(
+            JsonFixer::annotate("{\"a\": 1\n \"b\": [.5, 2,]").unwrap(),
+            "{\"a\": 1/*<-- inserted missing ','*/\n \"b\": [.5/*<-- added '0' before leading '.'*/, \
+             2,/*<-- removed trailing ','*/]/*<-- inserted missing '}'*/"
+        );
+        assert_eq!(
+            JsonFixer::annotate(r#"{"a": [1}"#).unwrap(),
+            r#"{"a": [1/*<-- inserted missing ']' before '}'*/}"#
+        );
+        // Repeated colons are only nested on demand
+        assert!(JsonFixer::annotate("{\"a\": \"b\": 1}\n").is_err());
+    }
+
+    #[test]
+    fn test_annotate_closed_brackets() {
+        let cases = [
+            (
+                "[1, [2",
+                "[1, [2/*<-- inserted missing ']'*//*<-- inserted missing ']'*/",
+            ),
+            (
+                "{\"a\": {\"b\": 1\n",
+                "{\"a\": {\"b\": 1/*<-- inserted missing '}'*//*<-- inserted missing '}'*/\n",
+            ),
+            ("{\"a\": [1]", "{\"a\": [1]/*<-- inserted missing '}'*/"),
+            (
+                "[{\"a\": 1]",
+                "[{\"a\": 1/*<-- inserted missing '}' before ']'*/]",
+            ),
+            (
+                "[{\"a\": 1], 2]",
+                "[{\"a\": 1/*<-- replaced ']' by '}'*/], 2]",
+            ),
+            (
+                "{\"a\": [1}, \"b\": 2}",
+                "{\"a\": [1/*<-- replaced '}' by ']'*/}, \"b\": 2}",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(JsonFixer::annotate(input).unwrap(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_annotate_escapes_comments() {
+        assert_eq!(
+            JsonFixer::annotate("[\"a\" '*/']").unwrap(),
+            "[\"a\"/*<-- inserted missing ','*/ '*/'/*<-- converted single quotes to double quotes*/]"
+        );
+    }
+
+    #[test]
+    fn test_annotate_error() {
+        assert!(JsonFixer::annotate("{\"a\": ~}").is_err());
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(all(test, feature = "arena"))]
+mod tests {
+    use crate::{Arena, ArenaValue, JsonFixer, JsonFixerError};
+
+    #[test]
+    fn test_valid_input() {
+        let arena = Arena::new();
+        let value = JsonFixer::fix_to_value_in(
+            &arena,
+            r#" {"a": [1, -2.5e3, true, false, null], "b": {}, "c": [], "d": "x\"yé"} "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.get("a"),
+            Some(&ArenaValue::Array(&[
+                ArenaValue::Number("1"),
+                ArenaValue::Number("-2.5e3"),
+                ArenaValue::Boolean(true),
+                ArenaValue::Boolean(false),
+                ArenaValue::Null,
+            ]))
+        );
+        assert_eq!(value.get("b"), Some(&ArenaValue::Object(&[])));
+        assert_eq!(value.get("c"), Some(&ArenaValue::Array(&[])));
+        assert_eq!(value.get("d"), Some(&ArenaValue::String("x\"yé")));
+        assert_eq!(value.get("e"), None);
+    }
+
+    #[test]
+    fn test_fixed_input() {
+        let arena = Arena::new();
+        let value = JsonFixer::fix_to_value_in(&arena, "{a: 1 b: ['x' 'y'], a: 2,").unwrap();
+
+        assert_eq!(
+            value,
+            ArenaValue::Object(&[
+                ("a", ArenaValue::Number("1")),
+                (
+                    "b",
+                    ArenaValue::Array(&[ArenaValue::String("x"), ArenaValue::String("y")])
+                ),
+                ("a", ArenaValue::Number("2")),
+            ])
+        );
+        // The last duplicate wins
+        assert_eq!(value.get("a"), Some(&ArenaValue::Number("2")));
+    }
+
+    #[test]
+    fn test_arena_reuse() {
+        let mut arena = Arena::new();
+        for i in 0..3 {
+            let input = format!("[{i}, {{n: {i}}}]");
+            let value = JsonFixer::fix_to_value_in(&arena, &input).unwrap();
+            let ArenaValue::Array(items) = value else {
+                panic!("Expected an array but found {:?}", value);
+            };
+            assert_eq!(items[1].get("n"), Some(&ArenaValue::Number(&i.to_string())));
+            arena.reset();
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let arena = Arena::new();
+        assert!(matches!(
+            JsonFixer::fix_to_value_in(&arena, ""),
+            Err(JsonFixerError::Syntax(_))
+        ));
+        assert!(JsonFixer::fix_to_value_in(&arena, "[1] 2").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::{BatchReport, JsonFixer, JsonFixerConfig, RepairKind};
+
+    #[test]
+    fn test_fix_batch() {
+        let inputs = vec!["{a: 1, b: 2}", "[1, 2]", "[1 2,]", "{a 1}", "{"];
+        let (results, report) = JsonFixer::fix_batch(inputs, JsonFixerConfig::default());
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().output, r#"{"a":1,"b":2}"#);
+        assert!(results[3].is_err());
+        assert_eq!(report.documents, 5);
+        assert_eq!(report.clean, 1);
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.failure_rate(), 0.2);
+        assert_eq!(report.count(RepairKind::QuotedKey), 2);
+        assert_eq!(report.count(RepairKind::InsertedComma), 1);
+        assert_eq!(report.count(RepairKind::ClosedBracket), 1);
+        assert_eq!(report.count(RepairKind::FixedNumber), 0);
+        assert_eq!(report.failure_samples.len(), 1);
+        assert_eq!(report.failure_samples[0].index, 3);
+    }
+
+    #[test]
+    fn test_size_percentiles() {
+        let mut report = BatchReport::new();
+        assert_eq!(report.size_percentile(50.0), None);
+
+        let inputs: Vec<String> = (1..=100).map(|n| format!("[{}]", "1,".repeat(n))).collect();
+        for input in &inputs {
+            report.record(
+                input,
+                &JsonFixer::fix_with_report(input, JsonFixerConfig::default()),
+            );
+        }
+        assert_eq!(report.size_percentile(0.0), Some(4));
+        assert_eq!(report.size_percentile(50.0), Some(102));
+        assert_eq!(report.size_percentile(90.0), Some(182));
+        assert_eq!(report.size_percentile(100.0), Some(202));
+        assert_eq!(report.count(RepairKind::RemovedComma), 100);
+    }
+
+    #[test]
+    fn test_merge() {
+        let config = JsonFixerConfig::default();
+        let failing: Vec<&str> = vec!["{a 1}"; 8];
+        let (_, mut report) = JsonFixer::fix_batch(failing, &config);
+        let (_, other) =
+            JsonFixer::fix_batch(vec!["[1]", "{a: 1}", "{a 1}", "{a 1}", "{a 1}"], &config);
+        report.merge(other);
+
+        assert_eq!(report.documents, 13);
+        assert_eq!(report.failures, 11);
+        assert_eq!(report.clean, 1);
+        assert_eq!(report.count(RepairKind::QuotedKey), 1);
+        let indexes: Vec<_> = report.failure_samples.iter().map(|s| s.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2, 3, 4, 5, 6, 7, 10, 11]);
+        assert!(report
+            .to_string()
+            .starts_with("13 documents, 1 clean, 11 failed\n  quoted key: 1"));
+    }
+
+    #[test]
+    fn test_fix_documents() {
+        let input = "\u{FEFF}{\"a\": 1}\n\u{FEFF}{b: 2}\x0C[1 2]\x0C\x0C\n{c 1}\u{FEFF}";
+        let (results, report) = JsonFixer::fix_documents(input, JsonFixerConfig::default());
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap().output, r#"{"a":1}"#);
+        assert_eq!(results[1].as_ref().unwrap().output, r#"{"b":2}"#);
+        assert_eq!(results[2].as_ref().unwrap().output, "[1,2]");
+        assert!(results[3].is_err());
+        assert_eq!((report.documents, report.clean, report.failures), (4, 1, 1));
+        assert_eq!(report.failure_samples[0].index, 3);
+
+        let separators: Vec<_> = report
+            .separators
+            .iter()
+            .map(|p| (p.line, p.column))
+            .collect();
+        assert_eq!(separators, vec![(2, 1), (2, 8), (2, 14), (2, 15), (3, 6)]);
+    }
+
+    #[test]
+    fn test_fix_documents_separators_in_strings() {
+        let input = "{a: '\x0C\u{FEFF}', b: \"\\\"\u{FEFF}\"}";
+        let (results, report) = JsonFixer::fix_documents(input, JsonFixerConfig::default());
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_ok());
+        assert!(report.separators.is_empty());
+
+        // An unclosed quote does not hide the separators of the next lines
+        let (results, report) =
+            JsonFixer::fix_documents("[\"a]\n\u{FEFF}[1]", JsonFixerConfig::default());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].as_ref().unwrap().output, "[1]");
+        assert_eq!(report.separators.len(), 1);
+    }
+}
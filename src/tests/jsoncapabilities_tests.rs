@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{capabilities, OutputStyle, RepairKind};
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = capabilities();
+        assert_eq!(capabilities.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(capabilities.repairs.len(), RepairKind::ALL.len());
+        assert_eq!(
+            capabilities.output_styles,
+            vec![OutputStyle::Json, OutputStyle::Json5, OutputStyle::Jsonc]
+        );
+        assert!(!capabilities.has_feature("unknown"));
+        assert_eq!(capabilities.has_feature("serde"), cfg!(feature = "serde"));
+        assert_eq!(capabilities.has_feature("schema"), cfg!(feature = "schema"));
+    }
+
+    #[test]
+    fn test_all_repair_kinds() {
+        let names: HashSet<String> = RepairKind::ALL.iter().map(|k| k.to_string()).collect();
+        assert_eq!(names.len(), RepairKind::ALL.len());
+    }
+}
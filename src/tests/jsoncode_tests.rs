@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::jsonfixer::jsoncode::CATALOGUE;
+    use crate::{Code, JsonFixer, JsonFixerConfig, JsonFixerError, LimitError, RepairKind};
+
+    #[test]
+    fn test_error_codes() {
+        let code = |input: &str| JsonFixer::fix(input).unwrap_err().code().to_string();
+        assert_eq!(code(r#"{"a": "b}"#), "JF1001");
+        assert_eq!(code(r#"{"a":"#), "JF1003");
+        assert_eq!(code(r#"{"a" 1}"#), "JF1006");
+        assert_eq!(code("[1.2.3]"), "JF1005");
+
+        let config = JsonFixerConfig {
+            max_repairs: Some(0),
+            ..Default::default()
+        };
+        let err = JsonFixer::fix_with_config("{a: 1}", &config).unwrap_err();
+        assert!(matches!(
+            err,
+            JsonFixerError::Limit(LimitError::TooManyRepairs { .. })
+        ));
+        assert_eq!(err.code().to_string(), "JF3005");
+    }
+
+    #[test]
+    fn test_repair_codes() {
+        let report =
+            JsonFixer::fix_with_report("{a: 1 'b': 2,}", JsonFixerConfig::default()).unwrap();
+        let codes: Vec<String> = report
+            .repairs
+            .iter()
+            .map(|r| r.code().to_string())
+            .collect();
+        assert_eq!(codes, ["JF2001", "JF2004", "JF2002", "JF2005"]);
+
+        assert_eq!(RepairKind::QuotedKey.code(), Code::parse("JF2001").unwrap());
+        assert_eq!(RepairKind::CleanedKey.code().number(), 2021);
+    }
+
+    #[test]
+    fn test_catalogue() {
+        let codes: HashSet<Code> = CATALOGUE.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes.len(), CATALOGUE.len());
+        assert!(CATALOGUE.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        for kind in RepairKind::ALL {
+            assert!(codes.contains(&kind.code()), "{:?}", kind);
+        }
+
+        for (code, description) in CATALOGUE {
+            assert_eq!(Code::parse(&code.to_string()), Some(code));
+            assert_eq!(code.description(), description);
+        }
+        assert_eq!(
+            Code::parse("JF1001").unwrap().description(),
+            "unmatched quote"
+        );
+        assert_eq!(Code::parse("JF9999"), None);
+        assert_eq!(Code::parse("JF01001"), None);
+        assert_eq!(Code::parse("1001"), None);
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use crate::{CompareOptions, JsonFixer, JsonFixerError};
+
+    fn equal(a: &str, b: &str) -> bool {
+        JsonFixer::semantically_equal(a, b, &CompareOptions::default()).unwrap()
+    }
+
+    fn equal_numbers(a: &str, b: &str) -> bool {
+        let options = CompareOptions {
+            ignore_number_format: true,
+        };
+        JsonFixer::semantically_equal(a, b, &options).unwrap()
+    }
+
+    #[test]
+    fn test_ignores_whitespace_and_key_order() {
+        assert!(equal(
+            "{\n  \"a\": 1,\n  \"b\": {\"c\": [1, 2], \"d\": null}\n}",
+            r#"{"b":{"d":null,"c":[1,2]},"a":1}"#
+        ));
+        assert!(equal(
+            "{a: 'x', b: [true false]}",
+            r#"{"b":[true,false],"a":"x"}"#
+        ));
+        assert!(equal("  42 ", "42"));
+    }
+
+    #[test]
+    fn test_ignores_whitespace_in_containers() {
+        for (a, b) in [
+            ("{ }", "{}"),
+            ("[ ]", "[]"),
+            ("[ 1]", "[1]"),
+            ("[1 , 2 ]", "[1,2]"),
+            ("{ \"a\" : [ 1 ] }", r#"{"a":[1]}"#),
+            ("{\n}", "{}"),
+        ] {
+            assert!(equal(a, b), "{:?} and {:?}", a, b);
+        }
+        assert!(!equal("[ ]", "[1]"));
+        assert!(!equal("{ }", "[ ]"));
+    }
+
+    #[test]
+    fn test_detects_differences() {
+        assert!(!equal("[1, 2]", "[2, 1]"));
+        assert!(!equal("[1, 2]", "[1, 2, 3]"));
+        assert!(!equal(r#"{"a": 1}"#, r#"{"a": 1, "b": 2}"#));
+        assert!(!equal(r#"{"a": 1}"#, r#"{"b": 1}"#));
+        assert!(!equal(r#"{"a": "1"}"#, r#"{"a": 1}"#));
+        assert!(!equal("[]", "{}"));
+        assert!(!equal(
+            r#"{"a": {"b": [1, {"c": true}]}}"#,
+            r#"{"a": {"b": [1, {"c": false}]}}"#
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_keys_keep_their_order() {
+        let options = CompareOptions::default();
+        let a = r#"{"a": 1, "b": 0, "a": 2}"#;
+        assert!(JsonFixer::semantically_equal(a, r#"{"b": 0, "a": 1, "a": 2}"#, &options).unwrap());
+        assert!(
+            !JsonFixer::semantically_equal(a, r#"{"b": 0, "a": 2, "a": 1}"#, &options).unwrap()
+        );
+    }
+
+    #[cfg(feature = "bignum")]
+    #[test]
+    fn test_exact_number_formatting() {
+        assert!(equal_numbers("[1e2, 0.5]", "[100.0, 5e-1]"));
+        assert!(equal_numbers("[1e400]", "[10e399]"));
+        assert!(!equal_numbers("[9007199254740993]", "[9007199254740992]"));
+        assert!(!equal_numbers("[0.1]", "[0.10000000000000001]"));
+    }
+
+    #[test]
+    fn test_number_formatting() {
+        assert!(equal("[1.5]", "[1.5]"));
+        assert!(!equal("[1e2]", "[100]"));
+        assert!(!equal("[0.50]", "[0.5]"));
+
+        assert!(equal_numbers("[1e2]", "[100]"));
+        assert!(equal_numbers("[0.50]", "[0.5]"));
+        assert!(equal_numbers("[1.0E+3]", "[1000]"));
+        assert!(!equal_numbers("[1.5]", "[1.25]"));
+        assert!(!equal_numbers(
+            "[12345678901234567890123]",
+            "[12345678901234567890124]"
+        ));
+    }
+
+    #[test]
+    fn test_unfixable_input() {
+        let result =
+            JsonFixer::semantically_equal(r#"{"a": ok}"#, "{}", &CompareOptions::default());
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+    }
+}
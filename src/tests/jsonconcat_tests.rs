@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod tests {
+    use crate::{ConcatOptions, JsonFixer, JsonFixerConfig, JsonFixerError};
+
+    fn concat(
+        documents: &[&str],
+        config: &JsonFixerConfig,
+        options: &ConcatOptions,
+    ) -> Result<(usize, String), JsonFixerError> {
+        let mut output = Vec::new();
+        let readers = documents.iter().map(|document| document.as_bytes());
+        let count = JsonFixer::concat(readers, &mut output, config, options)?;
+        Ok((count, String::from_utf8(output).unwrap()))
+    }
+
+    #[test]
+    fn test_concat() {
+        let documents = ["{a: 1}", "[1 2,]", "'x'", "{a: 1}"];
+        let config = JsonFixerConfig::default();
+        assert_eq!(
+            concat(&documents, &config, &ConcatOptions::default()).unwrap(),
+            (4, r#"[{"a":1},[1,2],"x",{"a":1}]"#.to_string())
+        );
+
+        let options = ConcatOptions {
+            deduplicate: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            concat(&documents, &config, &options).unwrap(),
+            (3, r#"[{"a":1},[1,2],"x"]"#.to_string())
+        );
+        assert_eq!(
+            concat(&[], &config, &options).unwrap(),
+            (0, "[]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_concat_merge_objects() {
+        let documents = ["{a: 1, b: [1 2]}", " {'c': {d: null}}", "{a: 2}"];
+        let config = JsonFixerConfig::default();
+        let mut options = ConcatOptions {
+            merge_objects: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            concat(&documents, &config, &options).unwrap(),
+            (4, r#"{"a":1,"b":[1,2],"c":{"d":null},"a":2}"#.to_string())
+        );
+
+        options.deduplicate = true;
+        assert_eq!(
+            concat(&documents, &config, &options).unwrap(),
+            (3, r#"{"a":1,"b":[1,2],"c":{"d":null}}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_concat_beautify() {
+        let config = JsonFixerConfig {
+            beautify: true,
+            indent_size: 2,
+            ..Default::default()
+        };
+        let (_, output) = concat(&["{a: 1}", "[1 2]"], &config, &Default::default()).unwrap();
+        assert_eq!(
+            output,
+            "[\n  {\n    \"a\": 1\n  },\n  [\n    1,\n    2\n  ]\n]"
+        );
+        assert_eq!(
+            output,
+            JsonFixer::fix_with_config("[{a: 1}, [1 2]]", config.clone()).unwrap()
+        );
+
+        let options = ConcatOptions {
+            merge_objects: true,
+            ..Default::default()
+        };
+        let (_, output) = concat(&["{a: [1]}", "{b: 2}"], &config, &options).unwrap();
+        assert_eq!(output, "{\n  \"a\": [\n    1\n  ],\n  \"b\": 2\n}");
+        assert_eq!(
+            concat(&[], &config, &options).unwrap(),
+            (0, "{}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_concat_errors() {
+        let config = JsonFixerConfig {
+            python_compat: true,
+            ..Default::default()
+        };
+        let options = ConcatOptions {
+            merge_objects: true,
+            ..Default::default()
+        };
+        for document in ["[1]", "{1, 2}"] {
+            let result = concat(&["{a: 1}", document], &config, &options);
+            assert!(
+                matches!(result, Err(JsonFixerError::Syntax(_))),
+                "{}",
+                document
+            );
+        }
+
+        let result = concat(&["[1", "{a"], &config, &ConcatOptions::default());
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+        let mut output = Vec::new();
+        let readers = [&[0xff_u8][..]];
+        let result = JsonFixer::concat(readers, &mut output, &config, &ConcatOptions::default());
+        assert!(matches!(result, Err(JsonFixerError::IoError(_))));
+    }
+}
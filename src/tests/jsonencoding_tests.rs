@@ -0,0 +1,54 @@
+#[cfg(all(test, feature = "encoding"))]
+mod tests {
+    use crate::{Encoding, JsonFixer, JsonFixerConfig};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Encoding::detect(b"{\"a\": 1}"), Encoding::Utf8);
+        assert_eq!(
+            Encoding::detect("{\"a\": \"é\"}".as_bytes()),
+            Encoding::Utf8
+        );
+        assert_eq!(
+            Encoding::detect(b"{\"a\": \"\xe9\"}"),
+            Encoding::Windows1252
+        );
+        assert_eq!(
+            Encoding::detect(b"{\"a\": \"\x80\"}"),
+            Encoding::Windows1252
+        );
+        // 0x81 is unassigned in Windows-1252
+        assert_eq!(Encoding::detect(b"{\"a\": \"\x81\x93\"}"), Encoding::Latin1);
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(Encoding::Utf8.decode(b"\xef\xbb\xbf[1]"), "[1]");
+        assert_eq!(Encoding::Windows1252.decode(b"\x80 \x96 \xff"), "€ – ÿ");
+        assert_eq!(Encoding::Windows1252.decode(b"\x81"), "\u{81}");
+        assert_eq!(Encoding::Latin1.decode(b"\x80 \xe9"), "\u{80} é");
+        assert_eq!(Encoding::Latin1.to_string(), "ISO-8859-1");
+    }
+
+    #[test]
+    fn test_fix_bytes() {
+        let config = JsonFixerConfig::default();
+
+        let report = JsonFixer::fix_bytes(
+            b"{ville: 'Besan\xe7on', prix: ['10 \x80' '12 \x80']}",
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"ville":"Besançon","prix":["10 €","12 €"]}"#
+        );
+        assert_eq!(report.encoding, Encoding::Windows1252);
+
+        let report = JsonFixer::fix_bytes("\u{feff}{name: 'Jérôme'}".as_bytes(), &config).unwrap();
+        assert_eq!(report.output, r#"{"name":"Jérôme"}"#);
+        assert_eq!(report.encoding, Encoding::Utf8);
+
+        assert!(JsonFixer::fix_bytes(b"{a \xe9}", &config).is_err());
+    }
+}
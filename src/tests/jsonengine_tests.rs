@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use crate::{
+        jsonfixer::jsonparser::JsonValue, FixReport, JsonFixer, JsonFixerConfig, JsonFixerEngine,
+        JsonFixerError,
+    };
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_engine_matches_fixer() {
+        let inputs = [
+            "{a: 1, b: [1 2 3], c: {d: 'x'}}",
+            "[1, 2,]",
+            r#"{"nested": [[1], [2, [3]], {"e": []}]}"#,
+            "  42  ",
+            "[{a: 1} {b: 2}",
+        ];
+
+        let config = JsonFixerConfig {
+            beautify: true,
+            ..Default::default()
+        };
+        let mut engine = JsonFixerEngine::new(config.clone());
+        // Twice so that the second round uses the recycled buffers
+        for _ in 0..2 {
+            for input in inputs {
+                let expected = JsonFixer::fix_with_config(input, config.clone()).unwrap();
+                assert_eq!(engine.fix(input).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_engine_errors() {
+        let mut engine = JsonFixerEngine::new(JsonFixerConfig::default());
+        assert!(engine.fix(r#"{"status": ok}"#).is_err());
+        // The engine can still be used after an error
+        assert_eq!(engine.fix("{a: [1]}").unwrap(), r#"{"a":[1]}"#);
+        assert!(engine.fix("").is_err());
+        assert_eq!(engine.fix("[]").unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_engine_reuses_output() {
+        let mut engine = JsonFixerEngine::new(JsonFixerConfig::default());
+        let first = engine.fix("[1, 2, 3, 4, 5, 6, 7, 8]").unwrap().as_ptr();
+        let second = engine.fix("[8, 7, 6]").unwrap().as_ptr();
+        assert_eq!(first, second);
+        assert!(!engine.config().beautify);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        assert_send_sync::<JsonFixerConfig>();
+        assert_send_sync::<JsonFixerEngine>();
+        assert_send_sync::<JsonValue>();
+        assert_send_sync::<FixReport>();
+        assert_send_sync::<JsonFixerError>();
+    }
+
+    #[test]
+    fn test_shared_config() {
+        let config = Arc::new(JsonFixerConfig {
+            sort_keys: true,
+            ..Default::default()
+        });
+
+        // The static methods accept owned, borrowed and shared configurations
+        let expected = r#"{"a":1,"b":2}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config("{b: 2, a: 1}", &config).unwrap(),
+            expected
+        );
+        assert_eq!(
+            JsonFixer::fix_with_config("{b: 2, a: 1}", config.as_ref()).unwrap(),
+            expected
+        );
+        assert_eq!(
+            JsonFixer::fix_with_config("{b: 2, a: 1}", JsonFixerConfig::clone(&config)).unwrap(),
+            expected
+        );
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let config = Arc::clone(&config);
+                thread::spawn(move || {
+                    let input = format!("{{z: {i}, y: [{i} {i}]}}");
+                    let mut engine = JsonFixerEngine::new(Arc::clone(&config));
+                    let fixed = engine.fix(&input).unwrap().to_string();
+                    assert_eq!(fixed, JsonFixer::fix_with_config(&input, config).unwrap());
+                    fixed
+                })
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(
+                handle.join().unwrap(),
+                format!(r#"{{"y":[{i},{i}],"z":{i}}}"#)
+            );
+        }
+    }
+}
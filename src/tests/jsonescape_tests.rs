@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use crate::jsonfixer::jsonfixer_error::SyntaxError;
+    use crate::{
+        escape_str, fix_string_literal, unescape_str, EscapePolicy, JsonFixer, JsonFixerError,
+    };
+
+    #[test]
+    fn test_escape_str() {
+        let s = "say \"hi\"\\\n\t\u{7}<é 😀>\u{2028}";
+        assert_eq!(
+            escape_str(s, EscapePolicy::Json),
+            "say \\\"hi\\\"\\\\\\n\\t\\u0007<é 😀>\u{2028}"
+        );
+        assert_eq!(
+            escape_str(s, EscapePolicy::Ascii),
+            "say \\\"hi\\\"\\\\\\n\\t\\u0007<\\u00e9 \\ud83d\\ude00>\\u2028"
+        );
+        assert_eq!(
+            escape_str("</script>&'é", EscapePolicy::Html),
+            "\\u003c/script\\u003e\\u0026\\u0027é"
+        );
+        assert_eq!(escape_str("", EscapePolicy::Json), "");
+    }
+
+    #[test]
+    fn test_escape_like_output() {
+        let s = "a\"b\\c\u{8}\u{c}\r\u{1f}";
+        let output = JsonFixer::fix(&format!("[\"{}\"]", escape_str(s, EscapePolicy::Json)));
+        assert_eq!(
+            output.unwrap(),
+            format!("[\"{}\"]", escape_str(s, EscapePolicy::Json))
+        );
+    }
+
+    #[test]
+    fn test_unescape_str() {
+        let cases = vec![
+            ("say \\\"hi\\\"\\n", "say \"hi\"\n"),
+            ("\\/\\\\\\b\\f\\r\\t", "/\\\u{8}\u{c}\r\t"),
+            ("\\u00e9\\u00E9 \\ud83d\\ude00", "éé 😀"),
+            // Unknown escapes are replaced by the escaped character
+            ("\\q\\'", "q'"),
+            // Invalid escapes are kept
+            ("\\u12\\uzzzz", "\\u12\\uzzzz"),
+            ("\\ud83d!", "\\ud83d!"),
+            ("\\ude00\\ud83d", "\\ude00\\ud83d"),
+            ("end\\", "end\\"),
+            ("no escapes", "no escapes"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(unescape_str(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let s = "line\nbreak \"quoted\" \\ é 😀 </p> \u{0}";
+        for policy in [EscapePolicy::Json, EscapePolicy::Ascii, EscapePolicy::Html] {
+            assert_eq!(unescape_str(&escape_str(s, policy)), s, "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn test_fix_string_literal() {
+        assert_eq!(fix_string_literal(r#""abc""#).unwrap(), r#""abc""#);
+        assert_eq!(fix_string_literal(r"'it\'s'").unwrap(), r#""it's""#);
+        assert_eq!(
+            fix_string_literal("  'say \"hi\"'\n").unwrap(),
+            r#""say \"hi\"""#
+        );
+        // Raw control characters and lenient escapes
+        assert_eq!(
+            fix_string_literal("\"a\tb\u{1}\\q\\u00e9\"").unwrap(),
+            r#""a\tb\u0001qé""#
+        );
+    }
+
+    #[test]
+    fn test_fix_string_literal_errors() {
+        for input in ["", "  ", "abc", "1", "\"a\" \"b\"", "[\"a\"]"] {
+            assert!(fix_string_literal(input).is_err(), "{:?}", input);
+        }
+        assert!(matches!(
+            fix_string_literal("\"abc"),
+            Err(JsonFixerError::Syntax(SyntaxError::UnmatchedQuotes(_)))
+        ));
+    }
+}
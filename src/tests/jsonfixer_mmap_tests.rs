@@ -0,0 +1,64 @@
+#[cfg(all(test, feature = "memmap"))]
+mod tests {
+    use std::io::Write;
+
+    use crate::{JsonFixer, JsonFixerConfig, JsonFixerError};
+
+    fn fix_file(content: &[u8]) -> Result<String, JsonFixerError> {
+        fix_file_with_config(content, &JsonFixerConfig::default())
+    }
+
+    fn fix_file_with_config(
+        content: &[u8],
+        config: &JsonFixerConfig,
+    ) -> Result<String, JsonFixerError> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(content).unwrap();
+
+        let mut output = Vec::new();
+        JsonFixer::fix_mmap(file.path(), config, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_fix_mmap() {
+        let output = fix_file(b"{ name: 'J\xc3\xa9r\xc3\xb4me', tags: [1 2,] }").unwrap();
+        assert_eq!(output, r#"{"name":"Jérôme","tags":[1,2]}"#);
+
+        // Written whole when the root is sorted
+        let config = JsonFixerConfig {
+            sort_keys: true,
+            beautify: true,
+            ..Default::default()
+        };
+        let input = "{b: [1, 2], a: {d: 1, c: 2}}";
+        let output = fix_file_with_config(input.as_bytes(), &config).unwrap();
+        assert_eq!(output, JsonFixer::fix_with_config(input, &config).unwrap());
+    }
+
+    #[test]
+    fn test_fix_mmap_errors() {
+        assert!(matches!(
+            fix_file(b"[1, 2]\xff"),
+            Err(JsonFixerError::IoError(_))
+        ));
+        assert!(matches!(fix_file(b"{a 1}"), Err(JsonFixerError::Syntax(_))));
+        assert!(matches!(fix_file(b""), Err(JsonFixerError::Syntax(_))));
+
+        let mut output = Vec::new();
+        let result = JsonFixer::fix_mmap(
+            "/nonexistent/file.json",
+            JsonFixerConfig::default(),
+            &mut output,
+        );
+        assert!(matches!(result, Err(JsonFixerError::IoError(_))));
+
+        // The elements before the error are written
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"[1, 2, x]").unwrap();
+        let mut output = Vec::new();
+        let result = JsonFixer::fix_mmap(file.path(), JsonFixerConfig::default(), &mut output);
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+        assert_eq!(output, b"[1,2");
+    }
+}
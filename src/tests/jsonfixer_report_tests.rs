@@ -0,0 +1,271 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BareWords, Confidence, DuplicateKeys, JsonFixer, JsonFixerConfig, RepairKind,
+        RepeatedColons, SizePolicy, Undefined,
+    };
+
+    fn repair_kinds(input: &str) -> Vec<RepairKind> {
+        let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+        report.repairs.iter().map(|r| r.kind).collect()
+    }
+
+    #[test]
+    fn test_clean_input() {
+        let input = r#"{"name": "John", "tags": ["a", "b"], "nested": {"x": [1, {}]}}"#;
+        let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.output, JsonFixer::fix(input).unwrap());
+    }
+
+    #[test]
+    fn test_commas() {
+        assert_eq!(
+            repair_kinds(r#"{"a": 1 "b": 2,}"#),
+            vec![RepairKind::InsertedComma, RepairKind::RemovedComma]
+        );
+        assert_eq!(
+            repair_kinds("[,1,,2 3,]"),
+            vec![
+                RepairKind::RemovedComma,
+                RepairKind::RemovedComma,
+                RepairKind::InsertedComma,
+                RepairKind::RemovedComma,
+            ]
+        );
+        assert_eq!(
+            repair_kinds(r#"{,"a": {"b": 1},, "c": [1]}"#),
+            vec![RepairKind::RemovedComma, RepairKind::RemovedComma]
+        );
+    }
+
+    #[test]
+    fn test_keys_quotes_and_numbers() {
+        assert_eq!(
+            repair_kinds("{key: 'value', \"n\": +5, \"m\": .5}"),
+            vec![
+                RepairKind::QuotedKey,
+                RepairKind::NormalizedQuotes,
+                RepairKind::FixedNumber,
+                RepairKind::FixedNumber,
+            ]
+        );
+        assert_eq!(repair_kinds("[1.]"), vec![RepairKind::FixedNumber]);
+    }
+
+    #[test]
+    fn test_closed_brackets() {
+        let report =
+            JsonFixer::fix_with_report(r#"{"a": [1, 2"#, JsonFixerConfig::default()).unwrap();
+        assert_eq!(report.output, r#"{"a":[1,2]}"#);
+        assert_eq!(report.count(RepairKind::ClosedBracket), 2);
+        assert!(report.repairs.iter().all(|r| r.position.is_some()));
+    }
+
+    #[test]
+    fn test_repair_positions() {
+        let input = "{\n  a: 1\n  'b': 2}";
+        let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+        let positions: Vec<(RepairKind, usize, usize)> = report
+            .repairs
+            .iter()
+            .map(|r| {
+                let position = r.position.as_ref().unwrap();
+                (r.kind, position.line, position.column)
+            })
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                (RepairKind::QuotedKey, 2, 3),
+                (RepairKind::InsertedComma, 3, 3),
+                (RepairKind::NormalizedQuotes, 3, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let report = JsonFixer::fix_with_report("{a: 1}", JsonFixerConfig::default()).unwrap();
+        assert_eq!(
+            report.repairs[0].to_string(),
+            "quoted key: Quoted key 'a' at line 1, column 2 (path 'a')"
+        );
+    }
+
+    #[test]
+    fn test_confidence() {
+        let config = JsonFixerConfig {
+            repeated_colons: RepeatedColons::Nest,
+            ..Default::default()
+        };
+        let confidences = |input: &str| {
+            let report = JsonFixer::fix_with_report(input, &config).unwrap();
+            let confidences: Vec<Confidence> =
+                report.repairs.iter().map(|r| r.confidence).collect();
+            (confidences, report.confidence())
+        };
+
+        assert_eq!(confidences("[1, 2]"), (vec![], Confidence::Certain));
+        assert_eq!(
+            confidences("{a: 'b', c: [1, 2"),
+            (vec![Confidence::Certain; 5], Confidence::Certain)
+        );
+        assert_eq!(
+            confidences("[1 2,]"),
+            (
+                vec![Confidence::Likely, Confidence::Certain],
+                Confidence::Likely
+            )
+        );
+        // Mismatched brackets
+        assert_eq!(
+            confidences(r#"{"a": [1}, "b": 2}"#),
+            (vec![Confidence::Speculative], Confidence::Speculative)
+        );
+        assert_eq!(
+            confidences(r#"{"a": "b": 1}"#),
+            (vec![Confidence::Speculative], Confidence::Speculative)
+        );
+
+        assert!(Confidence::Speculative < Confidence::Likely);
+        assert_eq!(RepairKind::QuotedValue.confidence(), Confidence::Likely);
+        assert_eq!(Confidence::Speculative.to_string(), "speculative");
+    }
+
+    #[test]
+    fn test_dropped_commas() {
+        let input = "[1,,2,]";
+        let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+        let dropped: Vec<_> = report
+            .repairs
+            .iter()
+            .map(|r| r.dropped.clone().unwrap())
+            .collect();
+        let starts: Vec<_> = dropped
+            .iter()
+            .map(|d| d.span.as_ref().unwrap().start)
+            .collect();
+        assert_eq!(starts, vec![3, 5]);
+        for dropped in dropped {
+            let span = dropped.span.unwrap();
+            assert_eq!(dropped.text, ",");
+            assert_eq!(&input[span.start..span.end], ",");
+        }
+    }
+
+    #[test]
+    fn test_dropped_comments() {
+        let input = "[1, // one\n2 /* two */]";
+        let config = JsonFixerConfig {
+            strip_comments: true,
+            ..Default::default()
+        };
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        let texts: Vec<_> = report
+            .repairs
+            .iter()
+            .map(|r| r.dropped.as_ref().unwrap().text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["// one", "/* two */"]);
+        for repair in &report.repairs {
+            let dropped = repair.dropped.as_ref().unwrap();
+            let span = dropped.span.as_ref().unwrap();
+            assert_eq!(&input[span.start..span.end], dropped.text);
+            assert_eq!(repair.code().to_string(), "JF2026");
+        }
+    }
+
+    #[test]
+    fn test_dropped_salvaged_section() {
+        let input = "{\"a\": 1,\n \"b\": @@ x y,\n \"c\": 2}";
+        // The words are not dropped as strays
+        let config = JsonFixerConfig {
+            salvage: true,
+            bare_words: BareWords::Error,
+            ..Default::default()
+        };
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"b":null,"c":2}"#);
+        let dropped = report.repairs[0].dropped.as_ref().unwrap();
+        assert_eq!(dropped.text, "@@ x y");
+        let span = dropped.span.as_ref().unwrap();
+        assert_eq!(&input[span.start..span.end], "@@ x y");
+        assert_eq!((span.position.line, span.position.column), (2, 7));
+    }
+
+    #[test]
+    fn test_dropped_from_document() {
+        let config = JsonFixerConfig {
+            duplicate_keys: DuplicateKeys::KeepLast,
+            denied_keys: vec!["secret".to_string()],
+            max_array_elements: Some(2),
+            size_policy: SizePolicy::Truncate,
+            ..Default::default()
+        };
+        let input = r#"{"a": 1, "a": 2, "secret": "pw", "l": [1, 2, 3, {"b": null}]}"#;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":2,"l":[1,2]}"#);
+        let dropped: Vec<_> = report
+            .repairs
+            .iter()
+            .map(|r| (r.kind, r.dropped.clone().unwrap()))
+            .collect();
+        assert_eq!(dropped.len(), 3);
+        let texts: Vec<_> = dropped.iter().map(|(k, d)| (*k, d.text.as_str())).collect();
+        assert_eq!(
+            texts,
+            vec![
+                (RepairKind::PrunedKey, r#""secret":"pw""#),
+                (RepairKind::RemovedDuplicate, r#""a": 1"#),
+                (RepairKind::TruncatedContainer, r#"3,{"b":null}"#),
+            ]
+        );
+        // The duplicates are found in the input
+        let spans: Vec<_> = dropped.iter().map(|(_, d)| d.span.clone()).collect();
+        assert!(spans[0].is_none() && spans[2].is_none());
+        let span = spans[1].as_ref().unwrap();
+        assert_eq!(&input[span.start..span.end], r#""a": 1"#);
+        assert_eq!((span.position.line, span.position.column), (1, 2));
+    }
+
+    #[test]
+    fn test_dropped_members() {
+        let config = JsonFixerConfig {
+            duplicate_keys: DuplicateKeys::KeepFirst,
+            undefined: Undefined::Drop,
+            ..Default::default()
+        };
+        let input = "{\"a\": [1, {\"b\": 2}],\n \"a\": {\"c\": [3]}, 'd' : undefined}";
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":[1,{"b":2}]}"#);
+        let members = [
+            (RepairKind::StrayWord, "'d' : undefined"),
+            (RepairKind::RemovedDuplicate, r#""a": {"c": [3]}"#),
+        ];
+        for (kind, text) in members {
+            let repair = report.repairs.iter().find(|r| r.kind == kind).unwrap();
+            let dropped = repair.dropped.as_ref().unwrap();
+            assert_eq!(dropped.text, text);
+            let span = dropped.span.as_ref().unwrap();
+            assert_eq!(&input[span.start..span.end], text);
+        }
+
+        // Written as fixed JSON when the input is not read as is
+        let config = JsonFixerConfig {
+            unescape_quotes: true,
+            ..config
+        };
+        let input = r#"{\"a\": 1, \"a\": 2, \"d\": undefined}"#;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1}"#);
+        let dropped: Vec<_> = report
+            .repairs
+            .iter()
+            .filter_map(|r| r.dropped.as_ref())
+            .collect();
+        let texts: Vec<_> = dropped.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, vec![",", r#""d":undefined"#, r#""a":2"#]);
+        assert!(dropped.iter().all(|d| d.span.is_none()));
+    }
+}
@@ -1,9 +1,42 @@
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
+    use crate::BareWords;
+    use crate::Compliance;
+    use crate::Confidence;
+    use crate::DuplicateKeys;
+    use crate::Formatter;
+    use crate::IndentStyle;
+    use crate::HomogenizeArrays;
     use crate::JsonFixer;
     use crate::JsonFixerConfig;
     use crate::JsonFixerError;
-    use crate::jsonfixer::jsonfixer_error::SyntaxError;
+    use crate::JsonFormatter;
+    use crate::LengthPolicy;
+    use crate::LimitError;
+    use crate::LineLengthPolicy;
+    use crate::NonFiniteNumbers;
+    use crate::NormalizeNulls;
+    use crate::OutputStyle;
+    use crate::QuoteKeys;
+    use crate::RepairKind;
+    use crate::Repairs;
+    use crate::RepeatedColons;
+    use crate::SizePolicy;
+    use crate::Token;
+    use crate::Undefined;
+    use crate::TokenAction;
+    use crate::Transform;
+    use crate::TransformAction;
+    use crate::Whitespaces;
+    use crate::JsonValue;
+    use crate::JsonNumber;
+    use crate::EscapePolicy;
+    use crate::escape_str;
+    use crate::unescape_str;
+    use crate::jsonfixer::json_tokenizer::Position;
+    use crate::jsonfixer::jsonfixer_error::{JsonFormatError, SyntaxError};
+    use crate::jsonfixer::jsonparser::MAX_DEPTH;
 
     /*
      ************************** Remove whitespaces *************************
@@ -39,6 +72,15 @@ mod tests {
         assert_eq!(JsonFixer::fix(input).unwrap(), input);
     }
 
+    #[test]
+    fn test_nested_object_last_member() {
+        let input = r#"{"a": {"b": {"c": 1}}, "d": {"e": []} "f": 2}"#;
+        assert_eq!(
+            JsonFixer::fix(input).unwrap(),
+            r#"{"a":{"b":{"c":1}},"d":{"e":[]},"f":2}"#
+        );
+    }
+
     #[test]
     fn test_whitespace() {
         let input = r#"
@@ -56,6 +98,593 @@ mod tests {
         assert_eq!(JsonFixer::fix(input).unwrap(), r#"{"name":"John","age":30}"#);
     }
 
+    #[test]
+    fn test_whitespaces() {
+        let input = "{\"a\":\u{a0}1,\n\"b\": [1,\u{b}\u{c}2]}";
+        let mut config = JsonFixerConfig::default();
+        config.preserve = true;
+        assert_eq!(JsonFixer::fix_with_config(input, &config).unwrap(), input);
+
+        config.whitespaces = Whitespaces::Json;
+        match JsonFixer::fix_with_config(input, &config) {
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedCharacter(ch, pos))) => {
+                assert_eq!(ch, '\u{a0}');
+                assert_eq!(pos, Position { line: 1, column: 6 });
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(
+            JsonFixer::fix_with_config(" [1,\t2]\r\n", &config).unwrap(),
+            "[1,\t2]"
+        );
+
+        // Reported by the strict validator as well
+        for input in ["[1,\u{b}2]", "[1]\u{c}"] {
+            assert!(JsonFixer::fix(input).is_ok(), "{:?}", input);
+            assert!(
+                JsonFixer::fix_with_config(input, JsonFixerConfig::strict()).is_err(),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_unquoted_keys_identifier_chars() {
+        let input = r#"{content-type: "json", user.name: "John", @id: 1, a@b.c-d: 2}"#;
+        // The '-' of `content-type` is read as a number by default
+        assert!(matches!(
+            JsonFixer::fix(input),
+            Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(n, _))) if n == "-"
+        ));
+
+        let mut config = JsonFixerConfig::default();
+        config.identifier_chars = "-.@".to_string();
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config).unwrap(),
+            r#"{"content-type":"json","user.name":"John","@id":1,"a@b.c-d":2}"#
+        );
+
+        let mut config = JsonFixerConfig::default();
+        config.identifier_chars = "$".to_string();
+        assert_eq!(
+            JsonFixer::fix_with_config("{$ref: 1, a$: 2}", config).unwrap(),
+            r#"{"$ref":1,"a$":2}"#
+        );
+    }
+
+    #[test]
+    fn test_quote_numeric_keys() {
+        let input = r#"{1: "a", 2: "b", -3.5: {10: 'c'}}"#;
+        assert!(matches!(
+            JsonFixer::fix(input),
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(_, _)))
+        ));
+
+        let mut config = JsonFixerConfig::default();
+        config.quote_numeric_keys = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"1":"a","2":"b","-3.5":{"10":"c"}}"#);
+        assert_eq!(report.count(RepairKind::QuotedKey), 4);
+
+        config.repairs.quote_keys = false;
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, config),
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(n, _))) if n == "1"
+        ));
+    }
+
+    #[test]
+    fn test_keyword_keys() {
+        let input = "{null: 1, true: false, false: null, 'nested': {true: [true]}}";
+        let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"null":1,"true":false,"false":null,"nested":{"true":[true]}}"#
+        );
+        assert_eq!(report.count(RepairKind::QuotedKey), 4);
+        assert_eq!(report.repairs[0].path.as_deref(), Some("null"));
+
+        // Keywords are still values after the colon and in arrays
+        assert_eq!(JsonFixer::fix("[null, true]").unwrap(), "[null,true]");
+        assert!(JsonFixer::fix("{true, false}").is_err());
+
+        let mut config = JsonFixerConfig::default();
+        config.repairs.quote_keys = false;
+        assert!(JsonFixer::fix_with_config("{null: 1}", config).is_err());
+    }
+
+    #[test]
+    fn test_repeated_colons_nest() {
+        let input = r#"{"a": "b": 1, "c": 'd': "e" : [1], "f": 2}"#;
+        // The colons are repeated by mistake more often than a level is missing
+        assert!(JsonFixer::fix(input).is_err());
+
+        let config = JsonFixerConfig {
+            repeated_colons: RepeatedColons::Nest,
+            ..Default::default()
+        };
+        let report = JsonFixer::fix_with_report(input, config).unwrap();
+        assert_eq!(report.output, r#"{"a":{"b":1},"c":{"d":{"e":[1]}},"f":2}"#);
+        assert_eq!(report.count(RepairKind::RepeatedColon), 3);
+        let paths: Vec<_> = report
+            .repairs
+            .iter()
+            .filter(|r| r.kind == RepairKind::RepeatedColon)
+            .map(|r| r.path.as_deref().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["a", "c", "c.d"]);
+    }
+
+    #[test]
+    fn test_repeated_colons_drop_and_error() {
+        let input = r#"{"a": "b": 1, "c": true: null: 2}"#;
+
+        let mut config = JsonFixerConfig::default();
+        config.repeated_colons = RepeatedColons::Drop;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"c":2}"#);
+        assert_eq!(report.count(RepairKind::RepeatedColon), 3);
+
+        config.repeated_colons = RepeatedColons::Error;
+        match JsonFixer::fix_with_config(input, &config) {
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(message, _))) => {
+                assert!(message.contains("Repeated ':' after the value of 'a'"))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_bare_words() {
+        let input = r#"{"a": oops 1, "b": hello world, "c": [1, stray, 2], "d": x}"#;
+
+        // The strays are dropped by the default policy, the other words are errors
+        let mut config = JsonFixerConfig::default();
+        let report = JsonFixer::fix_with_report(r#"{"a": oops 1}"#, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1}"#);
+        assert_eq!(report.count(RepairKind::StrayWord), 1);
+        match JsonFixer::fix_with_config("hello world", &config) {
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(word, pos))) => {
+                assert_eq!((word.as_str(), pos.column), ("world", 7))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        config.bare_words = BareWords::Drop;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"c":[1,2]}"#);
+        assert_eq!(report.count(RepairKind::StrayWord), 5);
+        let words: Vec<_> = report
+            .repairs
+            .iter()
+            .filter_map(|r| r.dropped.as_ref())
+            .map(|d| d.text.as_str())
+            .collect();
+        // Along with the dropped members and the commas of the dropped members and elements
+        assert_eq!(
+            words,
+            vec![
+                "oops",
+                "hello",
+                r#""b": hello world"#,
+                ",",
+                "stray",
+                ",",
+                ",",
+                "\"d\": x"
+            ]
+        );
+
+        config.bare_words = BareWords::Null;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"b":null,"c":[1,null,2],"d":null}"#);
+        assert_eq!(report.count(RepairKind::StrayWord), 5);
+
+        // Words are quoted only when they are the value
+        config.bare_words = BareWords::Quote;
+        config.repairs.quote_values = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"a": oops, "b": [x]}"#, &config).unwrap(),
+            r#"{"a":"oops","b":["x"]}"#
+        );
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":1,"b":"world","c":[1,"stray",2],"d":"x"}"#
+        );
+        assert_eq!(report.count(RepairKind::StrayWord), 2);
+        assert_eq!(
+            JsonFixer::fix_with_config("hello world", &config).unwrap(),
+            r#""world""#
+        );
+
+        config.bare_words = BareWords::Error;
+        assert!(JsonFixer::fix_with_config(r#"{"a": oops}"#, &config).is_err());
+    }
+
+    #[test]
+    fn test_scalar_roots() {
+        let cases = [
+            ("  \"ab\"  ", r#""ab""#),
+            (" 'x' ", r#""x""#),
+            ("\n.5\n", "0.5"),
+            (" -1e5 ", "-1e5"),
+            ("\ttrue ", "true"),
+            (" null", "null"),
+        ];
+        let mut configs = Vec::new();
+        for output_style in [OutputStyle::Json, OutputStyle::Json5] {
+            for (beautify, preserve) in [(false, false), (true, false), (false, true)] {
+                let mut config = JsonFixerConfig::default();
+                config.output_style = output_style.clone();
+                config.beautify = beautify;
+                config.indent_size = 2;
+                config.preserve = preserve;
+                configs.push(config);
+            }
+        }
+
+        // The whitespaces around the root are never kept
+        for config in &configs {
+            for (input, expected) in cases {
+                assert_eq!(
+                    JsonFixer::fix_with_config(input, config).unwrap(),
+                    expected,
+                    "{:?} {:?}",
+                    input,
+                    config.output_style
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_quote_bare_root() {
+        let mut config = JsonFixerConfig::default();
+        assert!(JsonFixer::fix_with_config(" hello world! ", &config).is_err());
+
+        config.quote_bare_root = true;
+        let report = JsonFixer::fix_with_report(" hello world! ", &config).unwrap();
+        assert_eq!(report.output, r#""hello world!""#);
+        assert_eq!(report.count(RepairKind::QuotedValue), 1);
+
+        // Only the input that cannot be fixed otherwise is quoted
+        config.bare_words = BareWords::Drop;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"json {"a": 1}"#, &config).unwrap(),
+            r#"{"a":1}"#
+        );
+        config.bare_words = BareWords::Error;
+        assert!(JsonFixer::fix_with_config("[hello]", &config).is_err());
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let input =
+            "{\n  // Settings\n  \"a\": 1, // count\n  \"b\": /* unused */ [2/**/]\n} /* end";
+        let mut config = JsonFixerConfig::default();
+        assert!(JsonFixer::fix_with_config(input, &config).is_err());
+
+        config.strip_comments = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"b":[2]}"#);
+        let messages: Vec<_> = report.repairs.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Stripped line comment",
+                "Stripped line comment",
+                "Stripped block comment",
+                "Stripped block comment",
+                "Stripped block comment",
+            ]
+        );
+
+        // Slashes of strings are not comments
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"url": "http://a/*b*/"}"#, &config).unwrap(),
+            r#"{"url":"http://a/*b*/"}"#
+        );
+
+        // The comments are removed along with the spaces before a line comment
+        config.preserve = true;
+        assert_eq!(
+            JsonFixer::fix_with_config("{\"a\": 1, // count\n \"b\": 2}", &config).unwrap(),
+            "{\"a\": 1,\n \"b\": 2}"
+        );
+    }
+
+    #[test]
+    fn test_keep_comments() {
+        let input =
+            "{\n  // Server\n  \"port\": 8080, // default\n  \"host\": \"local\" /* dev */\n}";
+        let mut config = JsonFixerConfig::default();
+        config.keep_comments = true;
+        config.sort_keys = true;
+        // Comments cannot be written in JSON
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"host":"local","port":8080}"#);
+        assert_eq!(report.count(RepairKind::StrippedComment), 3);
+
+        // The comments follow their member once sorted
+        config.output_style = OutputStyle::Jsonc;
+        config.beautify = true;
+        config.indent_size = 2;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            "{\n  \"host\": \"local\", /* dev */\n  // Server\n  \"port\": 8080 // default\n}"
+        );
+        assert!(report.repairs.is_empty());
+
+        config.beautify = false;
+        config.preserve = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "{\n  \"host\": \"local\", /* dev */\n\n  // Server\n  \"port\": 8080 // default\n}"
+        );
+        config.sort_keys = false;
+        assert_eq!(JsonFixer::fix_with_config(input, &config).unwrap(), input);
+
+        // No comment is written in the compact layout
+        config.preserve = false;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"port":8080,"host":"local"}"#
+        );
+
+        let input = "[\n  1, // one\n  [2, 3] /* pair */\n]";
+        config.beautify = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "[\n  1, // one\n  [\n    2,\n    3\n  ] /* pair */\n]"
+        );
+        config.beautify = false;
+        config.preserve = true;
+        assert_eq!(JsonFixer::fix_with_config(input, &config).unwrap(), input);
+    }
+
+    #[test]
+    fn test_keep_leading_comments() {
+        let input = concat!(
+            "{\n  // Ports\n  \"ports\": [ /* http */ 80,\n    // https\n    443\n",
+            "    // more\n  ],\n  \"empty\": {\n    // none\n  }\n  // End\n}"
+        );
+        let mut config = JsonFixerConfig::default();
+        config.keep_comments = true;
+        config.output_style = OutputStyle::Jsonc;
+        config.indent_size = 2;
+        config.preserve = true;
+        assert_eq!(JsonFixer::fix_with_config(input, &config).unwrap(), input);
+
+        // Each comment on its own line, those before a closing bracket included
+        config.preserve = false;
+        config.beautify = true;
+        config.sort_keys = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            concat!(
+                "{\n  \"empty\": {\n    // none\n  },\n  // Ports\n  \"ports\": [\n",
+                "    /* http */\n    80,\n    // https\n    443\n    // more\n  ]\n  // End\n}"
+            )
+        );
+
+        // Not in the compact layout
+        config.beautify = false;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"empty":{},"ports":[80,443]}"#
+        );
+    }
+
+    #[test]
+    fn test_fix_both() {
+        let inputs = [
+            r#"{name: 'John', age: 30, tags: ['a' 'b'],}"#,
+            r#"[1, {"a": [true, null]}, "x""#,
+            "  42  ",
+        ];
+        for input in inputs {
+            let (compact, pretty) = JsonFixer::fix_both(input).unwrap();
+            assert_eq!(compact, JsonFixer::fix(input).unwrap(), "input: {}", input);
+            assert_eq!(
+                pretty,
+                JsonFixer::fix_pretty(input).unwrap(),
+                "input: {}",
+                input
+            );
+        }
+        assert!(JsonFixer::fix_both("{a: }").is_err());
+    }
+
+    #[test]
+    fn test_json5_preset() {
+        let input = r#"// JSON5 sample
+{
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \
+No \\n's!",
+  hexadecimal: 0xdecaf, negative: -0x10,
+  leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+  positiveSign: +1,
+  /* block */ trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON",
+  $id: 1, ñame: 2,
+  special: [Infinity, -Infinity, NaN],
+  escapes: '\x41\v\0',
+}"#;
+        let output = JsonFixer::fix_with_config(input, JsonFixerConfig::json5()).unwrap();
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"unquoted":"and you can quote me on that","#,
+                r#""singleQuotes":"I can use \"double quotes\" here","#,
+                r#""lineBreaks":"Look, Mom! No \\n's!","hexadecimal":912559,"negative":-16,"#,
+                r#""leadingDecimalPoint":0.8675309,"andTrailing":8675309,"positiveSign":1,"#,
+                r#""trailingComma":"in objects","andIn":["arrays"],"#,
+                r#""backwardsCompatible":"with JSON","$id":1,"ñame":2,"#,
+                r#""special":[null,null,null],"escapes":"A\u000b\u0000"}"#
+            )
+        );
+        assert!(crate::is_valid_json(&output));
+        assert!(JsonFixer::fix(input).is_err());
+
+        // Signed leading decimal points
+        let input = "[+.5e-2, -.5, +.5, -.5E+1, +1.]";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, JsonFixerConfig::json5()).unwrap(),
+            "[0.5e-2,-0.5,0.5,-0.5E+1,1]"
+        );
+        for input in ["[+.]", "[-.]", "[+.e1]", "[+-.5]"] {
+            assert!(JsonFixer::fix_with_config(input, JsonFixerConfig::json5()).is_err());
+        }
+
+        let report =
+            JsonFixer::fix_with_report("[0xFFFFFFFFFFFFFFFFFFFF]", JsonFixerConfig::json5())
+                .unwrap();
+        assert_eq!(report.output, "[1208925819614629174706175]");
+        assert_eq!(
+            report.repairs[0].message,
+            "Converted hexadecimal '0xFFFFFFFFFFFFFFFFFFFF' to 1208925819614629174706175"
+        );
+
+        // Non-finite numbers are bare words by default
+        let mut config = JsonFixerConfig::default();
+        config.repairs.quote_values = true;
+        assert_eq!(
+            JsonFixer::fix_with_config("[NaN, Infinity]", &config).unwrap(),
+            r#"["NaN","Infinity"]"#
+        );
+        config.non_finite_numbers = NonFiniteNumbers::Null;
+        config.bare_words = BareWords::Drop;
+        assert_eq!(
+            JsonFixer::fix_with_config("{\"a\": NaN, \"b\": -Infinity}", &config).unwrap(),
+            r#"{"a":null,"b":null}"#
+        );
+    }
+
+    #[test]
+    fn test_non_finite_numbers() {
+        let input = "{a: NaN, b: -Infinity, c: [+Infinity, 1]}";
+        let mut config = JsonFixerConfig::default();
+        config.non_finite_numbers = NonFiniteNumbers::String;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":"NaN","b":"-Infinity","c":["Infinity",1]}"#
+        );
+        assert_eq!(report.count(RepairKind::FixedNumber), 3);
+        assert_eq!(report.repairs[1].message, "Replaced 'NaN' by a string");
+
+        config.non_finite_numbers = NonFiniteNumbers::Keep;
+        config.output_style = OutputStyle::Json5;
+        config.normalize_numbers = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":NaN,"b":-Infinity,"c":[+Infinity,1]}"#
+        );
+        assert_eq!(report.count(RepairKind::FixedNumber), 0);
+
+        // Only JSON5 accepts them
+        for output_style in [OutputStyle::Json, OutputStyle::Jsonc] {
+            config.output_style = output_style;
+            for input in [input, "[1]"] {
+                let result = JsonFixer::fix_with_config(input, &config);
+                assert!(matches!(
+                    result,
+                    Err(JsonFixerError::Incompatible {
+                        option: "non_finite_numbers",
+                        other: "output_style"
+                    })
+                ));
+            }
+        }
+
+        // Even though bare words are quoted
+        config.non_finite_numbers = NonFiniteNumbers::Error;
+        config.repairs.quote_values = true;
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, &config),
+            Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(word, _))) if word == "NaN"
+        ));
+        assert_eq!(
+            JsonFixer::fix_with_config("[NaNa]", &config).unwrap(),
+            r#"["NaNa"]"#
+        );
+    }
+
+    #[test]
+    fn test_undefined() {
+        let input = "{a: 1, b: undefined, c: [undefined, 2], d: undefinedValue}";
+        let mut config = JsonFixerConfig::default();
+        config.repairs.quote_values = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"a":1,"b":"undefined","c":["undefined",2],"d":"undefinedValue"}"#
+        );
+
+        config.undefined = Undefined::Null;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":1,"b":null,"c":[null,2],"d":"undefinedValue"}"#
+        );
+        assert_eq!(report.count(RepairKind::CoercedNull), 2);
+
+        // Like JSON.stringify, even though other bare words are quoted
+        config.undefined = Undefined::Drop;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"c":[2],"d":"undefinedValue"}"#);
+        assert_eq!(report.count(RepairKind::StrayWord), 2);
+        let dropped = report
+            .repairs
+            .iter()
+            .find_map(|r| r.dropped.as_ref())
+            .unwrap();
+        assert_eq!(dropped.text, "b: undefined");
+        assert_eq!(
+            JsonFixer::fix_with_config("[undefined]", &config).unwrap(),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn test_mismatched_brackets() {
+        let cases = vec![
+            // Missing closing bracket before the one of the enclosing container
+            (r#"[{"a": 1]"#, r#"[{"a":1}]"#),
+            (r#"{"a": [1, 2}"#, r#"{"a":[1,2]}"#),
+            (r#"[{"a": 1, ]"#, r#"[{"a":1}]"#),
+            (r#"[{"a": [1, {"b": 2]"#, r#"[{"a":[1,{"b":2}]}]"#),
+            // Closing bracket of the wrong kind
+            (r#"{"a": [1, 2}, "b": 3}"#, r#"{"a":[1,2],"b":3}"#),
+            (r#"[{"a": 1], {"b": 2}]"#, r#"[{"a":1},{"b":2}]"#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(JsonFixer::fix(input).unwrap(), expected, "input: {}", input);
+        }
+
+        let report =
+            JsonFixer::fix_with_report(r#"{"a": [1}, "b": [2}"#, JsonFixerConfig::default())
+                .unwrap();
+        assert_eq!(report.output, r#"{"a":[1],"b":[2]}"#);
+        let messages: Vec<_> = report.repairs.iter().map(|r| r.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["Replaced '}' by ']'", "Inserted missing ']' before '}'"]
+        );
+
+        // Swapped brackets are balanced, nothing tells which one is wrong
+        assert!(JsonFixer::fix(r#"{"a": [1, 2}]"#).is_err());
+
+        let mut config = JsonFixerConfig::default();
+        config.repairs.close_brackets = false;
+        assert!(JsonFixer::fix_with_config(r#"[{"a": 1]"#, config).is_err());
+    }
+
     #[test]
     fn test_single_quotes() {
         let input = r#"{'name': 'John', 'age': 30}"#;
@@ -93,7 +722,7 @@ mod tests {
     #[test]
     fn test_string_escapes() {
         let input = r#""Hello \"hello\\nnew line\" ""#;
-        let expect = r#""Hello \"hello\nnew line\" ""#;
+        let expect = r#""Hello \"hello\\nnew line\" ""#;
         let output = JsonFixer::fix(input).unwrap();
         println!("input : {:?}", input);
         println!("expect : {:?}", expect);
@@ -101,10 +730,76 @@ mod tests {
         assert_eq!(output, expect);
     }
 
+    #[test]
+    fn test_surrogate_escapes() {
+        let cases = vec![
+            (r#"["\ud83d\ude00"]"#, "[\"\u{1F600}\"]"),
+            (r#"['\ud83d\ude00!']"#, "[\"\u{1F600}!\"]"),
+            (r#"["a\ud800b"]"#, r#"["a\ud800b"]"#),
+            (r#"["\ude00\ud83d"]"#, r#"["\ude00\ud83d"]"#),
+            (r#"{"\uDBFF": 1}"#, r#"{"\udbff":1}"#),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(JsonFixer::fix(input).unwrap(), expected, "{}", input);
+        }
+        for input in [r#"["a\ud800b"]"#, r#"["\ude00\ud83d"]"#] {
+            let strict = JsonFixer::fix_with_config(input, JsonFixerConfig::strict());
+            assert_eq!(strict.unwrap(), input);
+        }
+
+        // Lone surrogates rejected by the compliance are replaced as a repair
+        let mut config = JsonFixerConfig::default();
+        config.compliance = Compliance::i_json();
+        let report = JsonFixer::fix_with_report(r#"["a\ud800b"]"#, &config).unwrap();
+        assert_eq!(report.output, "[\"a\u{FFFD}b\"]");
+        assert_eq!(report.count(RepairKind::ReplacedSurrogate), 1);
+        config.repairs.fix_escapes = false;
+        assert!(JsonFixer::fix_with_config(r#"["a\ud800b"]"#, config).is_err());
+
+        // Round trip through the escapes of a surrogate pair
+        let escaped = escape_str("\u{1F600}", EscapePolicy::Ascii);
+        assert_eq!(escaped, r#"\ud83d\ude00"#);
+        let fixed = JsonFixer::fix(&format!("\"{}\"", escaped)).unwrap();
+        assert_eq!(fixed, "\"\u{1F600}\"");
+        assert_eq!(unescape_str(&escaped), "\u{1F600}");
+    }
+
+    #[test]
+    fn test_invalid_escapes() {
+        let cases = vec![
+            (r#"["\q"]"#, r#"["q"]"#, RepairKind::FixedEscape),
+            (r#"["\x41\v"]"#, r#"["A\u000b"]"#, RepairKind::FixedEscape),
+            (r#"["\u12G4"]"#, r#"["u12G4"]"#, RepairKind::FixedEscape),
+        ];
+        for (input, expected, kind) in cases {
+            let report = JsonFixer::fix_with_report(input, JsonFixerConfig::default()).unwrap();
+            assert_eq!(report.output, expected, "{}", input);
+            assert!(report.count(kind) > 0, "{}", input);
+
+            let mut config = JsonFixerConfig::default();
+            config.repairs = Repairs::none();
+            assert!(
+                matches!(
+                    JsonFixer::fix_with_config(input, config),
+                    Err(JsonFixerError::Syntax(_))
+                ),
+                "{} should not be fixed",
+                input
+            );
+        }
+
+        let report = JsonFixer::fix_with_report(r#"["\q"]"#, JsonFixerConfig::default()).unwrap();
+        assert_eq!(
+            report.repairs[0].position.as_ref().map(|p| p.column),
+            Some(3)
+        );
+    }
+
     #[test]
     fn test_numbers() {
         let cases = vec![
             (r#"{"num1": .123}"#, r#"{"num1":0.123}"#),
+            (r#"{"num1": -.5}"#, r#"{"num1":-0.5}"#),
             (r#"{"num2": 123.}"#, r#"{"num2":123}"#),
             (r#"{"num": 42}"#, r#"{"num":42}"#),
             (r#"{"num": -42}"#, r#"{"num":-42}"#),
@@ -122,6 +817,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_normalize_numbers() {
+        let mut config = JsonFixerConfig::default();
+        config.normalize_numbers = true;
+
+        let cases = vec![
+            ("0.1", "0.1"),
+            ("0.10000000000000001", "0.1"),
+            ("0.30000000000000004", "0.30000000000000004"),
+            ("1.50", "1.5"),
+            ("-0.0", "-0"),
+            ("1e5", "100000"),
+            ("1.5E+2", "150"),
+            ("1.23e-4", "0.000123"),
+            ("1E-7", "1e-7"),
+            ("123456789e20", "1.23456789e28"),
+            (".5", "0.5"),
+            ("123.", "123"),
+            // Integers and numbers out of range are kept
+            ("12345678901234567890123", "12345678901234567890123"),
+            ("1e400", "1e400"),
+        ];
+        for (input, expected) in cases {
+            let output = JsonFixer::fix_with_config(&format!("[{}]", input), &config).unwrap();
+            assert_eq!(output, format!("[{}]", expected), "input: {}", input);
+
+            // Fixing again changes nothing and the value is the same
+            assert_eq!(
+                JsonFixer::fix_with_config(&output, &config).unwrap(),
+                output
+            );
+            let original: f64 = input.trim_end_matches('.').parse().unwrap();
+            let normalized: f64 = expected.parse().unwrap();
+            assert!(original == normalized || original.is_infinite());
+        }
+    }
+
+    #[test]
+    fn test_float_precision() {
+        let mut config = JsonFixerConfig::default();
+        config.float_precision = Some(2);
+
+        // Only applied to normalized numbers
+        let input = "{t: 21.456789012345678, h: [0.5, 1e-9, 100, -3.999, 1.50e2]}";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"t":21.456789012345678,"h":[0.5,1e-9,100,-3.999,1.50e2]}"#
+        );
+        config.normalize_numbers = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"t":21.46,"h":[0.5,0,100,-4,150]}"#
+        );
+
+        // Never applied when the formatting is preserved, the numbers being only normalized
+        config.preserve = true;
+        let input = "[21.456789012345678, 1.50]";
+        let expected = "[21.456789012345677, 1.5]";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            expected
+        );
+        config.preserve = false;
+        config.preserve_normalize = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_expand_exponents() {
+        let mut config = JsonFixerConfig::default();
+        config.expand_exponents = true;
+        let input = "[1e-7, 1.5e21, 1e5, 1e25]";
+
+        // Only applied to normalized numbers
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "[1e-7,1.5e21,1e5,1e25]"
+        );
+        config.normalize_numbers = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "[0.0000001,1500000000000000000000,100000,1e25]"
+        );
+        config.float_precision = Some(8);
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "[0.0000001,1500000000000000000000,100000,1e25]"
+        );
+    }
+
     #[test]
     fn test_boolean_and_null() {
         let input = r#"{"active": true, "verified": false, "data": null}"#;
@@ -155,6 +943,9 @@ mod tests {
             (r#"{"num3": 1.2.3}"#, r#"{"num3":1.2.3}"#),
             (r#"{"num4": --123}"#, r#"{"num4":--123}"#),
             (r#"{"num5": 1e}"#, r#"{"num5":1e}"#),
+            (r#"{"num7": 1-2}"#, r#"{"num7":1-2}"#),
+            (r#"{"num8": 1e5.5}"#, r#"{"num8":1e5.5}"#),
+            (r#"{"num9": -.}"#, r#"{"num9":-.}"#),
         ];
 
         for input in cases {
@@ -168,6 +959,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_number_grammar() {
+        // Numbers are not parsed, huge ones are kept as written
+        let huge = "9".repeat(400);
+        for input in [huge.as_str(), "1e99999", "-0.0e-0"] {
+            assert_eq!(JsonFixer::fix(input).unwrap(), input);
+        }
+
+        // Leading zeros kept as written, removed on demand, and rejected in strict mode
+        let config = JsonFixerConfig {
+            strip_leading_zeros: true,
+            ..Default::default()
+        };
+        let cases = [
+            ("007", "7"),
+            ("-01", "-1"),
+            ("00.5", "0.5"),
+            ("-00e2", "-0e2"),
+            ("0", "0"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(JsonFixer::fix(input).unwrap(), input);
+            let report = JsonFixer::fix_with_report(input, &config).unwrap();
+            assert_eq!(report.output, expected);
+            assert_eq!(report.repairs.len(), usize::from(input != expected));
+            if input != expected {
+                assert_eq!(report.repairs[0].message, "Removed leading zeros");
+                assert!(JsonFixer::fix_with_config(input, JsonFixerConfig::strict()).is_err());
+            }
+        }
+
+        // Reported at the start of the number as written
+        match JsonFixer::fix("[1,\n  +1.2.3]") {
+            Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(n, pos))) => {
+                assert_eq!(n, "+1.2.3");
+                assert_eq!(pos, Position { line: 2, column: 3 });
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
     #[test]
     fn test_error_unexpected_token() {
         let input = r#"{"name" _: "John", "age": 30}"#; // Missing comma
@@ -179,6 +1011,50 @@ mod tests {
             Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(_, _)))
         ));
     }
+
+    #[test]
+    fn test_error_positions() {
+        // Lines and columns are 1-based on every line, in lenient and strict mode
+        let position = |line, column| Position { line, column };
+        let error_position = |result: Result<String, JsonFixerError>| match result {
+            Err(JsonFixerError::Syntax(error)) => error.position().clone(),
+            result => panic!("expected a syntax error, got {:?}", result),
+        };
+        let cases = [
+            ("@", position(1, 1)),
+            ("{\"a\": 1,\n  \"b\": @}", position(2, 8)),
+            ("[1,\n\n@]", position(3, 1)),
+            // Numbers at their first character
+            ("{\"a\" 12345}", position(1, 6)),
+            ("", position(1, 1)),
+        ];
+        for (input, expected) in cases {
+            let mut config = JsonFixerConfig::default();
+            assert_eq!(
+                error_position(JsonFixer::fix_with_config(input, config.clone())),
+                expected
+            );
+
+            config.strict = true;
+            assert_eq!(
+                error_position(JsonFixer::fix_with_config(input, config)),
+                expected,
+                "{:?}",
+                input
+            );
+        }
+
+        let paths = JsonFixer::index_paths("[12345, -1.5]").unwrap();
+        for (_, _, span) in &paths[1..] {
+            assert_eq!(span.position, position(1, span.start + 1));
+        }
+
+        let legacy = position(2, 9);
+        assert_eq!(Position::from_legacy(&legacy), position(2, 8));
+        assert_eq!(position(2, 8).to_legacy(), legacy);
+        assert_eq!(position(1, 4).to_legacy(), position(1, 4));
+        assert_eq!(Position::from_legacy(&position(1, 4)), position(1, 4));
+    }
     #[test]
     fn test_fix_missing_comma() {
         let input = r#"{"name": "John" "age": 30 "id": 0 }"#;
@@ -428,4 +1304,1986 @@ mod tests {
             assert_eq!(result, input.1);
         }
     }
+
+    /*
+     ************************** Sort keys *************************
+     */
+
+    #[test]
+    fn test_sort_keys_stable() {
+        let mut config = JsonFixerConfig::default();
+        config.sort_keys = true;
+
+        let input = r#"{"b": 1, "a": 1, "b": 2, "a": 2, "b": 3}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"a":1,"a":2,"b":1,"b":2,"b":3}"#
+        );
+    }
+
+    #[test]
+    fn test_sort_keys_priority() {
+        let mut config = JsonFixerConfig::default();
+        config.sort_keys = true;
+        config.key_priority = vec!["$schema".to_string(), "id".to_string(), "name".to_string()];
+
+        let input = "{b: 1, name: 'x', a: {z: 1, id: 2}, '$schema': 's'}";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"$schema":"s","name":"x","a":{"id":2,"z":1},"b":1}"#
+        );
+
+        // Priorities are only used when sorting
+        config.sort_keys = false;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"b":1,"name":"x","a":{"z":1,"id":2},"$schema":"s"}"#
+        );
+    }
+
+    #[test]
+    fn test_key_order() {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+        let mut config = JsonFixerConfig::default();
+        config.key_order = vec![
+            ("".to_string(), keys(&["name", "version"])),
+            ("deps[*]".to_string(), keys(&["id", "range"])),
+        ];
+
+        let input =
+            "{z: 1, version: '1.0', deps: [{range: '^2', x: 0, id: 'a'}], name: 'pkg', b: 2}";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"name":"pkg","version":"1.0","z":1,"deps":[{"id":"a","range":"^2","x":0}],"b":2}"#
+        );
+
+        // The other keys are sorted after the ordered ones
+        config.sort_keys = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"name":"pkg","version":"1.0","b":2,"deps":[{"id":"a","range":"^2","x":0}],"z":1}"#
+        );
+
+        // Nested objects are matched by their path
+        config.key_order = vec![("a.b".to_string(), keys(&["y"]))];
+        config.sort_keys = false;
+        assert_eq!(
+            JsonFixer::fix_with_config("{b: {x: 1, y: 2}, a: {b: {x: 1, y: 2}}}", &config).unwrap(),
+            r#"{"b":{"x":1,"y":2},"a":{"b":{"y":2,"x":1}}}"#
+        );
+
+        // Preserved formatting
+        config.preserve = true;
+        config.key_order = vec![("".to_string(), keys(&["b"]))];
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"a": 1,"b": 2}"#, &config).unwrap(),
+            r#"{"b": 2,"a": 1}"#
+        );
+    }
+
+    /*
+     ************************** Repairs *************************
+     */
+
+    #[test]
+    fn test_repairs_disabled() {
+        type Disable = fn(&mut Repairs);
+        let cases: Vec<(&str, Disable)> = vec![
+            ("{a: 1}", |r| r.quote_keys = false),
+            ("['a']", |r| r.normalize_quotes = false),
+            ("[1 2]", |r| r.insert_commas = false),
+            ("[1, 2,]", |r| r.remove_commas = false),
+            ("{,\"a\": 1}", |r| r.remove_commas = false),
+            ("[1, [2", |r| r.close_brackets = false),
+            ("[+1]", |r| r.fix_numbers = false),
+            ("[.5]", |r| r.fix_numbers = false),
+        ];
+
+        for (input, disable) in cases {
+            assert!(JsonFixer::fix(input).is_ok());
+
+            let mut config = JsonFixerConfig::default();
+            disable(&mut config.repairs);
+            assert!(
+                matches!(
+                    JsonFixer::fix_with_config(input, config),
+                    Err(JsonFixerError::Syntax(_))
+                ),
+                "{} should not be fixed",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_repairs_errors() {
+        let mut config = JsonFixerConfig::default();
+        config.repairs.insert_commas = false;
+        assert!(matches!(
+            JsonFixer::fix_with_config(r#"{"a": 1 "b": 2}"#, config),
+            Err(JsonFixerError::Syntax(SyntaxError::MissingComma(_)))
+        ));
+
+        let mut config = JsonFixerConfig::default();
+        config.repairs.fix_numbers = false;
+        match JsonFixer::fix_with_config("[1.]", config) {
+            Err(JsonFixerError::Syntax(SyntaxError::InvalidNumber(n, _))) => assert_eq!(n, "1."),
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        // Only the missing comma is allowed
+        let mut config = JsonFixerConfig::default();
+        config.repairs = Repairs::none();
+        config.repairs.insert_commas = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"["a" "b"]"#, config.clone()).unwrap(),
+            r#"["a","b"]"#
+        );
+        assert!(matches!(
+            JsonFixer::fix_with_config(r#"["a" "b""#, config),
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedEndOfInput(_)))
+        ));
+    }
+
+    #[test]
+    fn test_repairs_quote_values() {
+        let input = r#"{"status": ok, "tags": [draft, 1]}"#;
+        assert!(JsonFixer::fix(input).is_err());
+
+        let mut config = JsonFixerConfig::default();
+        config.repairs.quote_values = true;
+        let report = JsonFixer::fix_with_report(input, config).unwrap();
+        assert_eq!(report.output, r#"{"status":"ok","tags":["draft",1]}"#);
+        assert_eq!(report.count(RepairKind::QuotedValue), 2);
+    }
+
+    #[test]
+    fn test_strict() {
+        let input = r#"{ "name": "John", "tags": ["a", "b"], "age": 30 }"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, JsonFixerConfig::strict()).unwrap(),
+            JsonFixer::fix(input).unwrap()
+        );
+
+        let mut config = JsonFixerConfig::strict();
+        config.beautify = true;
+        config.indent_size = 2;
+        assert_eq!(
+            JsonFixer::fix_with_config("[1,{}]", config).unwrap(),
+            "[\n  1,\n  {}\n]"
+        );
+
+        // Surrogate pair escapes decoded to the character they encode
+        let input = r#"{"emoji": "\ud83d\ude00"}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, JsonFixerConfig::strict()).unwrap(),
+            "{\"emoji\":\"\u{1F600}\"}"
+        );
+
+        // Inputs accepted or repaired by the lenient mode
+        let cases = vec![
+            "{a: 1}",
+            "['a']",
+            "[1 2]",
+            "[1,]",
+            "[1",
+            "[.5]",
+            "[01]",
+            r#"["\x"]"#,
+            "[\"a\tb\"]",
+            "[1] 2",
+            "\u{a0}[1]",
+        ];
+        for input in cases {
+            assert!(
+                matches!(
+                    JsonFixer::fix_with_config(input, JsonFixerConfig::strict()),
+                    Err(JsonFixerError::Syntax(_))
+                ),
+                "{:?} should be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_compliance() {
+        let mut config = JsonFixerConfig::strict();
+        assert!(JsonFixer::fix_with_config("[+1]", config.clone()).is_err());
+        config.compliance.leading_plus = true;
+        assert_eq!(JsonFixer::fix_with_config("[+1]", config).unwrap(), "[1]");
+
+        let mut config = JsonFixerConfig::strict();
+        config.compliance = Compliance::i_json();
+        assert!(matches!(
+            JsonFixer::fix_with_config(r#"{"a": 1, "a": 2}"#, config.clone()),
+            Err(JsonFixerError::Syntax(SyntaxError::DuplicateKey(_, _)))
+        ));
+        assert!(JsonFixer::fix_with_config("true", config).is_err());
+
+        // The output is checked when verified
+        let mut config = JsonFixerConfig::default();
+        config.verify_output = true;
+        config.compliance.scalar_root = false;
+        assert!(matches!(
+            JsonFixer::fix_with_config("'text'", config),
+            Err(JsonFixerError::Internal { .. })
+        ));
+    }
+
+    /*
+     ************************** Python compat *************************
+     */
+
+    #[test]
+    fn test_python_sets_and_tuples() {
+        let input =
+            "{'tags': {'a', 'b'}, 'point': (1, 2), 'ids': {3}, 'nested': [('x',), {(1, 2), null}]}";
+
+        let mut config = JsonFixerConfig::default();
+        config.python_compat = true;
+        let report = JsonFixer::fix_with_report(input, config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"tags":["a","b"],"point":[1,2],"ids":[3],"nested":[["x"],[[1,2],null]]}"#
+        );
+        assert_eq!(report.count(RepairKind::ConvertedToArray), 6);
+
+        // Dicts are kept, the empty braces are an empty dict
+        let mut config = JsonFixerConfig::default();
+        config.python_compat = true;
+        let output = JsonFixer::fix_with_config("{'a': {}, 'b': {'c', }}", config).unwrap();
+        assert_eq!(output, r#"{"a":{},"b":["c"]}"#);
+
+        // Sets of keywords, keywords followed by a colon are keys
+        let mut config = JsonFixerConfig::default();
+        config.python_compat = true;
+        let output = JsonFixer::fix_with_config("[{true, null, 1}, {null: 1}]", config).unwrap();
+        assert_eq!(output, r#"[[true,null,1],{"null":1}]"#);
+    }
+
+    #[test]
+    fn test_python_string_prefixes() {
+        let mut config = JsonFixerConfig::default();
+        config.python_compat = true;
+        let input = r#"{'a': b'bytes', 'b': u"text", 'c': f'{x}', Rb'key': BR"v"}"#;
+        let report = JsonFixer::fix_with_report(input, config.clone()).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":"bytes","b":"text","c":"{x}","key":"v"}"#
+        );
+        assert_eq!(report.count(RepairKind::StrippedStringPrefix), 5);
+
+        // Backslashes of raw strings are not escapes
+        assert_eq!(
+            JsonFixer::fix_with_config(r"[r'C:\temp', r'it\'s']", config.clone()).unwrap(),
+            JsonFixer::fix_with_config(r"['C:\\temp', 'it\\\'s']", config).unwrap()
+        );
+
+        // Identifiers looking like prefixes are kept
+        assert!(JsonFixer::fix("[b'bytes']").is_err());
+    }
+
+    #[test]
+    fn test_python_constants() {
+        let mut config = JsonFixerConfig::default();
+        config.python_compat = true;
+        config.repairs.quote_values = true;
+        let input = "{'a': True, 'b': [False, None], 'c': 'True', 'd': Nonetheless}";
+        let report = JsonFixer::fix_with_report(input, config.clone()).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":true,"b":[false,null],"c":"True","d":"Nonetheless"}"#
+        );
+        assert_eq!(report.count(RepairKind::CoercedBoolean), 2);
+        assert_eq!(report.count(RepairKind::CoercedNull), 1);
+        assert!(report
+            .repairs
+            .iter()
+            .any(|r| r.message == "Converted Python 'True' to true"));
+
+        // Otherwise bare words like the others
+        config.python_compat = false;
+        assert_eq!(
+            JsonFixer::fix_with_config("[True]", config).unwrap(),
+            r#"["True"]"#
+        );
+    }
+
+    #[test]
+    fn test_python_compat_disabled() {
+        assert!(matches!(
+            JsonFixer::fix("{'a', 'b'}"),
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedToken(_, _)))
+        ));
+        assert!(matches!(
+            JsonFixer::fix("[('a', 'b')]"),
+            Err(JsonFixerError::Syntax(SyntaxError::UnexpectedCharacter(
+                '(',
+                _
+            )))
+        ));
+    }
+
+    /*
+     ************************** Verify output *************************
+     */
+
+    #[test]
+    fn test_verify_output() {
+        let mut config = JsonFixerConfig::default();
+        config.verify_output = true;
+
+        let input = r#"{ name: 'John', tags: ['a' 'b'] }"#;
+        let result = JsonFixer::fix_with_config(input, config.clone());
+        assert_eq!(result.unwrap(), r#"{"name":"John","tags":["a","b"]}"#);
+
+        // Preserved whitespaces that are not JSON whitespaces make the output invalid
+        config.preserve = true;
+        let result = JsonFixer::fix_with_config("[1,\u{a0}2]", config.clone());
+        match result {
+            Err(JsonFixerError::Internal { output, diagnostic }) => {
+                assert_eq!(output, "[1,\u{a0}2]");
+                assert!(matches!(
+                    diagnostic,
+                    SyntaxError::UnexpectedCharacter('\u{a0}', _)
+                ));
+            }
+            other => panic!("Expected an internal error, got {:?}", other),
+        }
+    }
+
+    /*
+     ************************** Duplicate keys *************************
+     */
+
+    #[test]
+    fn test_duplicate_keys_keep() {
+        let input = r#"{"a": 1, "b": 2, "a": 3}"#;
+        let cases = vec![
+            (DuplicateKeys::KeepAll, r#"{"a":1,"b":2,"a":3}"#),
+            (DuplicateKeys::KeepFirst, r#"{"a":1,"b":2}"#),
+            (DuplicateKeys::KeepLast, r#"{"b":2,"a":3}"#),
+            (DuplicateKeys::MergeObjects, r#"{"a":3,"b":2}"#),
+        ];
+
+        for (policy, expected) in cases {
+            let mut config = JsonFixerConfig::default();
+            config.duplicate_keys = policy;
+            assert_eq!(JsonFixer::fix_with_config(input, config).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_keys_deep_merge() {
+        let input = r#"{
+            "db": {"host": "localhost", "tags": ["a"], "opts": {"x": 1}},
+            "db": {"port": 5432, "tags": ["b"], "opts": {"y": 2, "x": 3}}
+        }"#;
+        let mut config = JsonFixerConfig::default();
+        config.duplicate_keys = DuplicateKeys::MergeObjects;
+
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config).unwrap(),
+            r#"{"db":{"host":"localhost","tags":["a","b"],"opts":{"x":3,"y":2},"port":5432}}"#
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys_by_path() {
+        let input =
+            r#"{"a": 1, "a": 2, "users": [{"id": 1, "id": 2}], "nested": {"b": 1, "b": 2}}"#;
+        let mut config = JsonFixerConfig::default();
+        config.duplicate_keys = DuplicateKeys::KeepFirst;
+        config.duplicate_keys_by_path = vec![
+            ("users[*]".to_string(), DuplicateKeys::KeepLast),
+            ("nested".to_string(), DuplicateKeys::KeepAll),
+        ];
+
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config).unwrap(),
+            r#"{"a":1,"users":[{"id":2}],"nested":{"b":1,"b":2}}"#
+        );
+    }
+
+    /*
+     ************************** Homogenize arrays *************************
+     */
+
+    #[test]
+    fn test_homogenize_arrays() {
+        let input = r#"{"values": [1, {"value": 2}, 3], "other": [1, {"a": 1, "b": 2}]}"#;
+        let cases = vec![
+            (
+                HomogenizeArrays::Leave,
+                r#"{"values":[1,{"value":2},3],"other":[1,{"a":1,"b":2}]}"#,
+            ),
+            (
+                HomogenizeArrays::Unwrap,
+                r#"{"values":[1,2,3],"other":[1,{"a":1,"b":2}]}"#,
+            ),
+            (
+                HomogenizeArrays::Wrap,
+                r#"{"values":[{"value":1},{"value":2},{"value":3}],"other":[1,{"a":1,"b":2}]}"#,
+            ),
+        ];
+
+        for (rule, expected) in cases {
+            let mut config = JsonFixerConfig::default();
+            config.homogenize_arrays = rule;
+            assert_eq!(JsonFixer::fix_with_config(input, config).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_homogenize_arrays_report() {
+        let mut config = JsonFixerConfig::default();
+        config.homogenize_arrays = HomogenizeArrays::Wrap;
+
+        // Different keys cannot be used to wrap the scalars
+        let report = JsonFixer::fix_with_report(r#"[1, {"a": 2}, {"b": 3}]"#, config.clone());
+        assert!(report.unwrap().is_clean());
+        // Nor are the objects of different keys unwrapped
+        let unwrap = JsonFixerConfig {
+            homogenize_arrays: HomogenizeArrays::Unwrap,
+            ..config.clone()
+        };
+        let report = JsonFixer::fix_with_report(r#"[1, {"a": 2}, {"b": 3}, {"a": 4}]"#, unwrap);
+        let report = report.unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.output, r#"[1,{"a":2},{"b":3},{"a":4}]"#);
+
+        let report = JsonFixer::fix_with_report(r#"{"list": [{"id": 1}, 2]}"#, config).unwrap();
+        assert_eq!(report.output, r#"{"list":[{"id":1},{"id":2}]}"#);
+        assert_eq!(report.repairs.len(), 1);
+        assert_eq!(report.repairs[0].kind, RepairKind::HomogenizedArray);
+        assert_eq!(report.repairs[0].path.as_deref(), Some("list"));
+    }
+
+    #[test]
+    fn test_array_whitespace_entries() {
+        let cases = vec![("[ ,1]", "[1]"), ("[ ]", "[]"), ("[1, ,2]", "[1,2]")];
+
+        for (input, expected) in cases {
+            assert_eq!(JsonFixer::fix(input).unwrap(), expected);
+        }
+        assert_eq!(JsonFixer::fix_pretty("[ ]").unwrap(), "[]");
+    }
+
+    /*
+     ************************** Max line length *************************
+     */
+
+    #[test]
+    fn test_max_line_length_error() {
+        let mut config = JsonFixerConfig::default();
+        config.beautify = true;
+        config.indent_size = 2;
+        config.max_line_length = Some(16);
+
+        let input = r#"{"short": 1, "long": "a very long string value"}"#;
+        let result = JsonFixer::fix_with_config(input, config.clone());
+        assert!(matches!(
+            result,
+            Err(JsonFixerError::Format(JsonFormatError::LineTooLong {
+                line: 3,
+                length: 36,
+                max: 16
+            }))
+        ));
+
+        config.max_line_length = Some(40);
+        assert!(JsonFixer::fix_with_config(input, config).is_ok());
+    }
+
+    #[test]
+    fn test_max_line_length_wrap() {
+        let mut config = JsonFixerConfig::default();
+        config.indent_size = 2;
+        config.max_line_length = Some(24);
+        config.line_length_policy = LineLengthPolicy::Wrap;
+
+        let input =
+            r#"{"id": 1, "tags": ["a", "b"], "nested": {"key": "value", "list": [1, 2, 3]}}"#;
+        let expected = r#"{
+  "id":1,
+  "tags":["a","b"],
+  "nested":{
+    "key":"value",
+    "list":[1,2,3]
+  }
+}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            expected
+        );
+
+        // Fits on a single line
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"a": [1, 2]}"#, config.clone()).unwrap(),
+            r#"{"a":[1,2]}"#
+        );
+
+        // Strings are never broken
+        let input = r#"["a string longer than the maximum"]"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config).unwrap(),
+            "[\n  \"a string longer than the maximum\"\n]"
+        );
+    }
+
+    /*
+     ************************** Output style *************************
+     */
+
+    #[test]
+    fn test_json5_trailing_commas() {
+        let mut config = JsonFixerConfig::default();
+        config.beautify = true;
+        config.indent_size = 2;
+        config.output_style = OutputStyle::Json5;
+        config.emit_trailing_commas = true;
+        config.verify_output = true;
+
+        let input = "{a: 1, b: [1 2], c: {}, d: {e: []}}";
+        let expected = r#"{
+  "a": 1,
+  "b": [
+    1,
+    2,
+  ],
+  "c": {},
+  "d": {
+    "e": [],
+  },
+}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            expected
+        );
+
+        // Single line output has no trailing commas
+        config.beautify = false;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            r#"{"a":1,"b":[1,2],"c":{},"d":{"e":[]}}"#
+        );
+
+        // Wrapped lines account for the trailing commas
+        config.max_line_length = Some(8);
+        config.line_length_policy = LineLengthPolicy::Wrap;
+        assert_eq!(
+            JsonFixer::fix_with_config("[[1, 2], [3, 4]]", config.clone()).unwrap(),
+            "[\n  [1,2],\n  [3,4],\n]"
+        );
+        assert_eq!(
+            JsonFixer::fix_with_config("[[1, 2, 3]]", config).unwrap(),
+            "[\n  [\n    1,\n    2,\n    3,\n  ],\n]"
+        );
+    }
+
+    #[test]
+    fn test_trailing_commas_json_output() {
+        let mut config = JsonFixerConfig::default();
+        config.beautify = true;
+        config.emit_trailing_commas = true;
+
+        // Trailing commas are not valid JSON
+        assert_eq!(
+            JsonFixer::fix_with_config("[1, 2]", config).unwrap(),
+            "[\n1,\n2\n]"
+        );
+    }
+
+    #[test]
+    fn test_json5_quote_keys() {
+        let mut config = JsonFixerConfig::default();
+        config.output_style = OutputStyle::Json5;
+        config.identifier_chars = "-".to_string();
+        let input = r#"{name: 'a', "id": 1, first-name: 'b', null: 2, "_x": {y: 3}}"#;
+
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"name":"a","id":1,"first-name":"b","null":2,"_x":{"y":3}}"#
+        );
+
+        // Keys that are not identifiers are quoted anyway
+        config.quote_keys = QuoteKeys::AsInput;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{name:"a","id":1,"first-name":"b",null:2,"_x":{y:3}}"#
+        );
+
+        config.quote_keys = QuoteKeys::OnlyWhenNeeded;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{name:"a",id:1,"first-name":"b",null:2,_x:{y:3}}"#
+        );
+        let input = r#"{"": 1, "1a": 2, "a b": 3, "é_$1": 4}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"":1,"1a":2,"a b":3,é_$1:4}"#
+        );
+
+        config.preserve = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{ "a" : 1 }"#, &config).unwrap(),
+            "{ a : 1 }"
+        );
+
+        // Keys are always quoted in JSON
+        config.preserve = false;
+        config.output_style = OutputStyle::Json;
+        assert_eq!(
+            JsonFixer::fix_with_config("{a: 1}", &config).unwrap(),
+            r#"{"a":1}"#
+        );
+    }
+
+    /*
+     ************************** Limits *************************
+     */
+
+    #[test]
+    fn test_max_string_length() {
+        let mut config = JsonFixerConfig::default();
+        config.max_string_length = Some(5);
+
+        let input = r#"{"short": "abc", "exact": "12345", "user": {"bio": 'much too long'}}"#;
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, config.clone()),
+            Err(JsonFixerError::Limit(LimitError::StringTooLong { path, length: 13, max: 5 }))
+                if path == "user.bio"
+        ));
+
+        config.length_policy = LengthPolicy::Truncate("…".to_string());
+        let report = JsonFixer::fix_with_report(input, config.clone()).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"short":"abc","exact":"12345","user":{"bio":"much…"}}"#
+        );
+        assert_eq!(report.count(RepairKind::TruncatedString), 1);
+        assert_eq!(
+            report.repairs.last().unwrap().path.as_deref(),
+            Some("user.bio")
+        );
+
+        // Escapes are never cut in half
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"["a\"b\"c\"d"]"#, config.clone()).unwrap(),
+            r#"["a\"b\"…"]"#
+        );
+
+        // Keys are not limited by the string length
+        config.length_policy = LengthPolicy::Truncate(String::new());
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"long key": "long value"}"#, config.clone()).unwrap(),
+            r#"{"long key":"long "}"#
+        );
+
+        // A marker longer than the maximum is truncated too
+        config.length_policy = LengthPolicy::Truncate("[truncated]".to_string());
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"["long value", "short"]"#, config).unwrap(),
+            r#"["[trun","short"]"#
+        );
+    }
+
+    #[test]
+    fn test_max_key_length() {
+        let mut config = JsonFixerConfig::default();
+        config.max_key_length = Some(4);
+
+        let input = r#"{"list": [{"identifier": 1, "name": "a long name"}]}"#;
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, config.clone()),
+            Err(JsonFixerError::Limit(LimitError::KeyTooLong { path, length: 10, max: 4 }))
+                if path == "list[0]"
+        ));
+
+        config.length_policy = LengthPolicy::Truncate("~".to_string());
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            r#"{"list":[{"ide~":1,"name":"a long name"}]}"#
+        );
+
+        // Keys made identical by the truncation are reported, and handled like any duplicate
+        let input = "{abcde: 1, abcdf: 2, abcdg: 3, 'abc~': 4}";
+        let report = JsonFixer::fix_with_report(input, config.clone()).unwrap();
+        assert_eq!(report.output, r#"{"abc~":1,"abc~":2,"abc~":3,"abc~":4}"#);
+        let duplicated: Vec<_> = report
+            .repairs
+            .iter()
+            .filter(|repair| repair.message.ends_with("equals another key"))
+            .collect();
+        assert_eq!(duplicated.len(), 1);
+        assert_eq!(
+            duplicated[0].message,
+            r#"Truncated key "abc~" equals another key"#
+        );
+
+        config.duplicate_keys = DuplicateKeys::KeepFirst;
+        assert_eq!(
+            JsonFixer::fix_with_config("{abcde: 1, abcdf: 2}", config.clone()).unwrap(),
+            r#"{"abc~":1}"#
+        );
+        config.duplicate_keys = DuplicateKeys::KeepLast;
+        let report = JsonFixer::fix_with_report(input, config).unwrap();
+        assert_eq!(report.output, r#"{"abc~":4}"#);
+        assert_eq!(report.count(RepairKind::RemovedDuplicate), 3);
+    }
+
+    #[test]
+    fn test_max_array_elements() {
+        let mut config = JsonFixerConfig::default();
+        config.max_array_elements = Some(2);
+
+        let input = "{a: [1, 2], b: [[1, 2, 3], 4]}";
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, config.clone()),
+            Err(JsonFixerError::Limit(LimitError::TooManyElements { path, count: 3, max: 2 }))
+                if path == "b[0]"
+        ));
+
+        config.size_policy = SizePolicy::Truncate;
+        let report = JsonFixer::fix_with_report(input, config.clone()).unwrap();
+        assert_eq!(report.output, r#"{"a":[1,2],"b":[[1,2],4]}"#);
+        assert_eq!(report.count(RepairKind::TruncatedContainer), 1);
+        assert_eq!(
+            report.repairs.last().unwrap().to_string(),
+            "truncated container: Dropped 1 of 3 elements at path 'b[0]'"
+        );
+
+        config.preserve = true;
+        assert_eq!(
+            JsonFixer::fix_with_config("[ 1, 2 , 3 ]", config).unwrap(),
+            "[ 1, 2 ]"
+        );
+    }
+
+    #[test]
+    fn test_max_object_members() {
+        let mut config = JsonFixerConfig::default();
+        config.max_object_members = Some(2);
+
+        let input = "{a: 1, b: 2, c: {d: 1, e: 2, f: 3}}";
+        assert!(matches!(
+            JsonFixer::fix_with_config(input, config.clone()),
+            Err(JsonFixerError::Limit(LimitError::TooManyMembers { path, count: 3, max: 2 }))
+                if path.is_empty()
+        ));
+
+        config.size_policy = SizePolicy::Truncate;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            r#"{"a":1,"b":2}"#
+        );
+
+        // Members are counted once duplicate keys are handled
+        config.size_policy = SizePolicy::Error;
+        config.duplicate_keys = DuplicateKeys::KeepLast;
+        assert_eq!(
+            JsonFixer::fix_with_config("{a: 1, a: 2, b: 3}", config).unwrap(),
+            r#"{"a":2,"b":3}"#
+        );
+    }
+
+    #[test]
+    fn test_max_repairs() {
+        let mut config = JsonFixerConfig::default();
+        config.max_repairs = Some(2);
+        assert_eq!(
+            JsonFixer::fix_with_config("{a: 1, 'b': 2}", config.clone()).unwrap(),
+            r#"{"a":1,"b":2}"#
+        );
+        assert!(matches!(
+            JsonFixer::fix_with_config("{a: 1 b: 2}", config.clone()),
+            Err(JsonFixerError::Limit(LimitError::TooManyRepairs {
+                count: 3,
+                max: 2
+            }))
+        ));
+
+        // Fixing is aborted once the limit is exceeded
+        assert!(matches!(
+            JsonFixer::fix_with_config("[1 2 3 4 5 6 7 8 9]", config.clone()),
+            Err(JsonFixerError::Limit(LimitError::TooManyRepairs {
+                count: 3,
+                max: 2
+            }))
+        ));
+
+        // Valid documents are still formatted
+        config.max_repairs = Some(0);
+        config.space_between = true;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"a":[1,2]}"#, config.clone()).unwrap(),
+            r#"{ "a": [ 1, 2 ] }"#
+        );
+
+        // The repairs of the transformations count as well
+        config.normalize_nulls = NormalizeNulls::Always;
+        assert!(matches!(
+            JsonFixer::fix_with_config(r#"{"a": "NULL"}"#, config),
+            Err(JsonFixerError::Limit(LimitError::TooManyRepairs {
+                count: 1,
+                max: 0
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let check = || {
+            let arrays = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+            assert!(JsonFixer::fix(&arrays(MAX_DEPTH)).is_ok());
+
+            let objects = format!("{}1", "{a: ".repeat(10000));
+            for input in [arrays(MAX_DEPTH + 1), arrays(10000), objects] {
+                match JsonFixer::fix(&input) {
+                    Err(JsonFixerError::Limit(LimitError::TooDeep { path, max })) => {
+                        assert_eq!(max, MAX_DEPTH);
+                        assert!(path == "[0]".repeat(MAX_DEPTH) || path.ends_with(".a"));
+                    }
+                    result => panic!("unexpected result {:?}", result),
+                }
+            }
+        };
+        // Debug builds need the 8 MiB stack of the main thread at the maximum depth
+        let thread = std::thread::Builder::new().stack_size(8 << 20);
+        thread.spawn(check).unwrap().join().unwrap();
+    }
+
+    /*
+     ************************** Custom formatter *************************
+     */
+
+    /// Writes the elements of the root array in the Elasticsearch bulk format.
+    struct BulkFormatter {
+        index: &'static str,
+    }
+
+    impl Formatter for BulkFormatter {
+        fn format(
+            &self,
+            value: &JsonValue,
+            config: &JsonFixerConfig,
+        ) -> Result<String, JsonFixerError> {
+            let documents: Vec<&JsonValue> = match value {
+                JsonValue::Array(entries) => {
+                    entries.iter().filter_map(|e| e.value.as_ref()).collect()
+                }
+                value => vec![value],
+            };
+
+            let mut output = String::new();
+            for document in documents {
+                output.push_str(&format!(
+                    "{{\"index\":{{\"_index\":\"{}\"}}}}\n",
+                    self.index
+                ));
+                output.push_str(&JsonFormatter.format(document, config)?);
+                output.push('\n');
+            }
+            Ok(output)
+        }
+    }
+
+    #[test]
+    fn test_fix_with_formatter() {
+        let mut config = JsonFixerConfig::default();
+        config.sort_keys = true;
+        let formatter = BulkFormatter { index: "logs" };
+
+        let input = "[{msg: 'a', id: 1} {id: 2, msg: 'b'},]";
+        let expected = r#"{"index":{"_index":"logs"}}
+{"id":1,"msg":"a"}
+{"index":{"_index":"logs"}}
+{"id":2,"msg":"b"}
+"#;
+        assert_eq!(
+            JsonFixer::fix_with_formatter(input, &config, &formatter).unwrap(),
+            expected
+        );
+
+        // Formatters can be picked at runtime
+        let formatters: Vec<Box<dyn Formatter>> =
+            vec![Box::new(JsonFormatter), Box::new(formatter)];
+        assert_eq!(
+            JsonFixer::fix_with_formatter(input, &config, formatters[0].as_ref()).unwrap(),
+            JsonFixer::fix_with_config(input, &config).unwrap()
+        );
+
+        // The output of a custom formatter is verified like the default one
+        config.verify_output = true;
+        assert!(matches!(
+            JsonFixer::fix_with_formatter(input, &config, formatters[1].as_ref()),
+            Err(JsonFixerError::Internal { .. })
+        ));
+    }
+
+    /*
+     ************************** Token hooks *************************
+     */
+
+    #[test]
+    fn test_token_hook() {
+        let config = JsonFixerConfig::default();
+
+        // Strip a vendor directive and replace NaN by null
+        let hook = |token: &Token| match token {
+            Token::UnquotedString(s, _) if s == "pragma" => TokenAction::Drop,
+            Token::UnquotedString(s, pos) if s == "NaN" => {
+                TokenAction::Replace(Token::Null(pos.clone()))
+            }
+            _ => TokenAction::Keep,
+        };
+        assert_eq!(
+            JsonFixer::fix_with_token_hook("[pragma 1, NaN, {a: NaN}]", &config, hook).unwrap(),
+            r#"[1,null,{"a":null}]"#
+        );
+        assert!(JsonFixer::fix_with_config("[pragma 1]", &config).is_err());
+
+        // The first token goes through the hook as well
+        assert_eq!(
+            JsonFixer::fix_with_token_hook("pragma [1]", &config, hook).unwrap(),
+            "[1]"
+        );
+        assert_eq!(
+            JsonFixer::fix_with_token_hook("NaN", &config, hook).unwrap(),
+            "null"
+        );
+
+        // Stateful hooks
+        let mut strings = Vec::new();
+        let collect = |token: &Token| {
+            if let Token::String(s, _) = token {
+                strings.push(s.clone());
+            }
+            TokenAction::Keep
+        };
+        assert_eq!(
+            JsonFixer::fix_with_token_hook("{'a': 'b', c: 'd'}", &config, collect).unwrap(),
+            r#"{"a":"b","c":"d"}"#
+        );
+        assert_eq!(strings, vec!["a", "b", "d"]);
+    }
+
+    /*
+     ************************** Transforms *************************
+     */
+
+    fn camel_case(_: &str, key: Option<&mut String>, _: &mut JsonValue) -> TransformAction {
+        if let Some(key) = key {
+            let mut words = key.split('_');
+            let mut renamed = words.next().unwrap_or_default().to_string();
+            for word in words {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    renamed.extend(first.to_uppercase());
+                    renamed.push_str(chars.as_str());
+                }
+            }
+            *key = renamed;
+        }
+        TransformAction::Keep
+    }
+
+    #[test]
+    fn test_fix_with_transform() {
+        let config = JsonFixerConfig::default();
+        let input = "{user_name: 'john', login_info: {last_ip: '1.2.3.4', tags: [1, null, 3]}}";
+
+        assert_eq!(
+            JsonFixer::fix_with_transform(input, &config, camel_case).unwrap(),
+            r#"{"userName":"john","loginInfo":{"lastIp":"1.2.3.4","tags":[1,null,3]}}"#
+        );
+
+        // Paths hold the transformed keys of the parents, children are visited after their parent
+        let mut paths = Vec::new();
+        let record = |path: &str, _: Option<&mut String>, _: &mut JsonValue| {
+            paths.push(path.to_string());
+            TransformAction::Keep
+        };
+        let drop_nulls = |_: &str, _: Option<&mut String>, value: &mut JsonValue| match value {
+            JsonValue::Null => TransformAction::Drop,
+            _ => TransformAction::Keep,
+        };
+        let transform = camel_case.then(drop_nulls).then(record);
+        assert_eq!(
+            JsonFixer::fix_with_transform(input, &config, transform).unwrap(),
+            r#"{"userName":"john","loginInfo":{"lastIp":"1.2.3.4","tags":[1,3]}}"#
+        );
+        assert_eq!(
+            paths,
+            vec![
+                "",
+                "user_name",
+                "login_info",
+                "loginInfo.last_ip",
+                "loginInfo.tags",
+                "loginInfo.tags[0]",
+                "loginInfo.tags[1]",
+            ]
+        );
+
+        // Rewritten values are visited
+        let explode = |path: &str, _: Option<&mut String>, value: &mut JsonValue| {
+            if path == "a" {
+                *value = JsonValue::Array(Vec::new());
+            }
+            TransformAction::Keep
+        };
+        assert_eq!(
+            JsonFixer::fix_with_transform("{a: 1, b: 2}", &config, explode).unwrap(),
+            r#"{"a":[],"b":2}"#
+        );
+
+        let drop_all = |_: &str, _: Option<&mut String>, _: &mut JsonValue| TransformAction::Drop;
+        assert_eq!(
+            JsonFixer::fix_with_transform("[1]", &config, drop_all).unwrap(),
+            "null"
+        );
+    }
+
+    /*
+     ************************** Check indentation *************************
+     */
+
+    #[test]
+    fn test_check_indentation() {
+        let mut config = JsonFixerConfig::default();
+        config.indent_size = 4;
+
+        let mut pretty_config = config.clone();
+        pretty_config.beautify = true;
+        let input = r#"{"a": [1, {"b": "c"}], "d": {}}"#;
+        let pretty = JsonFixer::fix_with_config(input, pretty_config).unwrap();
+        assert_eq!(
+            JsonFixer::check_indentation(&pretty, config.clone()).unwrap(),
+            vec![]
+        );
+
+        let input = r#"{
+    "a": [
+      1,
+        2
+    ],
+  "b": "multi
+line string",
+    "c": {
+    }
+ }"#;
+        assert_eq!(
+            JsonFixer::check_indentation(input, config.clone()).unwrap(),
+            vec![
+                JsonFormatError::InvalidIndentation { line: 3 },
+                JsonFormatError::InvalidIndentation { line: 6 },
+                JsonFormatError::InvalidIndentation { line: 10 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_indentation_tabs() {
+        let mut config = JsonFixerConfig::default();
+        config.indent_style = IndentStyle::Tabs;
+
+        let input = "[\n\t1,\n    2\n]";
+        assert_eq!(
+            JsonFixer::check_indentation(input, config).unwrap(),
+            vec![JsonFormatError::InvalidIndentation { line: 3 }]
+        );
+    }
+
+    /*
+     ************************** Preview *************************
+     */
+
+    #[test]
+    fn test_preview_truncates_strings() {
+        let input = r#"{"short": "abc", "long": "0123456789", "list": ["éèàùç€", "quote\"d"]}"#;
+        let mut config = JsonFixerConfig::default();
+        config.max_string_display = Some(5);
+
+        assert_eq!(
+            JsonFixer::preview(input, config.clone()).unwrap(),
+            r#"{"short":"abc","long":"01234… (+5 chars)","list":["éèàùç… (+1 chars)","quote… (+2 chars)"]}"#
+        );
+
+        // The canonical output is never truncated
+        assert_eq!(
+            JsonFixer::fix_with_config(input, config.clone()).unwrap(),
+            r#"{"short":"abc","long":"0123456789","list":["éèàùç€","quote\"d"]}"#
+        );
+
+        config.max_string_display = None;
+        assert_eq!(
+            JsonFixer::preview(input, config.clone()).unwrap(),
+            JsonFixer::fix_with_config(input, config).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_preview_keeps_escapes() {
+        let mut config = JsonFixerConfig::default();
+        config.max_string_display = Some(5);
+        config.verify_output = true;
+
+        // Strings are truncated before being escaped, escapes are never cut in half
+        let preview = JsonFixer::preview(r#"["say \"hi\" now"]"#, config.clone()).unwrap();
+        assert_eq!(preview, r#"["say \"… (+7 chars)"]"#);
+        let preview = JsonFixer::preview(r#"["C:\\dir"]"#, config).unwrap();
+        assert_eq!(preview, r#"["C:\\di… (+1 chars)"]"#);
+    }
+
+    /*
+     ************************** Stringified JSON *************************
+     */
+
+    #[test]
+    fn test_stringified_json_kept() {
+        let mut config = JsonFixerConfig::default();
+        config.verify_output = true;
+
+        // Strings holding JSON are never re-parsed nor escaped twice
+        let input = r#"{"payload":"{\"a\":1,\"path\":\"C:\\\\dir\",\"list\":\"[1]\"}"}"#;
+        let output = JsonFixer::fix_with_config(input, config.clone()).unwrap();
+        assert_eq!(output, input);
+        assert_eq!(
+            JsonFixer::fix_with_config(&output, config.clone()).unwrap(),
+            output
+        );
+
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{payload: '{"a": 1}'}"#, config.clone()).unwrap(),
+            r#"{"payload":"{\"a\": 1}"}"#
+        );
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"["tab\tnew\nline\u0001 \/"]"#, config).unwrap(),
+            r#"["tab\tnew\nline\u0001 /"]"#
+        );
+    }
+
+    #[test]
+    fn test_unwrap_stringified_json() {
+        let mut config = JsonFixerConfig::default();
+        config.unwrap_stringified_json = true;
+        config.verify_output = true;
+
+        let input = r#"{
+            "payload": "{\"a\":1}",
+            "list": " [1, 2] ",
+            "nested": "{\"b\": \"[true]\"}",
+            "text": "{not json}",
+            "number": "42",
+            "fixable": "[1 2]"
+        }"#;
+        let report = JsonFixer::fix_with_report(input, config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"payload":{"a":1},"list":[1,2],"nested":{"b":[true]},"text":"{not json}","number":"42","fixable":"[1 2]"}"#
+        );
+        assert_eq!(report.count(RepairKind::UnwrappedJsonString), 4);
+        assert_eq!(report.repairs[0].path.as_deref(), Some("payload"));
+    }
+
+    #[test]
+    fn test_fix_double_encoded() {
+        let config = JsonFixerConfig::default();
+        let levels = |report: &crate::FixReport| report.count(RepairKind::UnwrappedJsonString);
+
+        // Not encoded
+        let report = JsonFixer::fix_double_encoded("{a: 1}", &config, 3).unwrap();
+        assert_eq!(report.output, r#"{"a":1}"#);
+        assert_eq!(levels(&report), 0);
+        assert_eq!(report.count(RepairKind::QuotedKey), 1);
+
+        let report = JsonFixer::fix_double_encoded(r#" "[1, 2,]" "#, &config, 3).unwrap();
+        assert_eq!(report.output, "[1,2]");
+        assert_eq!(levels(&report), 1);
+        assert_eq!(
+            report.repairs[0].to_string(),
+            "unwrapped JSON string: Removed string encoding level 1 at path ''"
+        );
+        assert_eq!(report.repairs[1].kind, RepairKind::RemovedComma);
+
+        // Three encodings, only two are removed
+        let input = r#""\"\\\"{\\\\\\\"a\\\\\\\":1}\\\"\"""#;
+        let report = JsonFixer::fix_double_encoded(input, &config, 3).unwrap();
+        assert_eq!(report.output, r#"{"a":1}"#);
+        assert_eq!(levels(&report), 3);
+        let report = JsonFixer::fix_double_encoded(input, &config, 2).unwrap();
+        assert_eq!(report.output, r#""{\"a\":1}""#);
+        assert_eq!(levels(&report), 2);
+
+        // Single quoted encodings
+        let report = JsonFixer::fix_double_encoded(r#"'{"a": [1 2]}'"#, &config, 3).unwrap();
+        assert_eq!(report.output, r#"{"a":[1,2]}"#);
+
+        // Strings holding something else than JSON are kept
+        let report = JsonFixer::fix_double_encoded(r#""hello {a}""#, &config, 3).unwrap();
+        assert_eq!(report.output, r#""hello {a}""#);
+        assert!(report.is_clean());
+
+        assert!(matches!(
+            JsonFixer::fix_double_encoded(r#""{\"status\": ok}""#, &config, 3),
+            Err(JsonFixerError::Syntax(_))
+        ));
+
+        // The configuration only applies to the innermost document
+        let mut config = JsonFixerConfig::default();
+        config.max_string_length = Some(10);
+        config.repairs = Repairs::none();
+        let input = r#""{\"name\": \"Ann\"}""#;
+        let report = JsonFixer::fix_double_encoded(input, &config, 3).unwrap();
+        assert_eq!(report.output, r#"{"name":"Ann"}"#);
+        assert!(
+            JsonFixer::fix_double_encoded(r#""{\"name\": \"Annabelle Smith\"}""#, &config, 3)
+                .is_err()
+        );
+        config.sort_keys = true;
+        let input = r#""{\"b\": 1, \"a\": 2}""#;
+        let report = JsonFixer::fix_double_encoded(input, &config, 3).unwrap();
+        assert_eq!(report.output, r#"{"a":2,"b":1}"#);
+    }
+
+    /*
+     ************************** HTML entities *************************
+     */
+
+    #[test]
+    fn test_html_entities_kept() {
+        let input = r#"{"title": "Tom &amp; Jerry"}"#;
+        assert_eq!(
+            JsonFixer::fix(input).unwrap(),
+            r#"{"title":"Tom &amp; Jerry"}"#
+        );
+    }
+
+    #[test]
+    fn test_decode_html_entities() {
+        let mut config = JsonFixerConfig::default();
+        config.decode_html_entities = true;
+
+        let cases = vec![
+            (
+                r#"{"title": "Tom &amp; Jerry"}"#,
+                r#"{"title":"Tom & Jerry"}"#,
+            ),
+            (r#"["&quot;quoted&quot;"]"#, r#"["\"quoted\""]"#),
+            (
+                r#"['it&#39;s', 'a&#x2F;b', '&lt;p&gt;']"#,
+                r#"["it's","a/b","<p>"]"#,
+            ),
+            (r#"{"caf&eacute;&amp;": 1}"#, r#"{"caf&eacute;&":1}"#),
+            // Not entities
+            (
+                r#"["a & b", "&amp", "&#xZZ;", "&;"]"#,
+                r#"["a & b","&amp","&#xZZ;","&;"]"#,
+            ),
+            // Decoded once
+            (r#"["&amp;quot;"]"#, r#"["&quot;"]"#),
+        ];
+        for (input, expected) in cases {
+            let output = JsonFixer::fix_with_config(input, &config).unwrap();
+            assert_eq!(output, expected, "input: {}", input);
+        }
+
+        let report =
+            JsonFixer::fix_with_report(r#"{"a&amp;b": "&euro;5", "c": "d"}"#, &config).unwrap();
+        assert_eq!(report.output, r#"{"a&b":"€5","c":"d"}"#);
+        assert_eq!(report.count(RepairKind::DecodedEntities), 2);
+    }
+
+    #[test]
+    fn test_compact_strings_whitespace() {
+        let input = "{\"text\": \"Hello,\\n\\t  world\", \"a  b\": [\" x  y \", \"c d\"]}";
+        assert_eq!(
+            JsonFixer::fix(input).unwrap(),
+            r#"{"text":"Hello,\n\t  world","a  b":[" x  y ","c d"]}"#
+        );
+
+        let mut config = JsonFixerConfig::default();
+        config.compact_strings_whitespace = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"text":"Hello, world","a  b":[" x y ","c d"]}"#
+        );
+        assert_eq!(report.count(RepairKind::CompactedWhitespace), 2);
+        assert_eq!(report.repairs[1].path.as_deref(), Some("a  b[0]"));
+
+        // Strings holding JSON are compacted once unwrapped
+        config.unwrap_stringified_json = true;
+        let input = r#"["{\"a\": \"x \\n y\"}", "   "]"#;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"[{"a":"x y"},"   "]"#);
+        assert_eq!(report.count(RepairKind::CompactedWhitespace), 1);
+    }
+
+    /*
+     ************************** Escaped quotes *************************
+     */
+
+    #[test]
+    fn test_unescape_quotes() {
+        let mut config = JsonFixerConfig::default();
+        config.unescape_quotes = true;
+
+        let cases = vec![
+            (r#"{\"a\": \"b\", \"c\": [1 2]}"#, r#"{"a":"b","c":[1,2]}"#),
+            (
+                r#"{\x22a\x22: \X22b\x22, \u0022c\u0022: \x27d\x27}"#,
+                r#"{"a":"b","c":"d"}"#,
+            ),
+            // Escapes of the strings are escaped once more
+            (
+                r#"[\"say \\\"hi\\\"\", \"a\\/b\", \"C:\\\\dir\", \"1\\n2\"]"#,
+                r#"["say \"hi\"","a/b","C:\\dir","1\n2"]"#,
+            ),
+            // Inputs whose first quote is not escaped are kept
+            (r#"{"a": "say \"hi\""}"#, r#"{"a":"say \"hi\""}"#),
+            (r#"{a: 'it\'s'}"#, r#"{"a":"it's"}"#),
+        ];
+        for (input, expected) in cases {
+            let output = JsonFixer::fix_with_config(input, &config).unwrap();
+            assert_eq!(output, expected, "input: {}", input);
+        }
+
+        let report = JsonFixer::fix_with_report(r#"{\"a\": 1}"#, &config).unwrap();
+        assert_eq!(report.output, r#"{"a":1}"#);
+        assert_eq!(report.count(RepairKind::UnescapedQuotes), 1);
+
+        // Not unescaped by default
+        assert!(JsonFixer::fix(r#"{\"a\": 1}"#).is_err());
+    }
+
+    /*
+     ************************** Header *************************
+     */
+
+    #[test]
+    fn test_header_key() {
+        let mut config = JsonFixerConfig::default();
+        config.header = Some("gen 1.2".to_string());
+
+        let output = JsonFixer::fix_with_config("{a: 1, b: 2}", &config);
+        assert_eq!(
+            output.unwrap(),
+            r#"{"_generated_by":"gen 1.2","a":1,"b":2}"#
+        );
+        // Only objects have keys, and the user's member is kept
+        for input in ["[1]", "1", "{a: 1, _generated_by: 'old', b: 2}"] {
+            let result = JsonFixer::fix_with_config(input, &config);
+            assert!(
+                matches!(
+                    &result,
+                    Err(JsonFixerError::Uninsertable { option: "header", path })
+                        if path == "_generated_by"
+                ),
+                "{}",
+                input
+            );
+        }
+
+        config.verify_output = true;
+        let report = JsonFixer::fix_with_report("{a: 1}", &config).unwrap();
+        assert_eq!(report.output, r#"{"_generated_by":"gen 1.2","a":1}"#);
+        assert_eq!(report.repairs.len(), 1);
+    }
+
+    #[test]
+    fn test_header_comment() {
+        let mut config = JsonFixerConfig::default();
+        config.header = Some("Generated by gen\n\nDo not edit".to_string());
+        config.output_style = OutputStyle::Jsonc;
+        config.beautify = true;
+        config.indent_size = 2;
+
+        let output = JsonFixer::fix_with_config("{a: 1, _generated_by: 'x'}", &config).unwrap();
+        assert_eq!(
+            output,
+            "// Generated by gen\n//\n// Do not edit\n{\n  \"a\": 1,\n  \"_generated_by\": \"x\"\n}"
+        );
+
+        config.output_style = OutputStyle::Json5;
+        config.beautify = false;
+        let output = JsonFixer::fix_with_config("[1 2]", &config).unwrap();
+        assert_eq!(output, "// Generated by gen\n//\n// Do not edit\n[1,2]");
+    }
+
+    /*
+     ************************** Provenance *************************
+     */
+
+    #[test]
+    fn test_provenance() {
+        let version = env!("CARGO_PKG_VERSION");
+        let mut config = JsonFixerConfig::default();
+        config.provenance_path = Some("__jsonfixer".to_string());
+
+        let output = JsonFixer::fix_with_config("{a: 1, b: [1 2],}", &config).unwrap();
+        let expected = format!(
+            r#"{{"a":1,"b":[1,2],"__jsonfixer":{{"repairs":4,"version":"{}"}}}}"#,
+            version
+        );
+        assert_eq!(output, expected);
+
+        // Valid documents are tracked as well
+        let output = JsonFixer::fix_with_config(r#"{"a": 1}"#, &config).unwrap();
+        let expected = format!(
+            r#"{{"a":1,"__jsonfixer":{{"repairs":0,"version":"{}"}}}}"#,
+            version
+        );
+        assert_eq!(output, expected);
+
+        // Nothing to insert into, or a member of the document in the way
+        for input in ["[1]", r#"{"a": 1, "__jsonfixer": "mine"}"#] {
+            let result = JsonFixer::fix_with_config(input, &config);
+            assert!(
+                matches!(
+                    &result,
+                    Err(JsonFixerError::Uninsertable { option: "provenance_path", path })
+                        if path == "__jsonfixer"
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_provenance_path() {
+        let version = env!("CARGO_PKG_VERSION");
+        let mut config = JsonFixerConfig::default();
+        config.provenance_path = Some("meta.fixer".to_string());
+
+        let output = JsonFixer::fix_with_config("{meta: {id: 2}}", &config).unwrap();
+        let expected = format!(
+            r#"{{"meta":{{"id":2,"fixer":{{"repairs":2,"version":"{}"}}}}}}"#,
+            version
+        );
+        assert_eq!(output, expected);
+
+        let output = JsonFixer::fix_with_config("{}", &config).unwrap();
+        let expected = format!(
+            r#"{{"meta":{{"fixer":{{"repairs":0,"version":"{}"}}}}}}"#,
+            version
+        );
+        assert_eq!(output, expected);
+
+        config.provenance_path = Some("[1].fixer".to_string());
+        let output = JsonFixer::fix_with_config("[1, {}]", &config).unwrap();
+        let expected = format!(r#"[1,{{"fixer":{{"repairs":0,"version":"{}"}}}}]"#, version);
+        assert_eq!(output, expected);
+
+        // The user's values are kept
+        config.provenance_path = Some("meta.fixer".to_string());
+        for input in ["{meta: {fixer: 1, id: 2}}", "{meta: 1}", "{meta: [{}]}"] {
+            let result = JsonFixer::fix_with_config(input, &config);
+            assert_eq!(result.unwrap_err().code().number(), 5007, "{}", input);
+        }
+        config.provenance_path = Some("[1].fixer".to_string());
+        for input in ["[1]", "[1, 2]", r#"[1, {"fixer": null}]"#] {
+            let result = JsonFixer::fix_with_config(input, &config);
+            assert_eq!(result.unwrap_err().code().number(), 5007, "{}", input);
+        }
+    }
+
+    /*
+     ************************** Salvage *************************
+     */
+
+    #[test]
+    fn test_salvage() {
+        let mut config = JsonFixerConfig::default();
+        config.salvage = true;
+
+        let input = "[\n  {\"id\": 1},\n  {\"id\": 2, \"name\": ~~},\n  {\"id\": 3}\n]";
+        assert!(JsonFixer::fix(input).is_err());
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"[{"id":1},{"id":2,"name":null},{"id":3}]"#);
+        assert_eq!(report.count(RepairKind::Salvaged), 1);
+        let repair = &report.repairs[0];
+        assert_eq!(repair.path.as_deref(), Some("[1].name"));
+        assert_eq!(repair.position.as_ref().map(|pos| pos.line), Some(3));
+
+        // Corrupted lines, brackets included, up to the next line ending with a comma
+        let input = "{\n  \"a\": 1,\n  \"b\": #{[ x,\n  ] },\n  \"c\": 3\n}";
+        let output = JsonFixer::fix_with_config(input, &config).unwrap();
+        assert_eq!(output, r#"{"a":1,"b":null,"c":3}"#);
+
+        // Members whose key is unknown are dropped
+        let input = "{\n  \"a\": 1,\n  ~~ \"b\": 2,\n  \"c\": 3\n}";
+        let output = JsonFixer::fix_with_config(input, &config).unwrap();
+        assert_eq!(output, r#"{"a":1,"c":3}"#);
+
+        // Up to the closing bracket of the enclosing container, keeping the parsed values
+        let cases = [
+            (r#"{"a": }"#, r#"{"a":null}"#),
+            ("[1, 2 ~~]", "[1,2]"),
+            ("[1, 2 ~~, [3 ~~]]", "[1,2,[3]]"),
+            (
+                "{\"a\": [1, 2,\n ~~\n ], \"d\": 2}",
+                r#"{"a":[1,2,null],"d":2}"#,
+            ),
+        ];
+        for (input, expected) in cases {
+            let output = JsonFixer::fix_with_config(input, &config).unwrap();
+            assert_eq!(output, expected, "{}", input);
+        }
+
+        // Up to the next comma separating the entries when no line ends with one
+        let cases = [
+            (r#"{"a": 1, "b": @@@, "c": 3}"#, r#"{"a":1,"b":null,"c":3}"#),
+            ("[1, %%%, 3]", "[1,null,3]"),
+            (
+                r#"{"a": {"b": ~~, "c": 1}, "d": 2}"#,
+                r#"{"a":{"b":null,"c":1},"d":2}"#,
+            ),
+            (
+                r#"[1, "x" ~~ [2, 3], {"y": ","}, 4]"#,
+                "[1,\"x\",{\"y\":\",\"},4]",
+            ),
+            ("{\"a\": ~~, \"b\": 2\n}", r#"{"a":null,"b":2}"#),
+        ];
+        for (input, expected) in cases {
+            let report = JsonFixer::fix_with_report(input, &config).unwrap();
+            assert_eq!(report.output, expected, "{}", input);
+            assert_eq!(report.count(RepairKind::Salvaged), 1, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_salvage_root() {
+        let mut config = JsonFixerConfig::default();
+        config.salvage = true;
+
+        // Only the entries of arrays and objects are salvaged
+        assert!(JsonFixer::fix_with_config("~~", &config).is_err());
+        assert!(JsonFixer::fix_with_config("[1] ~~", &config).is_err());
+    }
+
+    /*
+     ************************** Preserve normalize *************************
+     */
+
+    #[test]
+    fn test_preserve_normalize() {
+        let mut config = JsonFixerConfig::default();
+        config.preserve_normalize = true;
+        config.indent_size = 2;
+
+        let input = r#"{
+      a:    1,
+  "b" : [1,   2,
+        3],
+
+   "c": {"d":  'x  y'}
+    }"#;
+        let expected = r#"{
+  "a": 1,
+  "b" : [1, 2,
+    3],
+
+  "c": {"d": "x  y"}
+}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            expected
+        );
+
+        // Line endings are kept, and lines are indented with tabs
+        config.indent_style = IndentStyle::Tabs;
+        let input = "[\r\n    {\"a\": 1},\t\r\n  2  \r\n]";
+        let expected = "[\r\n\t{\"a\": 1},\r\n\t2\r\n]";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            expected
+        );
+
+        // The formatting options reformatting the whole output are ignored
+        config.beautify = true;
+        config.indent_style = IndentStyle::Spaces;
+        let input = "{\"a\":   [1,2]}";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"a": [1,2]}"#
+        );
+    }
+
+    /*
+     ************************** Boolean normalization *************************
+     */
+
+    #[test]
+    fn test_normalize_booleans() {
+        let input = r#"{"is_admin": "Y", "hasItems": " Off ", "can-edit": "1", "answer": "no"}"#;
+        assert_eq!(
+            JsonFixer::fix(input).unwrap(),
+            r#"{"is_admin":"Y","hasItems":" Off ","can-edit":"1","answer":"no"}"#
+        );
+
+        let mut config = JsonFixerConfig::default();
+        config.normalize_booleans = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"is_admin":true,"hasItems":false,"can-edit":true,"answer":"no"}"#
+        );
+        assert_eq!(report.count(RepairKind::CoercedBoolean), 3);
+        assert_eq!(report.repairs[0].path.as_deref(), Some("is_admin"));
+        assert_eq!(report.repairs[0].message, r#"Coerced "Y" to true"#);
+
+        let cases = vec![
+            // Not truthy or falsey
+            (r#"{"is_set": "maybe"}"#, r#"{"is_set":"maybe"}"#),
+            (r#"{"is_set": ""}"#, r#"{"is_set":""}"#),
+            // Not strings
+            (r#"{"is_set": 1}"#, r#"{"is_set":1}"#),
+            // Not hinted keys
+            (
+                r#"{"island": "yes", "is": "yes", "shouldered": "no", "this_is": "y"}"#,
+                r#"{"island":"yes","is":"yes","shouldered":"no","this_is":"y"}"#,
+            ),
+            // Nested fields
+            (
+                r#"{"users": [{"isActive": "TRUE"}, {"isActive": "n"}]}"#,
+                r#"{"users":[{"isActive":true},{"isActive":false}]}"#,
+            ),
+        ];
+        for (input, expected) in cases {
+            let output = JsonFixer::fix_with_config(input, &config).unwrap();
+            assert_eq!(output, expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_boolean_paths() {
+        let mut config = JsonFixerConfig::default();
+        config.normalize_booleans = true;
+        config.boolean_paths = vec!["users[*].admin".to_string(), "flags[*]".to_string()];
+
+        let input =
+            r#"{"users": [{"admin": "yes", "name": "no"}], "flags": ["on", "x"], "admin": "no"}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"users":[{"admin":true,"name":"no"}],"flags":[true,"x"],"admin":"no"}"#
+        );
+
+        // Hints are ignored unless enabled
+        config.normalize_booleans = false;
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"flags": ["on"]}"#, &config).unwrap(),
+            r#"{"flags":["on"]}"#
+        );
+    }
+
+    /*
+     ************************** Null normalization *************************
+     */
+
+    #[test]
+    fn test_normalize_nulls() {
+        let input =
+            r#"{"a": "NULL", "b": " nil ", "c": "None", "d": "", "e": ["null", "nullable"]}"#;
+        assert_eq!(
+            JsonFixer::fix(input).unwrap(),
+            r#"{"a":"NULL","b":" nil ","c":"None","d":"","e":["null","nullable"]}"#
+        );
+
+        let mut config = JsonFixerConfig::default();
+        config.normalize_nulls = NormalizeNulls::Always;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"a":null,"b":null,"c":null,"d":null,"e":[null,"nullable"]}"#
+        );
+        assert_eq!(report.count(RepairKind::CoercedNull), 5);
+        assert_eq!(report.repairs[4].path.as_deref(), Some("e[0]"));
+        assert_eq!(report.repairs[4].message, r#"Coerced "null" to null"#);
+
+        // Keys are kept
+        assert_eq!(
+            JsonFixer::fix_with_config(r#"{"null": "x"}"#, &config).unwrap(),
+            r#"{"null":"x"}"#
+        );
+    }
+
+    #[test]
+    fn test_null_paths() {
+        let mut config = JsonFixerConfig::default();
+        config.normalize_nulls = NormalizeNulls::Hinted;
+        config.null_paths = vec!["users[*].email".to_string()];
+        config.normalize_booleans = true;
+
+        let input = r#"{"users": [{"email": "None", "name": "None", "is_admin": "none"}]}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"users":[{"email":null,"name":"None","is_admin":"none"}]}"#
+        );
+
+        // Nulls are coerced before booleans
+        config.null_paths.push("users[*].is_admin".to_string());
+        let output = JsonFixer::fix_with_config(r#"{"users": [{"is_admin": ""}]}"#, &config);
+        assert_eq!(output.unwrap(), r#"{"users":[{"is_admin":null}]}"#);
+    }
+
+    /*
+     ************************** Key cleanup *************************
+     */
+
+    #[test]
+    fn test_trim_keys() {
+        let input = "{\" name\u{a0}\": 1, \"a b\": 2, \"\u{200b}id\": 3}";
+        let mut config = JsonFixerConfig::default();
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            "{\" name\u{a0}\":1,\"a b\":2,\"\u{200b}id\":3}"
+        );
+
+        config.trim_keys = true;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, "{\"name\":1,\"a b\":2,\"\u{200b}id\":3}");
+        assert_eq!(report.count(RepairKind::CleanedKey), 1);
+        assert_eq!(report.repairs[0].path.as_deref(), Some("name"));
+        assert_eq!(
+            report.repairs[0].message,
+            r#"Cleaned key " name\u{a0}" to "name""#
+        );
+    }
+
+    #[test]
+    fn test_normalize_key_whitespace() {
+        let mut config = JsonFixerConfig::default();
+        config.normalize_key_whitespace = true;
+
+        let input =
+            "{\"first\u{200b}\u{a0} \\tname\": 1, \"\u{feff}id\": 2, \" x \": {\"a\u{ad}b\": 3}}";
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"first name":1,"id":2," x ":{"ab":3}}"#);
+        assert_eq!(report.count(RepairKind::CleanedKey), 3);
+        assert_eq!(report.repairs[2].path.as_deref(), Some(" x .ab"));
+
+        // Keys made equal are duplicates
+        config.trim_keys = true;
+        config.duplicate_keys = DuplicateKeys::KeepLast;
+        let input = "{\"id\": 1, \"\u{feff}id \": 2}";
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"id":2}"#
+        );
+    }
+
+    #[test]
+    fn test_allowed_keys() {
+        let input = "{id: 1, user: {name: 'a', token: 'x'}, tags: [{id: 2, x: 3}], debug: true}";
+        let mut config = JsonFixerConfig::default();
+        config.allowed_keys = vec![
+            "id".to_string(),
+            "user.name".to_string(),
+            "tags[*].id".to_string(),
+        ];
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            r#"{"id":1,"user":{"name":"a"},"tags":[{"id":2}]}"#
+        );
+        let pruned: Vec<_> = report
+            .repairs
+            .iter()
+            .filter(|r| r.kind == RepairKind::PrunedKey)
+            .collect();
+        let paths: Vec<_> = pruned.iter().filter_map(|r| r.path.as_deref()).collect();
+        assert_eq!(paths, ["debug", "user.token", "tags[0].x"]);
+        assert_eq!(pruned[0].message, r#"Dropped member "debug""#);
+
+        // Everything within an allowed member is kept
+        config.allowed_keys = vec!["user".to_string()];
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"user":{"name":"a","token":"x"}}"#
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        let mut config = JsonFixerConfig::default();
+        config.defaults = vec![
+            ("server".to_string(), JsonValue::Object(Vec::new())),
+            (
+                "server.port".to_string(),
+                JsonValue::Number(JsonNumber::parse("8080")),
+            ),
+            (
+                "users[*].role".to_string(),
+                JsonValue::String("guest".to_string()),
+            ),
+            ("users[*]".to_string(), JsonValue::Null),
+        ];
+
+        let input = "{users: [{name: 'a'}, {name: 'b', role: 'admin'}, 'c']}";
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(
+            report.output,
+            concat!(
+                r#"{"users":[{"name":"a","role":"guest"},{"name":"b","role":"admin"},"c"],"#,
+                r#""server":{"port":8080}}"#
+            )
+        );
+        let inserted: Vec<_> = report
+            .repairs
+            .iter()
+            .filter(|r| r.kind == RepairKind::InsertedDefault)
+            .collect();
+        let paths: Vec<_> = inserted.iter().filter_map(|r| r.path.as_deref()).collect();
+        assert_eq!(paths, ["server", "users[0].role", "server.port"]);
+        assert_eq!(
+            inserted[0].message,
+            r#"Inserted default value of missing member "server""#
+        );
+
+        // Members set to null are not missing
+        let input = r#"{"server": {"port": null}}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"server":{"port":null}}"#
+        );
+    }
+
+    #[test]
+    fn test_denied_keys() {
+        let input = "{user: {name: 'a', password: 'x'}, password: 'y', users: [{password: 'z'}]}";
+        let mut config = JsonFixerConfig::default();
+        config.denied_keys = vec!["*.password".to_string(), "users[*].password".to_string()];
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"user":{"name":"a"},"password":"y","users":[{}]}"#
+        );
+
+        // Denied wins over allowed
+        config.allowed_keys = vec!["user".to_string()];
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"user":{"name":"a"}}"#
+        );
+
+        // Keys containing dots are quoted
+        let input = r#"{"c.d": 1, "c": {"d": 2, "e.f": [3]}}"#;
+        config.allowed_keys = Vec::new();
+        config.denied_keys = vec!["c.d".to_string(), r#"c["e.f"]"#.to_string()];
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"c.d":1,"c":{}}"#
+        );
+        config.denied_keys = vec![r#"["c.d"]"#.to_string()];
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"c":{"d":2,"e.f":[3]}}"#
+        );
+    }
+
+    /*
+     ************************** Confidence *************************
+     */
+
+    #[test]
+    fn test_min_confidence() {
+        let mut config = JsonFixerConfig {
+            repeated_colons: RepeatedColons::Nest,
+            ..Default::default()
+        };
+        let nest = config.clone();
+        config.min_confidence = Confidence::Likely;
+
+        // Speculative repairs fail
+        for input in [r#"[{"a": 1]"#, r#"{"a": [1}, "b": 2}"#, r#"{"a": "b": 1}"#] {
+            assert!(
+                JsonFixer::fix_with_config(input, &nest).is_ok(),
+                "{}",
+                input
+            );
+            assert!(
+                JsonFixer::fix_with_config(input, &config).is_err(),
+                "{}",
+                input
+            );
+        }
+        let input = "[\n  {\"id\": ~~},\n  {\"id\": 2}\n]";
+        config.salvage = true;
+        assert!(JsonFixer::fix_with_config(input, &config).is_err());
+        config.min_confidence = Confidence::Speculative;
+        assert!(JsonFixer::fix_with_config(input, &config).is_ok());
+
+        // Likely repairs fail as well
+        config.min_confidence = Confidence::Certain;
+        config.repairs = Repairs::all();
+        assert!(JsonFixer::fix("[1 2]").is_ok());
+        assert!(JsonFixer::fix_with_config("[1 2]", &config).is_err());
+        assert!(JsonFixer::fix_with_config("{a: b}", &config).is_err());
+        assert_eq!(
+            JsonFixer::fix_with_config("{a: 'b', c: [1, 2,],", &config).unwrap(),
+            r#"{"a":"b","c":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn test_min_confidence_of_boolean_hints() {
+        let mut config = JsonFixerConfig::default();
+        config.normalize_booleans = true;
+        config.min_confidence = Confidence::Certain;
+
+        // Hinted by the key only
+        let input = r#"{"is_admin": "Y", "active": "N"}"#;
+        assert_eq!(
+            JsonFixer::fix_with_config(input, &config).unwrap(),
+            r#"{"is_admin":"Y","active":"N"}"#
+        );
+
+        config.boolean_paths = vec!["active".to_string()];
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"is_admin":"Y","active":false}"#);
+        assert_eq!(report.confidence(), Confidence::Certain);
+
+        config.min_confidence = Confidence::Likely;
+        let report = JsonFixer::fix_with_report(input, &config).unwrap();
+        assert_eq!(report.output, r#"{"is_admin":true,"active":false}"#);
+        assert_eq!(report.repairs[0].confidence, Confidence::Likely);
+        assert_eq!(report.confidence(), Confidence::Likely);
+    }
+
+    #[test]
+    fn test_suppress() {
+        let mut config = JsonFixerConfig::default();
+        config.suppress = vec![
+            RepairKind::RemovedComma.code(),
+            RepairKind::QuotedKey.code(),
+        ];
+
+        // Suppressed repairs are applied but not reported
+        let report = JsonFixer::fix_with_report("{a: [1, 2,], 'b': 3}", &config).unwrap();
+        assert_eq!(report.output, r#"{"a":[1,2],"b":3}"#);
+        let kinds: Vec<RepairKind> = report.repairs.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, [RepairKind::NormalizedQuotes]);
+
+        // Nor counted against the limit of repairs
+        config.max_repairs = Some(0);
+        assert!(JsonFixer::fix_with_config("{a: [1, 2,],}", &config).is_ok());
+        assert!(JsonFixer::fix_with_config("{a: [1 2]}", &config).is_err());
+
+        // And applied whatever their confidence
+        config.max_repairs = None;
+        config.min_confidence = Confidence::Certain;
+        assert!(JsonFixer::fix_with_config("[1 2]", &config).is_err());
+        config.suppress.push(RepairKind::InsertedComma.code());
+        assert_eq!(
+            JsonFixer::fix_with_config("[1 2]", &config).unwrap(),
+            "[1,2]"
+        );
+
+        // Disabled repairs stay disabled
+        config.repairs.quote_keys = false;
+        assert!(JsonFixer::fix_with_config("{a: 1}", &config).is_err());
+    }
 }
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use crate::{JsonFixer, JsonFixerConfig};
+
+    fn flatten(input: &str, separator: &str) -> String {
+        JsonFixer::flatten(input, separator, JsonFixerConfig::default()).unwrap()
+    }
+
+    fn unflatten(input: &str, separator: &str) -> String {
+        JsonFixer::unflatten(input, separator, JsonFixerConfig::default()).unwrap()
+    }
+
+    #[test]
+    fn test_flatten() {
+        assert_eq!(
+            flatten("{a: {b: 1, c: [true, null, {d: 'x'}]}}", "."),
+            r#"{"a.b":1,"a.c[0]":true,"a.c[1]":null,"a.c[2].d":"x"}"#
+        );
+        assert_eq!(
+            flatten("[[1 2] {a: 3}]", "."),
+            r#"{"[0][0]":1,"[0][1]":2,"[1].a":3}"#
+        );
+        assert_eq!(flatten("{a: {b: 1}}", "__"), r#"{"a__b":1}"#);
+        assert_eq!(
+            flatten("{a: {}, b: [], c: {'': 1}}", "."),
+            r#"{"a":{},"b":[],"c.":1}"#
+        );
+    }
+
+    #[test]
+    fn test_flatten_scalars_and_empty_roots() {
+        assert_eq!(flatten("'x'", "."), r#""x""#);
+        assert_eq!(flatten("12", "."), "12");
+        assert_eq!(flatten("{}", "."), "{}");
+        assert_eq!(flatten("[]", "."), "[]");
+    }
+
+    #[test]
+    fn test_unflatten() {
+        assert_eq!(
+            unflatten(
+                r#"{"a.b":1,"a.c[0]":true,"a.c[1]":null,"a.c[2].d":"x"}"#,
+                "."
+            ),
+            r#"{"a":{"b":1,"c":[true,null,{"d":"x"}]}}"#
+        );
+        assert_eq!(
+            unflatten(r#"{"[0][0]":1,"[0][1]":2,"[1].a":3}"#, "."),
+            r#"[[1,2],{"a":3}]"#
+        );
+        assert_eq!(unflatten(r#"{"a__b":1}"#, "__"), r#"{"a":{"b":1}}"#);
+        assert_eq!(unflatten(r#"{"a.b":1}"#, ""), r#"{"a.b":1}"#);
+        assert_eq!(unflatten("[1, {'a.b': 2}]", "."), r#"[1,{"a.b":2}]"#);
+    }
+
+    #[test]
+    fn test_unflatten_indices() {
+        assert_eq!(unflatten("{'a[2]': 3, 'a[0]': 1}", "."), r#"{"a":[1,3]}"#);
+        assert_eq!(
+            unflatten("{'a[x]': 1, 'b[]': 2}", "."),
+            r#"{"a[x]":1,"b[]":2}"#
+        );
+        assert_eq!(
+            unflatten("{'a[99999999999999999999]': 1}", "."),
+            r#"{"a[99999999999999999999]":1}"#
+        );
+    }
+
+    #[test]
+    fn test_unflatten_conflicts() {
+        assert_eq!(unflatten("{a: 1, 'a.b': 2}", "."), r#"{"a":{"b":2}}"#);
+        assert_eq!(unflatten("{'a.b': 2, a: 1}", "."), r#"{"a":1}"#);
+        assert_eq!(
+            unflatten("{a: {b: 1}, 'a.c': 2, l: [1], 'l[1]': 2}", "."),
+            r#"{"a":{"b":1,"c":2},"l":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn test_flatten_round_trip() {
+        let input =
+            r#"{"id":1,"user":{"name":"Ann","tags":["a","b"],"roles":[]},"m":[[{"x":null}]]}"#;
+        assert_eq!(unflatten(&flatten(input, "."), "."), input);
+        assert_eq!(unflatten(&flatten(input, "/"), "/"), input);
+    }
+
+    #[test]
+    fn test_flatten_with_config() {
+        let config = JsonFixerConfig {
+            beautify: true,
+            indent_size: 2,
+            sort_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            JsonFixer::flatten("{b: [1], a: {c: 2}}", ".", &config).unwrap(),
+            "{\n  \"a.c\": 2,\n  \"b[0]\": 1\n}"
+        );
+        assert_eq!(
+            JsonFixer::unflatten("{'b.x': 1, 'a[0]': 2}", ".", &config).unwrap(),
+            "{\n  \"a\": [\n    2\n  ],\n  \"b\": {\n    \"x\": 1\n  }\n}"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(all(test, feature = "hash"))]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use crate::JsonFixer;
+
+    fn sha256(canonical: &str) -> [u8; 32] {
+        Sha256::digest(canonical.as_bytes()).into()
+    }
+
+    fn assert_canonical(input: &str, canonical: &str) {
+        assert_eq!(
+            JsonFixer::canonical_hash(input).unwrap(),
+            sha256(canonical),
+            "{}",
+            input
+        );
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        assert_canonical("{b: 1, a: [true, null,],}", r#"{"a":[true,null],"b":1}"#);
+        assert_canonical(
+            "{\n  \"a\" : { \"z\": 1, \"y\": 2 }\n}",
+            r#"{"a":{"y":2,"z":1}}"#,
+        );
+        assert_canonical("'text'", r#""text""#);
+        assert_canonical("[]", "[]");
+        assert!(JsonFixer::canonical_hash("{a: }}").is_err());
+    }
+
+    #[test]
+    fn test_canonical_key_order() {
+        // Sorted by UTF-16 code units: U+10000 is a surrogate pair below U+E000
+        assert_canonical(
+            "{\"\u{e000}\": 1, \"\u{10000}\": 2, \"b\": 3, \"a\": 4, \"aa\": 5}",
+            "{\"a\":4,\"aa\":5,\"b\":3,\"\u{10000}\":2,\"\u{e000}\":1}",
+        );
+        // The last occurrence of a duplicated key is kept
+        assert_canonical(r#"{"a": 1, "a": 2}"#, r#"{"a":2}"#);
+    }
+
+    #[test]
+    fn test_canonical_numbers() {
+        assert_canonical(
+            "[1.0, 1.50e2, -0, 0.000001, 1e-7, 1e21, 123e18, 1E+30, 4.50, 12345678901234567890]",
+            "[1,150,0,0.000001,1e-7,1e+21,123000000000000000000,1e+30,4.5,12345678901234567000]",
+        );
+        // Out of the range of f64
+        assert_canonical("[1e400]", "[1e400]");
+    }
+
+    #[test]
+    fn test_canonical_strings() {
+        assert_canonical(
+            r#"["a\"b\\c\/d", "\u0001\b\t\n\f\r", "\u007fé\u2028", "😀"]"#,
+            "[\"a\\\"b\\\\c/d\",\"\\u0001\\b\\t\\n\\f\\r\",\"\u{7f}é\u{2028}\",\"😀\"]",
+        );
+    }
+}
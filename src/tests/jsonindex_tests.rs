@@ -0,0 +1,176 @@
+#[cfg(test)]
+mod tests {
+    use crate::jsonfixer::json_tokenizer::Position;
+    use crate::jsonfixer::jsonformatter::IndentStyle;
+    use crate::{JsonFixer, JsonFixerConfig, ValueKind};
+
+    /// Returns the path, kind and text of every value of `input`.
+    fn index(input: &str) -> Vec<(String, ValueKind, &str)> {
+        JsonFixer::index_paths(input)
+            .unwrap()
+            .into_iter()
+            .map(|(path, kind, span)| (path, kind, &input[span.start..span.end]))
+            .collect()
+    }
+
+    #[test]
+    fn test_index_paths() {
+        let input = r#"{"a": [1, true, null], "b": {"c": "x\"y"}, "d": -1.5e3}"#;
+        let expected = vec![
+            ("", ValueKind::Object, input),
+            ("a", ValueKind::Array, "[1, true, null]"),
+            ("a[0]", ValueKind::Number, "1"),
+            ("a[1]", ValueKind::Boolean, "true"),
+            ("a[2]", ValueKind::Null, "null"),
+            ("b", ValueKind::Object, r#"{"c": "x\"y"}"#),
+            ("b.c", ValueKind::String, r#""x\"y""#),
+            ("d", ValueKind::Number, "-1.5e3"),
+        ];
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(path, kind, text)| (path.to_string(), kind, text))
+            .collect();
+        assert_eq!(index(input), expected);
+    }
+
+    #[test]
+    fn test_index_broken_input() {
+        let input = "  {name: 'é' tags: ['a' 'b'], nested: {x: 1";
+        let index_entries = index(input);
+        let texts: Vec<_> = index_entries
+            .iter()
+            .map(|(path, kind, text)| (path.as_str(), kind.to_string(), *text))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                ("", "object".to_string(), &input[2..]),
+                ("name", "string".to_string(), "'é'"),
+                ("tags", "array".to_string(), "['a' 'b']"),
+                ("tags[0]", "string".to_string(), "'a'"),
+                ("tags[1]", "string".to_string(), "'b'"),
+                ("nested", "object".to_string(), "{x: 1"),
+                ("nested.x", "number".to_string(), "1"),
+            ]
+        );
+
+        let index = JsonFixer::index_paths(input).unwrap();
+        assert_eq!(index[0].2.position, Position { line: 1, column: 3 });
+        assert_eq!(
+            index[1].2.position,
+            Position {
+                line: 1,
+                column: 10
+            }
+        );
+    }
+
+    #[test]
+    fn test_index_scalar_root() {
+        assert_eq!(
+            index("  42 "),
+            vec![(String::new(), ValueKind::Number, "42")]
+        );
+        assert!(JsonFixer::index_paths("{a 1}").is_err());
+    }
+
+    fn fields(input: &str, paths: &[&str]) -> Vec<Option<String>> {
+        JsonFixer::extract_fields(input, paths)
+    }
+
+    #[test]
+    fn test_extract_fields() {
+        let input = "{user: {id: 42, tags: ['a' 'b']}, event: {type: 'click'}}";
+        assert_eq!(
+            fields(input, &["user.id", "user.tags", "event", "user.name", ""]),
+            vec![
+                Some("42".to_string()),
+                Some(r#"["a","b"]"#.to_string()),
+                Some(r#"{"type":"click"}"#.to_string()),
+                None,
+                Some(r#"{"user":{"id":42,"tags":["a","b"]},"event":{"type":"click"}}"#.to_string()),
+            ]
+        );
+        assert_eq!(fields("{a: 1, a: 2}", &["a"]), vec![Some("2".to_string())]);
+    }
+
+    #[test]
+    fn test_extract_fields_of_broken_documents() {
+        // Unrepairable section before the fields, skipped up to the end of its line
+        let input = "{\n\"data\": <<binary>>,\n\"user\": {\"id\": 7},\n\"type\": \"login\"\n}";
+        assert!(JsonFixer::fix(input).is_err());
+        assert_eq!(
+            fields(input, &["user.id", "type", "data"]),
+            vec![Some("7".to_string()), Some(r#""login""#.to_string()), None]
+        );
+
+        // Unrepairable end, skipped as well, the values parsed before it are kept
+        let input = r#"[{"id": 1, "tags": ["x"]}, {"id": 2, "tags": ["y" @@"#;
+        assert_eq!(
+            fields(
+                input,
+                &["[0].id", "[0].tags", "[1].id", "[1].tags[0]", "[1].tags"]
+            ),
+            vec![
+                Some("1".to_string()),
+                Some(r#"["x"]"#.to_string()),
+                Some("2".to_string()),
+                Some(r#""y""#.to_string()),
+                Some(r#"["y"]"#.to_string()),
+            ]
+        );
+
+        assert_eq!(fields("", &["a"]), vec![None]);
+    }
+
+    #[test]
+    fn test_format_at() {
+        let input = "{\n\t\"a\" :  [1,2],  \n\t\"b\": {x: [1 2], 'y': {}},\n\t\"c\":[ 3 ]\n}";
+        let config = JsonFixerConfig {
+            beautify: true,
+            indent_style: IndentStyle::Tabs,
+            indent_size: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            JsonFixer::format_at(input, "b", &config).unwrap().unwrap(),
+            concat!(
+                "{\n\t\"a\" :  [1,2],  \n",
+                "\t\"b\": {\n\t\t\"x\": [\n\t\t\t1,\n\t\t\t2\n\t\t],\n\t\t\"y\": {}\n\t},\n",
+                "\t\"c\":[ 3 ]\n}"
+            )
+        );
+        assert_eq!(
+            JsonFixer::format_at(input, "b.x[1]", JsonFixerConfig::default()).unwrap(),
+            Some(input.to_string())
+        );
+        assert_eq!(
+            JsonFixer::format_at(input, "c", JsonFixerConfig::default()).unwrap(),
+            Some(input.replace("[ 3 ]", "[3]"))
+        );
+        assert_eq!(JsonFixer::format_at(input, "d", &config).unwrap(), None);
+    }
+
+    #[test]
+    fn test_format_at_root_and_broken_documents() {
+        let config = JsonFixerConfig {
+            space_between: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            JsonFixer::format_at("  {a: [1 2]  \n", "", &config)
+                .unwrap()
+                .unwrap(),
+            "  { \"a\": [ 1, 2 ] }  \n"
+        );
+        // Only the formatted value is fixed
+        assert_eq!(
+            JsonFixer::format_at("{a: 'x', b: [1 2", "b", &config)
+                .unwrap()
+                .unwrap(),
+            "{a: 'x', b: [ 1, 2 ]"
+        );
+        assert!(JsonFixer::format_at("{a: ~}", "a", &config).is_err());
+    }
+}
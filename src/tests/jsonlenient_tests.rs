@@ -0,0 +1,78 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{JsonFixerError, Lenient};
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Event {
+        kind: Lenient<String>,
+        person: Lenient<Person>,
+    }
+
+    #[test]
+    fn test_deserialize_fixes_strings() {
+        let input = r#"{"kind": "{a: 1}", "person": "{name: 'John', age: 30,}"}"#;
+        let event: Event = serde_json::from_str(input).unwrap();
+        // Strings accepted by `T` are kept as is
+        assert_eq!(*event.kind, "{a: 1}");
+        assert_eq!(
+            event.person.into_inner(),
+            Person {
+                name: "John".to_string(),
+                age: 30
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_values() {
+        let input = r#"{"kind": "k", "person": {"name": "Jane", "age": 28}}"#;
+        let event: Event = serde_json::from_str(input).unwrap();
+        assert_eq!(event.person.name, "Jane");
+
+        let numbers: Lenient<Vec<u32>> = serde_json::from_str(r#""[1 2 3]""#).unwrap();
+        assert_eq!(numbers.0, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        // Fixed, but not a person
+        let input = r#"{"kind": "k", "person": "{name: 'John'}"}"#;
+        let err = serde_json::from_str::<Event>(input).unwrap_err();
+        assert!(err.to_string().contains("missing field `age`"), "{}", err);
+
+        // Not JSON
+        let input = r#"{"kind": "k", "person": "John"}"#;
+        let err = serde_json::from_str::<Event>(input).unwrap_err();
+        assert!(
+            err.to_string().contains("expected struct Person"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_from_str_and_serialize() {
+        let person: Lenient<Person> = "{name: 'John' age: 30}".parse().unwrap();
+        assert_eq!(
+            serde_json::to_string(&person).unwrap(),
+            r#"{"name":"John","age":30}"#
+        );
+
+        assert!(matches!(
+            "{name: 'John'".parse::<Lenient<Person>>(),
+            Err(JsonFixerError::SerdeError(_))
+        ));
+        assert!(matches!(
+            "{name 'John'}".parse::<Lenient<Person>>(),
+            Err(JsonFixerError::Syntax(_))
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use crate::JsonFixer;
+
+    fn members(input: &str) -> Vec<(String, String)> {
+        let mut members = Vec::new();
+        JsonFixer::for_each_member(input, |key, value| {
+            members.push((key.to_string(), value.to_string()));
+        })
+        .unwrap();
+        members
+    }
+
+    #[test]
+    fn test_for_each_member() {
+        let input = r#"{"a\"b": 1, c: [{"d": 2}, 'x'] "e": {"f": {}}, "a\"b": null}"#;
+        let expected = [
+            ("a\"b", "1"),
+            ("c", r#"[{"d":2},"x"]"#),
+            ("e", r#"{"f":{}}"#),
+            ("a\"b", "null"),
+        ];
+        assert_eq!(
+            members(input),
+            expected.map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+
+        assert!(members("  {}  ").is_empty());
+        assert!(members("{ , }").is_empty());
+    }
+
+    #[test]
+    fn test_for_each_member_streams_before_errors() {
+        let mut keys = Vec::new();
+        let result = JsonFixer::for_each_member(r#"{"a": 1, "b": [2], "c": ~}"#, |key, _| {
+            keys.push(key.to_string());
+        });
+        assert!(result.is_err());
+        assert_eq!(keys, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_for_each_member_of_non_objects() {
+        for input in ["[{\"a\": 1}]", "1", "  ", ""] {
+            let mut called = false;
+            let result = JsonFixer::for_each_member(input, |_, _| called = true);
+            assert!(result.is_err(), "{:?}", input);
+            assert!(!called);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(all(test, feature = "bignum"))]
+mod tests {
+    use std::{cmp::Ordering, str::FromStr};
+
+    use bigdecimal::BigDecimal;
+    use num_bigint::BigInt;
+
+    use crate::JsonNumber;
+
+    #[test]
+    fn test_as_big_decimal() {
+        let number = JsonNumber::parse("123456789012345678901234567890.000000001");
+        assert_eq!(
+            number.as_big_decimal(),
+            Some(BigDecimal::from_str("123456789012345678901234567890.000000001").unwrap())
+        );
+        assert_eq!(
+            JsonNumber::parse("1e400").as_big_decimal(),
+            Some(BigDecimal::from_str("1e400").unwrap())
+        );
+        assert_eq!(
+            JsonNumber::parse("-42").as_big_decimal(),
+            Some(BigDecimal::from(-42))
+        );
+    }
+
+    #[test]
+    fn test_as_big_int() {
+        assert_eq!(
+            JsonNumber::parse("123456789012345678901234567890").as_big_int(),
+            Some(BigInt::from_str("123456789012345678901234567890").unwrap())
+        );
+        assert_eq!(
+            JsonNumber::parse("1.5e3").as_big_int(),
+            Some(BigInt::from(1500))
+        );
+        assert_eq!(JsonNumber::parse("1.5").as_big_int(), None);
+        assert_eq!(JsonNumber::parse("-7").as_big_int(), Some(BigInt::from(-7)));
+
+        // Not expanded to all their digits
+        assert_eq!(JsonNumber::parse("1e100000").as_big_int(), None);
+        assert_eq!(
+            JsonNumber::parse("1e4096").as_big_int(),
+            Some(BigInt::from(10).pow(4096))
+        );
+    }
+
+    #[test]
+    fn test_exact_comparisons() {
+        let a = JsonNumber::parse("9007199254740993");
+        let b = JsonNumber::parse("9007199254740992");
+        // Equal as f64, not as exact values
+        assert_eq!(a.as_f64(), b.as_f64());
+        assert_eq!(a.exact_cmp(&b), Some(Ordering::Greater));
+
+        let a = JsonNumber::parse("100000000000000000000000000001");
+        let b = JsonNumber::parse("100000000000000000000000000000");
+        assert_eq!(a.exact_cmp(&b), Some(Ordering::Greater));
+
+        assert!(JsonNumber::parse("1e2").exact_eq(&JsonNumber::parse("100")));
+        assert!(JsonNumber::parse("100.0").exact_eq(&JsonNumber::parse("100")));
+        assert!(JsonNumber::parse("0.1").exact_eq(&JsonNumber::parse("1e-1")));
+        assert!(!JsonNumber::parse("0.1").exact_eq(&JsonNumber::parse("0.10000000000000001")));
+    }
+
+    #[test]
+    fn test_exact_operations() {
+        let n = |s: &str| JsonNumber::parse(s);
+        assert!(n("0.1").exact_add(&n("0.2")).unwrap().exact_eq(&n("0.3")));
+        assert_eq!(
+            n("18446744073709551615").exact_add(&n("1")),
+            Some(JsonNumber::BigRaw("18446744073709551616".to_string()))
+        );
+        assert!(n("1").exact_sub(&n("0.9")).unwrap().exact_eq(&n("0.1")));
+        assert!(n("1.5e3")
+            .exact_mul(&n("-2"))
+            .unwrap()
+            .exact_eq(&n("-3000")));
+
+        // Too far apart to be added without expanding them
+        assert_eq!(n("1e100000").exact_add(&n("1")), None);
+        assert_eq!(n("1").exact_mul(&n("1e-100000")), None);
+        assert_eq!(n("1e100000").exact_cmp(&n("1")), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let price = JsonNumber::parse("0.1").as_big_decimal().unwrap();
+        let total = JsonNumber::from(&price + &price + &price);
+        assert!(total.exact_eq(&JsonNumber::parse("0.3")));
+
+        let big = JsonNumber::parse("18446744073709551615")
+            .as_big_int()
+            .unwrap();
+        assert_eq!(
+            JsonNumber::from(big + 1),
+            JsonNumber::BigRaw("18446744073709551616".to_string())
+        );
+    }
+}
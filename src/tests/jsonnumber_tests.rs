@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use crate::jsonfixer::jsonnumber::expand_exponent;
+    use crate::{JsonFixer, JsonFixerConfig, JsonNumber, JsonValue, TransformAction};
+
+    #[test]
+    fn test_parse_variants() {
+        assert_eq!(JsonNumber::parse("42"), JsonNumber::I64(42));
+        assert_eq!(JsonNumber::parse("-7"), JsonNumber::I64(-7));
+        assert_eq!(
+            JsonNumber::parse("18446744073709551615"),
+            JsonNumber::U64(u64::MAX)
+        );
+        assert_eq!(JsonNumber::parse("2.75"), JsonNumber::F64(2.75));
+        assert_eq!(JsonNumber::parse("1e-7"), JsonNumber::F64(1e-7));
+
+        // Not in canonical form, or too large: kept as written
+        for literal in [
+            "1e5",
+            "1.50",
+            "1E-7",
+            "18446744073709551616",
+            "1e400",
+            "007",
+        ] {
+            assert_eq!(
+                JsonNumber::parse(literal),
+                JsonNumber::BigRaw(literal.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_keeps_text() {
+        for literal in [
+            "0",
+            "-0",
+            "42",
+            "3.14",
+            "1e5",
+            "1.50",
+            "0.10000000000000001",
+            "1e400",
+        ] {
+            assert_eq!(JsonNumber::parse(literal).to_string(), literal);
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        let number = JsonNumber::parse("1e5");
+        assert_eq!(number.as_i64(), Some(100000));
+        assert_eq!(number.as_u64(), Some(100000));
+        assert_eq!(number.as_f64(), Some(100000.0));
+
+        let number = JsonNumber::parse("-1.5");
+        assert_eq!(number.as_i64(), None);
+        assert_eq!(number.as_u64(), None);
+        assert_eq!(number.as_f64(), Some(-1.5));
+
+        let number = JsonNumber::parse("-3");
+        assert_eq!(number.as_i64(), Some(-3));
+        assert_eq!(number.as_u64(), None);
+
+        let number = JsonNumber::parse("18446744073709551615");
+        assert_eq!(number.as_i64(), None);
+        assert_eq!(number.as_u64(), Some(u64::MAX));
+
+        let number = JsonNumber::parse("1e400");
+        assert_eq!(number.as_f64(), None);
+        assert_eq!(number.as_i64(), None);
+
+        // Beyond 2^53 a float is not an exact integer anymore
+        assert_eq!(JsonNumber::parse("1e17").as_i64(), None);
+    }
+
+    #[test]
+    fn test_rounded() {
+        let cases = vec![
+            ("3.14159", 2, "3.14"),
+            ("2.675", 2, "2.67"), // 2.675 is 2.67499999... as an f64
+            ("-0.001", 2, "0"),
+            ("1.5", 0, "2"),
+            ("1.50", 3, "1.5"),
+            ("1e5", 1, "100000"),
+            ("1.5e-7", 2, "0"),
+            ("1.23456e25", 2, "1.23456e25"),
+            ("21.456789012345678", 4, "21.4568"),
+            // Integers and numbers out of range are kept
+            ("42", 0, "42"),
+            ("12345678901234567890123", 2, "12345678901234567890123"),
+            ("1e400", 2, "1e400"),
+        ];
+        for (literal, decimals, expected) in cases {
+            let rounded = JsonNumber::parse(literal).rounded(decimals);
+            assert_eq!(rounded, expected, "{} to {} decimals", literal, decimals);
+        }
+    }
+
+    #[test]
+    fn test_expand_exponent() {
+        let cases = vec![
+            ("1e5", "100000"),
+            ("1.2e-3", "0.0012"),
+            ("-1.5E+2", "-150"),
+            ("1.25e1", "12.5"),
+            ("1.23456789e28", "1.23456789e28"),
+            ("1.5e21", "1500000000000000000000"),
+            ("1e-21", "0.000000000000000000001"),
+            ("-0.05e2", "-5"),
+            ("00.10e1", "1"),
+            ("0e5", "0"),
+            // No exponent or exponent out of range
+            ("1.50", "1.50"),
+            ("1e22", "1e22"),
+            ("1e-400", "1e-400"),
+        ];
+        for (literal, expected) in cases {
+            assert_eq!(expand_exponent(literal), expected, "literal: {}", literal);
+        }
+    }
+
+    #[test]
+    fn test_parsed_values_are_typed() {
+        let mut numbers = Vec::new();
+        let collect = |_: &str, _: Option<&mut String>, value: &mut JsonValue| {
+            if let JsonValue::Number(n) = value {
+                numbers.push(n.clone());
+            }
+            TransformAction::Keep
+        };
+        let output = JsonFixer::fix_with_transform(
+            "[1, -2.5, 123456789012345678901234567890, 1e5]",
+            JsonFixerConfig::default(),
+            collect,
+        )
+        .unwrap();
+
+        assert_eq!(output, "[1,-2.5,123456789012345678901234567890,1e5]");
+        assert_eq!(
+            numbers,
+            vec![
+                JsonNumber::I64(1),
+                JsonNumber::F64(-2.5),
+                JsonNumber::BigRaw("123456789012345678901234567890".to_string()),
+                JsonNumber::BigRaw("1e5".to_string()),
+            ]
+        );
+    }
+}
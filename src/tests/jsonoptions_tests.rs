@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use crate::{BareWords, Confidence, IndentStyle, JsonFixer, JsonFixerConfig, OutputStyle};
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let config = JsonFixerConfig::from_vars(
+            "JSON_FIXER",
+            vars(&[
+                ("JSON_FIXER_PRETTY", "1"),
+                ("JSON_FIXER_INDENT", "2"),
+                ("JSON_FIXER_SORT_KEYS", "yes"),
+                ("JSON_FIXER_MAX_REPAIRS", "10"),
+                ("JSON_FIXER_MIN_CONFIDENCE", "likely"),
+                ("HOME", "/root"),
+            ]),
+        )
+        .unwrap();
+        assert!(config.beautify);
+        assert!(config.sort_keys);
+        assert_eq!(config.indent_size, 2);
+        assert_eq!(config.max_repairs, Some(10));
+        assert_eq!(config.min_confidence, Confidence::Likely);
+        assert_eq!(
+            JsonFixer::fix_with_config("{b: 1, a: 2}", &config).unwrap(),
+            "{\n  \"a\": 2,\n  \"b\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn test_from_vars_strict_first() {
+        // The strict preset does not undo the options set along with it
+        let config = JsonFixerConfig::from_vars(
+            "APP_",
+            vars(&[("APP_QUOTE_VALUES", "on"), ("APP_STRICT", "true")]),
+        )
+        .unwrap();
+        assert!(config.strict);
+        assert!(config.repairs.quote_values);
+        assert!(!config.repairs.quote_keys);
+    }
+
+    #[test]
+    fn test_from_vars_errors() {
+        let err = JsonFixerConfig::from_vars("JSON_FIXER", vars(&[("JSON_FIXER_PRETY", "1")]))
+            .unwrap_err();
+        assert_eq!(err.option, "JSON_FIXER_PRETY");
+        assert_eq!(err.message, "unknown option");
+
+        let err = JsonFixerConfig::from_vars("JSON_FIXER", vars(&[("JSON_FIXER_INDENT", "two")]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "JSON_FIXER_INDENT=\"two\": expected a number"
+        );
+    }
+
+    #[test]
+    fn test_set() {
+        let mut config = JsonFixerConfig::default();
+        config.set("indent_style", "tabs").unwrap();
+        config.set("output_style", "json5").unwrap();
+        config.set("max_line_length", "80").unwrap();
+        config.set("max_line_length", "none").unwrap();
+        config.set("header", "generated").unwrap();
+        config.set("bare_words", "null").unwrap();
+        assert!(matches!(config.indent_style, IndentStyle::Tabs));
+        assert_eq!(config.output_style, OutputStyle::Json5);
+        assert_eq!(config.max_line_length, None);
+        assert_eq!(config.header.as_deref(), Some("generated"));
+        assert_eq!(config.bare_words, BareWords::Null);
+
+        let err = config.set("pretty", "maybe").unwrap_err();
+        assert_eq!(err.option, "pretty");
+        assert_eq!(err.message, "expected a boolean");
+        assert!(config.set("output_style", "yaml").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{JsonFixer, JsonFixerError};
+
+    #[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+    struct Address {
+        city: String,
+        zip: Option<u32>,
+    }
+
+    #[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+    struct User {
+        name: String,
+        age: Option<u32>,
+        address: Address,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_complete() {
+        let input = "{name: 'John', age: 30, address: {city: 'Oslo', zip: 150}, tags: ['a']}";
+        let user = JsonFixer::from_fixed_partial::<User>(input, None).unwrap();
+        assert!(user.is_complete());
+        assert_eq!(user.value.age, Some(30));
+        assert_eq!(user.value.address.city, "Oslo");
+    }
+
+    #[test]
+    fn test_failed_fields() {
+        let input = "{name: 'John', age: 'old', address: {city: 'Oslo', zip: 'x'}, tags: [1 2]}";
+        let user = JsonFixer::from_fixed_partial::<User>(input, None).unwrap();
+        assert_eq!(
+            user.value,
+            User {
+                name: "John".to_string(),
+                age: None,
+                address: Address {
+                    city: "Oslo".to_string(),
+                    zip: None,
+                },
+                tags: Vec::new(),
+            }
+        );
+        let paths: Vec<_> = user.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["address.zip", "age", "tags"]);
+        assert!(user.errors[1].message.contains("invalid type"));
+        assert!(user.errors[1].to_string().ends_with("at path 'age'"));
+    }
+
+    #[test]
+    fn test_missing_fields_and_root() {
+        // Missing fields take their default value as well
+        let user = JsonFixer::from_fixed_partial::<User>("{name: 'Jane'}", None).unwrap();
+        assert_eq!(user.value.name, "Jane");
+        assert!(user.is_complete());
+
+        let user = JsonFixer::from_fixed_partial::<User>("[1, 2]", None).unwrap();
+        assert_eq!(user.value, User::default());
+        assert_eq!(user.errors.len(), 1);
+        assert_eq!(user.errors[0].path, "");
+
+        assert!(matches!(
+            JsonFixer::from_fixed_partial::<User>("{name 'Jane'}", None),
+            Err(JsonFixerError::Syntax(_))
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use crate::jsonfixer::jsonpath;
+
+    #[test]
+    fn test_build_paths() {
+        assert_eq!(jsonpath::key("", "user"), "user");
+        assert_eq!(jsonpath::key("user", "name"), "user.name");
+        assert_eq!(jsonpath::index("users", 2), "users[2]");
+        assert_eq!(jsonpath::index("", 0), "[0]");
+    }
+
+    #[test]
+    fn test_quoted_keys() {
+        assert_eq!(jsonpath::key("", "c.d"), r#"["c.d"]"#);
+        assert_eq!(jsonpath::key("a", "[0]"), r#"a["[0]"]"#);
+        assert_eq!(jsonpath::key("a", ""), r#"a[""]"#);
+        assert_eq!(jsonpath::key("a", r#"x."y"\"#), r#"a["x.\"y\"\\"]"#);
+        // Quotes need no escaping out of brackets
+        assert_eq!(jsonpath::key("a", r#"say "hi""#), r#"a.say "hi""#);
+
+        let path = jsonpath::key(&jsonpath::key("a", r#"x."y].z"#), "b");
+        let segments = jsonpath::segments(&path);
+        assert_eq!(segments, vec!["a", r#"["x.\"y].z"]"#, "b"]);
+        let keys: Vec<_> = segments
+            .iter()
+            .map(|s| jsonpath::segment_key(s).unwrap())
+            .collect();
+        assert_eq!(keys, vec!["a", r#"x."y].z"#, "b"]);
+        assert_eq!(jsonpath::segment_key("[0]"), None);
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(jsonpath::segments("a.b[0].c"), vec!["a", "b", "[0]", "c"]);
+        assert_eq!(jsonpath::segments("[1][2]"), vec!["[1]", "[2]"]);
+        assert_eq!(
+            jsonpath::segments(r#"a["b.c"][0]["d[e]"]"#),
+            vec!["a", r#"["b.c"]"#, "[0]", r#"["d[e]"]"#]
+        );
+        assert!(jsonpath::segments("").is_empty());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(jsonpath::matches("", ""));
+        assert!(jsonpath::matches("users[*].name", "users[3].name"));
+        assert!(jsonpath::matches("*.id", "user.id"));
+        assert!(!jsonpath::matches("users[*]", "users.name"));
+        assert!(!jsonpath::matches("user", "user.id"));
+        assert!(jsonpath::matches(r#"*["a.b"]"#, r#"x["a.b"]"#));
+        assert!(jsonpath::matches(r#"x.*"#, r#"x["a.b"]"#));
+        assert!(!jsonpath::matches("x[*]", r#"x["a.b"]"#));
+        assert!(!jsonpath::matches("x.a.b", r#"x["a.b"]"#));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        assert!(jsonpath::overlaps("user.name", "user"));
+        assert!(jsonpath::overlaps("user.name", "user.name"));
+        assert!(jsonpath::overlaps("user.name", "user.name.first"));
+        assert!(jsonpath::overlaps("users[*].id", "users[2]"));
+        assert!(!jsonpath::overlaps("users[*].id", "users[2].name"));
+        assert!(!jsonpath::overlaps("user.name", "id"));
+    }
+}
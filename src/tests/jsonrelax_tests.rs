@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use crate::{JsonFixer, JsonFixerConfig, OutputStyle};
+
+    fn comments() -> Vec<(String, String)> {
+        vec![
+            ("name".to_string(), "Name of the service".to_string()),
+            ("ports[*]".to_string(), "Exposed port".to_string()),
+            (
+                "env.debug".to_string(),
+                "Verbose logs\n\nNever in production".to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_relax_json5() {
+        let input =
+            r#"{"name": "api", "ports": [80, 443], "env": {"debug": false, "log-level": 2}}"#;
+        let relaxed = JsonFixer::relax(input, OutputStyle::Json5, &comments()).unwrap();
+        let expected = concat!(
+            "{\n",
+            "  // Name of the service\n",
+            "  name: \"api\",\n",
+            "  ports: [\n",
+            "    // Exposed port\n",
+            "    80,\n",
+            "    // Exposed port\n",
+            "    443,\n",
+            "  ],\n",
+            "  env: {\n",
+            "    // Verbose logs\n",
+            "    //\n",
+            "    // Never in production\n",
+            "    debug: false,\n",
+            "    \"log-level\": 2,\n",
+            "  },\n",
+            "}",
+        );
+        assert_eq!(relaxed, expected);
+    }
+
+    #[test]
+    fn test_relax_jsonc() {
+        let relaxed = JsonFixer::relax(
+            r#"{"name": "api", "ports": []}"#,
+            OutputStyle::Jsonc,
+            &comments(),
+        )
+        .unwrap();
+        assert_eq!(
+            relaxed,
+            "{\n  // Name of the service\n  \"name\": \"api\",\n  \"ports\": []\n}"
+        );
+        // Strict JSON has no comments
+        let relaxed = JsonFixer::relax(r#"{"name": "api"}"#, OutputStyle::Json, &comments());
+        assert_eq!(relaxed.unwrap(), "{\n  \"name\": \"api\"\n}");
+    }
+
+    #[test]
+    fn test_relax_invalid_json() {
+        assert!(JsonFixer::relax("{name: 'api'}", OutputStyle::Json5, &[]).is_err());
+    }
+
+    #[test]
+    fn test_comments_not_pretty_printed() {
+        let config = JsonFixerConfig {
+            output_style: OutputStyle::Json5,
+            comments: comments(),
+            ..Default::default()
+        };
+        assert_eq!(
+            JsonFixer::fix_with_config("{name: 'api'}", &config).unwrap(),
+            r#"{"name":"api"}"#
+        );
+    }
+}
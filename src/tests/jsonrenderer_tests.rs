@@ -0,0 +1,168 @@
+#[cfg(all(test, feature = "terminal"))]
+mod ansi_tests {
+    use crate::{AnsiTheme, JsonFixer};
+
+    fn test_theme() -> AnsiTheme {
+        AnsiTheme {
+            key: "<k>".to_string(),
+            string: "<s>".to_string(),
+            number: "<n>".to_string(),
+            boolean: "<b>".to_string(),
+            null: String::new(),
+            punctuation: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let rendered = JsonFixer::render_ansi(
+            r#"{name: 'John', age: 30, tags: ["a"], ok: true, none: null}"#,
+            &test_theme(),
+        )
+        .unwrap();
+
+        let expected = "{
+  <k>\"name\"\x1b[0m: <s>\"John\"\x1b[0m,
+  <k>\"age\"\x1b[0m: <n>30\x1b[0m,
+  <k>\"tags\"\x1b[0m: [
+    <s>\"a\"\x1b[0m
+  ],
+  <k>\"ok\"\x1b[0m: <b>true\x1b[0m,
+  <k>\"none\"\x1b[0m: null
+}";
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn test_render_ansi_default_theme() {
+        let rendered = JsonFixer::render_ansi("[1, 'é']", &AnsiTheme::default()).unwrap();
+        assert!(rendered.starts_with("\x1b[1;39m[\x1b[0m"));
+        assert!(rendered.contains("\x1b[0;32m\"é\"\x1b[0m"));
+
+        // Removing the escape sequences gives back the pretty output
+        let plain: String = rendered
+            .split("\x1b[")
+            .fold(String::new(), |mut acc, part| {
+                acc.push_str(part.split_once('m').map(|(_, rest)| rest).unwrap_or(part));
+                acc
+            });
+        assert_eq!(plain, "[\n  1,\n  \"é\"\n]");
+    }
+}
+
+#[cfg(test)]
+mod html_tests {
+    use crate::jsonfixer::jsonrenderer::window;
+    use crate::{JsonFixer, JsonFixerConfig, JsonValue};
+
+    #[test]
+    fn test_render_html() {
+        let html = JsonFixer::render_html(
+            r#"{"a": [true, null], "b": "<x>"}"#,
+            JsonFixerConfig::default(),
+        )
+        .unwrap();
+        let expected = concat!(
+            r#"<pre class="json-fixer"><span class="jf-punctuation">{</span>"#,
+            r#"<span class="jf-key">&quot;a&quot;</span><span class="jf-punctuation">:</span>"#,
+            r#"<span class="jf-punctuation">[</span><span class="jf-boolean">true</span>"#,
+            r#"<span class="jf-punctuation">,</span><span class="jf-null">null</span>"#,
+            r#"<span class="jf-punctuation">]</span><span class="jf-punctuation">,</span>"#,
+            r#"<span class="jf-key">&quot;b&quot;</span><span class="jf-punctuation">:</span>"#,
+            r#"<span class="jf-string">&quot;&lt;x&gt;&quot;</span>"#,
+            r#"<span class="jf-punctuation">}</span></pre>"#,
+        );
+        assert_eq!(html, expected);
+    }
+
+    #[test]
+    fn test_render_html_repairs() {
+        let html =
+            JsonFixer::render_html("{a: 1, b: [1 'x']}", JsonFixerConfig::default()).unwrap();
+
+        assert!(html.contains(concat!(
+            r#"<mark class="jf-repair" title="quoted key: Quoted key &#39;a&#39; at line 1, column 2 (path &#39;a&#39;)">"#,
+            r#"<span class="jf-key">&quot;a&quot;</span></mark>"#,
+        )));
+        // Inserted tokens are marked themselves
+        assert!(html.contains(concat!(
+            r#"inserted comma: Inserted missing &#39;,&#39; at line 1, column 14 (path &#39;b&#39;)">"#,
+            r#"<span class="jf-punctuation">,</span></mark>"#,
+        )));
+        assert!(html.contains(concat!(
+            r#"(path &#39;b[1]&#39;)"><span class="jf-string">&quot;x&quot;</span></mark>"#,
+            r#"<span class="jf-punctuation">]</span>"#,
+        )));
+    }
+
+    #[test]
+    fn test_render_html_repair_positions() {
+        let config = JsonFixerConfig {
+            defaults: vec![("c".to_string(), JsonValue::Null)],
+            ..Default::default()
+        };
+        let html = JsonFixer::render_html(r#"{"a": [1, 2,], "b": [3, [4"#, &config).unwrap();
+        let marked: Vec<&str> = html
+            .split("<mark class=\"jf-repair\" title=\"")
+            .skip(1)
+            .map(|mark| mark.split_once("</mark>").unwrap().0)
+            .collect();
+
+        assert_eq!(marked.len(), 5, "{}", html);
+        // A dropped token is marked on the token preceding it
+        assert!(marked[0].starts_with("removed comma: Removed trailing &#39;,&#39;"));
+        assert!(marked[0].ends_with(r#""><span class="jf-number">2</span>"#));
+        // The brackets closed at the end are marked in order
+        assert!(marked[1].starts_with("closed bracket: Inserted missing &#39;]&#39;"));
+        assert!(marked[1].contains("(path &#39;b[1]&#39;)"));
+        assert!(marked[1].ends_with(r#""><span class="jf-punctuation">]</span>"#));
+        assert!(marked[2].starts_with("closed bracket: Inserted missing &#39;]&#39;"));
+        assert!(marked[2].contains("(path &#39;b&#39;)"));
+        assert!(marked[3].starts_with("inserted default: "));
+        assert!(marked[4].starts_with("closed bracket: Inserted missing &#39;}&#39;"));
+        assert!(marked[4].ends_with(r#""><span class="jf-punctuation">}</span>"#));
+        // A repair of the parsed document is marked on its path
+        assert!(marked[3].ends_with(r#""><span class="jf-key">&quot;c&quot;</span>"#));
+    }
+
+    #[test]
+    fn test_render_html_error() {
+        let html =
+            JsonFixer::render_html("{\n  \"a\": 1,\n  \"b\" 2\n}", JsonFixerConfig::default())
+                .unwrap();
+        assert!(html.starts_with("<pre class=\"json-fixer jf-invalid\">{\n  &quot;a&quot;: 1,\n"));
+        assert!(html.contains("<mark class=\"jf-error\" title=\"Unexpected Token : "));
+        assert!(html.ends_with("\">  &quot;b&quot; 2</mark>\n}</pre>"));
+    }
+
+    #[test]
+    fn test_render_html_error_window() {
+        let input = format!("[{}1 2{}]", "1, ".repeat(1000), ", 3".repeat(1000));
+        let config = JsonFixerConfig {
+            error_window: Some(4),
+            ..JsonFixerConfig::strict()
+        };
+        let html = JsonFixer::render_html(&input, &config).unwrap();
+        assert!(html.ends_with("\">…, 1 2, 3,…</mark></pre>"), "{}", html);
+
+        let config = JsonFixerConfig {
+            error_window: None,
+            ..config
+        };
+        let html = JsonFixer::render_html(&input, &config).unwrap();
+        assert!(html.ends_with(&format!("\">{}</mark></pre>", input)));
+    }
+
+    #[test]
+    fn test_window() {
+        let line = "ab€def";
+        assert_eq!(window(line, 3, 1).to_string(), "…b€d…");
+        assert_eq!(window(line, 3, 1).column, 2);
+        assert_eq!(window(line, 1, 2).to_string(), "ab€…");
+        assert_eq!(window(line, 6, 2).to_string(), "…def");
+        assert_eq!(window(line, 3, 10).to_string(), line);
+        // Past the end of the line
+        assert_eq!(window(line, 20, 1).to_string(), "…f");
+        assert_eq!(window("", 1, 1).to_string(), "");
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::{JsonFixer, JsonFixerConfig, JsonFixerError, JsonFormatter, TokenBuffer};
+
+    const INPUTS: [&str; 4] = [
+        r#"{"name": "John", "tags": ["a", "b"]}"#,
+        "{key: 'value', \"n\": +5, \"m\": .5}",
+        "[1 2,, 3, {a: [true false}",
+        "{\n  a: 1,\n  b: [1.50e2, 'x'],\n}",
+    ];
+
+    fn configs() -> Vec<JsonFixerConfig> {
+        let pretty = JsonFixerConfig {
+            beautify: true,
+            sort_keys: true,
+            ..Default::default()
+        };
+        let preserve = JsonFixerConfig {
+            preserve: true,
+            ..Default::default()
+        };
+        vec![JsonFixerConfig::default(), pretty, preserve]
+    }
+
+    #[test]
+    fn test_replay_matches_fix() {
+        for input in INPUTS {
+            let tokens = TokenBuffer::capture(input, &JsonFixerConfig::default()).unwrap();
+            for config in configs() {
+                assert_eq!(
+                    tokens.fix_with_config(&config).unwrap(),
+                    JsonFixer::fix_with_config(input, &config).unwrap(),
+                    "{}",
+                    input
+                );
+                assert_eq!(
+                    tokens.fix_with_report(&config).unwrap(),
+                    JsonFixer::fix_with_report(input, &config).unwrap(),
+                    "{}",
+                    input
+                );
+                assert_eq!(
+                    tokens.fix_with_formatter(&config, &JsonFormatter).unwrap(),
+                    JsonFixer::fix_with_formatter(input, &config, &JsonFormatter).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_is_repeatable() {
+        let tokens = TokenBuffer::capture("{a: 'x' b: 2}", &JsonFixerConfig::default()).unwrap();
+        let first = tokens.fix_with_report(&JsonFixerConfig::default()).unwrap();
+        let second = tokens.fix_with_report(&JsonFixerConfig::default()).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.repairs.len(), 4);
+        assert_eq!(tokens.len(), 11);
+    }
+
+    #[test]
+    fn test_capture_errors() {
+        assert!(TokenBuffer::capture("{a: ~}", &JsonFixerConfig::default()).is_err());
+        assert!(TokenBuffer::capture("{a: 'x}", &JsonFixerConfig::default()).is_err());
+
+        let tokens = TokenBuffer::capture("", &JsonFixerConfig::default()).unwrap();
+        assert!(tokens.is_empty());
+        assert!(tokens.fix_with_config(&JsonFixerConfig::default()).is_err());
+
+        // Parser errors are only found when replaying
+        let tokens = TokenBuffer::capture("{a: 1} 2", &JsonFixerConfig::default()).unwrap();
+        assert!(tokens.fix_with_config(&JsonFixerConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_capture_lexing_options() {
+        let config = JsonFixerConfig {
+            python_compat: true,
+            ..Default::default()
+        };
+        let tokens = TokenBuffer::capture("{'a': (1, 2)}", &config).unwrap();
+
+        // The tuple was tokenized with the capture configuration
+        assert_eq!(
+            tokens.fix_with_config(&config).unwrap(),
+            JsonFixer::fix_with_config("{'a': (1, 2)}", &config).unwrap()
+        );
+        assert!(TokenBuffer::capture("{'a': (1, 2)}", &JsonFixerConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_unreplayable_options() {
+        let capture = JsonFixerConfig {
+            strip_comments: true,
+            ..Default::default()
+        };
+        let tokens = TokenBuffer::capture("{a: 1} // one", &capture).unwrap();
+        let cases: Vec<(&str, JsonFixerConfig)> = vec![
+            ("strict", JsonFixerConfig::strict()),
+            (
+                "salvage",
+                JsonFixerConfig {
+                    salvage: true,
+                    ..capture.clone()
+                },
+            ),
+            ("strip_comments", JsonFixerConfig::default()),
+            (
+                "python_compat",
+                JsonFixerConfig {
+                    python_compat: true,
+                    ..capture.clone()
+                },
+            ),
+        ];
+        for (option, config) in cases {
+            for result in [
+                tokens.fix_with_config(&config),
+                tokens.fix_with_report(&config).map(|report| report.output),
+                tokens.fix_with_formatter(&config, &JsonFormatter),
+            ] {
+                assert!(
+                    matches!(result, Err(JsonFixerError::Unreplayable(o)) if o == option),
+                    "{}",
+                    option
+                );
+            }
+        }
+
+        // The options of the parsing and the formatting can change
+        let config = JsonFixerConfig {
+            beautify: true,
+            ..capture
+        };
+        assert_eq!(
+            tokens.fix_with_config(&config).unwrap(),
+            JsonFixer::fix_with_config("{a: 1} // one", &config).unwrap()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use crate::{JsonFixer, StructureScan};
+
+    #[test]
+    fn test_balanced() {
+        let scan = JsonFixer::inspect(r#"{"a": [1, {"b": "}]"}], 'c': "[\"{"}"#);
+        assert_eq!(
+            scan,
+            StructureScan {
+                open_braces: 2,
+                close_braces: 2,
+                open_brackets: 1,
+                close_brackets: 1,
+                strings: 5,
+                unclosed_string: false,
+                max_depth: 3,
+            }
+        );
+        assert!(scan.is_balanced());
+        assert!(scan.hints().is_empty());
+        assert_eq!(scan.to_string(), "balanced");
+    }
+
+    #[test]
+    fn test_truncated() {
+        let scan = JsonFixer::inspect(r#"{"a": [[1, 2], [{"b": "tex"#);
+        assert_eq!(scan.unclosed_objects(), 2);
+        assert_eq!(scan.unclosed_arrays(), 2);
+        assert!(scan.unclosed_string);
+        assert_eq!(
+            scan.to_string(),
+            "unclosed string, 2 unclosed objects, 2 unclosed arrays"
+        );
+    }
+
+    #[test]
+    fn test_extra_brackets() {
+        let scan = JsonFixer::inspect("[1]]}");
+        assert!(!scan.is_balanced());
+        assert_eq!(scan.hints(), vec!["1 extra '}'", "1 extra ']'"]);
+        assert_eq!(scan.max_depth, 1);
+    }
+
+    #[test]
+    fn test_escaped_quotes() {
+        let scan = JsonFixer::inspect(r#"["a\"]", 'b\'}']"#);
+        assert!(scan.is_balanced());
+        assert_eq!(scan.strings, 2);
+    }
+}
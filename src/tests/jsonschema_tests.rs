@@ -0,0 +1,259 @@
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use crate::{JsonFixer, JsonFixerError, SchemaReport};
+
+    fn validate(input: &str, schema: &str) -> SchemaReport {
+        JsonFixer::fix_and_validate(input, schema).unwrap()
+    }
+
+    /// (pointer, keyword) of each error
+    fn errors(report: &SchemaReport) -> Vec<(&str, &str)> {
+        report
+            .errors
+            .iter()
+            .map(|error| (error.pointer.as_str(), error.keyword.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_document() {
+        let schema = r#"{
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+            },
+            "required": ["name"],
+            "additionalProperties": false
+        }"#;
+        let report = validate("{name: 'John', tags: ['a' 'b']}", schema);
+        assert_eq!(report.output, r#"{"name":"John","tags":["a","b"]}"#);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_types() {
+        let schema = r#"{"type": "integer"}"#;
+        assert!(validate("42", schema).is_valid());
+        assert!(validate("1.0", schema).is_valid());
+        assert_eq!(errors(&validate("1.5", schema)), vec![("", "type")]);
+        assert_eq!(
+            validate("'x'", schema).errors[0].message,
+            "Expected integer but found string"
+        );
+
+        let schema = r#"{"type": ["number", "null"]}"#;
+        assert!(validate("42", schema).is_valid());
+        assert!(validate("null", schema).is_valid());
+        assert_eq!(errors(&validate("true", schema)), vec![("", "type")]);
+    }
+
+    #[test]
+    fn test_enum_and_const() {
+        let schema = r#"{"enum": ["red", 1e2, {"a": [1, 2], "b": null}]}"#;
+        assert!(validate("'red'", schema).is_valid());
+        assert!(validate("100", schema).is_valid());
+        assert!(validate("{b: null, a: [1 2]}", schema).is_valid());
+        assert_eq!(errors(&validate("'blue'", schema)), vec![("", "enum")]);
+
+        let schema = r#"{"const": 1}"#;
+        assert!(validate("1.0", schema).is_valid());
+        assert_eq!(errors(&validate("2", schema)), vec![("", "const")]);
+    }
+
+    #[test]
+    fn test_number_and_string_bounds() {
+        let schema = r#"{
+            "properties": {
+                "age": {"minimum": 0, "exclusiveMaximum": 150},
+                "code": {"minLength": 2, "maxLength": 3}
+            }
+        }"#;
+        assert!(validate("{age: 0, code: 'ééé'}", schema).is_valid());
+
+        let report = validate("{age: 150, code: 'abcd'}", schema);
+        assert_eq!(
+            errors(&report),
+            vec![("/age", "exclusiveMaximum"), ("/code", "maxLength")]
+        );
+        assert_eq!(
+            report.errors[0].message,
+            "150 is greater than or equal to 150"
+        );
+    }
+
+    #[cfg(feature = "bignum")]
+    #[test]
+    fn test_exact_number_bounds() {
+        // Equal as f64, not as exact values
+        let schema = r#"{"maximum": 9007199254740992, "exclusiveMinimum": 0.1}"#;
+        assert!(validate("9007199254740992", schema).is_valid());
+        let report = validate("9007199254740993", schema);
+        assert_eq!(errors(&report), vec![("", "maximum")]);
+        assert_eq!(
+            report.errors[0].message,
+            "9007199254740993 is greater than 9007199254740992"
+        );
+        assert!(validate("0.1000000000000000000001", schema).is_valid());
+        assert!(!validate("0.1", schema).is_valid());
+        assert!(validate("1e400", r#"{"minimum": 1e399}"#).is_valid());
+        assert!(!validate("1e399", r#"{"minimum": 1e400}"#).is_valid());
+    }
+
+    #[test]
+    fn test_arrays() {
+        let schema = r#"{
+            "prefixItems": [{"type": "string"}, {"type": "integer"}],
+            "items": {"type": "boolean"},
+            "minItems": 2,
+            "maxItems": 4
+        }"#;
+        assert!(validate("['a', 1, true, false]", schema).is_valid());
+        assert_eq!(
+            errors(&validate("[1, 'a', true, 2, false]", schema)),
+            vec![
+                ("/0", "type"),
+                ("/1", "type"),
+                ("/3", "type"),
+                ("", "maxItems")
+            ]
+        );
+        assert_eq!(errors(&validate("['a']", schema)), vec![("", "minItems")]);
+
+        let schema = r#"{"uniqueItems": true}"#;
+        assert_eq!(
+            errors(&validate("[{a: 1, b: 2}, {b: 2, a: 1}]", schema)),
+            vec![("", "uniqueItems")]
+        );
+    }
+
+    #[test]
+    fn test_objects() {
+        let schema = r#"{
+            "properties": {"id": {"type": "integer"}},
+            "additionalProperties": {"type": "string"},
+            "required": ["id", "name"],
+            "maxProperties": 2
+        }"#;
+        let report = validate("{id: 'x', a: 'b', c: 3}", schema);
+        assert_eq!(
+            errors(&report),
+            vec![
+                ("/id", "type"),
+                ("/c", "type"),
+                ("", "required"),
+                ("", "maxProperties")
+            ]
+        );
+        assert_eq!(report.errors[2].message, "Missing required property 'name'");
+
+        let schema = r#"{"additionalProperties": false}"#;
+        assert_eq!(errors(&validate("{a: 1}", schema)), vec![("/a", "false")]);
+    }
+
+    #[test]
+    fn test_pointer_escaping() {
+        let schema = r#"{"properties": {"a/b": {"properties": {"c~d": {"type": "null"}}}}}"#;
+        let report = validate(r#"{"a/b": {"c~d": 1}}"#, schema);
+        assert_eq!(errors(&report), vec![("/a~1b/c~0d", "type")]);
+    }
+
+    #[test]
+    fn test_combinators() {
+        let schema = r#"{"anyOf": [{"type": "string"}, {"minimum": 10}]}"#;
+        assert!(validate("'x'", schema).is_valid());
+        assert!(validate("12", schema).is_valid());
+        assert_eq!(errors(&validate("5", schema)), vec![("", "anyOf")]);
+
+        let schema = r#"{"oneOf": [{"type": "integer"}, {"minimum": 10}]}"#;
+        assert!(validate("5", schema).is_valid());
+        assert!(validate("10.5", schema).is_valid());
+        assert_eq!(errors(&validate("12", schema)), vec![("", "oneOf")]);
+
+        let schema = r#"{"allOf": [{"type": "integer"}, {"minimum": 10}], "not": {"const": 11}}"#;
+        assert!(validate("10", schema).is_valid());
+        assert_eq!(errors(&validate("5", schema)), vec![("", "minimum")]);
+        assert_eq!(errors(&validate("11", schema)), vec![("", "not")]);
+    }
+
+    #[test]
+    fn test_references() {
+        let schema = r##"{
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": {"type": "integer"},
+                        "children": {"type": "array", "items": {"$ref": "#/$defs/node"}}
+                    }
+                }
+            },
+            "$ref": "#/$defs/node"
+        }"##;
+        assert!(validate("{value: 1, children: [{value: 2, children: []}]}", schema).is_valid());
+        assert_eq!(
+            errors(&validate("{value: 1, children: [{value: 'x'}]}", schema)),
+            vec![("/children/0/value", "type")]
+        );
+
+        let report = validate("1", r##"{"$ref": "#/$defs/missing"}"##);
+        assert_eq!(errors(&report), vec![("", "$ref")]);
+
+        // Cycles end with an error instead of overflowing the stack
+        let report = validate("1", r##"{"$ref": "#"}"##);
+        assert_eq!(errors(&report), vec![("", "$ref")]);
+    }
+
+    #[test]
+    fn test_malformed_schema() {
+        // The schema is fixed too
+        let report = validate("{a: 1}", "{properties: {a: {type: 'string'}}}");
+        assert_eq!(errors(&report), vec![("/a", "type")]);
+
+        let result = JsonFixer::fix_and_validate("1", r#"{"type": ok}"#);
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_paths_of_type() {
+        let schema = r##"{
+            "$defs": {"flag": {"type": ["boolean", "null"]}},
+            "type": "object",
+            "properties": {
+                "active": {"type": "boolean"},
+                "name": {"type": "string"},
+                "tags": {"type": "array", "items": {"$ref": "#/$defs/flag"}},
+                "pair": {"prefixItems": [{"type": "integer"}, {"type": "boolean"}]},
+                "extra": {"additionalProperties": {"type": "boolean"}},
+                "either": {"anyOf": [{"type": "string"}, {"type": "boolean"}]},
+                "tree": {"$ref": "#"}
+            }
+        }"##;
+        assert_eq!(
+            JsonFixer::paths_of_type(schema, "boolean").unwrap(),
+            vec![
+                "active",
+                "tags[*]",
+                "pair[1]",
+                "extra.*",
+                "either",
+                // The root is followed once more
+                "tree.active",
+                "tree.tags[*]",
+                "tree.pair[1]",
+                "tree.extra.*",
+                "tree.either"
+            ]
+        );
+        assert_eq!(
+            JsonFixer::paths_of_type(schema, "null").unwrap(),
+            vec!["tags[*]", "tree.tags[*]"]
+        );
+        assert_eq!(
+            JsonFixer::paths_of_type(schema, "object").unwrap(),
+            vec!["", "tree"]
+        );
+        assert!(JsonFixer::paths_of_type("{", "boolean").is_ok());
+        assert!(JsonFixer::paths_of_type("{a 1}", "boolean").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::borrow::Cow;
+
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    use crate::jsonfixer::jsonparser::{JsonEntryValue, MAX_DEPTH};
+    use crate::jsonfixer::JsonParser;
+    use crate::{Formatter, JsonFixer, JsonFixerConfig, JsonFixerError, JsonFormatter};
+    use crate::{JsonNumber, JsonValue, LimitError};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Event<'a> {
+        kind: &'a str,
+        #[serde(borrow)]
+        message: Cow<'a, str>,
+    }
+
+    fn parse(input: &str) -> JsonValue {
+        let config = JsonFixerConfig::default();
+        let mut parser = JsonParser::with_borrowed_config(input, &config);
+        parser.parse_document().unwrap().0
+    }
+
+    fn format(value: &JsonValue) -> String {
+        JsonFormatter
+            .format(value, &JsonFixerConfig::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_to_serde() {
+        let value = parse("{name: 'Ann', tags: ['a', 'b',], age: 30, ok: true, none: null,}");
+        assert_eq!(
+            Value::try_from(value).unwrap(),
+            json!({"name": "Ann", "tags": ["a", "b"], "age": 30, "ok": true, "none": null})
+        );
+        // The last occurrence of a duplicated key is kept
+        assert_eq!(
+            Value::try_from(parse(r#"{"a": 1, "a": 2}"#)).unwrap(),
+            json!({"a": 2})
+        );
+        // The lone surrogates serde_json cannot hold are replaced
+        assert_eq!(
+            Value::try_from(parse(r#"{"\ud800": "a\udc00"}"#)).unwrap(),
+            json!({"\u{FFFD}": "a\u{FFFD}"})
+        );
+    }
+
+    #[test]
+    fn test_to_serde_numbers() {
+        let value =
+            Value::try_from(parse("[-7, 18446744073709551615, 3.25, 1.50, 1e400]")).unwrap();
+        assert_eq!(value[0].as_i64(), Some(-7));
+        assert_eq!(value[1].as_u64(), Some(u64::MAX));
+        assert_eq!(value[2].as_f64(), Some(3.25));
+        assert_eq!(value[3].as_f64(), Some(1.5));
+        // Out of the range of f64
+        assert_eq!(value[4], Value::Null);
+        assert_eq!(
+            Value::try_from(JsonValue::Number(JsonNumber::BigRaw("NaN".to_string()))).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_from_serde() {
+        let value = json!({"b": [1, -2, 2.5, u64::MAX], "a": {"s": "x\ny", "n": null}});
+        let parsed = JsonValue::try_from(value.clone()).unwrap();
+        // serde_json sorts the keys without its preserve_order feature
+        assert_eq!(
+            format(&parsed),
+            r#"{"a":{"n":null,"s":"x\ny"},"b":[1,-2,2.5,18446744073709551615]}"#
+        );
+        assert_eq!(Value::try_from(parsed).unwrap(), value);
+
+        let JsonValue::Array(entries) = JsonValue::try_from(json!([1, 2.5, 1e21])).unwrap() else {
+            panic!("expected an array");
+        };
+        let numbers: Vec<JsonNumber> = entries
+            .into_iter()
+            .filter_map(|entry| match entry.value {
+                Some(JsonValue::Number(n)) => Some(n),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                JsonNumber::I64(1),
+                JsonNumber::F64(2.5),
+                JsonNumber::BigRaw("1e+21".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conversion_depth() {
+        let mut parsed = JsonValue::Null;
+        let mut value = Value::Null;
+        for _ in 0..MAX_DEPTH {
+            parsed = JsonValue::Array(vec![JsonEntryValue {
+                value: Some(parsed),
+                ..JsonEntryValue::new()
+            }]);
+            value = Value::Array(vec![value]);
+        }
+        assert!(Value::try_from(parsed.clone()).is_ok());
+        assert!(JsonValue::try_from(value.clone()).is_ok());
+
+        // Nested deeper than the parser accepts
+        let parsed = JsonValue::Array(vec![JsonEntryValue {
+            value: Some(parsed),
+            ..JsonEntryValue::new()
+        }]);
+        let value = Value::Array(vec![value]);
+        let too_deep = |error| match error {
+            JsonFixerError::Limit(LimitError::TooDeep { path, max }) => {
+                assert_eq!(max, MAX_DEPTH);
+                assert_eq!(path, "[0]".repeat(MAX_DEPTH));
+            }
+            error => panic!("unexpected error {:?}", error),
+        };
+        too_deep(Value::try_from(parsed).unwrap_err());
+        too_deep(JsonValue::try_from(value).unwrap_err());
+    }
+
+    #[test]
+    fn test_borrowed_deserialization() {
+        let input = r#"{"kind": "login", "message": "from \"admin\""}"#;
+        let event: Event = JsonFixer::from_str(input).unwrap();
+        assert_eq!(event.kind, "login");
+        // Strings with escapes are unescaped into an owned string
+        assert!(matches!(event.message, Cow::Owned(_)));
+        assert_eq!(event.message, r#"from "admin""#);
+
+        let mut buffer = String::new();
+        let input = "{kind: 'logout', message: 'bye'";
+        let event: Event = JsonFixer::from_fixed_borrowed(input, None, &mut buffer).unwrap();
+        assert_eq!(event.kind, "logout");
+        assert!(matches!(event.message, Cow::Borrowed("bye")));
+        assert!(JsonFixer::from_fixed_borrowed::<Event>("{kind: }}", None, &mut buffer).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use crate::{JsonFixer, JsonFixerError, Shape, ShapeViolation};
+
+    fn violations(input: &str, shape: &Shape) -> Vec<String> {
+        match JsonFixer::fix_expecting(input, shape) {
+            Err(JsonFixerError::Shape(err)) => {
+                err.violations.iter().map(|v| v.to_string()).collect()
+            }
+            result => panic!("expected a shape error, got {:?}", result),
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn test_fix_expecting_object() {
+        let shape = Shape::Object {
+            required_keys: keys(&["id", "name"]),
+        };
+        assert_eq!(
+            JsonFixer::fix_expecting("{id: 1, name: 'a', extra: true,}", &shape).unwrap(),
+            r#"{"id":1,"name":"a","extra":true}"#
+        );
+        assert_eq!(
+            violations("{name: 'a'}", &shape),
+            ["Missing required key 'id' at path ''"]
+        );
+        assert_eq!(
+            violations("[{id: 1, name: 'a'}]", &shape),
+            ["Expected an object but found an array at path ''"]
+        );
+    }
+
+    #[test]
+    fn test_fix_expecting_array_of_objects() {
+        let shape = Shape::ArrayOfObjects {
+            required_keys: keys(&["id"]),
+        };
+        assert_eq!(JsonFixer::fix_expecting("[]", &shape).unwrap(), "[]");
+        assert_eq!(
+            violations("[{id: 1}, 'x', {name: 'b'}, null", &shape),
+            [
+                "Expected an object but found a string at path '[1]'",
+                "Missing required key 'id' at path '[2]'",
+                "Expected an object but found null at path '[3]'",
+            ]
+        );
+
+        let err = JsonFixer::fix_expecting("{}", &Shape::Array).unwrap_err();
+        assert_eq!(err.code().to_string(), "JF6001");
+        assert_eq!(
+            err.to_string(),
+            "Shape error: Expected an array but found an object at path ''"
+        );
+        let JsonFixerError::Shape(err) = err else {
+            panic!("expected a shape error");
+        };
+        assert_eq!(
+            err.violations,
+            [ShapeViolation {
+                path: String::new(),
+                message: "Expected an array but found an object".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fix_expecting_syntax_error() {
+        let result = JsonFixer::fix_expecting("{a 1}", &Shape::Array);
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+    }
+}
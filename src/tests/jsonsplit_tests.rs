@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::{ChunkFormat, JsonFixer, JsonFixerError};
+
+    fn split(input: &str, chunk_size: usize, format: ChunkFormat) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let count = JsonFixer::split_array(input, chunk_size, format, |chunk| {
+            chunks.push(chunk.to_string());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(count, chunks.len());
+        chunks
+    }
+
+    #[test]
+    fn test_split_array() {
+        let input = "[{id: 1, tags: ['a' 'b']}, {id: 2}, {id: 3},, 'x', null,]";
+        assert_eq!(
+            split(input, 2, ChunkFormat::Array),
+            [
+                r#"[{"id":1,"tags":["a","b"]},{"id":2}]"#,
+                r#"[{"id":3},"x"]"#,
+                "[null]",
+            ]
+        );
+        assert_eq!(split(input, 10, ChunkFormat::Array).len(), 1);
+        assert!(split(" []", 2, ChunkFormat::Array).is_empty());
+    }
+
+    #[test]
+    fn test_split_array_ndjson() {
+        assert_eq!(
+            split("[1, [2, 3] 'a'", 2, ChunkFormat::NdJson),
+            ["1\n[2,3]\n", "\"a\"\n"]
+        );
+        // Empty chunks are never written
+        assert_eq!(split("[1]", 0, ChunkFormat::NdJson), ["1\n"]);
+    }
+
+    #[test]
+    fn test_split_array_errors() {
+        let result = JsonFixer::split_array("{a: [1, 2]}", 1, ChunkFormat::Array, |_| Ok(()));
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+
+        // The chunks before the error have been written
+        let mut chunks = 0;
+        let result = JsonFixer::split_array("[1, 2, 3", 1, ChunkFormat::Array, |_| {
+            chunks += 1;
+            match chunks {
+                2 => Err(io::Error::other("disk full")),
+                _ => Ok(()),
+            }
+        });
+        assert!(matches!(result, Err(JsonFixerError::IoError(_))));
+        assert_eq!(chunks, 2);
+    }
+}
@@ -0,0 +1,357 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+
+    use crate::{
+        DuplicateKeys, HomogenizeArrays, JsonFixer, JsonFixerConfig, JsonFixerError, JsonValue,
+        LengthPolicy, NonFiniteNumbers, NormalizeNulls, OutputStyle, SizePolicy, StreamOptions,
+    };
+
+    /// Writer keeping what was written when each flush happened
+    #[derive(Default)]
+    struct FlushRecorder {
+        written: Vec<u8>,
+        flushes: Vec<String>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes
+                .push(String::from_utf8(self.written.clone()).unwrap());
+            Ok(())
+        }
+    }
+
+    fn fix_to_writer(
+        input: &str,
+        options: &StreamOptions,
+    ) -> Result<(usize, FlushRecorder), JsonFixerError> {
+        let mut output = FlushRecorder::default();
+        let count =
+            JsonFixer::fix_to_writer(input, &mut output, JsonFixerConfig::default(), options)?;
+        Ok((count, output))
+    }
+
+    #[test]
+    fn test_fix_to_writer() {
+        let (count, output) =
+            fix_to_writer("{a: 1, 'b': [1 2,], c: {d: 'x'}", &Default::default()).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            output.flushes,
+            vec![
+                r#"{"a":1"#,
+                r#"{"a":1,"b":[1,2]"#,
+                r#"{"a":1,"b":[1,2],"c":{"d":"x"}"#,
+                r#"{"a":1,"b":[1,2],"c":{"d":"x"}}"#,
+            ]
+        );
+
+        let (count, output) = fix_to_writer("[1, {a: 2},]", &Default::default()).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            output.flushes,
+            vec!["[1", r#"[1,{"a":2}"#, r#"[1,{"a":2}]"#]
+        );
+
+        for (input, expected) in [("{}", "{}"), ("[ ]", "[]"), ("'x'", r#""x""#)] {
+            let (count, output) = fix_to_writer(input, &Default::default()).unwrap();
+            assert_eq!((count, output.flushes), (0, vec![expected.to_string()]));
+        }
+    }
+
+    #[test]
+    fn test_fix_to_writer_buffer_cap() {
+        let options = StreamOptions { buffer_cap: 8 };
+        let (count, output) = fix_to_writer("[1, 2, 3, 'long string', 4]", &options).unwrap();
+        assert_eq!(count, 5);
+        assert_eq!(
+            output.flushes,
+            vec![r#"[1,2,3,"long string""#, r#"[1,2,3,"long string",4]"#]
+        );
+    }
+
+    /// Asserts that `input` is written by `fix_to_writer` as `fix` returns it, or that both
+    /// fail with the same error code.
+    fn assert_fixes_alike(input: &str, config: &JsonFixerConfig) {
+        let mut output = Vec::new();
+        let streamed = JsonFixer::fix_to_writer(input, &mut output, config, &Default::default())
+            .map(|_| String::from_utf8(output).unwrap());
+        match (streamed, JsonFixer::fix_with_config(input, config)) {
+            (Ok(streamed), Ok(fixed)) => assert_eq!(streamed, fixed, "{:?}", input),
+            (Err(streamed), Err(fixed)) => assert_eq!(streamed.code(), fixed.code(), "{}", fixed),
+            (streamed, fixed) => panic!("{:?}: {:?} but {:?}", input, streamed, fixed),
+        }
+    }
+
+    #[test]
+    fn test_fix_to_writer_config() {
+        let inputs = [
+            "{a: 1, ' b ': 'None', c: {x: 'abcdef', y: [1, {v: 2}]}, b: 2, d: '{\"e\": 3}'}",
+            "[1, 'nil', [2, 3, 4], {a: 'abcdef'}, '[5]']",
+            "{a: 1, a: {b: 2}, abcdef: 3, abcdeg: 4, _generated_by: 'x'}",
+            "[NaN, 1]",
+            "{b: 1}",
+            "{}",
+            "[]",
+            "'abcdef'",
+        ];
+        let configs = [
+            JsonFixerConfig {
+                denied_keys: vec!["b".to_string(), "c.x".to_string()],
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                allowed_keys: vec!["a".to_string(), "c.y".to_string()],
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                duplicate_keys: DuplicateKeys::KeepFirst,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                normalize_nulls: NormalizeNulls::Always,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                trim_keys: true,
+                duplicate_keys: DuplicateKeys::KeepFirst,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                max_string_length: Some(4),
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                max_string_length: Some(4),
+                max_key_length: Some(4),
+                length_policy: LengthPolicy::Truncate("~".to_string()),
+                duplicate_keys: DuplicateKeys::KeepFirst,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                max_object_members: Some(2),
+                max_array_elements: Some(2),
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                max_object_members: Some(2),
+                max_array_elements: Some(2),
+                size_policy: SizePolicy::Truncate,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                unwrap_stringified_json: true,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                non_finite_numbers: NonFiniteNumbers::Keep,
+                output_style: OutputStyle::Json5,
+                verify_output: true,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                header: Some("tool".to_string()),
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                header: Some("tool".to_string()),
+                output_style: OutputStyle::Jsonc,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                beautify: true,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                beautify: true,
+                space_between: true,
+                output_style: OutputStyle::Json5,
+                emit_trailing_commas: true,
+                comments: vec![("a".to_string(), "first".to_string())],
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                space_between: true,
+                ..Default::default()
+            },
+            JsonFixerConfig {
+                defaults: vec![
+                    ("a".to_string(), JsonValue::Null),
+                    ("f".to_string(), JsonValue::Boolean(true)),
+                    ("c.z".to_string(), JsonValue::Null),
+                ],
+                ..Default::default()
+            },
+        ];
+        for config in &configs {
+            for input in inputs {
+                assert_fixes_alike(input, config);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fix_to_writer_count() {
+        let config = JsonFixerConfig {
+            header: Some("tool".to_string()),
+            defaults: vec![("b".to_string(), JsonValue::Null)],
+            denied_keys: vec!["c".to_string()],
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let count =
+            JsonFixer::fix_to_writer("{a: 1, c: 2}", &mut output, &config, &Default::default());
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"{"_generated_by":"tool","a":1,"b":null}"#
+        );
+    }
+
+    #[test]
+    fn test_fix_to_writer_unstreamable() {
+        let unstreamable = [
+            (
+                JsonFixerConfig {
+                    sort_keys: true,
+                    ..Default::default()
+                },
+                "sort_keys",
+            ),
+            (
+                JsonFixerConfig {
+                    duplicate_keys: DuplicateKeys::KeepLast,
+                    ..Default::default()
+                },
+                "duplicate_keys",
+            ),
+        ];
+        for (config, option) in unstreamable {
+            let mut output = FlushRecorder::default();
+            let result =
+                JsonFixer::fix_to_writer("{a: 1}", &mut output, &config, &Default::default());
+            assert!(matches!(result, Err(JsonFixerError::Unstreamable(o)) if o == option));
+            assert_eq!(output.written, b"");
+            // Arrays and scalars are written whatever the order of keys
+            assert_fixes_alike("[{b: 1, a: 2}, {a: 3, a: 4}]", &config);
+            assert_fixes_alike("{}", &config);
+        }
+
+        let config = JsonFixerConfig {
+            preserve: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let result = JsonFixer::fix_to_writer("[1]", &mut output, &config, &Default::default());
+        assert!(matches!(
+            result,
+            Err(JsonFixerError::Unstreamable("preserve"))
+        ));
+        assert_fixes_alike(" 'x' ", &config);
+
+        let config = JsonFixerConfig {
+            homogenize_arrays: HomogenizeArrays::Wrap,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let result =
+            JsonFixer::fix_to_writer("[1, {v: 2}]", &mut output, &config, &Default::default());
+        assert!(matches!(
+            result,
+            Err(JsonFixerError::Unstreamable("homogenize_arrays"))
+        ));
+        assert_fixes_alike("{a: [1, {v: 2}]}", &config);
+    }
+
+    #[test]
+    fn test_fix_to_writer_errors() {
+        let mut output = FlushRecorder::default();
+        let config = JsonFixerConfig::default();
+        let result =
+            JsonFixer::fix_to_writer("[1, 2, x]", &mut output, config, &Default::default());
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+        assert_eq!(output.flushes.last().unwrap(), "[1,2");
+    }
+
+    #[test]
+    fn test_fix_ndjson() {
+        let input = "{id: 1}\n\n  \n{'id': 2,}\r\n[1 2]";
+        let mut output = FlushRecorder::default();
+        let options = StreamOptions::default();
+        let count = JsonFixer::fix_ndjson(
+            input.as_bytes(),
+            &mut output,
+            JsonFixerConfig::default(),
+            &options,
+        );
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(
+            output.flushes,
+            vec![
+                "{\"id\":1}\n",
+                "{\"id\":1}\n{\"id\":2}\n",
+                "{\"id\":1}\n{\"id\":2}\n[1,2]\n",
+                "{\"id\":1}\n{\"id\":2}\n[1,2]\n",
+            ]
+        );
+
+        let mut output = FlushRecorder::default();
+        let options = StreamOptions { buffer_cap: 1024 };
+        let count = JsonFixer::fix_ndjson(
+            input.as_bytes(),
+            &mut output,
+            JsonFixerConfig::default(),
+            &options,
+        );
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(output.flushes, vec!["{\"id\":1}\n{\"id\":2}\n[1,2]\n"]);
+
+        // One document per line even when beautified
+        let config = JsonFixerConfig {
+            beautify: true,
+            indent_size: 2,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let count = JsonFixer::fix_ndjson(input.as_bytes(), &mut output, &config, &options);
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"id\":1}\n{\"id\":2}\n[1,2]\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_ndjson_errors() {
+        let config = JsonFixerConfig::default();
+        let mut output = FlushRecorder::default();
+        let options = StreamOptions { buffer_cap: 1024 };
+        let result =
+            JsonFixer::fix_ndjson("[1]\n[x]\n[3]".as_bytes(), &mut output, &config, &options);
+        assert!(matches!(result, Err(JsonFixerError::Syntax(_))));
+        assert_eq!(output.flushes, vec!["[1]\n"]);
+
+        // Located in the stream
+        let inputs = ["[1]\n\n[2, x]\n[3]", "[1]\r\n\r\n[2, x]", "[1]\n\n[2, x\n"];
+        for input in inputs {
+            let mut output = Vec::new();
+            match JsonFixer::fix_ndjson(input.as_bytes(), &mut output, &config, &options) {
+                Err(JsonFixerError::Syntax(error)) => {
+                    let position = error.position();
+                    assert_eq!((position.line, position.column), (3, 5), "{:?}", input);
+                }
+                result => panic!("unexpected result {:?}", result),
+            }
+        }
+
+        let mut output = Vec::new();
+        let result = JsonFixer::fix_ndjson(&[0xff_u8][..], &mut output, &config, &options);
+        assert!(matches!(result, Err(JsonFixerError::IoError(_))));
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use crate::JsonFixer;
+
+    #[test]
+    fn test_summarize_scalars() {
+        assert_eq!(JsonFixer::summarize("null", 0).unwrap(), "null");
+        assert_eq!(JsonFixer::summarize("1.50", 0).unwrap(), "1.50");
+        assert_eq!(JsonFixer::summarize("'a\"b'", 0).unwrap(), r#""a\"b""#);
+        assert_eq!(
+            JsonFixer::summarize(r#""Lorem ipsum dolor sit amet, consectetur""#, 0).unwrap(),
+            r#""Lorem ipsum dolor sit am…""#
+        );
+        assert_eq!(
+            JsonFixer::summarize(&format!("\"{}\"", "é".repeat(24)), 0).unwrap(),
+            format!("\"{}\"", "é".repeat(24))
+        );
+    }
+
+    #[test]
+    fn test_summarize_containers() {
+        assert_eq!(JsonFixer::summarize("[]", 0).unwrap(), "[]");
+        assert_eq!(JsonFixer::summarize("{}", 1).unwrap(), "{}");
+        assert_eq!(JsonFixer::summarize("[1]", 0).unwrap(), "[1 item]");
+        assert_eq!(
+            JsonFixer::summarize("[1 2 3]", 1).unwrap(),
+            "[3 items: 1, 2, 3]"
+        );
+        assert_eq!(
+            JsonFixer::summarize("[1, [2, 3], 4, 5]", 1).unwrap(),
+            "[4 items: 1, [2 items], 4, …]"
+        );
+
+        let input = "{a: 1, b: true, c: null, d: 'x', e: {f: [1]}, g: 2, h: 3}";
+        assert_eq!(JsonFixer::summarize(input, 0).unwrap(), "{7 keys}");
+        assert_eq!(
+            JsonFixer::summarize(input, 1).unwrap(),
+            r#"{a: 1, b: true, c: null, d: "x", e: {1 key}, … 2 more keys}"#
+        );
+        assert_eq!(
+            JsonFixer::summarize(input, 3).unwrap(),
+            r#"{a: 1, b: true, c: null, d: "x", e: {f: [1 item: 1]}, … 2 more keys}"#
+        );
+        assert_eq!(
+            JsonFixer::summarize("{a: 1, b: 2, c: 3, d: 4, e: 5, f: 6}", 1).unwrap(),
+            "{a: 1, b: 2, c: 3, d: 4, e: 5, … 1 more key}"
+        );
+    }
+
+    #[test]
+    fn test_summarize_error() {
+        assert!(JsonFixer::summarize("{a 1}", 1).is_err());
+    }
+}
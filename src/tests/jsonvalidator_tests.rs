@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod tests {
+    use crate::jsonfixer::jsonfixer_error::SyntaxError;
+    use crate::jsonfixer::jsonvalidator::MAX_VALID_DEPTH;
+    use crate::jsonfixer::{is_valid_json, JsonValidator};
+    use crate::Compliance;
+
+    #[test]
+    fn test_valid_documents() {
+        let inputs = vec![
+            "{}",
+            "[]",
+            "  null  ",
+            r#"{"name":"John","age":30,"tags":["a","b"],"ok":true}"#,
+            r#"[-0, 0.5, 1e5, 1.23E-4, -12.0e+3]"#,
+            r#""escapes \" \\ \/ \b \f \n \r \t é""#,
+            "{\n\t\"key\" : \"välue\"\r\n}",
+        ];
+
+        for input in inputs {
+            assert!(JsonValidator::validate(input).is_ok(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let inputs = vec![
+            "",
+            "{name:1}",
+            "{'a':1}",
+            "[1,]",
+            "[1 2]",
+            "{\"a\":1,}",
+            "\"raw\nnewline\"",
+            r#""bad \x escape""#,
+            "true false",
+        ];
+
+        for input in inputs {
+            assert!(JsonValidator::validate(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        for input in ["01", "+1", ".5", "1.", "1e", "--1"] {
+            assert!(
+                matches!(
+                    JsonValidator::validate(input),
+                    Err(SyntaxError::InvalidNumber(_, _))
+                        | Err(SyntaxError::UnexpectedCharacter(_, _))
+                ),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_compliance() {
+        let default = Compliance::default();
+        let i_json = Compliance::i_json();
+
+        for input in [
+            r#"{"a":1,"a":2}"#,
+            r#"{"a":1,"\u0061":2}"#,
+            r#"["\ud800"]"#,
+            r#"["\udc00\ud800"]"#,
+            "42",
+        ] {
+            assert!(
+                JsonValidator::validate_with(input, &default).is_ok(),
+                "{}",
+                input
+            );
+            assert!(
+                JsonValidator::validate_with(input, &i_json).is_err(),
+                "{}",
+                input
+            );
+        }
+
+        // Paired surrogates and distinct keys are fine
+        let input = r#"{"a":"\ud83d\ude00","b":{"a":1}}"#;
+        assert!(JsonValidator::validate_with(input, &i_json).is_ok());
+
+        assert!(matches!(
+            JsonValidator::validate_with(r#"{"a":1,"b":2,"a":3}"#, &i_json),
+            Err(SyntaxError::DuplicateKey(key, _)) if key == "a"
+        ));
+
+        let plus = Compliance {
+            leading_plus: true,
+            ..Default::default()
+        };
+        assert!(JsonValidator::validate("[+1]").is_err());
+        assert!(JsonValidator::validate_with("[+1, -1, +0.5e3]", &plus).is_ok());
+        assert!(JsonValidator::validate_with("[+-1]", &plus).is_err());
+    }
+
+    #[test]
+    fn test_is_valid_json_agrees_with_validator() {
+        let inputs = [
+            "{}",
+            "[]",
+            "  null  ",
+            "42",
+            "-0.5e-3",
+            r#"{"name":"John","age":30,"tags":["a","b"],"ok":true}"#,
+            r#"[{"a":[{}]},[[]],{"b":{"c":null}}]"#,
+            r#""escapes \" \\ \/ \b \f \n \r \t \u00e9 é""#,
+            "{\n\t\"key\" : \"välue\"\r\n}",
+            "",
+            "   ",
+            "{name:1}",
+            "{'a':1}",
+            "[1,]",
+            "[,1]",
+            "[1 2]",
+            "{\"a\":1,}",
+            "{\"a\" 1}",
+            "{\"a\":}",
+            "{\"a\":1",
+            "[1]]",
+            "[1}",
+            "{\"a\":1]",
+            "\"raw\nnewline\"",
+            r#""bad \x escape""#,
+            r#""\u12""#,
+            "\"unclosed",
+            "true false",
+            "tru",
+            "nul",
+            "01",
+            "1.",
+            "1e",
+            "-",
+            "+1",
+        ];
+
+        for input in inputs {
+            assert_eq!(
+                is_valid_json(input),
+                JsonValidator::validate(input).is_ok(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_valid_json_depth() {
+        const { assert!(is_valid_json(r#"{"a":[1,2]}"#)) };
+
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(is_valid_json(&nested(MAX_VALID_DEPTH)));
+        assert!(!is_valid_json(&nested(MAX_VALID_DEPTH + 1)));
+
+        let objects = "{\"a\":".repeat(100) + "1" + &"}".repeat(100);
+        assert!(is_valid_json(&objects));
+    }
+}
@@ -1 +1,35 @@
-pub mod jsonfixer_tests;
\ No newline at end of file
+pub mod jsonfixer_tests;
+pub mod jsonvalidator_tests;
+pub mod jsonpath_tests;
+pub mod jsonfixer_report_tests;
+pub mod jsonrenderer_tests;
+pub mod jsonengine_tests;
+pub mod jsonfixer_mmap_tests;
+pub mod jsonarena_tests;
+pub mod jsonnumber_tests;
+pub mod jsonnumber_bignum_tests;
+pub mod jsoncompare_tests;
+pub mod jsonschema_tests;
+pub mod testing_tests;
+pub mod jsonscan_tests;
+pub mod jsonencoding_tests;
+pub mod jsoncapabilities_tests;
+pub mod jsonlenient_tests;
+pub mod jsonpartial_tests;
+pub mod jsonbatch_tests;
+pub mod jsonescape_tests;
+pub mod jsonsummary_tests;
+pub mod jsonindex_tests;
+pub mod jsonflatten_tests;
+pub mod jsonreplay_tests;
+pub mod jsonannotate_tests;
+pub mod jsonmembers_tests;
+pub mod jsoncode_tests;
+pub mod jsonrelax_tests;
+pub mod jsonsplit_tests;
+pub mod jsonconcat_tests;
+pub mod jsonshape_tests;
+pub mod jsonoptions_tests;
+pub mod jsonhash_tests;
+pub mod jsonserde_tests;
+pub mod jsonstream_tests;
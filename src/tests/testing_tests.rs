@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::jsonfixer::json_tokenizer::Position;
+    use crate::testing::corpus::{assert_corpus, Corpus, Expected};
+    use crate::testing::{
+        assert_fix_fails, assert_fixes_to, assert_same_tokens, parse_tokens, tokenize, TokenBuilder,
+    };
+    use crate::{JsonFixerConfig, JsonFixerError, RepairKind, Token};
+
+    #[test]
+    fn test_builder_matches_tokenizer() {
+        let tokens = TokenBuilder::new()
+            .begin_object()
+            .key("a")
+            .whitespace(" ")
+            .begin_array()
+            .number("1")
+            .comma()
+            .boolean(true)
+            .comma()
+            .null()
+            .end_array()
+            .comma()
+            .unquoted("b")
+            .colon()
+            .string("x")
+            .end_object()
+            .build();
+        assert_same_tokens(&tokens, &tokenize(r#"{"a": [1,true,null],b:"x"}"#));
+    }
+
+    #[test]
+    fn test_builder_positions() {
+        let tokens = TokenBuilder::new()
+            .begin_array()
+            .string("ab")
+            .whitespace("\n  ")
+            .boolean(false)
+            .end_array()
+            .build();
+        let positions: Vec<(usize, usize)> = tokens
+            .iter()
+            .map(|token| (token.pos().line, token.pos().column))
+            .collect();
+        assert_eq!(positions, vec![(1, 1), (1, 2), (1, 6), (2, 3), (2, 8)]);
+
+        // Raw tokens are moved to the current position
+        let far = Position { line: 9, column: 9 };
+        let tokens = TokenBuilder::new().null().token(Token::Comma(far)).build();
+        assert_eq!(tokens[1], Token::Comma(Position { line: 1, column: 5 }));
+    }
+
+    #[test]
+    fn test_builder_positions_match_tokenizer() {
+        let tokens = TokenBuilder::new()
+            .begin_array()
+            .string("ab")
+            .whitespace("\n  ")
+            .boolean(false)
+            .whitespace("\r\n")
+            .end_array()
+            .build();
+        let positions = |tokens: &[Token]| -> Vec<Position> {
+            tokens.iter().map(|token| token.pos().clone()).collect()
+        };
+        assert_eq!(
+            positions(&tokens),
+            positions(&tokenize("[\"ab\"\n  false\r\n]"))
+        );
+    }
+
+    #[test]
+    fn test_parse_tokens() {
+        let tokens = TokenBuilder::new()
+            .begin_object()
+            .unquoted("a")
+            .colon()
+            .number("1")
+            .unquoted("b")
+            .colon()
+            .number("2")
+            .comma()
+            .end_object()
+            .build();
+        let report = parse_tokens(tokens, &JsonFixerConfig::default()).unwrap();
+        assert_eq!(report.output, r#"{"a":1,"b":2}"#);
+        assert_eq!(report.count(RepairKind::QuotedKey), 2);
+        assert_eq!(report.count(RepairKind::InsertedComma), 1);
+        assert_eq!(report.count(RepairKind::RemovedComma), 1);
+    }
+
+    #[test]
+    fn test_unclosed_tokens() {
+        let tokens = TokenBuilder::new()
+            .begin_array()
+            .begin_object()
+            .key("a")
+            .number("1")
+            .build_unclosed();
+        assert_fixes_to(tokens, r#"[{"a":1}]"#);
+    }
+
+    #[test]
+    fn test_fix_fails() {
+        let tokens = TokenBuilder::new().number("1").number("2").build();
+        assert!(matches!(
+            assert_fix_fails(tokens),
+            JsonFixerError::Syntax(_)
+        ));
+    }
+
+    #[test]
+    fn test_strict_is_skipped() {
+        let tokens = TokenBuilder::new()
+            .begin_array()
+            .number("1")
+            .end_array()
+            .build();
+        let report = parse_tokens(tokens, &JsonFixerConfig::strict()).unwrap();
+        assert_eq!(report.output, "[1]");
+    }
+
+    #[test]
+    #[should_panic(expected = "tokens")]
+    fn test_assert_fixes_to_panics() {
+        let tokens = TokenBuilder::new().begin_array().end_array().build();
+        assert_fixes_to(tokens, "{}");
+    }
+
+    #[test]
+    fn test_builtin_corpus() {
+        let corpus = Corpus::builtin();
+        for category in ["llm", "python", "javascript", "logs"] {
+            assert!(corpus.category(category).count() >= 4, "{}", category);
+        }
+        assert_corpus(&corpus);
+    }
+
+    #[test]
+    fn test_parse_corpus() {
+        let text = concat!(
+            "# Cases\n\n=== api/pretty python_compat salvage\n{a: (1,\n 2)}\n---\n{\"a\":[1,2]}\n\n",
+            "# Next\n=== api/broken\n[~]\n---\n!error\n"
+        );
+        let corpus = Corpus::parse(text).unwrap();
+        assert_eq!(corpus.cases.len(), 2);
+
+        let case = &corpus.cases[0];
+        assert_eq!(
+            (case.category.as_str(), case.name.as_str()),
+            ("api", "pretty")
+        );
+        assert_eq!(case.input, "{a: (1,\n 2)}");
+        assert_eq!(
+            case.expected,
+            Expected::Output(r#"{"a":[1,2]}"#.to_string())
+        );
+        assert!(case.config.python_compat && case.config.salvage);
+        assert_eq!(corpus.cases[1].expected, Expected::Error);
+        assert!(corpus.run().is_empty());
+    }
+
+    #[test]
+    fn test_corpus_mismatches() {
+        let corpus =
+            Corpus::parse("=== a/fixed\n[1 2]\n---\n!error\n=== a/same\n[]\n---\n[]").unwrap();
+        let mismatches = corpus.run();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "a/fixed: expected !error but got [1,2]"
+        );
+
+        let result = std::panic::catch_unwind(|| assert_corpus(&corpus));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_corpus_errors() {
+        let cases = [
+            ("{}\n---\n{}", 1),
+            ("=== nocategory\n{}\n---\n{}", 1),
+            ("\n=== a/b unknown\n{}\n---\n{}", 2),
+            ("=== a/b\n{}", 1),
+            ("=== a/b\n{}\n---\n\n=== a/c\n{}\n---\n{}", 1),
+        ];
+        for (text, line) in cases {
+            assert_eq!(Corpus::parse(text).unwrap_err().line, line, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_load_corpus() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "=== file/case\n{{a: 1}}\n---\n{{\"a\":1}}").unwrap();
+        let corpus = Corpus::load(file.path()).unwrap();
+        assert_eq!(corpus.cases[0].input, "{a: 1}");
+        assert_corpus(&corpus);
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{{a: 1}}\n---\n{{\"a\":1}}").unwrap();
+        let error = Corpus::load(file.path()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}